@@ -0,0 +1,114 @@
+//! `--annotate-dst`: synthetic transition records in an output stream.
+//!
+//! Charts built from `bucket` or `count` output often mark each DST
+//! transition. With the flag, a [`TransitionRecord`] is written for every
+//! offset change inside the range of instants seen, so no second query is
+//! needed. [`DstAnnotations`] reports each transition once, as soon as the
+//! observed range grows over it; in time-ordered input it lands between the
+//! records before and after it.
+
+use chrono::{DateTime, Duration, Offset, TimeZone, Utc};
+use chrono_tz::Tz;
+use serde::Serialize;
+use tzbucket_core::OutputSchemaVersion;
+use tzbucket_core::tz::{format_rfc3339, format_rfc3339_utc, offset_transitions};
+
+use crate::error::{CliResult, OutputFormat};
+use crate::shared::{Versioned, json_line};
+
+/// A DST (or other UTC offset) transition, as injected by `--annotate-dst`.
+#[derive(Debug, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub(crate) struct TransitionRecord {
+    /// Always `transition`, to tell the record from data records.
+    #[serde(rename = "type")]
+    kind: &'static str,
+    tz: String,
+    /// First instant with the new offset (UTC).
+    at_utc: String,
+    /// The same instant in local time, with the new offset.
+    at_local: String,
+    offset_before: String,
+    offset_after: String,
+}
+
+impl TransitionRecord {
+    fn new(tz: Tz, at: DateTime<Utc>) -> Self {
+        let offset_at = |t: DateTime<Utc>| tz.offset_from_utc_datetime(&t.naive_utc()).fix();
+        Self {
+            kind: "transition",
+            tz: tz.to_string(),
+            at_utc: format_rfc3339_utc(&at),
+            at_local: format_rfc3339(&at.with_timezone(&tz)),
+            offset_before: offset_at(at - Duration::seconds(1)).to_string(),
+            offset_after: offset_at(at).to_string(),
+        }
+    }
+
+    /// First instant with the new offset (UTC, RFC3339).
+    pub fn at_utc(&self) -> &str {
+        &self.at_utc
+    }
+
+    /// The text form: `transition <at_utc> (<at_local>): <before> -> <after>`.
+    pub fn text(&self) -> String {
+        format!(
+            "transition {} ({}): {} -> {}",
+            self.at_utc, self.at_local, self.offset_before, self.offset_after
+        )
+    }
+
+    /// The record as one output line: compact JSON or [`Self::text`].
+    pub fn line(
+        &self,
+        output_format: OutputFormat,
+        schema_version: OutputSchemaVersion,
+    ) -> CliResult<String> {
+        match output_format {
+            OutputFormat::Json => json_line(&Versioned::new(self, schema_version)),
+            OutputFormat::Text => Ok(self.text()),
+        }
+    }
+}
+
+/// The range of instants seen so far, reporting the transitions it grows
+/// over.
+pub struct DstAnnotations {
+    tz: Tz,
+    /// Earliest and latest instant seen.
+    range: Option<(DateTime<Utc>, DateTime<Utc>)>,
+}
+
+impl DstAnnotations {
+    pub fn new(tz: Tz) -> Self {
+        Self { tz, range: None }
+    }
+
+    /// Record `instant` and return the transitions newly inside the observed
+    /// range, in time order. The range runs from just after the earliest
+    /// instant to the latest, so a single instant has none.
+    pub fn observe(&mut self, instant: DateTime<Utc>) -> Vec<TransitionRecord> {
+        let (from, to) = match self.range {
+            None => {
+                self.range = Some((instant, instant));
+                return Vec::new();
+            }
+            Some((earliest, latest)) if instant < earliest => {
+                self.range = Some((instant, latest));
+                (instant, earliest)
+            }
+            Some((earliest, latest)) if instant > latest => {
+                self.range = Some((earliest, instant));
+                (latest, instant)
+            }
+            Some(_) => return Vec::new(),
+        };
+
+        // Transitions fall on whole seconds; scan one past `to` to include it.
+        offset_transitions(self.tz, from, to + Duration::seconds(1))
+            .into_iter()
+            .filter(|&at| from < at && at <= to)
+            .map(|at| TransitionRecord::new(self.tz, at))
+            .collect()
+    }
+}
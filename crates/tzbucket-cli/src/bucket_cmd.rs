@@ -1,76 +1,797 @@
-use std::fs::File;
-use std::io::{self, BufRead, BufReader};
+use std::cell::Cell;
+use std::fmt;
+use std::io::Write;
 use std::process::ExitCode;
 
+use chrono::{DateTime, Duration, Utc};
 use chrono_tz::Tz;
-use tzbucket_core::{BucketResult, TimestampFormat, compute_bucket, parse_timestamp};
+use serde::Serialize;
+use tzbucket_core::metrics::{AMBIGUOUS_BOUNDARIES, NONEXISTENT_BOUNDARIES};
+use tzbucket_core::tz::{format_rfc3339_utc, offset_transitions};
+use tzbucket_core::{
+    Bucket, BucketIndex, BucketResultRef, Clock, InputTimestampRef, Interval, MetricsSink,
+    RoundTrip, TimestampFormat, WeekKeyFormat, WeekStart, compute_bucket_with_metrics,
+    compute_bucket_with_week_key, compute_buckets_for_intervals, parse_timestamp,
+};
 
+use crate::annotate::DstAnnotations;
+use crate::build_index_cmd::load_index;
 use crate::cli::BucketArgs;
 use crate::error::{CliError, CliResult, EXIT_SUCCESS, OutputFormat};
-use crate::shared::{parse_format, parse_interval, parse_tz_or_input_error, parse_week_start};
+use crate::input::{open_input, parse_input_mode};
+use crate::shard::ShardWriter;
+use crate::shared::{
+    DayOffset, FuturePolicy, ShardBy, Versioned, anchor_intervals, check_day_offset,
+    check_legacy_tz, format_interval_list, format_skew, json_line, parse_rfc3339_to_utc,
+    resolve_zones, week_options, write_error,
+};
+use crate::skipped::LineErrors;
+use crate::style::Style;
+use crate::summary::Summary;
 
-pub fn run_bucket(args: BucketArgs, output_format: OutputFormat) -> CliResult<ExitCode> {
-    let tz = parse_tz_or_input_error(&args.tz)?;
-    let interval = parse_interval(&args.interval)?;
-    let week_start = parse_week_start(&args.week_start)?;
-    let format = parse_format(&args.format)?;
+/// Run `bucket`: read timestamps from `args.input` and write one bucket
+/// record per timestamp to `out` (or to shards with `--output-dir`).
+///
+/// `clock` is the current time for `--future-policy`.
+pub fn run_bucket(args: BucketArgs, clock: &dyn Clock, mut out: impl Write) -> CliResult<ExitCode> {
+    let output_format = args.output_format;
+    let intervals = anchor_intervals(&args.interval, args.anchor)?;
+    let location = args.lat.zip(args.lon);
+    let zones = resolve_zones(&args.tz, args.country.as_deref(), args.all_zones, location)?;
+    let (&[interval], &[tz]) = (intervals.as_slice(), zones.as_slice()) else {
+        return run_bucket_multi(args, &intervals, &zones, output_format, clock, out);
+    };
+    let canonical_tz = check_legacy_tz(tz, args.strict_tz, output_format)?;
+    let (week_start, week_key) = week_options(args.week_start, args.week_key, args.week_scheme);
+    let format = args.format;
+    let label = args.label;
+    let schema_version = args.schema_version;
+    let skew = args.skew;
+    let future_policy = args.future_policy;
+    if args.summary && output_format != OutputFormat::Text {
+        return Err(CliError::input("--summary requires --output-format text"));
+    }
+    let mut summary = args.summary.then(|| Summary::new(tz));
+    let near_threshold = args.warn_near_boundary;
+    let day_offset = check_day_offset(args.day_offset, args.policy(), interval)?;
+    let source = match (&args.index, day_offset) {
+        (_, Some(offset)) => BucketSource::DayOffset(offset),
+        (Some(path), None) => BucketSource::Index(load_index(path, tz, interval, week_start)?),
+        (None, None) => BucketSource::Compute,
+    };
 
-    let reader: Box<dyn BufRead> = if args.stdin || args.input == "-" {
-        Box::new(io::stdin().lock())
-    } else {
-        let file = File::open(&args.input).map_err(|e| {
-            CliError::runtime(format!("Failed to open file '{}': {}", args.input, e))
-        })?;
-        Box::new(BufReader::new(file))
+    let mut shards = match &args.output_dir {
+        Some(dir) => {
+            // Key is the only shard mode so far; the flag reserves the choice.
+            let ShardBy::Key = args.shard_by.unwrap_or(ShardBy::Key);
+            let rotate_size = args.rotate_size;
+            let extension = match output_format {
+                OutputFormat::Json => "ndjson",
+                OutputFormat::Text => "txt",
+            };
+            Some(ShardWriter::create(dir, extension, rotate_size)?)
+        }
+        None => None,
+    };
+
+    // Shard files stay plain.
+    let style = match shards {
+        Some(_) => Style::plain(),
+        None => Style::stdout(),
     };
 
-    for line in reader.lines() {
-        let line = line.map_err(|e| CliError::runtime(format!("Failed to read line: {}", e)))?;
-        let trimmed = line.trim();
+    let mut annotations = args.annotate_dst.then(|| DstAnnotations::new(tz));
+    let mut decoder = parse_input_mode(&args.input_mode)?;
+    let mut errors = LineErrors::open(args.errors_to.as_deref())?;
+    let mut lines = open_input(if args.stdin { "-" } else { &args.input })?;
 
+    while let Some((line_number, trimmed)) = lines.next_numbered_line()? {
         if trimmed.is_empty() {
             continue;
         }
+        let Some(record) = errors
+            .check(line_number, trimmed, decoder.decode(trimmed))?
+            .flatten()
+        else {
+            continue;
+        };
+        let ts = record.ts_str.as_str();
 
-        let result = process_bucket_line(trimmed, &tz, interval, week_start, format)
-            .map_err(|e| CliError::input(format!("Error processing '{}': {}", trimmed, e)))?;
+        let audit = BoundaryAudit::default();
+        let processed = parse_instant(ts, format, skew, future_policy, clock)
+            .map_err(|e| CliError::input(format!("Error processing '{}': {}", ts, e)))
+            .and_then(|parsed| {
+                // Policy errors at a --day-offset boundary keep their own message.
+                let result = process_bucket_line(
+                    ts,
+                    parsed.instant,
+                    &tz,
+                    interval,
+                    week_start,
+                    &source,
+                    &audit,
+                )?;
+                Ok((result, parsed))
+            });
+        let Some((mut result, parsed)) = errors.check(line_number, trimmed, processed)? else {
+            continue;
+        };
+        // Before --inclusive-end moves the end boundary.
+        if let Some(summary) = &mut summary {
+            summary.record(parsed.instant, &result.bucket)?;
+        }
+        let near_boundary = near_threshold
+            .map(|threshold| boundary_proximity(&result, tz, threshold))
+            .transpose()?
+            .flatten();
+        if args.week_numbers && interval == Interval::Week {
+            result.bucket = result.bucket.with_week_numbers();
+        }
+        if interval == Interval::Week {
+            result.bucket = result.bucket.with_week_key(week_start, week_key);
+        }
+        if let Some(point) = label {
+            result.bucket = result.bucket.with_label(point);
+        }
+        if args.dst_info {
+            result.bucket = result.bucket.with_dst_info(tz);
+        }
+        if args.inclusive_end {
+            result.bucket = result.bucket.with_inclusive_end(tz);
+        }
+        if let Some(annotations) = &mut annotations {
+            for transition in annotations.observe(parsed.instant) {
+                let line = transition.line(output_format, schema_version)?;
+                writeln!(out, "{}", line).map_err(write_error)?;
+            }
+        }
+        let roundtrip = args.verify_roundtrip.then(|| audit.roundtrip());
+        let utc_fixed = if args.compare_utc_fixed {
+            Some(compare_utc_fixed(&result, week_start, week_key)?)
+        } else {
+            None
+        };
 
-        match output_format {
+        let (key, line) = match output_format {
             OutputFormat::Json => {
-                let json = serde_json::to_string(&result)
-                    .map_err(|e| CliError::runtime(format!("Failed to serialize JSON: {}", e)))?;
-                println!("{}", json);
+                let record = Versioned::new(
+                    AuditedBucketResult {
+                        result,
+                        canonical_tz: canonical_tz.map(|tz| tz.to_string()),
+                        roundtrip,
+                        utc_fixed,
+                        near_boundary,
+                        skew: parsed.skew,
+                        clamped_from_epoch_ms: parsed.clamped_from.map(|t| t.timestamp_millis()),
+                    },
+                    schema_version,
+                );
+                let json = json_line(&record)?;
+                (record.record.result.bucket.key, json)
+            }
+            OutputFormat::Text if args.print0 => {
+                let line = result.bucket.key.to_string();
+                (result.bucket.key, line)
             }
             OutputFormat::Text => {
-                println!(
+                let bucket = &result.bucket;
+                let mut line = format!(
                     "{} -> {} to {}",
-                    result.bucket.key, result.bucket.start_local, result.bucket.end_local
+                    style.bucket_key(&bucket.key, &bucket.start_local, &bucket.end_local),
+                    bucket.start_local,
+                    bucket.end_local
                 );
+                if let Some(roundtrip) = roundtrip {
+                    line.push_str(&format!(" (roundtrip {})", roundtrip));
+                }
+                if let Some(fixed) = utc_fixed {
+                    line.push_str(&format!(
+                        " (utc-fixed {}, start shift {}s, duration delta {}s)",
+                        fixed.bucket.key, fixed.start_shift_seconds, fixed.duration_delta_seconds
+                    ));
+                }
+                if let Some(skew) = parsed.skew {
+                    line.push_str(&format!(" (skew {})", skew.offset));
+                }
+                if let Some(original) = parsed.clamped_from {
+                    line.push_str(&format!(
+                        " (clamped from {})",
+                        format_rfc3339_utc(&original)
+                    ));
+                }
+                if let Some(near) = near_boundary {
+                    if let Some(side) = near.boundary {
+                        line.push_str(&format!(
+                            " (near {} boundary: {}s)",
+                            side,
+                            near.seconds_to_boundary.unwrap_or_default()
+                        ));
+                    }
+                    if let Some(transition) = near.dst_transition {
+                        line.push_str(&format!(" (near offset change at {})", transition));
+                    }
+                }
+                (result.bucket.key, line)
             }
+        };
+
+        match &mut shards {
+            Some(shards) => shards.write(&key, &line)?,
+            None if args.print0 => write!(out, "{}\0", line).map_err(write_error)?,
+            None => writeln!(out, "{}", line).map_err(write_error)?,
         }
     }
 
+    if let Some(shards) = shards {
+        shards.finish()?;
+    }
+    if let Some(summary) = summary {
+        summary.write(&mut out).map_err(write_error)?;
+    }
+    out.flush().map_err(write_error)?;
+
     Ok(ExitCode::from(EXIT_SUCCESS))
 }
 
-fn process_bucket_line(
+/// `bucket` with a list of intervals or zones: one record per timestamp and
+/// zone carrying its bucket in every interval, for one-pass roll-up keys.
+fn run_bucket_multi(
+    args: BucketArgs,
+    intervals: &[Interval],
+    zones: &[Tz],
+    output_format: OutputFormat,
+    clock: &dyn Clock,
+    mut out: impl Write,
+) -> CliResult<ExitCode> {
+    let single_only = [
+        ("--index", args.index.is_some()),
+        ("--output-dir", args.output_dir.is_some()),
+        ("--compare-utc-fixed", args.compare_utc_fixed),
+        ("--warn-near-boundary", args.warn_near_boundary.is_some()),
+        ("--verify-roundtrip", args.verify_roundtrip),
+        ("--summary", args.summary),
+        ("--print0", args.print0),
+    ];
+    if let Some((flag, _)) = single_only.iter().find(|(_, set)| *set) {
+        return Err(CliError::input(if intervals.len() > 1 {
+            format!(
+                "{} requires a single --interval, not '{}'",
+                flag,
+                format_interval_list(&args.interval)
+            )
+        } else {
+            format!("{} requires a single zone, not {} zones", flag, zones.len())
+        }));
+    }
+    let (week_start, week_key) = week_options(args.week_start, args.week_key, args.week_scheme);
+    let format = args.format;
+    let label = args.label;
+    let schema_version = args.schema_version;
+    let skew = args.skew;
+    let future_policy = args.future_policy;
+    let mut day_offset = None;
+    for &interval in intervals {
+        day_offset = check_day_offset(args.day_offset, args.policy(), interval)?;
+    }
+    let canonical_zones = zones
+        .iter()
+        .map(|&tz| check_legacy_tz(tz, args.strict_tz, output_format))
+        .collect::<CliResult<Vec<_>>>()?;
+    let style = Style::stdout();
+
+    let mut annotations: Vec<DstAnnotations> = if args.annotate_dst {
+        zones.iter().map(|&tz| DstAnnotations::new(tz)).collect()
+    } else {
+        Vec::new()
+    };
+    let mut decoder = parse_input_mode(&args.input_mode)?;
+    let mut errors = LineErrors::open(args.errors_to.as_deref())?;
+    let mut lines = open_input(if args.stdin { "-" } else { &args.input })?;
+
+    while let Some((line_number, trimmed)) = lines.next_numbered_line()? {
+        if trimmed.is_empty() {
+            continue;
+        }
+        let Some(record) = errors
+            .check(line_number, trimmed, decoder.decode(trimmed))?
+            .flatten()
+        else {
+            continue;
+        };
+        let ts = record.ts_str.as_str();
+
+        let buckets_in = |instant, tz| -> CliResult<Vec<Bucket>> {
+            match day_offset {
+                Some(offset) => intervals
+                    .iter()
+                    .map(|&interval| offset.bucket(instant, tz, interval, week_start))
+                    .collect(),
+                None => {
+                    let mut buckets =
+                        compute_buckets_for_intervals(instant, tz, intervals, Some(week_start));
+                    Ok(intervals
+                        .iter()
+                        .map(|interval| buckets.remove(interval).expect("bucket per interval"))
+                        .collect())
+                }
+            }
+        };
+        let processed = parse_instant(ts, format, skew, future_policy, clock)
+            .map_err(|e| CliError::input(format!("Error processing '{}': {}", ts, e)))
+            .and_then(|parsed| {
+                let per_zone = zones
+                    .iter()
+                    .map(|&tz| buckets_in(parsed.instant, tz))
+                    .collect::<CliResult<Vec<_>>>()?;
+                Ok((parsed, per_zone))
+            });
+        let Some((parsed, per_zone)) = errors.check(line_number, trimmed, processed)? else {
+            continue;
+        };
+
+        for annotations in &mut annotations {
+            for transition in annotations.observe(parsed.instant) {
+                let line = transition.line(output_format, schema_version)?;
+                writeln!(out, "{}", line).map_err(write_error)?;
+            }
+        }
+        for ((&tz, canonical_tz), buckets) in zones.iter().zip(&canonical_zones).zip(per_zone) {
+            let buckets: Vec<(Interval, Bucket)> = intervals
+                .iter()
+                .zip(buckets)
+                .map(|(&interval, mut bucket)| {
+                    if interval == Interval::Week {
+                        if args.week_numbers {
+                            bucket = bucket.with_week_numbers();
+                        }
+                        bucket = bucket.with_week_key(week_start, week_key);
+                    }
+                    if let Some(point) = label {
+                        bucket = bucket.with_label(point);
+                    }
+                    if args.dst_info {
+                        bucket = bucket.with_dst_info(tz);
+                    }
+                    if args.inclusive_end {
+                        bucket = bucket.with_inclusive_end(tz);
+                    }
+                    (interval, bucket)
+                })
+                .collect();
+
+            let line = match output_format {
+                OutputFormat::Json => {
+                    let record = Versioned::new(
+                        MultiBucketResult {
+                            input: tzbucket_core::InputTimestamp {
+                                ts: ts.to_string(),
+                                epoch_ms: parsed.instant.timestamp_millis(),
+                            },
+                            tz: tz.to_string(),
+                            canonical_tz: canonical_tz.map(|tz| tz.to_string()),
+                            buckets: IntervalBuckets(buckets),
+                            skew: parsed.skew.clone(),
+                            clamped_from_epoch_ms: parsed
+                                .clamped_from
+                                .map(|t| t.timestamp_millis()),
+                        },
+                        schema_version,
+                    );
+                    json_line(&record)?
+                }
+                OutputFormat::Text => {
+                    let mut keys: Vec<String> = buckets
+                        .iter()
+                        .map(|(interval, bucket)| {
+                            format!(
+                                "{}={}",
+                                interval,
+                                style.bucket_key(
+                                    &bucket.key,
+                                    &bucket.start_local,
+                                    &bucket.end_local
+                                )
+                            )
+                        })
+                        .collect();
+                    // Several zones: lead with the zone each line is for.
+                    if zones.len() > 1 {
+                        keys.insert(0, tz.to_string());
+                    }
+                    let mut line = keys.join(" ");
+                    if let Some(skew) = &parsed.skew {
+                        line.push_str(&format!(" (skew {})", skew.offset));
+                    }
+                    if let Some(original) = parsed.clamped_from {
+                        line.push_str(&format!(
+                            " (clamped from {})",
+                            format_rfc3339_utc(&original)
+                        ));
+                    }
+                    line
+                }
+            };
+            writeln!(out, "{}", line).map_err(write_error)?;
+        }
+    }
+    out.flush().map_err(write_error)?;
+
+    Ok(ExitCode::from(EXIT_SUCCESS))
+}
+
+/// A `bucket` record for a list of intervals.
+#[derive(Debug, Serialize)]
+struct MultiBucketResult {
+    input: tzbucket_core::InputTimestamp,
+    tz: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    canonical_tz: Option<String>,
+    buckets: IntervalBuckets,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    skew: Option<SkewAdjustment>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    clamped_from_epoch_ms: Option<i64>,
+}
+
+/// Buckets keyed by interval, serialized as a JSON object in `--interval`
+/// order.
+#[derive(Debug)]
+struct IntervalBuckets(Vec<(Interval, Bucket)>);
+
+impl Serialize for IntervalBuckets {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(
+            self.0
+                .iter()
+                .map(|(interval, bucket)| (interval.to_string(), bucket)),
+        )
+    }
+}
+
+/// An input instant after the `--skew` correction and `--future-policy`.
+struct ParsedInstant {
+    instant: DateTime<Utc>,
+    skew: Option<SkewAdjustment>,
+    /// The (skew-corrected) instant `clamp_now` replaced with the current time.
+    clamped_from: Option<DateTime<Utc>>,
+}
+
+/// Parse one input line, apply the `--skew` correction, then the
+/// `--future-policy` against the clock.
+fn parse_instant(
     input: &str,
-    tz: &Tz,
-    interval: tzbucket_core::Interval,
-    week_start: tzbucket_core::WeekStart,
     format: TimestampFormat,
-) -> CliResult<BucketResult> {
-    let instant = parse_timestamp(input, format).map_err(|e| CliError::input(e.to_string()))?;
+    skew: Option<Duration>,
+    future_policy: FuturePolicy,
+    clock: &dyn Clock,
+) -> CliResult<ParsedInstant> {
+    let parsed = parse_timestamp(input, format).map_err(|e| CliError::input(e.to_string()))?;
+    let mut instant = match skew {
+        Some(skew) => parsed
+            .checked_add_signed(skew)
+            .ok_or_else(|| CliError::input("skewed instant out of range"))?,
+        None => parsed,
+    };
+    let skew = skew.map(|skew| SkewAdjustment {
+        offset: format_skew(skew),
+        original_epoch_ms: parsed.timestamp_millis(),
+    });
+
+    let mut clamped_from = None;
+    if future_policy != FuturePolicy::Allow {
+        let now = clock.now();
+        if instant > now {
+            if future_policy == FuturePolicy::Reject {
+                return Err(CliError::input(format!(
+                    "instant {} is after the current time {} (--future-policy=reject)",
+                    format_rfc3339_utc(&instant),
+                    format_rfc3339_utc(&now)
+                )));
+            }
+            clamped_from = Some(instant);
+            instant = now;
+        }
+    }
+
+    Ok(ParsedInstant {
+        instant,
+        skew,
+        clamped_from,
+    })
+}
 
-    let bucket = compute_bucket(instant, *tz, interval, Some(week_start));
+/// Where a line's bucket comes from.
+enum BucketSource {
+    /// Computed from the interval.
+    Compute,
+    /// Looked up in a `--index`, computed on a miss.
+    Index(BucketIndex),
+    /// Computed with days starting at `--day-offset`.
+    DayOffset(DayOffset),
+}
 
-    Ok(BucketResult {
-        input: tzbucket_core::InputTimestamp {
-            ts: input.to_string(),
+fn process_bucket_line<'a>(
+    input: &'a str,
+    instant: DateTime<Utc>,
+    tz: &Tz,
+    interval: Interval,
+    week_start: WeekStart,
+    source: &BucketSource,
+    metrics: &dyn MetricsSink,
+) -> CliResult<BucketResultRef<'a>> {
+    let compute = || compute_bucket_with_metrics(instant, *tz, interval, Some(week_start), metrics);
+    let bucket = match source {
+        BucketSource::Compute => compute(),
+        BucketSource::Index(index) => index.lookup(instant).cloned().unwrap_or_else(compute),
+        BucketSource::DayOffset(offset) => offset.bucket(instant, *tz, interval, week_start)?,
+    };
+
+    Ok(BucketResultRef {
+        input: InputTimestampRef {
+            ts: input,
             epoch_ms: instant.timestamp_millis(),
         },
-        tz: tz.to_string(),
+        tz: tz.name(),
         interval,
         bucket,
     })
 }
+
+/// A bucket record with the optional audit fields of `--verify-roundtrip`,
+/// `--compare-utc-fixed`, `--warn-near-boundary`, `--skew` and
+/// `--future-policy=clamp_now`.
+#[derive(Debug, Serialize)]
+struct AuditedBucketResult<'a> {
+    #[serde(flatten)]
+    result: BucketResultRef<'a>,
+    /// Current name of a legacy `tz` such as `US/Eastern`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    canonical_tz: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    roundtrip: Option<RoundTrip>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    utc_fixed: Option<UtcFixedComparison>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    near_boundary: Option<BoundaryProximity>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    skew: Option<SkewAdjustment>,
+    /// The instant before `--future-policy=clamp_now` replaced it with the
+    /// current time.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    clamped_from_epoch_ms: Option<i64>,
+}
+
+/// The `--skew` correction behind a record's `input.epoch_ms`.
+#[derive(Debug, Clone, Serialize)]
+struct SkewAdjustment {
+    /// Offset added to the parsed instant, `[+|-]HH:MM:SS`.
+    offset: String,
+    /// The parsed instant before the correction.
+    original_epoch_ms: i64,
+}
+
+/// The naive UTC-aligned bucket of the same instant, and how far the
+/// DST-aware bucket deviates from it.
+#[derive(Debug, Serialize)]
+struct UtcFixedComparison {
+    /// Bucket computed in UTC: fixed 24h days and 7x24h weeks.
+    bucket: Bucket,
+    /// DST-aware `start_utc` minus UTC-fixed `start_utc`.
+    start_shift_seconds: i64,
+    /// DST-aware duration minus UTC-fixed duration.
+    duration_delta_seconds: i64,
+    /// Whether both buckets have the same key.
+    same_key: bool,
+}
+
+fn compare_utc_fixed(
+    result: &BucketResultRef<'_>,
+    week_start: WeekStart,
+    week_key: WeekKeyFormat,
+) -> CliResult<UtcFixedComparison> {
+    let instant = DateTime::from_timestamp_millis(result.input.epoch_ms)
+        .ok_or_else(|| CliError::runtime("Input instant out of range"))?;
+    let fixed = compute_bucket_with_week_key(
+        instant,
+        Tz::UTC,
+        result.interval,
+        Some(week_start),
+        week_key,
+    );
+
+    let aware_start = parse_rfc3339_to_utc(&result.bucket.start_utc)?;
+    let aware_end = parse_rfc3339_to_utc(&result.bucket.end_utc)?;
+    let fixed_start = parse_rfc3339_to_utc(&fixed.start_utc)?;
+    let fixed_end = parse_rfc3339_to_utc(&fixed.end_utc)?;
+
+    Ok(UtcFixedComparison {
+        start_shift_seconds: (aware_start - fixed_start).num_seconds(),
+        duration_delta_seconds: ((aware_end - aware_start) - (fixed_end - fixed_start))
+            .num_seconds(),
+        same_key: fixed.key == result.bucket.key,
+        bucket: fixed,
+    })
+}
+
+/// Why an input instant is at risk of misattribution under clock skew: it
+/// lies close to an edge of its bucket, or close to a UTC offset change
+/// (where local time jumps through a gap or repeats an overlap).
+#[derive(Debug, Serialize)]
+struct BoundaryProximity {
+    /// The bucket boundary within the threshold, the nearer one if both are.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    boundary: Option<BoundarySide>,
+    /// Whole seconds between the instant and `boundary`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    seconds_to_boundary: Option<i64>,
+    /// First instant (UTC) with the new offset, if an offset change is within
+    /// the threshold.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dst_transition: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum BoundarySide {
+    Start,
+    End,
+}
+
+impl fmt::Display for BoundarySide {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BoundarySide::Start => write!(f, "start"),
+            BoundarySide::End => write!(f, "end"),
+        }
+    }
+}
+
+/// `None` when the instant is farther than `threshold` from both bucket
+/// boundaries and from any offset change.
+fn boundary_proximity(
+    result: &BucketResultRef<'_>,
+    tz: Tz,
+    threshold: Duration,
+) -> CliResult<Option<BoundaryProximity>> {
+    let instant = DateTime::from_timestamp_millis(result.input.epoch_ms)
+        .ok_or_else(|| CliError::runtime("Input instant out of range"))?;
+    let since_start = instant - parse_rfc3339_to_utc(&result.bucket.start_utc)?;
+    let until_end = parse_rfc3339_to_utc(&result.bucket.end_utc)? - instant;
+
+    let nearest = if since_start <= until_end {
+        (BoundarySide::Start, since_start)
+    } else {
+        (BoundarySide::End, until_end)
+    };
+    let (boundary, seconds_to_boundary) = match nearest {
+        (side, distance) if distance <= threshold => (Some(side), Some(distance.num_seconds())),
+        _ => (None, None),
+    };
+    // offset_transitions reports changes after its start, so begin one
+    // second early to catch one exactly `threshold` before the instant.
+    let margin = threshold + Duration::seconds(1);
+    let dst_transition = offset_transitions(tz, instant - margin, instant + margin)
+        .first()
+        .map(format_rfc3339_utc);
+
+    if boundary.is_none() && dst_transition.is_none() {
+        return Ok(None);
+    }
+    Ok(Some(BoundaryProximity {
+        boundary,
+        seconds_to_boundary,
+        dst_transition,
+    }))
+}
+
+/// Collects the DST edge cases hit by a bucket's local boundaries.
+///
+/// A nonexistent boundary was shifted forward, so its local wall-clock time no
+/// longer reads midnight; an ambiguous one round-trips but was disambiguated.
+#[derive(Debug, Default)]
+struct BoundaryAudit {
+    ambiguous: Cell<u64>,
+    nonexistent: Cell<u64>,
+}
+
+impl BoundaryAudit {
+    fn roundtrip(&self) -> RoundTrip {
+        if self.nonexistent.get() > 0 {
+            RoundTrip::Shifted
+        } else if self.ambiguous.get() > 0 {
+            RoundTrip::Disambiguated
+        } else {
+            RoundTrip::Exact
+        }
+    }
+}
+
+impl MetricsSink for BoundaryAudit {
+    fn counter(&self, name: &'static str, value: u64) {
+        match name {
+            AMBIGUOUS_BOUNDARIES => self.ambiguous.set(self.ambiguous.get() + value),
+            NONEXISTENT_BOUNDARIES => self.nonexistent.set(self.nonexistent.get() + value),
+            _ => {}
+        }
+    }
+
+    fn histogram(&self, _name: &'static str, _value: f64) {}
+}
+
+// The simd-json encoder must write the same bytes as serde_json for the
+// records `bucket` emits: flattened results, skipped optional fields, and
+// input strings that need escaping.
+#[cfg(all(test, feature = "simd-json"))]
+mod tests {
+    use chrono::TimeZone;
+    use tzbucket_core::{LabelPoint, MonthClamp, OutputSchemaVersion, compute_bucket};
+
+    use super::*;
+
+    #[test]
+    fn simd_json_records_match_serde_json() {
+        let berlin = chrono_tz::Europe::Berlin;
+        let intervals = [
+            Interval::Day,
+            Interval::Minutes(15),
+            Interval::TrailingMonth {
+                anchor_day: 31,
+                clamp: MonthClamp::RollForward,
+            },
+        ];
+        let inputs = [
+            "2026-03-31T12:00:00Z",
+            "\"quoted\" back\\slash\ttab \u{1}\u{7f} caf\u{e9} \u{2603} \u{1f30d}",
+        ];
+        let at = Utc.with_ymd_and_hms(2026, 3, 31, 12, 0, 0).unwrap();
+
+        let mut records = 0;
+        for interval in intervals {
+            let bare = compute_bucket(at, berlin, interval, None);
+            let decorated = bare
+                .clone()
+                .with_label(LabelPoint::Midpoint)
+                .with_dst_info(berlin);
+            assert!(decorated.label_ts.is_some() && decorated.dst_info.is_some());
+            for (bucket, ts) in [bare, decorated].into_iter().zip(inputs) {
+                let audited = bucket.label_ts.is_some();
+                let record = AuditedBucketResult {
+                    result: BucketResultRef {
+                        input: InputTimestampRef {
+                            ts,
+                            epoch_ms: at.timestamp_millis(),
+                        },
+                        tz: "Europe/Berlin",
+                        interval,
+                        bucket,
+                    },
+                    canonical_tz: audited.then(|| "Europe/Berlin".to_string()),
+                    roundtrip: None,
+                    utc_fixed: None,
+                    near_boundary: audited.then_some(BoundaryProximity {
+                        boundary: Some(BoundarySide::End),
+                        seconds_to_boundary: Some(43_200),
+                        dst_transition: None,
+                    }),
+                    skew: audited.then(|| SkewAdjustment {
+                        offset: "-00:00:30".to_string(),
+                        original_epoch_ms: at.timestamp_millis() + 30_000,
+                    }),
+                    clamped_from_epoch_ms: audited.then_some(at.timestamp_millis() + 1),
+                };
+                for version in [OutputSchemaVersion::V1, OutputSchemaVersion::V2] {
+                    let record = Versioned::new(&record, version);
+                    assert_eq!(
+                        json_line(&record).unwrap(),
+                        serde_json::to_string(&record).unwrap()
+                    );
+                    records += 1;
+                }
+            }
+        }
+        assert_eq!(records, 12);
+
+        let clamped = compute_bucket(at, berlin, intervals[2], None);
+        assert_eq!(clamped.month_clamp, Some(MonthClamp::RollForward));
+    }
+}
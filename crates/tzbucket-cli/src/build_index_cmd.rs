@@ -0,0 +1,114 @@
+use std::fs;
+use std::io::{BufWriter, Write};
+use std::process::ExitCode;
+
+use chrono_tz::Tz;
+use tzbucket_core::index::BINARY_INDEX_MAGIC;
+use tzbucket_core::{BucketIndex, Interval, TimestampFormat, WeekStart, parse_timestamp};
+
+use crate::cli::BuildIndexArgs;
+use crate::error::{CliError, CliResult, EXIT_SUCCESS};
+use crate::shared::{parse_interval, parse_tz_or_input_error, parse_week_start};
+
+/// File formats `build-index` writes; `load_index` tells them apart by the
+/// binary header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum IndexFormat {
+    Binary,
+    Json,
+}
+
+fn parse_index_format(s: &str) -> CliResult<IndexFormat> {
+    match s.to_lowercase().as_str() {
+        "binary" => Ok(IndexFormat::Binary),
+        "json" => Ok(IndexFormat::Json),
+        _ => Err(CliError::input(format!(
+            "Invalid index_format '{}'. Expected: binary, json",
+            s
+        ))),
+    }
+}
+
+pub fn run_build_index(args: BuildIndexArgs, out: impl Write) -> CliResult<ExitCode> {
+    let format = parse_index_format(&args.index_format)?;
+    let tz = parse_tz_or_input_error(&args.tz)?;
+    let interval = parse_interval(&args.interval)?;
+    let week_start = parse_week_start(&args.week_start)?;
+
+    let start_utc = parse_timestamp(&args.start, TimestampFormat::Rfc3339)
+        .map_err(|e| CliError::input(format!("Invalid start timestamp: {}", e)))?;
+    let end_utc = parse_timestamp(&args.end, TimestampFormat::Rfc3339)
+        .map_err(|e| CliError::input(format!("Invalid end timestamp: {}", e)))?;
+
+    if start_utc >= end_utc {
+        return Err(CliError::input(format!(
+            "Invalid range: start '{}' must be earlier than end '{}'",
+            args.start, args.end
+        )));
+    }
+
+    let index = BucketIndex::builder(tz, interval)
+        .week_start(week_start)
+        .range(start_utc, end_utc)
+        .build()
+        .map_err(|e| CliError::runtime(format!("Failed to build index: {}", e)))?;
+
+    let mut writer = BufWriter::new(out);
+    match format {
+        IndexFormat::Binary => index
+            .write_binary(&mut writer)
+            .map_err(|e| CliError::runtime(format!("Failed to write index: {}", e)))?,
+        IndexFormat::Json => {
+            serde_json::to_writer(&mut writer, &index)
+                .map_err(|e| CliError::runtime(format!("Failed to write index: {}", e)))?;
+            writeln!(writer)
+                .map_err(|e| CliError::runtime(format!("Failed to write index: {}", e)))?;
+        }
+    }
+    writer
+        .flush()
+        .map_err(|e| CliError::runtime(format!("Failed to write index: {}", e)))?;
+
+    Ok(ExitCode::from(EXIT_SUCCESS))
+}
+
+/// Load an index written by `build-index`, checking it was built for the
+/// requested timezone, interval and week start.
+///
+/// A file starting with the binary header is decoded as a binary index;
+/// anything else is read as a JSON index.
+pub fn load_index(
+    path: &str,
+    tz: Tz,
+    interval: Interval,
+    week_start: WeekStart,
+) -> CliResult<BucketIndex> {
+    let bytes = fs::read(path)
+        .map_err(|e| CliError::runtime(format!("Failed to read file '{}': {}", path, e)))?;
+    let index = if bytes.starts_with(BINARY_INDEX_MAGIC) {
+        BucketIndex::from_binary(&bytes)
+            .map_err(|e| CliError::runtime(format!("Invalid index '{}': {}", path, e)))?
+    } else {
+        serde_json::from_slice(&bytes)
+            .map_err(|e| CliError::runtime(format!("Invalid index '{}': {}", path, e)))?
+    };
+
+    let uses_week_start = interval == Interval::Week;
+    if index.tz() != tz
+        || index.interval() != interval
+        || (uses_week_start && index.week_start() != week_start)
+    {
+        return Err(CliError::input(format!(
+            "Index '{}' was built for {} {} (week start {}), not {} {} (week start {})",
+            path,
+            index.tz(),
+            index.interval(),
+            index.week_start(),
+            tz,
+            interval,
+            week_start
+        )));
+    }
+
+    Ok(index)
+}
@@ -1,4 +1,55 @@
+//! Subcommand and argument definitions (clap derive).
+//!
+//! Field doc comments are the `--help` text, written for the terminal rather
+//! than for rustdoc.
+//!
+//! The arguments of `bucket`, `range` and `explain` can also be built in
+//! code: `Default` holds the command-line defaults and the `with_*` setters
+//! take typed values, so that embedders can call the `run_*` functions
+//! directly:
+//!
+//! ```
+//! use chrono::{TimeZone, Utc};
+//! use tzbucket_cli::cli::RangeArgs;
+//! use tzbucket_cli::error::OutputFormat;
+//! use tzbucket_core::{Interval, SystemClock};
+//!
+//! let args = RangeArgs::default()
+//!     .with_tz(chrono_tz::Europe::Berlin)
+//!     .with_interval(Interval::Day)
+//!     .with_start(Utc.with_ymd_and_hms(2026, 3, 28, 23, 0, 0).unwrap())
+//!     .with_end(Utc.with_ymd_and_hms(2026, 3, 29, 22, 0, 0).unwrap());
+//!
+//! let mut out = Vec::new();
+//! tzbucket_cli::run_range(args.with_output_format(OutputFormat::Text), &SystemClock, &mut out)
+//!     .unwrap();
+//! assert_eq!(
+//!     String::from_utf8(out).unwrap(),
+//!     "2026-03-29: 2026-03-29T00:00:00+01:00 to 2026-03-30T00:00:00+02:00\n"
+//! );
+//! ```
+//!
+//! Fields hold parsed values: clap runs the same parsers on the command line
+//! and on the `default_value`s that `Default` is built from. A `humantime`
+//! range bound keeps its expression ([`RangeBound::Human`]), since it is
+//! evaluated against the clock when the command runs.
+
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use chrono_tz::Tz;
 use clap::{Parser, Subcommand};
+use tzbucket_core::{
+    AmbiguousPolicy, Interval, LabelPoint, NonexistentPolicy, OutputSchemaVersion, Policy,
+    TimestampFormat, WeekKeyFormat, WeekScheme, WeekStart,
+};
+
+use crate::error::{OutputFormat, parse_output_format};
+pub use crate::shared::{FuturePolicy, IntervalArg, RangeBound, ShardBy};
+use crate::shared::{
+    parse_ambiguous_policy, parse_anchor, parse_day_offset_time, parse_duration, parse_format,
+    parse_future_policy, parse_interval, parse_interval_arg, parse_label_point, parse_local_time,
+    parse_nonexistent_policy, parse_range_bound, parse_schema_version, parse_shard_by, parse_size,
+    parse_skew, parse_tz_or_input_error, parse_week_key, parse_week_scheme, parse_week_start,
+};
 
 /// DST-safe time bucketing tool
 #[derive(Parser, Debug)]
@@ -7,8 +58,27 @@ use clap::{Parser, Subcommand};
 pub struct Cli {
     #[command(subcommand)]
     pub command: Commands,
+
+    /// Use this instant (RFC3339 with offset) as the current time, e.g. to replay a DST incident
+    #[arg(long, global = true)]
+    pub now_override: Option<String>,
+
+    /// Colorize text output: auto (terminal and NO_COLOR unset), always, never
+    #[arg(long, global = true, default_value = "auto")]
+    pub color: String,
+
+    /// Write output to this file instead of stdout, replacing it only when the command succeeds
+    #[arg(short, long, global = true)]
+    pub output: Option<String>,
+
+    /// Append to the --output file instead of replacing it (still all-or-nothing)
+    #[arg(long, global = true, requires = "output")]
+    pub append: bool,
 }
 
+// Parsed once per process, so the size of `bucket`'s arguments does not
+// matter; boxing them would only complicate matching for embedders.
+#[allow(clippy::large_enum_variant)]
 #[derive(Subcommand, Debug)]
 pub enum Commands {
     /// Compute time buckets for timestamps
@@ -17,15 +87,646 @@ pub enum Commands {
     Range(RangeArgs),
     /// Explain local time resolution (DST handling)
     Explain(ExplainArgs),
+    /// Print the bucket containing the current time
+    Now(NowArgs),
+    /// Print one instant's local time, offset and bucket in every zone of a list
+    Matrix(MatrixArgs),
+    /// Export the UTC offset history of a zone (e.g. as CSV for a dimension table)
+    Offsets(OffsetsArgs),
+    /// Check that two zones have identical UTC offsets over a range of years
+    SameRules(SameRulesArgs),
+    /// Print the buckets of stored bucket keys (UTC and local boundaries)
+    Key(KeyArgs),
+    /// Print the bucket N intervals away from a bucket key
+    Shift(ShiftArgs),
+    /// Count the intervals between two bucket keys
+    Span(SpanArgs),
+    /// List every bucket key between two keys (inclusive)
+    Keys(KeysArgs),
+    /// Resolve naive local timestamps (one per line) to UTC instants
+    Resolve(ResolveArgs),
+    /// Count timestamps per bucket
+    Count(CountArgs),
+    /// Roll per-bucket counts up into coarser buckets (e.g. days into weeks and months)
+    Rollup(RollupArgs),
+    /// Compare two bucket outputs and report changed bucket assignments
+    DiffOutput(DiffOutputArgs),
+    /// Report bucket assignment changes between two tzdata versions
+    Tzimpact(TzImpactArgs),
+    /// Propose balanced partitions from per-bucket row counts
+    Plan(PlanArgs),
+    /// Write a static HTML report of the buckets and DST transitions in a range
+    Report(ReportArgs),
+    /// Precompute the buckets of a range into an index file for `bucket --index`
+    BuildIndex(BuildIndexArgs),
+    /// Emit the conformance test vectors (JSON) for cross-language implementations
+    Vectors(VectorsArgs),
+    /// Check DST edge cases against the linked tzdata (e.g. at container startup)
+    Selftest(SelftestArgs),
+    /// Compare bucket boundaries of random instants and zones against GNU `date` (developer tool)
+    Difftest(DifftestArgs),
+    /// Print the JSON Schema of an output type
+    #[cfg(feature = "schemars")]
+    Schema(SchemaArgs),
 }
 
 #[derive(clap::Args, Debug)]
 pub struct BucketArgs {
-    /// IANA timezone (e.g., Europe/Berlin)
+    /// IANA timezone (e.g., Europe/Berlin); repeat to bucket every timestamp in each zone (one record per zone, in the multiple-interval shape)
+    #[arg(short, long, default_value = "UTC", value_parser = parse_tz_or_input_error)]
+    pub tz: Vec<Tz>,
+
+    /// ISO 3166 country code (e.g., DE) instead of --tz; fails if the country has several zones unless --all-zones (needs the zone-tab feature)
+    #[arg(long, conflicts_with = "tz")]
+    pub country: Option<String>,
+
+    /// With --country, bucket every timestamp in each of the country's zones: one record per zone, in the multiple-interval shape
+    #[arg(long, requires = "country")]
+    pub all_zones: bool,
+
+    /// Latitude in degrees instead of --tz, bucketing in the zone at --lat/--lon (needs the tzf-rs feature)
+    #[arg(long, requires = "lon", conflicts_with_all = ["tz", "country"], allow_hyphen_values = true)]
+    pub lat: Option<f64>,
+
+    /// Longitude in degrees, with --lat
+    #[arg(long, requires = "lat", allow_hyphen_values = true)]
+    pub lon: Option<f64>,
+
+    /// Reject legacy zone names such as US/Eastern instead of warning and reporting canonical_tz
+    #[arg(long)]
+    pub strict_tz: bool,
+
+    /// Bucket interval: `<N>m`, hour, `<N>h`, day, `<N>d:<anchor>`, week, month, quarter, `fiscal_quarter:<start month 1-12>`, year, `trailing_month:<1-31>[:roll_forward]`, `fixed:<seconds>`; or a comma-separated list (day,week,month) for every bucket of each timestamp in one record
+    #[arg(short = 'i', long, default_value = "day", value_delimiter = ',', value_parser = parse_interval_arg)]
+    pub interval: Vec<IntervalArg>,
+
+    /// Start date of one period for `<N>d` intervals (e.g. --interval 14d --anchor 2026-01-05)
+    #[arg(long, value_parser = parse_anchor)]
+    pub anchor: Option<NaiveDate>,
+
+    /// Start days at this local time instead of midnight (`HH:MM[:SS]`, e.g. 06:00 for an operational day running 06:00-06:00)
+    #[arg(long, conflicts_with = "index", value_parser = parse_day_offset_time)]
+    pub day_offset: Option<NaiveTime>,
+
+    /// Policy for a --day-offset boundary skipped by DST: error, shift_forward
+    #[arg(long, default_value = "error", requires = "day_offset", value_parser = parse_nonexistent_policy)]
+    pub policy_nonexistent: NonexistentPolicy,
+
+    /// Policy for a --day-offset boundary repeated by DST: error, first, second
+    #[arg(long, default_value = "error", requires = "day_offset", value_parser = parse_ambiguous_policy)]
+    pub policy_ambiguous: AmbiguousPolicy,
+
+    /// Week start day: monday or sunday (for week interval)
+    #[arg(long, default_value = "monday", value_parser = parse_week_start)]
+    pub week_start: WeekStart,
+
+    /// Week bucket key format: start_date (YYYY-MM-DD), or YYYY-Www numbered by iso, mmwr or broadcast weeks
+    #[arg(long, default_value = "start_date", value_parser = parse_week_key)]
+    pub week_key: WeekKeyFormat,
+
+    /// Week-numbering scheme, setting both --week-start and --week-key: iso, mmwr (CDC epidemiological weeks, from Sunday), broadcast
+    #[arg(long, conflicts_with_all = ["week_start", "week_key"], value_parser = parse_week_scheme)]
+    pub week_scheme: Option<WeekScheme>,
+
+    /// Input format: epoch_ms, epoch_s, rfc3339
+    #[arg(short = 'f', long, default_value = "epoch_ms", value_parser = parse_format)]
+    pub format: TimestampFormat,
+
+    /// Input line format: plain, `csv:<column name or 1-based index>`, `ndjson:<field>`, `regex:<pattern>` (group `ts` or the first group)
+    #[arg(long, default_value = "plain")]
+    pub input_mode: String,
+
+    /// Output format: json, text
+    #[arg(long, default_value = "text", value_parser = parse_output_format)]
+    pub output_format: OutputFormat,
+
+    /// Input file path (use - for stdin)
+    #[arg(long, default_value = "-")]
+    pub input: String,
+
+    /// Skip lines that fail to parse or resolve, reporting each as an NDJSON record to stderr, null (discard) or a file path, instead of aborting
+    #[arg(long, value_name = "stderr|null|PATH")]
+    pub errors_to: Option<String>,
+
+    /// Read from stdin
+    #[arg(long)]
+    pub stdin: bool,
+
+    /// Round-trip the bucket's local boundaries and add a `roundtrip` audit field
+    #[arg(long)]
+    pub verify_roundtrip: bool,
+
+    /// Add a utc_fixed field comparing each bucket with its naive UTC-aligned counterpart
+    #[arg(long)]
+    pub compare_utc_fixed: bool,
+
+    /// Add iso_week, iso_week_year and us_week_number to week buckets
+    #[arg(long)]
+    pub week_numbers: bool,
+
+    /// Add a label_ts field at the bucket start, end, or midpoint
+    #[arg(long, value_parser = parse_label_point)]
+    pub label: Option<LabelPoint>,
+
+    /// Add is_dst_transition, start_offset_seconds and end_offset_seconds to each bucket
+    #[arg(long)]
+    pub dst_info: bool,
+
+    /// Render bucket ends as the last millisecond inside the bucket (closed intervals)
+    #[arg(long)]
+    pub inclusive_end: bool,
+
+    /// JSON output schema version: 1 (default, unversioned shape) or 2 (with schema_version and newer fields)
+    #[arg(long, default_value = "1", value_parser = parse_schema_version)]
+    pub schema_version: OutputSchemaVersion,
+
+    /// Index file from `build-index` to look buckets up in (computed outside its range)
+    #[arg(long, conflicts_with = "verify_roundtrip")]
+    pub index: Option<String>,
+
+    /// Write records into one file per shard in this directory, plus manifest.json
+    #[arg(long)]
+    pub output_dir: Option<String>,
+
+    /// Shard records by: key (default with --output-dir)
+    #[arg(long, requires = "output_dir", value_parser = parse_shard_by)]
+    pub shard_by: Option<ShardBy>,
+
+    /// Start a new shard file before one exceeds this size (bytes, or with K, M, G suffix)
+    #[arg(long, requires = "output_dir", value_parser = parse_size)]
+    pub rotate_size: Option<u64>,
+
+    /// Add a near_boundary field to records within this duration (e.g. 5m) of a bucket boundary or offset change
+    #[arg(long, value_parser = parse_duration)]
+    pub warn_near_boundary: Option<Duration>,
+
+    /// Clock-skew correction added to every parsed instant before bucketing: [+|-]HH:MM:SS
+    #[arg(long, allow_hyphen_values = true, value_parser = parse_skew)]
+    pub skew: Option<Duration>,
+
+    /// Handling of instants after the current time (see --now-override): allow, reject, clamp_now
+    #[arg(long, default_value = "allow", value_parser = parse_future_policy)]
+    pub future_policy: FuturePolicy,
+
+    /// End text output with totals: lines, distinct and DST-affected buckets, earliest/latest instant
+    #[arg(long)]
+    pub summary: bool,
+
+    /// Terminate records with NUL instead of newline (for xargs -0); text mode prints only keys
+    #[arg(short = '0', long, conflicts_with_all = ["output_dir", "summary"])]
+    pub print0: bool,
+
+    /// Add a `type: transition` record for every DST transition between the earliest and latest timestamp, with the offsets before and after
+    #[arg(long, conflicts_with_all = ["output_dir", "print0"])]
+    pub annotate_dst: bool,
+}
+
+impl Default for BucketArgs {
+    /// The `tzbucket bucket` defaults: UTC day buckets of epoch-millisecond
+    /// timestamps read from stdin.
+    fn default() -> Self {
+        parse_defaults(&[])
+    }
+}
+
+impl BucketArgs {
+    pub fn with_tz(mut self, tz: Tz) -> Self {
+        self.tz = vec![tz];
+        self
+    }
+
+    /// Bucket in the zone at `lat`/`lon` degrees (like `--lat` and `--lon`).
+    pub fn with_location(mut self, lat: f64, lon: f64) -> Self {
+        self.lat = Some(lat);
+        self.lon = Some(lon);
+        self
+    }
+
+    /// Bucket in each of `zones`, one record per zone (like repeating `--tz`).
+    pub fn with_zones(mut self, zones: &[Tz]) -> Self {
+        self.tz = zones.to_vec();
+        self
+    }
+
+    pub fn with_interval(mut self, interval: Interval) -> Self {
+        self.interval = vec![interval.into()];
+        self
+    }
+
+    /// Start days at `time` instead of midnight, resolving boundaries at
+    /// that time with `policy`.
+    pub fn with_day_offset(mut self, time: NaiveTime, policy: Policy) -> Self {
+        self.day_offset = Some(time);
+        self.policy_nonexistent = policy.nonexistent;
+        self.policy_ambiguous = policy.ambiguous;
+        self
+    }
+
+    pub fn with_week_start(mut self, week_start: WeekStart) -> Self {
+        self.week_start = week_start;
+        self
+    }
+
+    pub fn with_week_key(mut self, week_key: WeekKeyFormat) -> Self {
+        self.week_key = week_key;
+        self
+    }
+
+    pub fn with_week_scheme(mut self, week_scheme: WeekScheme) -> Self {
+        self.week_scheme = Some(week_scheme);
+        self
+    }
+
+    pub fn with_format(mut self, format: TimestampFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Read timestamps from this file instead of stdin.
+    pub fn with_input(mut self, path: impl Into<String>) -> Self {
+        self.input = path.into();
+        self
+    }
+
+    pub fn with_label(mut self, label: LabelPoint) -> Self {
+        self.label = Some(label);
+        self
+    }
+
+    pub fn with_schema_version(mut self, version: OutputSchemaVersion) -> Self {
+        self.schema_version = version;
+        self
+    }
+
+    pub fn with_output_format(mut self, output_format: OutputFormat) -> Self {
+        self.output_format = output_format;
+        self
+    }
+
+    /// The `--policy-nonexistent` and `--policy-ambiguous` for `--day-offset`.
+    pub fn policy(&self) -> Policy {
+        Policy {
+            nonexistent: self.policy_nonexistent,
+            ambiguous: self.policy_ambiguous,
+        }
+    }
+}
+
+#[derive(clap::Args, Debug)]
+pub struct RangeArgs {
+    /// IANA timezone
+    #[arg(short, long, value_parser = parse_tz_or_input_error)]
+    pub tz: Tz,
+
+    /// Reject legacy zone names such as US/Eastern instead of warning about them
+    #[arg(long)]
+    pub strict_tz: bool,
+
+    /// Bucket interval: `<N>m`, hour, `<N>h`, day, `<N>d:<anchor>`, week, month, quarter, `fiscal_quarter:<start month 1-12>`, year, `trailing_month:<1-31>[:roll_forward]`, `fixed:<seconds>`
+    #[arg(short = 'i', long, default_value = "day", value_parser = parse_interval_arg)]
+    pub interval: IntervalArg,
+
+    /// Start date of one period for `<N>d` intervals (e.g. --interval 14d --anchor 2026-01-05)
+    #[arg(long, value_parser = parse_anchor)]
+    pub anchor: Option<NaiveDate>,
+
+    /// Start days at this local time instead of midnight (`HH:MM[:SS]`, e.g. 06:00 for an operational day running 06:00-06:00)
+    #[arg(long, value_parser = parse_day_offset_time)]
+    pub day_offset: Option<NaiveTime>,
+
+    /// Policy for a --day-offset boundary skipped by DST: error, shift_forward
+    #[arg(long, default_value = "error", requires = "day_offset", value_parser = parse_nonexistent_policy)]
+    pub policy_nonexistent: NonexistentPolicy,
+
+    /// Policy for a --day-offset boundary repeated by DST: error, first, second
+    #[arg(long, default_value = "error", requires = "day_offset", value_parser = parse_ambiguous_policy)]
+    pub policy_ambiguous: AmbiguousPolicy,
+
+    /// Week start day
+    #[arg(long, default_value = "monday", value_parser = parse_week_start)]
+    pub week_start: WeekStart,
+
+    /// Week bucket key format: start_date (YYYY-MM-DD), or YYYY-Www numbered by iso, mmwr or broadcast weeks
+    #[arg(long, default_value = "start_date", value_parser = parse_week_key)]
+    pub week_key: WeekKeyFormat,
+
+    /// Week-numbering scheme, setting both --week-start and --week-key: iso, mmwr (CDC epidemiological weeks, from Sunday), broadcast
+    #[arg(long, conflicts_with_all = ["week_start", "week_key"], value_parser = parse_week_scheme)]
+    pub week_scheme: Option<WeekScheme>,
+
+    /// Start of range (inclusive, RFC3339; with the humantime feature also e.g. "2 weeks ago")
+    #[arg(long, value_parser = parse_range_bound)]
+    pub start: RangeBound,
+
+    /// End of range (exclusive, RFC3339; with the humantime feature also e.g. "today")
+    #[arg(long, value_parser = parse_range_bound)]
+    pub end: RangeBound,
+
+    /// Output format: json, text
+    #[arg(long, default_value = "json", value_parser = parse_output_format)]
+    pub output_format: OutputFormat,
+
+    /// Print a plan (bucket count, first/last boundary, output size) instead of the buckets
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Add iso_week, iso_week_year and us_week_number to week buckets
+    #[arg(long)]
+    pub week_numbers: bool,
+
+    /// Add a label_ts field at the bucket start, end, or midpoint
+    #[arg(long, value_parser = parse_label_point)]
+    pub label: Option<LabelPoint>,
+
+    /// Add is_dst_transition, start_offset_seconds and end_offset_seconds to each bucket
+    #[arg(long)]
+    pub dst_info: bool,
+
+    /// Render bucket ends as the last millisecond inside the bucket (closed intervals)
+    #[arg(long)]
+    pub inclusive_end: bool,
+
+    /// JSON output schema version: 1 (default, unversioned shape) or 2 (with schema_version and newer fields)
+    #[arg(long, default_value = "1", value_parser = parse_schema_version)]
+    pub schema_version: OutputSchemaVersion,
+}
+
+impl Default for RangeArgs {
+    /// The `tzbucket range` defaults. `tz`, `start` and `end` are required on
+    /// the command line and start out as UTC and an empty range at the Unix
+    /// epoch here.
+    fn default() -> Self {
+        parse_defaults(&["--tz", "UTC", "--start", EPOCH, "--end", EPOCH])
+    }
+}
+
+impl RangeArgs {
+    pub fn with_tz(mut self, tz: Tz) -> Self {
+        self.tz = tz;
+        self
+    }
+
+    pub fn with_interval(mut self, interval: Interval) -> Self {
+        self.interval = interval.into();
+        self
+    }
+
+    /// Start days at `time` instead of midnight, resolving boundaries at
+    /// that time with `policy`.
+    pub fn with_day_offset(mut self, time: NaiveTime, policy: Policy) -> Self {
+        self.day_offset = Some(time);
+        self.policy_nonexistent = policy.nonexistent;
+        self.policy_ambiguous = policy.ambiguous;
+        self
+    }
+
+    pub fn with_week_start(mut self, week_start: WeekStart) -> Self {
+        self.week_start = week_start;
+        self
+    }
+
+    pub fn with_week_key(mut self, week_key: WeekKeyFormat) -> Self {
+        self.week_key = week_key;
+        self
+    }
+
+    pub fn with_week_scheme(mut self, week_scheme: WeekScheme) -> Self {
+        self.week_scheme = Some(week_scheme);
+        self
+    }
+
+    pub fn with_start(mut self, start: DateTime<Utc>) -> Self {
+        self.start = start.into();
+        self
+    }
+
+    pub fn with_end(mut self, end: DateTime<Utc>) -> Self {
+        self.end = end.into();
+        self
+    }
+
+    pub fn with_label(mut self, label: LabelPoint) -> Self {
+        self.label = Some(label);
+        self
+    }
+
+    pub fn with_schema_version(mut self, version: OutputSchemaVersion) -> Self {
+        self.schema_version = version;
+        self
+    }
+
+    pub fn with_output_format(mut self, output_format: OutputFormat) -> Self {
+        self.output_format = output_format;
+        self
+    }
+
+    /// The `--policy-nonexistent` and `--policy-ambiguous` for `--day-offset`.
+    pub fn policy(&self) -> Policy {
+        Policy {
+            nonexistent: self.policy_nonexistent,
+            ambiguous: self.policy_ambiguous,
+        }
+    }
+}
+
+#[derive(clap::Args, Debug)]
+pub struct ExplainArgs {
+    /// IANA timezone
+    #[arg(short, long, value_parser = parse_tz_or_input_error)]
+    pub tz: Tz,
+
+    /// Local time string (without offset, e.g., 2026-03-29T02:30:00)
+    #[arg(long, value_parser = parse_local_time)]
+    pub local: NaiveDateTime,
+
+    /// Policy for nonexistent times: error, shift_forward
+    #[arg(long, default_value = "error", value_parser = parse_nonexistent_policy)]
+    pub policy_nonexistent: NonexistentPolicy,
+
+    /// Policy for ambiguous times: error, first, second
+    #[arg(long, default_value = "error", value_parser = parse_ambiguous_policy)]
+    pub policy_ambiguous: AmbiguousPolicy,
+
+    /// Also report the bucket of the resolved instant: `<N>m`, hour, `<N>h`, day, `<N>d:<anchor>`, week, month, quarter, `fiscal_quarter:<start month 1-12>`, year, `trailing_month:<1-31>[:roll_forward]`, `fixed:<seconds>`
+    #[arg(short = 'i', long, value_parser = parse_interval)]
+    pub interval: Option<Interval>,
+
+    /// Week start day (with --interval week)
+    #[arg(long, default_value = "monday", value_parser = parse_week_start)]
+    pub week_start: WeekStart,
+
+    /// Output format: json, text
+    #[arg(long, default_value = "json", value_parser = parse_output_format)]
+    pub output_format: OutputFormat,
+
+    /// JSON output schema version: 1 (default, unversioned shape) or 2 (with schema_version and newer fields)
+    #[arg(long, default_value = "1", value_parser = parse_schema_version)]
+    pub schema_version: OutputSchemaVersion,
+}
+
+impl Default for ExplainArgs {
+    /// The `tzbucket explain` defaults. `tz` and `local` are required on the
+    /// command line and start out as UTC and the Unix epoch here.
+    fn default() -> Self {
+        parse_defaults(&["--tz", "UTC", "--local", &EPOCH[..19]])
+    }
+}
+
+impl ExplainArgs {
+    pub fn with_tz(mut self, tz: Tz) -> Self {
+        self.tz = tz;
+        self
+    }
+
+    pub fn with_local(mut self, local: NaiveDateTime) -> Self {
+        self.local = local;
+        self
+    }
+
+    pub fn with_policy(mut self, policy: Policy) -> Self {
+        self.policy_nonexistent = policy.nonexistent;
+        self.policy_ambiguous = policy.ambiguous;
+        self
+    }
+
+    pub fn with_interval(mut self, interval: Interval) -> Self {
+        self.interval = Some(interval);
+        self
+    }
+
+    pub fn with_week_start(mut self, week_start: WeekStart) -> Self {
+        self.week_start = week_start;
+        self
+    }
+
+    pub fn with_schema_version(mut self, version: OutputSchemaVersion) -> Self {
+        self.schema_version = version;
+        self
+    }
+
+    pub fn with_output_format(mut self, output_format: OutputFormat) -> Self {
+        self.output_format = output_format;
+        self
+    }
+
+    /// The `--policy-nonexistent` and `--policy-ambiguous`.
+    pub fn policy(&self) -> Policy {
+        Policy {
+            nonexistent: self.policy_nonexistent,
+            ambiguous: self.policy_ambiguous,
+        }
+    }
+}
+
+/// The Unix epoch, standing in for required instants in `Default`.
+const EPOCH: &str = "1970-01-01T00:00:00Z";
+
+/// Arguments parsed from `args` alone, so that `Default` takes the values of
+/// the `default_value`s above.
+fn parse_defaults<A: clap::Args + clap::FromArgMatches>(args: &[&str]) -> A {
+    let command = A::augment_args(clap::Command::new("tzbucket"));
+    command
+        .try_get_matches_from(std::iter::once("tzbucket").chain(args.iter().copied()))
+        .and_then(|matches| A::from_arg_matches(&matches))
+        .expect("default arguments parse")
+}
+
+#[derive(clap::Args, Debug)]
+pub struct NowArgs {
+    /// IANA timezone
     #[arg(short, long, default_value = "UTC")]
     pub tz: String,
 
-    /// Bucket interval: day, week, month
+    /// Bucket interval: `<N>m`, hour, `<N>h`, day, `<N>d:<anchor>`, week, month, quarter, `fiscal_quarter:<start month 1-12>`, year, `trailing_month:<1-31>[:roll_forward]`, `fixed:<seconds>`
+    #[arg(short = 'i', long, default_value = "day")]
+    pub interval: String,
+
+    /// Start date of one period for `<N>d` intervals (e.g. --interval 14d --anchor 2026-01-05)
+    #[arg(long)]
+    pub anchor: Option<String>,
+
+    /// Week start day: monday or sunday (for week interval)
+    #[arg(long, default_value = "monday")]
+    pub week_start: String,
+
+    /// Week bucket key format: start_date (YYYY-MM-DD), or YYYY-Www numbered by iso, mmwr or broadcast weeks
+    #[arg(long, default_value = "start_date")]
+    pub week_key: String,
+
+    /// Week-numbering scheme, setting both --week-start and --week-key: iso, mmwr (CDC epidemiological weeks, from Sunday), broadcast
+    #[arg(long, conflicts_with_all = ["week_start", "week_key"])]
+    pub week_scheme: Option<String>,
+
+    /// Output format: json, text
+    #[arg(long, default_value = "text")]
+    pub output_format: String,
+
+    /// JSON output schema version: 1 (default, unversioned shape) or 2 (with schema_version and newer fields)
+    #[arg(long, default_value = "1")]
+    pub schema_version: String,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct KeyArgs {
+    /// IANA timezone
+    #[arg(short, long)]
+    pub tz: String,
+
+    /// Bucket interval: `<N>m`, hour, `<N>h`, day, `<N>d:<anchor>`, week, month, quarter, `fiscal_quarter:<start month 1-12>`, year, `trailing_month:<1-31>[:roll_forward]`, `fixed:<seconds>`
+    #[arg(short = 'i', long, default_value = "day")]
+    pub interval: String,
+
+    /// Week start day: monday or sunday (for week interval)
+    #[arg(long, default_value = "monday")]
+    pub week_start: String,
+
+    /// Bucket keys (e.g. 2026-03 for month buckets); a single - reads keys from stdin, one per line
+    #[arg(required = true)]
+    pub keys: Vec<String>,
+
+    /// Output format: json, text
+    #[arg(long, default_value = "json")]
+    pub output_format: String,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct ShiftArgs {
+    /// IANA timezone
+    #[arg(short, long)]
+    pub tz: String,
+
+    /// Bucket interval: `<N>m`, hour, `<N>h`, day, `<N>d:<anchor>`, week, month, quarter, `fiscal_quarter:<start month 1-12>`, year, `trailing_month:<1-31>[:roll_forward]`, `fixed:<seconds>`
+    #[arg(short = 'i', long, default_value = "day")]
+    pub interval: String,
+
+    /// Week start day: monday or sunday (for week interval)
+    #[arg(long, default_value = "monday")]
+    pub week_start: String,
+
+    /// Bucket key to start from (e.g. 2026-03-29 for day buckets)
+    #[arg(long, required_unless_present = "at")]
+    pub key: Option<String>,
+
+    /// Start from the bucket containing this instant (RFC3339) instead of --key
+    #[arg(long, conflicts_with = "key")]
+    pub at: Option<String>,
+
+    /// Number of intervals to move by (negative moves back)
+    #[arg(short = 'n', long, allow_negative_numbers = true)]
+    pub by: i64,
+
+    /// Output format: json, text
+    #[arg(long, default_value = "json")]
+    pub output_format: String,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct SpanArgs {
+    /// IANA timezone
+    #[arg(short, long)]
+    pub tz: String,
+
+    /// Bucket interval: `<N>m`, hour, `<N>h`, day, `<N>d:<anchor>`, week, month, quarter, `fiscal_quarter:<start month 1-12>`, year, `trailing_month:<1-31>[:roll_forward]`, `fixed:<seconds>`
     #[arg(short = 'i', long, default_value = "day")]
     pub interval: String,
 
@@ -33,30 +734,278 @@ pub struct BucketArgs {
     #[arg(long, default_value = "monday")]
     pub week_start: String,
 
+    /// Bucket key to count from
+    #[arg(long, required_unless_present = "from_at")]
+    pub from_key: Option<String>,
+
+    /// Bucket key to count to (earlier than --from-key gives a negative count)
+    #[arg(long, required_unless_present = "to_at")]
+    pub to_key: Option<String>,
+
+    /// Count from the bucket containing this instant (RFC3339) instead of --from-key
+    #[arg(long, conflicts_with = "from_key")]
+    pub from_at: Option<String>,
+
+    /// Count to the bucket containing this instant (RFC3339) instead of --to-key
+    #[arg(long, conflicts_with = "to_key")]
+    pub to_at: Option<String>,
+
+    /// Output format: json, text
+    #[arg(long, default_value = "json")]
+    pub output_format: String,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct KeysArgs {
+    /// IANA timezone (only hour keys depend on it)
+    #[arg(short, long, default_value = "UTC")]
+    pub tz: String,
+
+    /// Bucket interval: `<N>m`, hour, `<N>h`, day, `<N>d:<anchor>`, week, month, quarter, `fiscal_quarter:<start month 1-12>`, year, `trailing_month:<1-31>[:roll_forward]`, `fixed:<seconds>`
+    #[arg(short = 'i', long, default_value = "day")]
+    pub interval: String,
+
+    /// Week start day: monday or sunday (for week interval)
+    #[arg(long, default_value = "monday")]
+    pub week_start: String,
+
+    /// First bucket key (inclusive)
+    #[arg(long)]
+    pub from: String,
+
+    /// Last bucket key (inclusive)
+    #[arg(long)]
+    pub to: String,
+
+    /// Output format: text (one key per line), json
+    #[arg(long, default_value = "text")]
+    pub output_format: String,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct ResolveArgs {
+    /// IANA timezone the local timestamps are in
+    #[arg(short, long)]
+    pub tz: String,
+
+    /// Policy for nonexistent times: error, shift_forward
+    #[arg(long, default_value = "error")]
+    pub policy_nonexistent: String,
+
+    /// Policy for ambiguous times: error, first, second
+    #[arg(long, default_value = "error")]
+    pub policy_ambiguous: String,
+
+    /// Output format: json (NDJSON), csv, text
+    #[arg(long, default_value = "json")]
+    pub output_format: String,
+
+    /// Input file path (use - for stdin)
+    #[arg(long, default_value = "-")]
+    pub input: String,
+
+    /// Skip lines that fail to parse or resolve, reporting each as an NDJSON record to stderr, null (discard) or a file path, instead of aborting
+    #[arg(long, value_name = "stderr|null|PATH")]
+    pub errors_to: Option<String>,
+
+    /// Convert each resolved instant back to local time and add a `roundtrip` audit field
+    #[arg(long)]
+    pub verify_roundtrip: bool,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct CountArgs {
+    /// IANA timezone (e.g., Europe/Berlin)
+    #[arg(short, long, default_value = "UTC")]
+    pub tz: String,
+
+    /// Bucket interval: `<N>m`, hour, `<N>h`, day, `<N>d:<anchor>`, week, month, quarter, `fiscal_quarter:<start month 1-12>`, year, `trailing_month:<1-31>[:roll_forward]`, `fixed:<seconds>`
+    #[arg(short = 'i', long, default_value = "day")]
+    pub interval: String,
+
+    /// Week start day: monday or sunday (for week interval and calendar output)
+    #[arg(long, default_value = "monday")]
+    pub week_start: String,
+
     /// Input format: epoch_ms, epoch_s, rfc3339
     #[arg(short = 'f', long, default_value = "epoch_ms")]
     pub format: String,
 
+    /// Input line format: plain, `csv:<column name or 1-based index>`, `ndjson:<field>`, `regex:<pattern>` (group `ts` or the first group)
+    #[arg(long, default_value = "plain")]
+    pub input_mode: String,
+
+    /// Output format: json, text, calendar (day interval only), vega (Vega-Lite spec)
+    #[arg(long, default_value = "json")]
+    pub output_format: String,
+
+    /// Input file path (use - for stdin)
+    #[arg(long, default_value = "-")]
+    pub input: String,
+
+    /// Skip lines that fail to parse or resolve, reporting each as an NDJSON record to stderr, null (discard) or a file path, instead of aborting
+    #[arg(long, value_name = "stderr|null|PATH")]
+    pub errors_to: Option<String>,
+
+    /// End text output with totals: lines, distinct and DST-affected buckets, earliest/latest instant
+    #[arg(long)]
+    pub summary: bool,
+
+    /// Add the first and last timestamp seen in each bucket (UTC and local; json and text output)
+    #[arg(long)]
+    pub first_last: bool,
+
+    /// Check the buckets from the first to the last event against a roster file of entities (one per line) expected in each, reporting every bucket/entity pair with too few events (exit code 3 if any)
+    #[arg(
+        long,
+        value_name = "ROSTER",
+        requires = "entity_field",
+        conflicts_with = "first_last"
+    )]
+    pub completeness: Option<String>,
+
+    /// Input field naming the entity of each event (an --input-mode csv, ndjson or regex field)
+    #[arg(long, requires = "completeness")]
+    pub entity_field: Option<String>,
+
+    /// Events expected per entity and bucket for --completeness
+    #[arg(long, default_value_t = 1, requires = "completeness")]
+    pub expected: u64,
+
+    /// Add a `type: transition` record after the bucket of every DST transition between the earliest and latest timestamp (json and text output)
+    #[arg(long, conflicts_with = "completeness")]
+    pub annotate_dst: bool,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct DiffOutputArgs {
+    /// Previous bucket output (NDJSON from `bucket --output-format json`)
+    pub old: String,
+
+    /// New bucket output to compare against
+    pub new: String,
+
     /// Output format: json, text
     #[arg(long, default_value = "text")]
     pub output_format: String,
+}
 
-    /// Input file path (use - for stdin)
+#[derive(clap::Args, Debug)]
+pub struct MatrixArgs {
+    /// File listing IANA timezones, one per line (# comments allowed; use - for stdin)
+    #[arg(long)]
+    pub tz_list: String,
+
+    /// Instant to show (RFC3339); defaults to the current time
+    #[arg(long)]
+    pub at: Option<String>,
+
+    /// Bucket interval: `<N>m`, hour, `<N>h`, day, `<N>d:<anchor>`, week, month, quarter, `fiscal_quarter:<start month 1-12>`, year, `trailing_month:<1-31>[:roll_forward]`, `fixed:<seconds>`
+    #[arg(short = 'i', long, default_value = "day")]
+    pub interval: String,
+
+    /// Week start day: monday or sunday (for week interval)
+    #[arg(long, default_value = "monday")]
+    pub week_start: String,
+
+    /// Output format: json, text
+    #[arg(long, default_value = "text")]
+    pub output_format: String,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct OffsetsArgs {
+    /// IANA timezone
+    #[arg(short, long)]
+    pub tz: String,
+
+    /// Start of the history: a year (from January 1, UTC) or an RFC3339 instant (inclusive)
+    #[arg(long)]
+    pub from: String,
+
+    /// End of the history: a year (through December 31, UTC) or an RFC3339 instant (exclusive)
+    #[arg(long)]
+    pub to: String,
+
+    /// Output format: json (NDJSON), csv, text
+    #[arg(long, default_value = "json")]
+    pub output_format: String,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct SameRulesArgs {
+    /// First IANA timezone
+    pub first: String,
+
+    /// Second IANA timezone
+    pub second: String,
+
+    /// Start of the range: a year (from January 1, UTC) or an RFC3339 instant (inclusive)
+    #[arg(long)]
+    pub from: String,
+
+    /// End of the range: a year (through December 31, UTC) or an RFC3339 instant (exclusive)
+    #[arg(long)]
+    pub to: String,
+
+    /// Output format: json, text
+    #[arg(long, default_value = "text")]
+    pub output_format: String,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct RollupArgs {
+    /// IANA timezone
+    #[arg(short, long, default_value = "UTC")]
+    pub tz: String,
+
+    /// Interval of the input buckets
+    #[arg(short = 'i', long, default_value = "day")]
+    pub interval: String,
+
+    /// Coarser intervals to roll up into, comma-separated (e.g. week,month)
+    #[arg(long)]
+    pub to: String,
+
+    /// Week start day
+    #[arg(long, default_value = "monday")]
+    pub week_start: String,
+
+    /// Per-bucket counts: CSV with header key,count or `count` JSON output (use - for stdin)
     #[arg(long, default_value = "-")]
     pub input: String,
 
-    /// Read from stdin
+    /// Parent of an input bucket that crosses a target boundary: error, start, end, majority, split
+    #[arg(long, default_value = "error")]
+    pub attribution: String,
+
+    /// Output format: json, text
+    #[arg(long, default_value = "json")]
+    pub output_format: String,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct PlanArgs {
+    /// Per-bucket row counts (CSV with header: start_utc,end_utc,count and optional key)
     #[arg(long)]
-    pub stdin: bool,
+    pub counts: String,
+
+    /// Target number of rows per partition
+    #[arg(long)]
+    pub target_rows_per_partition: u64,
+
+    /// Output format: json, text
+    #[arg(long, default_value = "json")]
+    pub output_format: String,
 }
 
 #[derive(clap::Args, Debug)]
-pub struct RangeArgs {
+pub struct ReportArgs {
     /// IANA timezone
     #[arg(short, long)]
     pub tz: String,
 
-    /// Bucket interval: day, week, month
+    /// Bucket interval: `<N>m`, hour, `<N>h`, day, `<N>d:<anchor>`, week, month, quarter, `fiscal_quarter:<start month 1-12>`, year, `trailing_month:<1-31>[:roll_forward]`, `fixed:<seconds>`
     #[arg(short = 'i', long, default_value = "day")]
     pub interval: String,
 
@@ -72,30 +1021,145 @@ pub struct RangeArgs {
     #[arg(long)]
     pub end: String,
 
-    /// Output format: json, text
-    #[arg(long, default_value = "json")]
-    pub output_format: String,
+    /// Timestamps to count per bucket (use - for stdin)
+    #[arg(long)]
+    pub input: Option<String>,
+
+    /// Input format: epoch_ms, epoch_s, rfc3339
+    #[arg(short = 'f', long, default_value = "epoch_ms")]
+    pub format: String,
+
+    /// Input line format: plain, `csv:<column name or 1-based index>`, `ndjson:<field>`, `regex:<pattern>` (group `ts` or the first group)
+    #[arg(long, default_value = "plain")]
+    pub input_mode: String,
 }
 
 #[derive(clap::Args, Debug)]
-pub struct ExplainArgs {
+pub struct BuildIndexArgs {
     /// IANA timezone
     #[arg(short, long)]
     pub tz: String,
 
-    /// Local time string (without offset, e.g., 2026-03-29T02:30:00)
+    /// Bucket interval: `<N>m`, hour, `<N>h`, day, `<N>d:<anchor>`, week, month, quarter, `fiscal_quarter:<start month 1-12>`, year, `trailing_month:<1-31>[:roll_forward]`, `fixed:<seconds>`
+    #[arg(short = 'i', long, default_value = "day")]
+    pub interval: String,
+
+    /// Week start day
+    #[arg(long, default_value = "monday")]
+    pub week_start: String,
+
+    /// Start of range (inclusive, RFC3339)
     #[arg(long)]
-    pub local: String,
+    pub start: String,
 
-    /// Policy for nonexistent times: error, shift_forward
-    #[arg(long, default_value = "error")]
-    pub policy_nonexistent: String,
+    /// End of range (exclusive, RFC3339)
+    #[arg(long)]
+    pub end: String,
 
-    /// Policy for ambiguous times: error, first, second
-    #[arg(long, default_value = "error")]
-    pub policy_ambiguous: String,
+    /// Index file format: binary (fast to load for `bucket --index`) or json (readable, slower to load)
+    #[arg(long, default_value = "binary")]
+    pub index_format: String,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct TzImpactArgs {
+    /// File with one IANA zone name per line (`#` starts a comment)
+    #[arg(long)]
+    pub tz_list: String,
+
+    /// Directory containing one compiled TZif tree per version (e.g., 2024a/, 2025b/)
+    #[arg(long)]
+    pub tzdata_dir: String,
+
+    /// Baseline tzdata version (subdirectory of --tzdata-dir)
+    #[arg(long)]
+    pub from_version: String,
+
+    /// Candidate tzdata version (subdirectory of --tzdata-dir)
+    #[arg(long)]
+    pub to_version: String,
+
+    /// Start of analyzed range (inclusive, RFC3339)
+    #[arg(long)]
+    pub start: String,
+
+    /// End of analyzed range (exclusive, RFC3339)
+    #[arg(long)]
+    pub end: String,
+
+    /// Bucket interval: `<N>m`, hour, `<N>h`, day, `<N>d:<anchor>`, week, month, quarter, `fiscal_quarter:<start month 1-12>`, year, `trailing_month:<1-31>[:roll_forward]`
+    #[arg(short = 'i', long, default_value = "day")]
+    pub interval: String,
+
+    /// Week start day
+    #[arg(long, default_value = "monday")]
+    pub week_start: String,
 
     /// Output format: json, text
-    #[arg(long, default_value = "json")]
+    #[arg(long, default_value = "text")]
     pub output_format: String,
 }
+
+#[derive(clap::Args, Debug)]
+pub struct VectorsArgs {
+    /// Print the JSON Schema of the vector file instead of the vectors
+    #[arg(long)]
+    pub schema: bool,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct SelftestArgs {
+    /// Output format: json, text
+    #[arg(long, default_value = "text")]
+    pub output_format: String,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct DifftestArgs {
+    /// Number of random (instant, zone) samples; each runs `date` once
+    #[arg(long, default_value_t = 100)]
+    pub count: usize,
+
+    /// Seed of the sampler, to reproduce an earlier run (default: from the clock, printed in the report)
+    #[arg(long)]
+    pub seed: Option<u64>,
+
+    /// Sample only these IANA zones (repeatable); default: every zone present in --zoneinfo-dir
+    #[arg(short, long)]
+    pub tz: Vec<String>,
+
+    /// Bucket interval of a day or longer: day, `<N>d:<anchor>`, week, month, quarter, `fiscal_quarter:<start month 1-12>`, year, `trailing_month:<1-31>[:roll_forward]`
+    #[arg(short = 'i', long, default_value = "day")]
+    pub interval: String,
+
+    /// Week start day
+    #[arg(long, default_value = "monday")]
+    pub week_start: String,
+
+    /// Start of the sampled range: a year (from January 1, UTC) or an RFC3339 instant (inclusive)
+    #[arg(long, default_value = "1970")]
+    pub from: String,
+
+    /// End of the sampled range: a year (through December 31, UTC) or an RFC3339 instant (exclusive)
+    #[arg(long, default_value = "2037")]
+    pub to: String,
+
+    /// GNU date executable to compare against
+    #[arg(long, default_value = "date")]
+    pub date_command: String,
+
+    /// Compiled tzdata read by `date` (passed as TZDIR); zones missing here are not sampled
+    #[arg(long, default_value = "/usr/share/zoneinfo")]
+    pub zoneinfo_dir: String,
+
+    /// Output format: json, text
+    #[arg(long, default_value = "text")]
+    pub output_format: String,
+}
+
+#[cfg(feature = "schemars")]
+#[derive(clap::Args, Debug)]
+pub struct SchemaArgs {
+    /// Output type: bucket, range, explain, error, skipped, transition
+    pub output_type: String,
+}
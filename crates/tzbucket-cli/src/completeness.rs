@@ -0,0 +1,156 @@
+//! Completeness check for `count --completeness`.
+//!
+//! A roster file lists the entities (devices, sensors, ...) expected to
+//! report in every bucket. [`Completeness`] counts events per bucket and
+//! entity, and [`Completeness::report`] lists every bucket/entity pair with
+//! fewer events than expected, over all buckets from the first event's to the
+//! last event's.
+
+use std::collections::{HashMap, HashSet};
+
+use serde::Serialize;
+use tzbucket_core::{Bucket, BucketKey};
+
+use crate::error::{CliError, CliResult};
+use crate::input::{RawRecord, open_input};
+
+/// Events per bucket and roster entity.
+pub struct Completeness {
+    roster: Vec<String>,
+    entity_field: String,
+    expected: u64,
+    /// Keyed by bucket `start_utc`, then by roster index.
+    observed: HashMap<String, HashMap<usize, u64>>,
+    index: HashMap<String, usize>,
+}
+
+impl Completeness {
+    /// Read the roster at `path`: one entity per line; blank lines and
+    /// lines starting with `#` are ignored.
+    pub fn load(path: &str, entity_field: &str, expected: u64) -> CliResult<Self> {
+        if expected == 0 {
+            return Err(CliError::input(
+                "Invalid expected count '0'. Expected: a positive number of events per bucket",
+            ));
+        }
+        let mut roster = Vec::new();
+        let mut seen = HashSet::new();
+        let mut lines = open_input(path)?;
+        while let Some(line) = lines.next_line()? {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if seen.insert(line.to_string()) {
+                roster.push(line.to_string());
+            }
+        }
+        if roster.is_empty() {
+            return Err(CliError::input(format!(
+                "Roster '{}' lists no entities",
+                path
+            )));
+        }
+        let index = roster
+            .iter()
+            .enumerate()
+            .map(|(i, entity)| (entity.clone(), i))
+            .collect();
+
+        Ok(Self {
+            roster,
+            entity_field: entity_field.to_string(),
+            expected,
+            observed: HashMap::new(),
+            index,
+        })
+    }
+
+    /// The entity of an input record, from its `--entity-field`.
+    pub fn entity<'a>(&self, record: &'a RawRecord) -> CliResult<&'a str> {
+        record
+            .extra_fields
+            .iter()
+            .find(|(name, _)| *name == self.entity_field)
+            .map(|(_, value)| value.trim())
+            .ok_or_else(|| {
+                CliError::input(format!(
+                    "Input line with timestamp '{}' has no field '{}'",
+                    record.ts_str, self.entity_field
+                ))
+            })
+    }
+
+    /// Count one event of `entity` in `bucket`. Entities missing from the
+    /// roster are not checked and not counted.
+    pub fn record(&mut self, bucket: &Bucket, entity: &str) {
+        if let Some(&i) = self.index.get(entity) {
+            *self
+                .observed
+                .entry(bucket.start_utc.clone())
+                .or_default()
+                .entry(i)
+                .or_default() += 1;
+        }
+    }
+
+    /// Check every bucket of `buckets` (in order) against the roster.
+    pub fn report(&self, buckets: &[Bucket]) -> CompletenessReport {
+        let mut missing = Vec::new();
+        for bucket in buckets {
+            let observed = self.observed.get(&bucket.start_utc);
+            for (i, entity) in self.roster.iter().enumerate() {
+                let count = observed
+                    .and_then(|counts| counts.get(&i))
+                    .copied()
+                    .unwrap_or(0);
+                if count < self.expected {
+                    missing.push(Gap {
+                        key: bucket.key.clone(),
+                        start_local: bucket.start_local.clone(),
+                        end_local: bucket.end_local.clone(),
+                        start_utc: bucket.start_utc.clone(),
+                        end_utc: bucket.end_utc.clone(),
+                        entity: entity.clone(),
+                        count,
+                        expected: self.expected,
+                    });
+                }
+            }
+        }
+
+        CompletenessReport {
+            buckets: buckets.len(),
+            entities: self.roster.len(),
+            expected: self.expected,
+            incomplete: missing.len(),
+            missing,
+        }
+    }
+}
+
+/// Result of a completeness check.
+#[derive(Debug, Serialize)]
+pub struct CompletenessReport {
+    /// Buckets checked.
+    pub buckets: usize,
+    /// Entities in the roster.
+    pub entities: usize,
+    /// Events expected per bucket and entity.
+    pub expected: u64,
+    /// Bucket/entity pairs with fewer events than expected.
+    pub incomplete: usize,
+    pub missing: Vec<Gap>,
+}
+
+/// A bucket/entity pair with fewer events than expected.
+#[derive(Debug, Serialize)]
+pub struct Gap {
+    pub key: BucketKey,
+    pub start_local: String,
+    pub end_local: String,
+    pub start_utc: String,
+    pub end_utc: String,
+    pub entity: String,
+    pub count: u64,
+    pub expected: u64,
+}
@@ -0,0 +1,471 @@
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::process::ExitCode;
+
+use chrono::{DateTime, Datelike, NaiveDate, SecondsFormat, Utc};
+use chrono_tz::Tz;
+use serde::Serialize;
+use serde_json::{Value, json};
+use tzbucket_core::{Bucket, BucketKey, Interval, WeekStart, compute_bucket, parse_timestamp};
+
+use crate::annotate::{DstAnnotations, TransitionRecord};
+use crate::cli::CountArgs;
+use crate::completeness::{Completeness, CompletenessReport};
+use crate::error::{CliError, CliResult, EXIT_RUNTIME_ERROR, EXIT_SUCCESS};
+use crate::input::{open_input, parse_input_mode};
+use crate::range_cmd::generate_buckets_in_range;
+use crate::shared::{
+    parse_format, parse_interval, parse_rfc3339_to_utc, parse_tz_or_input_error, parse_week_start,
+    write_error,
+};
+use crate::skipped::LineErrors;
+use crate::style::Style;
+use crate::summary::Summary;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CountFormat {
+    Json,
+    Text,
+    Calendar,
+    Vega,
+}
+
+pub fn run_count(args: CountArgs, mut out: impl Write) -> CliResult<ExitCode> {
+    let format = parse_count_format(&args.output_format)?;
+    let tz = parse_tz_or_input_error(&args.tz)?;
+    let interval = parse_interval(&args.interval)?;
+    let week_start = parse_week_start(&args.week_start)?;
+    let timestamp_format = parse_format(&args.format)?;
+
+    if format == CountFormat::Calendar && interval != Interval::Day {
+        return Err(CliError::input(format!(
+            "Invalid interval '{}' for calendar output. Expected: day",
+            args.interval
+        )));
+    }
+    if args.summary && format != CountFormat::Text {
+        return Err(CliError::input("--summary requires --output-format text"));
+    }
+    if args.first_last && !matches!(format, CountFormat::Json | CountFormat::Text) {
+        return Err(CliError::input(
+            "--first-last requires --output-format json or text",
+        ));
+    }
+    if args.annotate_dst && !matches!(format, CountFormat::Json | CountFormat::Text) {
+        return Err(CliError::input(
+            "--annotate-dst requires --output-format json or text",
+        ));
+    }
+    if args.completeness.is_some() && !matches!(format, CountFormat::Json | CountFormat::Text) {
+        return Err(CliError::input(
+            "--completeness requires --output-format json or text",
+        ));
+    }
+    let mut summary = args.summary.then(|| Summary::new(tz));
+    let mut completeness = match (&args.completeness, &args.entity_field) {
+        (Some(roster), Some(field)) => Some(Completeness::load(roster, field, args.expected)?),
+        _ => None,
+    };
+
+    let mut annotations = args.annotate_dst.then(|| DstAnnotations::new(tz));
+    let mut transitions = Vec::new();
+
+    let mut decoder = parse_input_mode(&args.input_mode)?;
+    let mut errors = LineErrors::open(args.errors_to.as_deref())?;
+    let mut lines = open_input(&args.input)?;
+
+    // Keyed by `start_utc`, which sorts chronologically as text.
+    let mut counts: BTreeMap<String, Counted> = BTreeMap::new();
+    while let Some((line_number, trimmed)) = lines.next_numbered_line()? {
+        if trimmed.is_empty() {
+            continue;
+        }
+        let Some(record) = errors
+            .check(line_number, trimmed, decoder.decode(trimmed))?
+            .flatten()
+        else {
+            continue;
+        };
+
+        let parsed = parse_timestamp(&record.ts_str, timestamp_format)
+            .map_err(|e| CliError::input(format!("Error processing '{}': {}", record.ts_str, e)))
+            .and_then(|instant| {
+                let entity = completeness
+                    .as_ref()
+                    .map(|check| check.entity(&record))
+                    .transpose()?;
+                Ok((instant, entity))
+            });
+        let Some((instant, entity)) = errors.check(line_number, trimmed, parsed)? else {
+            continue;
+        };
+        let bucket = compute_bucket(instant, tz, interval, Some(week_start));
+        if let Some(annotations) = &mut annotations {
+            transitions.extend(annotations.observe(instant));
+        }
+        if let Some(summary) = &mut summary {
+            summary.record(instant, &bucket)?;
+        }
+        if let (Some(check), Some(entity)) = (&mut completeness, entity) {
+            check.record(&bucket, entity);
+        }
+        let counted = counts
+            .entry(bucket.start_utc.clone())
+            .or_insert_with(|| Counted {
+                record: CountRecord {
+                    key: bucket.key,
+                    start_local: bucket.start_local,
+                    end_local: bucket.end_local,
+                    start_utc: bucket.start_utc,
+                    end_utc: bucket.end_utc,
+                    count: 0,
+                    first_ts: None,
+                    first_ts_local: None,
+                    last_ts: None,
+                    last_ts_local: None,
+                },
+                first: instant,
+                last: instant,
+            });
+        counted.record.count += 1;
+        counted.first = counted.first.min(instant);
+        counted.last = counted.last.max(instant);
+    }
+    if let Some(check) = &completeness {
+        let buckets = counted_range(&counts, tz, interval, week_start)?;
+        return write_completeness(&check.report(&buckets), format, summary.as_ref(), out);
+    }
+    let records: Vec<CountRecord> = counts
+        .into_values()
+        .map(|counted| {
+            let mut record = counted.record;
+            if args.first_last {
+                record.first_ts = Some(format_utc(counted.first));
+                record.first_ts_local = Some(format_local(counted.first, tz));
+                record.last_ts = Some(format_utc(counted.last));
+                record.last_ts_local = Some(format_local(counted.last, tz));
+            }
+            record
+        })
+        .collect();
+
+    match format {
+        CountFormat::Json if annotations.is_some() => {
+            let items = interleave(&records, &mut transitions);
+            let json = serde_json::to_string_pretty(&items)
+                .map_err(|e| CliError::runtime(format!("Failed to serialize JSON: {}", e)))?;
+            writeln!(out, "{}", json).map_err(write_error)?;
+        }
+        CountFormat::Json => {
+            let json = serde_json::to_string_pretty(&records)
+                .map_err(|e| CliError::runtime(format!("Failed to serialize JSON: {}", e)))?;
+            writeln!(out, "{}", json).map_err(write_error)?;
+        }
+        CountFormat::Text => {
+            let style = Style::stdout();
+            for item in interleave(&records, &mut transitions) {
+                let record = match item {
+                    CountItem::Bucket(record) => record,
+                    CountItem::Transition(transition) => {
+                        writeln!(out, "{}", transition.text()).map_err(write_error)?;
+                        continue;
+                    }
+                };
+                write!(
+                    out,
+                    "{}: {}",
+                    style.bucket_key(&record.key, &record.start_local, &record.end_local),
+                    record.count
+                )
+                .map_err(write_error)?;
+                if let (Some(first), Some(last)) = (&record.first_ts_local, &record.last_ts_local) {
+                    write!(out, " (first {}, last {})", first, last).map_err(write_error)?;
+                }
+                writeln!(out).map_err(write_error)?;
+            }
+            if let Some(summary) = &summary {
+                summary.write(&mut out).map_err(write_error)?;
+            }
+        }
+        CountFormat::Calendar => {
+            write!(out, "{}", render_calendar(&records, week_start)?).map_err(write_error)?
+        }
+        CountFormat::Vega => {
+            let spec = vega_lite_spec(&records, &tz.to_string(), interval);
+            let json = serde_json::to_string_pretty(&spec)
+                .map_err(|e| CliError::runtime(format!("Failed to serialize JSON: {}", e)))?;
+            writeln!(out, "{}", json).map_err(write_error)?;
+        }
+    }
+
+    Ok(ExitCode::from(EXIT_SUCCESS))
+}
+
+/// One element of `count` output: a bucket record or, with
+/// `--annotate-dst`, a transition.
+#[derive(Serialize)]
+#[serde(untagged)]
+enum CountItem<'a> {
+    Bucket(&'a CountRecord),
+    Transition(&'a TransitionRecord),
+}
+
+/// Bucket records with each transition placed after the records of buckets
+/// starting at or before it. `transitions` are sorted first, since they are
+/// found in input order.
+fn interleave<'a>(
+    records: &'a [CountRecord],
+    transitions: &'a mut [TransitionRecord],
+) -> Vec<CountItem<'a>> {
+    transitions.sort_by(|a, b| a.at_utc().cmp(b.at_utc()));
+    let mut pending = transitions.iter().peekable();
+    let mut items = Vec::with_capacity(records.len() + pending.len());
+    for record in records {
+        while let Some(transition) =
+            pending.next_if(|transition| transition.at_utc() < record.start_utc.as_str())
+        {
+            items.push(CountItem::Transition(transition));
+        }
+        items.push(CountItem::Bucket(record));
+    }
+    items.extend(pending.map(CountItem::Transition));
+    items
+}
+
+/// Every bucket from the first to the last counted one, including those
+/// without events.
+fn counted_range(
+    counts: &BTreeMap<String, Counted>,
+    tz: Tz,
+    interval: Interval,
+    week_start: WeekStart,
+) -> CliResult<Vec<Bucket>> {
+    match (counts.first_key_value(), counts.last_key_value()) {
+        (Some((start, _)), Some((_, last))) => generate_buckets_in_range(
+            parse_rfc3339_to_utc(start)?,
+            parse_rfc3339_to_utc(&last.record.end_utc)?,
+            tz,
+            interval,
+            week_start,
+            None,
+        ),
+        _ => Ok(Vec::new()),
+    }
+}
+
+/// Write the `--completeness` report. Exit code `3` when any bucket/entity
+/// pair is incomplete.
+fn write_completeness(
+    report: &CompletenessReport,
+    format: CountFormat,
+    summary: Option<&Summary>,
+    mut out: impl Write,
+) -> CliResult<ExitCode> {
+    if format == CountFormat::Json {
+        let json = serde_json::to_string_pretty(report)
+            .map_err(|e| CliError::runtime(format!("Failed to serialize JSON: {}", e)))?;
+        writeln!(out, "{}", json).map_err(write_error)?;
+    } else {
+        let style = Style::stdout();
+        for gap in &report.missing {
+            writeln!(
+                out,
+                "{} {}: {} of {}",
+                style.bucket_key(&gap.key, &gap.start_local, &gap.end_local),
+                gap.entity,
+                gap.count,
+                gap.expected
+            )
+            .map_err(write_error)?;
+        }
+        writeln!(
+            out,
+            "{} of {} bucket/entity pairs incomplete",
+            report.incomplete,
+            report.buckets * report.entities
+        )
+        .map_err(write_error)?;
+        if let Some(summary) = summary {
+            summary.write(&mut out).map_err(write_error)?;
+        }
+    }
+
+    if report.incomplete > 0 {
+        Ok(ExitCode::from(EXIT_RUNTIME_ERROR))
+    } else {
+        Ok(ExitCode::from(EXIT_SUCCESS))
+    }
+}
+
+fn parse_count_format(s: &str) -> CliResult<CountFormat> {
+    match s.to_lowercase().as_str() {
+        "json" => Ok(CountFormat::Json),
+        "text" => Ok(CountFormat::Text),
+        "calendar" => Ok(CountFormat::Calendar),
+        "vega" => Ok(CountFormat::Vega),
+        _ => Err(CliError::input(format!(
+            "Invalid output_format '{}'. Expected: json, text, calendar, vega",
+            s
+        ))),
+    }
+}
+
+/// A bucket's record while counting, with the earliest and latest instant
+/// seen in it.
+struct Counted {
+    record: CountRecord,
+    first: DateTime<Utc>,
+    last: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+struct CountRecord {
+    key: BucketKey,
+    start_local: String,
+    end_local: String,
+    start_utc: String,
+    end_utc: String,
+    count: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    first_ts: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    first_ts_local: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_ts: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_ts_local: Option<String>,
+}
+
+/// An event timestamp in UTC, with fractional seconds only when present.
+fn format_utc(instant: DateTime<Utc>) -> String {
+    instant.to_rfc3339_opts(SecondsFormat::AutoSi, true)
+}
+
+/// An event timestamp in `tz` local time with its offset.
+fn format_local(instant: DateTime<Utc>, tz: Tz) -> String {
+    instant
+        .with_timezone(&tz)
+        .to_rfc3339_opts(SecondsFormat::AutoSi, false)
+}
+
+/// Vega-Lite v5 bar chart of counts per bucket.
+///
+/// Bucket boundaries are given as local wall-clock times without offset and
+/// parsed as UTC with a UTC scale, so the axis shows `tz` local time no matter
+/// which timezone the viewer's browser is in.
+fn vega_lite_spec(records: &[CountRecord], tz: &str, interval: Interval) -> Value {
+    let wall_clock = |local: &str| local.get(..19).unwrap_or(local).to_string();
+    let values: Vec<Value> = records
+        .iter()
+        .map(|record| {
+            json!({
+                "key": record.key,
+                "start": wall_clock(&record.start_local),
+                "end": wall_clock(&record.end_local),
+                "start_utc": record.start_utc,
+                "end_utc": record.end_utc,
+                "count": record.count,
+            })
+        })
+        .collect();
+    let parse = "utc:'%Y-%m-%dT%H:%M:%S'";
+
+    json!({
+        "$schema": "https://vega.github.io/schema/vega-lite/v5.json",
+        "title": format!("Counts per {} ({})", interval, tz),
+        "data": {
+            "values": values,
+            "format": { "parse": { "start": parse, "end": parse } }
+        },
+        "mark": "bar",
+        "encoding": {
+            "x": {
+                "field": "start",
+                "type": "temporal",
+                "scale": { "type": "utc" },
+                "title": format!("Local time ({})", tz)
+            },
+            "x2": { "field": "end" },
+            "y": { "field": "count", "type": "quantitative", "title": "Count" },
+            "tooltip": [
+                { "field": "key", "type": "nominal", "title": "Bucket" },
+                { "field": "count", "type": "quantitative", "title": "Count" },
+                { "field": "start_utc", "type": "nominal", "title": "Start (UTC)" },
+                { "field": "end_utc", "type": "nominal", "title": "End (UTC)" }
+            ]
+        }
+    })
+}
+
+/// Shading from no events to the busiest day.
+const SHADES: [char; 5] = ['·', '░', '▒', '▓', '█'];
+
+/// Render day counts as one month grid per calendar month, with weeks as
+/// rows starting on `week_start`. Each cell is a shade (quartile of the
+/// maximum count) followed by the count.
+fn render_calendar(records: &[CountRecord], week_start: WeekStart) -> CliResult<String> {
+    let mut days: BTreeMap<NaiveDate, u64> = BTreeMap::new();
+    for record in records {
+        let date = NaiveDate::parse_from_str(&record.key, "%Y-%m-%d")
+            .map_err(|e| CliError::runtime(format!("Invalid day key '{}': {}", record.key, e)))?;
+        days.insert(date, record.count);
+    }
+
+    let (Some((&first, _)), Some((&last, _))) = (days.first_key_value(), days.last_key_value())
+    else {
+        return Ok(String::new());
+    };
+    let max = days.values().copied().max().unwrap_or(0);
+    let width = max.to_string().len().max(2);
+
+    let weekdays = match week_start {
+        WeekStart::Monday => ["Mo", "Tu", "We", "Th", "Fr", "Sa", "Su"],
+        WeekStart::Sunday => ["Su", "Mo", "Tu", "We", "Th", "Fr", "Sa"],
+    };
+    let header: Vec<String> = weekdays
+        .iter()
+        .map(|name| format!("{:>w$}", name, w = width + 1))
+        .collect();
+
+    let mut output = String::new();
+    let mut month_start = first.with_day(1).expect("day 1 exists in every month");
+    while month_start <= last {
+        let next_month = month_start
+            .checked_add_months(chrono::Months::new(1))
+            .expect("date within chrono's range");
+
+        output.push_str(&format!("{}\n", month_start.format("%B %Y")));
+        output.push_str(&format!("{}\n", header.join(" ")));
+
+        let leading = match week_start {
+            WeekStart::Monday => month_start.weekday().num_days_from_monday(),
+            WeekStart::Sunday => month_start.weekday().num_days_from_sunday(),
+        } as usize;
+        let mut cells = vec![" ".repeat(width + 1); leading];
+        for date in month_start.iter_days().take_while(|d| *d < next_month) {
+            let count = days.get(&date).copied().unwrap_or(0);
+            cells.push(format!("{}{:>w$}", shade(count, max), count, w = width));
+        }
+        for week in cells.chunks(7) {
+            output.push_str(week.join(" ").trim_end());
+            output.push('\n');
+        }
+        output.push('\n');
+
+        month_start = next_month;
+    }
+
+    output.push_str(&format!(
+        "{} 0  {} {} {} {} quartiles of max {}\n",
+        SHADES[0], SHADES[1], SHADES[2], SHADES[3], SHADES[4], max
+    ));
+    Ok(output)
+}
+
+fn shade(count: u64, max: u64) -> char {
+    if count == 0 || max == 0 {
+        return SHADES[0];
+    }
+    let level = (count * 4).div_ceil(max).clamp(1, 4);
+    SHADES[level as usize]
+}
@@ -0,0 +1,218 @@
+use std::collections::{HashMap, VecDeque};
+use std::fs::File;
+use std::io::{BufReader, Write};
+use std::process::ExitCode;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::cli::DiffOutputArgs;
+use crate::error::{CliError, CliResult, EXIT_SUCCESS, OutputFormat};
+use crate::input::LineReader;
+use crate::shared::write_error;
+
+pub fn run_diff_output(
+    args: DiffOutputArgs,
+    output_format: OutputFormat,
+    mut out: impl Write,
+) -> CliResult<ExitCode> {
+    let old_records = read_records(&args.old)?;
+    let new_records = read_records(&args.new)?;
+
+    let report = diff_records(old_records, new_records);
+
+    match output_format {
+        OutputFormat::Json => {
+            let json = serde_json::to_string_pretty(&report)
+                .map_err(|e| CliError::runtime(format!("Failed to serialize JSON: {}", e)))?;
+            writeln!(out, "{}", json).map_err(write_error)?;
+        }
+        OutputFormat::Text => {
+            for change in &report.changes {
+                let key = |bucket: &Option<Value>| {
+                    bucket
+                        .as_ref()
+                        .and_then(|b| b["key"].as_str())
+                        .unwrap_or("-")
+                        .to_string()
+                };
+                writeln!(
+                    out,
+                    "{} {} {} {}: {} -> {}",
+                    change.status,
+                    change.ts,
+                    change.tz,
+                    change.interval,
+                    key(&change.old),
+                    key(&change.new)
+                )
+                .map_err(write_error)?;
+            }
+            writeln!(
+                out,
+                "Compared: {}, changed: {}, only in old: {}, only in new: {}",
+                report.compared, report.changed, report.only_in_old, report.only_in_new
+            )
+            .map_err(write_error)?;
+        }
+    }
+
+    Ok(ExitCode::from(EXIT_SUCCESS))
+}
+
+/// Identity of one bucketed record: input timestamp text, timezone, interval.
+type RecordKey = (String, String, String);
+
+struct Record {
+    key: RecordKey,
+    bucket: Value,
+}
+
+#[derive(Debug, Serialize)]
+struct DiffReport {
+    compared: usize,
+    changed: usize,
+    only_in_old: usize,
+    only_in_new: usize,
+    changes: Vec<Change>,
+}
+
+#[derive(Debug, Serialize)]
+struct Change {
+    status: &'static str,
+    ts: String,
+    tz: String,
+    interval: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    old: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    new: Option<Value>,
+}
+
+impl Change {
+    fn new(status: &'static str, key: RecordKey, old: Option<Value>, new: Option<Value>) -> Self {
+        let (ts, tz, interval) = key;
+        Self {
+            status,
+            ts,
+            tz,
+            interval,
+            old,
+            new,
+        }
+    }
+}
+
+fn read_records(path: &str) -> CliResult<Vec<Record>> {
+    let file = File::open(path)
+        .map_err(|e| CliError::runtime(format!("Failed to open file '{}': {}", path, e)))?;
+    let mut records = Vec::new();
+
+    let mut lines = LineReader::new(BufReader::new(file));
+    while let Some((line_number, trimmed)) = lines.next_numbered_line()? {
+        if trimmed.is_empty() {
+            continue;
+        }
+
+        let value: Value = serde_json::from_str(trimmed).map_err(|e| {
+            CliError::input(format!("{}:{}: invalid JSON: {}", path, line_number, e))
+        })?;
+        let field = |pointer: &str| {
+            value
+                .pointer(pointer)
+                .and_then(Value::as_str)
+                .map(str::to_string)
+                .ok_or_else(|| {
+                    CliError::input(format!(
+                        "{}:{}: missing string field '{}'",
+                        path, line_number, pointer
+                    ))
+                })
+        };
+
+        let key = (field("/input/ts")?, field("/tz")?, field("/interval")?);
+        let bucket = value.get("bucket").cloned().ok_or_else(|| {
+            CliError::input(format!("{}:{}: missing field 'bucket'", path, line_number))
+        })?;
+
+        records.push(Record { key, bucket });
+    }
+
+    Ok(records)
+}
+
+/// Fields that decide which bucket a record is assigned to. Other fields
+/// (week numbers, labels, DST info, `duration_seconds`, ...) are derived from
+/// these or added by newer versions, and do not count as a change.
+const ASSIGNMENT_FIELDS: [&str; 3] = ["key", "start_utc", "end_utc"];
+
+fn same_assignment(old: &Value, new: &Value) -> bool {
+    ASSIGNMENT_FIELDS
+        .iter()
+        .all(|field| old.get(field) == new.get(field))
+}
+
+/// Pair up records by key (in file order, so repeated inputs match
+/// occurrence-by-occurrence) and collect every difference.
+fn diff_records(old_records: Vec<Record>, new_records: Vec<Record>) -> DiffReport {
+    let mut pending: HashMap<RecordKey, VecDeque<(usize, Value)>> = HashMap::new();
+    for (position, record) in new_records.into_iter().enumerate() {
+        pending
+            .entry(record.key)
+            .or_default()
+            .push_back((position, record.bucket));
+    }
+
+    let mut report = DiffReport {
+        compared: 0,
+        changed: 0,
+        only_in_old: 0,
+        only_in_new: 0,
+        changes: Vec::new(),
+    };
+
+    for record in old_records {
+        match pending.get_mut(&record.key).and_then(VecDeque::pop_front) {
+            Some((_, new_bucket)) => {
+                report.compared += 1;
+                if !same_assignment(&record.bucket, &new_bucket) {
+                    report.changed += 1;
+                    report.changes.push(Change::new(
+                        "changed",
+                        record.key,
+                        Some(record.bucket),
+                        Some(new_bucket),
+                    ));
+                }
+            }
+            None => {
+                report.only_in_old += 1;
+                report.changes.push(Change::new(
+                    "only_in_old",
+                    record.key,
+                    Some(record.bucket),
+                    None,
+                ));
+            }
+        }
+    }
+
+    let mut unmatched: Vec<(usize, RecordKey, Value)> = pending
+        .into_iter()
+        .flat_map(|(key, buckets)| {
+            buckets
+                .into_iter()
+                .map(move |(position, bucket)| (position, key.clone(), bucket))
+        })
+        .collect();
+    unmatched.sort_by_key(|(position, _, _)| *position);
+
+    for (_, key, bucket) in unmatched {
+        report.only_in_new += 1;
+        report
+            .changes
+            .push(Change::new("only_in_new", key, None, Some(bucket)));
+    }
+
+    report
+}
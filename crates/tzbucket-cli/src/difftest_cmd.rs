@@ -0,0 +1,287 @@
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, ExitCode, Stdio};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use chrono::{DateTime, TimeDelta, Utc};
+use chrono_tz::{TZ_VARIANTS, Tz};
+use serde::Serialize;
+use tzbucket_core::tz::format_rfc3339_utc;
+use tzbucket_core::{BucketKey, Interval, compute_bucket_typed};
+
+use crate::cli::DifftestArgs;
+use crate::error::{CliError, CliResult, EXIT_RUNTIME_ERROR, EXIT_SUCCESS, OutputFormat};
+use crate::shared::{
+    parse_interval, parse_tz_or_input_error, parse_week_start, parse_year_range, write_error,
+};
+
+/// Local time as rendered on both sides: `date +FORMAT` and chrono's
+/// `format` agree on these specifiers. `%::z` keeps the seconds of
+/// pre-standard (LMT) offsets.
+const LOCAL_FORMAT: &str = "%Y-%m-%dT%H:%M:%S %::z";
+
+/// Run `difftest`: bucket random instants in random zones and check the
+/// boundaries against an independent implementation, GNU `date` reading the
+/// system's compiled tzdata.
+///
+/// Each boundary is checked twice: `date` must render it as the same local
+/// time and offset, and must put the second before it on an earlier local
+/// date. Exits `3` on any divergence.
+pub fn run_difftest(
+    args: DifftestArgs,
+    output_format: OutputFormat,
+    mut out: impl Write,
+) -> CliResult<ExitCode> {
+    let interval = parse_interval(&args.interval)?;
+    if is_sub_day(interval) {
+        return Err(CliError::input(format!(
+            "difftest checks local date boundaries; interval '{}' is shorter than a day",
+            args.interval
+        )));
+    }
+    let week_start = parse_week_start(&args.week_start)?;
+    let (from, to) = parse_year_range(&args.from, &args.to)?;
+    let zoneinfo = Path::new(&args.zoneinfo_dir);
+    let zones = candidate_zones(&args.tz, zoneinfo)?;
+    let seed = args.seed.unwrap_or_else(|| {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or_default()
+    });
+
+    let mut rng = SplitMix64(seed);
+    let span = (to - from).num_seconds() as u64;
+    let mut divergences = Vec::new();
+    for _ in 0..args.count {
+        let tz = zones[rng.below(zones.len() as u64) as usize];
+        let instant = from + TimeDelta::seconds(rng.below(span) as i64);
+        let bucket = compute_bucket_typed(instant, tz, interval, Some(week_start));
+
+        let boundaries = [("start", bucket.start_utc), ("end", bucket.end_utc)];
+        let probes: Vec<_> = boundaries
+            .iter()
+            .flat_map(|&(_, at)| [at - TimeDelta::seconds(1), at])
+            .collect();
+        let rendered = run_date(&args.date_command, tz, zoneinfo, &probes)?;
+
+        for (i, &(boundary, at)) in boundaries.iter().enumerate() {
+            let (date_before, date) = (&rendered[2 * i], &rendered[2 * i + 1]);
+            let tzbucket = at.with_timezone(&tz).format(LOCAL_FORMAT).to_string();
+            let reason = if tzbucket != *date {
+                "local time differs"
+            } else if date_before.get(..10) == date.get(..10) {
+                "not a local date boundary"
+            } else {
+                continue;
+            };
+            divergences.push(Divergence {
+                tz: tz.name().to_string(),
+                instant_utc: format_rfc3339_utc(&instant),
+                key: bucket.key.clone(),
+                boundary,
+                at_utc: format_rfc3339_utc(&at),
+                reason,
+                tzbucket,
+                date: date.clone(),
+                date_before: date_before.clone(),
+            });
+        }
+    }
+
+    let report = DifftestReport {
+        seed,
+        tzdb_version: chrono_tz::IANA_TZDB_VERSION,
+        interval: args.interval,
+        zones: zones.len(),
+        samples: args.count,
+        divergences,
+    };
+
+    match output_format {
+        OutputFormat::Json => {
+            let json = serde_json::to_string_pretty(&report)
+                .map_err(|e| CliError::runtime(format!("Failed to serialize JSON: {}", e)))?;
+            writeln!(out, "{}", json).map_err(write_error)?;
+        }
+        OutputFormat::Text => {
+            for d in &report.divergences {
+                writeln!(
+                    out,
+                    "DIFF {} {} ({}) {} {}: {}: tzbucket {}, date {} (second before: {})",
+                    d.tz,
+                    d.instant_utc,
+                    d.key,
+                    d.boundary,
+                    d.at_utc,
+                    d.reason,
+                    d.tzbucket,
+                    d.date,
+                    d.date_before
+                )
+                .map_err(write_error)?;
+            }
+            writeln!(
+                out,
+                "{} samples in {} zones, {} divergences (seed {}, tzdata {})",
+                report.samples,
+                report.zones,
+                report.divergences.len(),
+                report.seed,
+                report.tzdb_version
+            )
+            .map_err(write_error)?;
+        }
+    }
+
+    if report.divergences.is_empty() {
+        Ok(ExitCode::from(EXIT_SUCCESS))
+    } else {
+        Ok(ExitCode::from(EXIT_RUNTIME_ERROR))
+    }
+}
+
+/// The `--tz` zones, or every linked zone, restricted to zones compiled
+/// into `zoneinfo`: `date` silently falls back to UTC for a missing one.
+fn candidate_zones(names: &[String], zoneinfo: &Path) -> CliResult<Vec<Tz>> {
+    let installed = |tz: &Tz| zoneinfo.join(tz.name()).is_file();
+
+    if names.is_empty() {
+        let zones: Vec<Tz> = TZ_VARIANTS.iter().copied().filter(installed).collect();
+        if zones.is_empty() {
+            return Err(CliError::input(format!(
+                "No zone found in --zoneinfo-dir {}",
+                zoneinfo.display()
+            )));
+        }
+        return Ok(zones);
+    }
+
+    names
+        .iter()
+        .map(|name| {
+            let tz = parse_tz_or_input_error(name)?;
+            if !installed(&tz) {
+                return Err(CliError::input(format!(
+                    "Zone '{}' is not in --zoneinfo-dir {}",
+                    name,
+                    zoneinfo.display()
+                )));
+            }
+            Ok(tz)
+        })
+        .collect()
+}
+
+/// Render `instants` in `tz` with one `date -f -` process, one line each.
+fn run_date(
+    command: &str,
+    tz: Tz,
+    zoneinfo: &Path,
+    instants: &[DateTime<Utc>],
+) -> CliResult<Vec<String>> {
+    let spawn_error =
+        |e: std::io::Error| CliError::runtime(format!("Failed to run '{}': {}", command, e));
+    let mut child = Command::new(command)
+        .args(["-f", "-", &format!("+{}", LOCAL_FORMAT)])
+        .env("TZ", tz.name())
+        .env("TZDIR", zoneinfo)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(spawn_error)?;
+
+    let input: String = instants
+        .iter()
+        .map(|at| format!("@{}\n", at.timestamp()))
+        .collect();
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(input.as_bytes()).map_err(spawn_error)?;
+    }
+    let output = child.wait_with_output().map_err(spawn_error)?;
+    if !output.status.success() {
+        return Err(CliError::runtime(format!(
+            "'{}' failed for {}: {}",
+            command,
+            tz.name(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    let lines: Vec<String> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        // `date` prints `-00` offsets (local time unknown, e.g. an
+        // uninhabited Antarctic station) with a minus sign; chrono has none.
+        .map(|line| match line.strip_suffix(" -00:00:00") {
+            Some(local) => format!("{} +00:00:00", local),
+            None => line.to_string(),
+        })
+        .collect();
+    if lines.len() != instants.len() {
+        return Err(CliError::runtime(format!(
+            "'{}' printed {} lines for {} instants; difftest needs GNU date",
+            command,
+            lines.len(),
+            instants.len()
+        )));
+    }
+    Ok(lines)
+}
+
+/// Intervals whose boundaries are not local date changes.
+fn is_sub_day(interval: Interval) -> bool {
+    matches!(
+        interval,
+        Interval::Minutes(_) | Interval::Hour | Interval::Hours(_) | Interval::FixedSeconds(_)
+    )
+}
+
+/// SplitMix64: a tiny seedable generator, so a reported seed reproduces a
+/// run without pulling in a random number crate.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// A value in `0..n`. The modulo bias is negligible for the zone counts
+    /// and second ranges sampled here.
+    fn below(&mut self, n: u64) -> u64 {
+        self.next() % n.max(1)
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct DifftestReport {
+    /// Seed of the run; pass it to `--seed` to reproduce the samples.
+    seed: u64,
+    tzdb_version: &'static str,
+    interval: String,
+    /// Number of zones sampled from.
+    zones: usize,
+    samples: usize,
+    divergences: Vec<Divergence>,
+}
+
+#[derive(Debug, Serialize)]
+struct Divergence {
+    tz: String,
+    instant_utc: String,
+    key: BucketKey,
+    /// `start` or `end`.
+    boundary: &'static str,
+    at_utc: String,
+    reason: &'static str,
+    /// The boundary in local time, as computed by tzbucket.
+    tzbucket: String,
+    /// The boundary in local time, as rendered by `date`.
+    date: String,
+    /// The second before the boundary, as rendered by `date`.
+    date_before: String,
+}
@@ -1,8 +1,12 @@
+//! CLI errors, exit codes and their rendering, and stderr warnings.
+
 use std::fmt;
 use std::process::ExitCode;
 
 use serde::Serialize;
 
+use crate::style::Style;
+
 pub const EXIT_SUCCESS: u8 = 0;
 pub const EXIT_INPUT_ERROR: u8 = 2;
 pub const EXIT_RUNTIME_ERROR: u8 = 3;
@@ -70,9 +74,13 @@ impl std::error::Error for CliError {}
 pub type CliResult<T> = std::result::Result<T, CliError>;
 
 #[derive(Debug, Serialize)]
-struct ErrorOutput {
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub(crate) struct ErrorOutput {
+    /// Error message.
     error: String,
+    /// Process exit code (`2` input/policy, `3` runtime).
     exit_code: u8,
+    /// DST policy status (`nonexistent`, `ambiguous`), if applicable.
     #[serde(skip_serializing_if = "Option::is_none")]
     status: Option<String>,
 }
@@ -92,13 +100,51 @@ pub fn render_error(err: &CliError, output_format: OutputFormat) -> ExitCode {
             }
         }
         OutputFormat::Text => {
-            eprintln!("Error: {}", err.message);
+            eprintln!("{} {}", Style::stderr().error("Error:"), err.message);
         }
     }
 
     ExitCode::from(err.exit_code())
 }
 
+/// A warning on stderr; unlike an error, the command carries on.
+#[derive(Debug, Serialize)]
+pub(crate) struct WarningOutput<T> {
+    /// Warning message.
+    warning: String,
+    /// Warning type, e.g. `legacy_tz`.
+    code: &'static str,
+    /// Fields specific to `code`.
+    #[serde(flatten)]
+    details: T,
+}
+
+/// Print a warning to stderr: one JSON object per line in JSON mode, a
+/// `Warning:` line otherwise.
+pub fn render_warning<T: Serialize>(
+    message: String,
+    code: &'static str,
+    details: T,
+    output_format: OutputFormat,
+) {
+    match output_format {
+        OutputFormat::Json => {
+            let warning = WarningOutput {
+                warning: message,
+                code,
+                details,
+            };
+            match serde_json::to_string(&warning) {
+                Ok(json) => eprintln!("{}", json),
+                Err(_) => eprintln!("Warning: {}", warning.warning),
+            }
+        }
+        OutputFormat::Text => {
+            eprintln!("{} {}", Style::stderr().warning("Warning:"), message);
+        }
+    }
+}
+
 pub fn output_format_hint(s: &str) -> OutputFormat {
     if s.eq_ignore_ascii_case("json") {
         OutputFormat::Json
@@ -1,196 +1,103 @@
+use std::io::Write;
 use std::process::ExitCode;
 
-use chrono::{DateTime, NaiveDateTime, TimeZone};
+use chrono::NaiveDateTime;
 use chrono_tz::Tz;
 use serde::Serialize;
-use tzbucket_core::{AmbiguousPolicy, NonexistentPolicy};
+use tzbucket_core::{Bucket, Interval, Policy, WeekStart, resolve_local};
 
 use crate::cli::ExplainArgs;
 use crate::error::{CliError, CliResult, EXIT_SUCCESS, OutputFormat};
-use crate::shared::{
-    format_rfc3339, parse_ambiguous_policy, parse_nonexistent_policy, parse_tz_or_input_error,
-};
+use crate::shared::{Versioned, format_rfc3339, resolve_error_to_cli, write_error};
+use crate::style::Style;
 
-pub fn run_explain(args: ExplainArgs, output_format: OutputFormat) -> CliResult<ExitCode> {
-    let tz = parse_tz_or_input_error(&args.tz)?;
-    let nonexistent_policy = parse_nonexistent_policy(&args.policy_nonexistent)?;
-    let ambiguous_policy = parse_ambiguous_policy(&args.policy_ambiguous)?;
-    let local = parse_local_time(&args.local)?;
-
-    let result = explain_local_time(local, tz, nonexistent_policy, ambiguous_policy)?;
+/// Run `explain`: write how a local time resolves in `args.tz`, and its
+/// bucket with `--interval`, to `out`.
+pub fn run_explain(args: ExplainArgs, mut out: impl Write) -> CliResult<ExitCode> {
+    let output_format = args.output_format;
+    let schema_version = args.schema_version;
+    let bucket_spec = args.interval.map(|interval| (interval, args.week_start));
+    let result = explain_local_time(args.local, args.tz, args.policy(), bucket_spec)?;
 
     match output_format {
         OutputFormat::Json => {
-            let json = serde_json::to_string_pretty(&result)
+            let json = serde_json::to_string_pretty(&Versioned::new(&result, schema_version))
                 .map_err(|e| CliError::runtime(format!("Failed to serialize JSON: {}", e)))?;
-            println!("{}", json);
+            writeln!(out, "{}", json).map_err(write_error)?;
         }
         OutputFormat::Text => {
-            println!("Local time: {}", result.local_time);
-            println!("Timezone: {}", result.tz);
-            println!("Status: {}", result.status);
+            writeln!(out, "Local time: {}", result.local_time).map_err(write_error)?;
+            writeln!(out, "Timezone: {}", result.tz).map_err(write_error)?;
+            writeln!(out, "Status: {}", result.status).map_err(write_error)?;
             if let Some(resolution) = result.resolution {
-                println!("Resolution: {} -> {}", resolution.policy, resolution.result);
+                writeln!(
+                    out,
+                    "Resolution: {} -> {}",
+                    resolution.policy, resolution.result
+                )
+                .map_err(write_error)?;
+            }
+            if let Some(bucket) = result.bucket {
+                writeln!(
+                    out,
+                    "Bucket: {} -> {} to {}",
+                    Style::stdout().bucket_key(&bucket.key, &bucket.start_local, &bucket.end_local),
+                    bucket.start_local,
+                    bucket.end_local
+                )
+                .map_err(write_error)?;
             }
         }
     }
+    out.flush().map_err(write_error)?;
 
     Ok(ExitCode::from(EXIT_SUCCESS))
 }
 
 #[derive(Debug, Serialize)]
-struct ExplainResult {
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub(crate) struct ExplainResult {
+    /// Input local time.
     local_time: String,
+    /// IANA timezone.
     tz: String,
+    /// `normal`, `nonexistent`, or `ambiguous`.
     status: String,
+    /// Policy resolution for nonexistent/ambiguous times.
     #[serde(skip_serializing_if = "Option::is_none")]
     resolution: Option<Resolution>,
+    /// Bucket of the resolved instant, when `--interval` is given.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    bucket: Option<Bucket>,
 }
 
 #[derive(Debug, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 struct Resolution {
+    /// Policy used to resolve the DST case.
     policy: String,
+    /// Resolved local time with offset.
     result: String,
 }
 
-fn parse_local_time(s: &str) -> CliResult<NaiveDateTime> {
-    let formats = [
-        "%Y-%m-%dT%H:%M:%S",
-        "%Y-%m-%d %H:%M:%S",
-        "%Y-%m-%dT%H:%M",
-        "%Y-%m-%d %H:%M",
-    ];
-
-    for fmt in &formats {
-        if let Ok(dt) = chrono::NaiveDateTime::parse_from_str(s, fmt) {
-            return Ok(dt);
-        }
-    }
-
-    Err(CliError::input(format!(
-        "Invalid local time format '{}'. Expected: YYYY-MM-DDTHH:MM:SS",
-        s
-    )))
-}
-
 fn explain_local_time(
     local: NaiveDateTime,
     tz: Tz,
-    nonexistent_policy: NonexistentPolicy,
-    ambiguous_policy: AmbiguousPolicy,
+    policy: Policy,
+    bucket_spec: Option<(Interval, WeekStart)>,
 ) -> CliResult<ExplainResult> {
-    use chrono::offset::LocalResult;
-
-    let local_result = tz.from_local_datetime(&local);
-
-    let (status, resolution) = match local_result {
-        LocalResult::Single(_dt) => ("normal".to_string(), None),
-        LocalResult::Ambiguous(first, second) => match ambiguous_policy {
-            AmbiguousPolicy::Error => {
-                return Err(CliError::policy(
-                    format!(
-                        "Ambiguous time '{}' in timezone '{}'. Occurs twice due to DST fall back. \
-                         Use --policy-ambiguous=first or --policy-ambiguous=second to resolve.",
-                        local.format("%Y-%m-%dT%H:%M:%S"),
-                        tz
-                    ),
-                    "ambiguous",
-                ));
-            }
-            AmbiguousPolicy::First => (
-                "ambiguous".to_string(),
-                Some(Resolution {
-                    policy: "first".to_string(),
-                    result: format_rfc3339(&first),
-                }),
-            ),
-            AmbiguousPolicy::Second => (
-                "ambiguous".to_string(),
-                Some(Resolution {
-                    policy: "second".to_string(),
-                    result: format_rfc3339(&second),
-                }),
-            ),
-        },
-        LocalResult::None => match nonexistent_policy {
-            NonexistentPolicy::Error => {
-                return Err(CliError::policy(
-                    format!(
-                        "Nonexistent time '{}' in timezone '{}'. Skipped due to DST spring forward. \
-                         Use --policy-nonexistent=shift_forward to resolve.",
-                        local.format("%Y-%m-%dT%H:%M:%S"),
-                        tz
-                    ),
-                    "nonexistent",
-                ));
-            }
-            NonexistentPolicy::ShiftForward => {
-                let result_dt = resolve_nonexistent_shift_forward(local, tz).ok_or_else(|| {
-                    CliError::runtime("Could not resolve shifted time with shift_forward policy")
-                })?;
-
-                (
-                    "nonexistent".to_string(),
-                    Some(Resolution {
-                        policy: "shift_forward".to_string(),
-                        result: format_rfc3339(&result_dt),
-                    }),
-                )
-            }
-        },
-    };
+    let resolved =
+        resolve_local(local, tz, policy).map_err(|e| resolve_error_to_cli(e, local, tz))?;
 
     Ok(ExplainResult {
         local_time: local.format("%Y-%m-%dT%H:%M:%S").to_string(),
         tz: tz.to_string(),
-        status,
-        resolution,
+        status: resolved.status.to_string(),
+        resolution: resolved.applied.map(|applied| Resolution {
+            policy: applied.to_string(),
+            result: format_rfc3339(&resolved.instant),
+        }),
+        bucket: bucket_spec
+            .map(|(interval, week_start)| resolved.bucket(interval, Some(week_start))),
     })
 }
-
-fn resolve_nonexistent_shift_forward(local: NaiveDateTime, tz: Tz) -> Option<DateTime<Tz>> {
-    let previous = find_previous_valid_local_time(local, tz)?;
-    let next = find_next_valid_local_time(local, tz)?;
-
-    // Compute the skipped wall-clock gap and preserve the local minute/second offset.
-    let gap = next.naive_local() - previous.naive_local() - chrono::Duration::seconds(1);
-    let shifted_local = local + gap;
-    let shifted_result = tz.from_local_datetime(&shifted_local);
-
-    shifted_result
-        .single()
-        .or_else(|| shifted_result.earliest())
-        .or(Some(next))
-}
-
-fn find_next_valid_local_time(local: NaiveDateTime, tz: Tz) -> Option<DateTime<Tz>> {
-    // Search forward second-by-second and return the first representable local time.
-    // The wide bound handles rare historical transitions with large gaps.
-    let max_seconds = 2 * 24 * 60 * 60;
-
-    for seconds in 1..=max_seconds {
-        let candidate = local + chrono::Duration::seconds(i64::from(seconds));
-        let local_result = tz.from_local_datetime(&candidate);
-
-        if let Some(dt) = local_result.single().or_else(|| local_result.earliest()) {
-            return Some(dt);
-        }
-    }
-
-    None
-}
-
-fn find_previous_valid_local_time(local: NaiveDateTime, tz: Tz) -> Option<DateTime<Tz>> {
-    let max_seconds = 2 * 24 * 60 * 60;
-
-    for seconds in 1..=max_seconds {
-        let candidate = local - chrono::Duration::seconds(i64::from(seconds));
-        let local_result = tz.from_local_datetime(&candidate);
-
-        if let Some(dt) = local_result.single().or_else(|| local_result.latest()) {
-            return Some(dt);
-        }
-    }
-
-    None
-}
@@ -0,0 +1,327 @@
+//! Line-oriented input shared by the subcommands.
+//!
+//! [`LineReader`] reads with `read_until` into one reused buffer and hands
+//! out trimmed lines borrowed from it, so large inputs are read without an
+//! allocation per line (unlike `BufRead::lines`).
+//!
+//! An [`InputDecoder`] then finds the timestamp in each line. `--input-mode`
+//! selects one: [`PlainDecoder`] (the whole line), [`CsvDecoder`],
+//! [`NdjsonDecoder`], [`RegexDecoder`], or a decoder registered by an
+//! embedding binary with [`register_decoder`].
+
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::sync::Mutex;
+
+use regex::Regex;
+
+use crate::error::{CliError, CliResult};
+
+/// Trimmed lines of a file or stdin, borrowed from an internal buffer.
+pub struct LineReader<R> {
+    reader: R,
+    buf: Vec<u8>,
+    line_number: usize,
+}
+
+/// Open `path` for line reading; `-` reads stdin.
+pub fn open_input(path: &str) -> CliResult<LineReader<Box<dyn BufRead>>> {
+    let reader: Box<dyn BufRead> = if path == "-" {
+        Box::new(io::stdin().lock())
+    } else {
+        let file = File::open(path)
+            .map_err(|e| CliError::runtime(format!("Failed to open file '{}': {}", path, e)))?;
+        Box::new(BufReader::new(file))
+    };
+    Ok(LineReader::new(reader))
+}
+
+impl<R: BufRead> LineReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            buf: Vec::with_capacity(256),
+            line_number: 0,
+        }
+    }
+
+    /// The next line with surrounding whitespace (including `\r\n`) trimmed,
+    /// or `None` at end of input. Blank lines are returned as `""`.
+    pub fn next_line(&mut self) -> CliResult<Option<&str>> {
+        Ok(self.next_numbered_line()?.map(|(_, line)| line))
+    }
+
+    /// Like [`next_line`](Self::next_line), with the 1-based line number.
+    pub fn next_numbered_line(&mut self) -> CliResult<Option<(usize, &str)>> {
+        self.buf.clear();
+        let read = self
+            .reader
+            .read_until(b'\n', &mut self.buf)
+            .map_err(|e| CliError::runtime(format!("Failed to read line: {}", e)))?;
+        if read == 0 {
+            return Ok(None);
+        }
+        self.line_number += 1;
+
+        let line = std::str::from_utf8(&self.buf).map_err(|_| {
+            CliError::runtime("Failed to read line: stream did not contain valid UTF-8")
+        })?;
+        Ok(Some((self.line_number, line.trim())))
+    }
+}
+
+/// A decoded input line: the timestamp text and the other fields the
+/// decoder found, in input order.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RawRecord {
+    pub ts_str: String,
+    pub extra_fields: Vec<(String, String)>,
+}
+
+impl RawRecord {
+    /// A record with only a timestamp.
+    pub fn new(ts_str: impl Into<String>) -> Self {
+        Self {
+            ts_str: ts_str.into(),
+            extra_fields: Vec::new(),
+        }
+    }
+}
+
+/// Finds the timestamp in an input line.
+///
+/// Decoders see every non-empty line once, trimmed and in order. `Ok(None)`
+/// skips the line (a header, a log line without a timestamp); an error
+/// aborts the command like an unparsable timestamp does.
+pub trait InputDecoder {
+    fn decode(&mut self, line: &str) -> CliResult<Option<RawRecord>>;
+}
+
+/// Builds a decoder from the argument of `--input-mode <name>[:<arg>]`
+/// (empty without `:`).
+pub type DecoderFactory = fn(&str) -> CliResult<Box<dyn InputDecoder>>;
+
+static CUSTOM_DECODERS: Mutex<Vec<(&str, DecoderFactory)>> = Mutex::new(Vec::new());
+
+/// Make `--input-mode <name>[:<arg>]` use `factory`. Registering a built-in
+/// or already registered name replaces it.
+pub fn register_decoder(name: &'static str, factory: DecoderFactory) {
+    let mut decoders = CUSTOM_DECODERS.lock().unwrap_or_else(|e| e.into_inner());
+    decoders.retain(|(registered, _)| *registered != name);
+    decoders.push((name, factory));
+}
+
+/// Parse `--input-mode`: `plain`, `csv:<column>`, `ndjson:<field>`,
+/// `regex:<pattern>`, or a registered decoder.
+pub fn parse_input_mode(s: &str) -> CliResult<Box<dyn InputDecoder>> {
+    let (name, arg) = s.split_once(':').unwrap_or((s, ""));
+    let custom = CUSTOM_DECODERS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .find(|(registered, _)| *registered == name)
+        .map(|&(_, factory)| factory);
+    if let Some(factory) = custom {
+        return factory(arg);
+    }
+
+    match (name, arg) {
+        ("plain", "") => Ok(Box::new(PlainDecoder)),
+        ("csv", column) if !column.is_empty() => Ok(Box::new(CsvDecoder::new(column))),
+        ("ndjson", field) if !field.is_empty() => Ok(Box::new(NdjsonDecoder::new(field))),
+        ("regex", pattern) => Ok(Box::new(RegexDecoder::new(pattern)?)),
+        _ => Err(CliError::input(format!(
+            "Invalid input_mode '{}'. Expected: plain, csv:<column name or 1-based index>, ndjson:<field>, regex:<pattern>",
+            s
+        ))),
+    }
+}
+
+/// The whole line is the timestamp.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PlainDecoder;
+
+impl InputDecoder for PlainDecoder {
+    fn decode(&mut self, line: &str) -> CliResult<Option<RawRecord>> {
+        Ok(Some(RawRecord::new(line)))
+    }
+}
+
+/// One CSV column is the timestamp.
+///
+/// A column name reads the first line as the header; a 1-based index means
+/// there is no header. Fields may be quoted, with `""` for a quote, but not
+/// span lines.
+#[derive(Debug, Clone)]
+pub struct CsvDecoder {
+    column: CsvColumn,
+    header: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone)]
+enum CsvColumn {
+    Name(String),
+    Index(usize),
+}
+
+impl CsvDecoder {
+    pub fn new(column: &str) -> Self {
+        let column = match column.parse::<usize>() {
+            Ok(index) if index > 0 => CsvColumn::Index(index - 1),
+            _ => CsvColumn::Name(column.to_string()),
+        };
+        Self {
+            column,
+            header: None,
+        }
+    }
+}
+
+impl InputDecoder for CsvDecoder {
+    fn decode(&mut self, line: &str) -> CliResult<Option<RawRecord>> {
+        let fields = split_csv_line(line);
+        let index = match &self.column {
+            CsvColumn::Index(index) => *index,
+            CsvColumn::Name(name) => {
+                let index = fields
+                    .iter()
+                    .position(|field| field == name)
+                    .ok_or_else(|| {
+                        CliError::input(format!("CSV header '{}' has no column '{}'", line, name))
+                    })?;
+                self.column = CsvColumn::Index(index);
+                self.header = Some(fields);
+                return Ok(None);
+            }
+        };
+
+        let mut ts_str = None;
+        let mut extra_fields = Vec::new();
+        for (i, field) in fields.into_iter().enumerate() {
+            if i == index {
+                ts_str = Some(field);
+                continue;
+            }
+            let name = self
+                .header
+                .as_ref()
+                .and_then(|header| header.get(i).cloned())
+                .unwrap_or_else(|| (i + 1).to_string());
+            extra_fields.push((name, field));
+        }
+        let ts_str = ts_str.ok_or_else(|| {
+            CliError::input(format!("CSV line '{}' has no column {}", line, index + 1))
+        })?;
+        Ok(Some(RawRecord {
+            ts_str: ts_str.trim().to_string(),
+            extra_fields,
+        }))
+    }
+}
+
+/// Split a CSV line into unquoted fields.
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+/// One field of a JSON object per line is the timestamp: a string, or a
+/// number for epoch formats.
+#[derive(Debug, Clone)]
+pub struct NdjsonDecoder {
+    field: String,
+}
+
+impl NdjsonDecoder {
+    pub fn new(field: &str) -> Self {
+        Self {
+            field: field.to_string(),
+        }
+    }
+}
+
+impl InputDecoder for NdjsonDecoder {
+    fn decode(&mut self, line: &str) -> CliResult<Option<RawRecord>> {
+        let object: serde_json::Map<String, serde_json::Value> = serde_json::from_str(line)
+            .map_err(|e| CliError::input(format!("Invalid NDJSON line '{}': {}", line, e)))?;
+
+        let mut ts_str = None;
+        let mut extra_fields = Vec::new();
+        for (name, value) in object {
+            let value = match value {
+                serde_json::Value::String(s) => s,
+                other => other.to_string(),
+            };
+            if name == self.field {
+                ts_str = Some(value);
+            } else {
+                extra_fields.push((name, value));
+            }
+        }
+        let ts_str = ts_str.ok_or_else(|| {
+            CliError::input(format!(
+                "NDJSON line '{}' has no field '{}'",
+                line, self.field
+            ))
+        })?;
+        Ok(Some(RawRecord {
+            ts_str,
+            extra_fields,
+        }))
+    }
+}
+
+/// A regular expression finds the timestamp: the group named `ts`, or else
+/// the first group, or else the whole match. Other named groups become extra
+/// fields. Lines that do not match are skipped.
+#[derive(Debug, Clone)]
+pub struct RegexDecoder {
+    regex: Regex,
+}
+
+impl RegexDecoder {
+    pub fn new(pattern: &str) -> CliResult<Self> {
+        let regex = Regex::new(pattern)
+            .map_err(|e| CliError::input(format!("Invalid regex '{}': {}", pattern, e)))?;
+        Ok(Self { regex })
+    }
+}
+
+impl InputDecoder for RegexDecoder {
+    fn decode(&mut self, line: &str) -> CliResult<Option<RawRecord>> {
+        let Some(captures) = self.regex.captures(line) else {
+            return Ok(None);
+        };
+        let ts = captures
+            .name("ts")
+            .or_else(|| captures.get(1))
+            .or_else(|| captures.get(0))
+            .map_or("", |m| m.as_str());
+        let extra_fields = self
+            .regex
+            .capture_names()
+            .flatten()
+            .filter(|&name| name != "ts")
+            .filter_map(|name| Some((name.to_string(), captures.name(name)?.as_str().to_string())))
+            .collect();
+        Ok(Some(RawRecord {
+            ts_str: ts.to_string(),
+            extra_fields,
+        }))
+    }
+}
@@ -0,0 +1,71 @@
+use std::io::Write;
+use std::process::ExitCode;
+
+use chrono_tz::Tz;
+use tzbucket_core::{Bucket, Interval, WeekStart};
+
+use crate::cli::KeyArgs;
+use crate::error::{CliError, CliResult, EXIT_SUCCESS, OutputFormat};
+use crate::input::open_input;
+use crate::shared::{parse_interval, parse_tz_or_input_error, parse_week_start, write_error};
+use crate::style::Style;
+
+/// Run `key`: print the bucket of every given key, such as keys stored in a
+/// database, one record per key.
+///
+/// A single `-` reads the keys from stdin, one per line.
+pub fn run_key(
+    args: KeyArgs,
+    output_format: OutputFormat,
+    mut out: impl Write,
+) -> CliResult<ExitCode> {
+    let tz = parse_tz_or_input_error(&args.tz)?;
+    let interval = parse_interval(&args.interval)?;
+    let week_start = parse_week_start(&args.week_start)?;
+
+    if args.keys.len() == 1 && args.keys[0] == "-" {
+        let mut lines = open_input("-")?;
+        while let Some((line_number, line)) = lines.next_numbered_line()? {
+            if line.is_empty() {
+                continue;
+            }
+            let bucket = rehydrate(line, tz, interval, week_start)
+                .map_err(|e| CliError::input(format!("stdin:{}: {}", line_number, e)))?;
+            write_bucket(&bucket, output_format, &mut out)?;
+        }
+    } else {
+        for key in &args.keys {
+            let bucket = rehydrate(key, tz, interval, week_start)?;
+            write_bucket(&bucket, output_format, &mut out)?;
+        }
+    }
+
+    Ok(ExitCode::from(EXIT_SUCCESS))
+}
+
+fn rehydrate(key: &str, tz: Tz, interval: Interval, week_start: WeekStart) -> CliResult<Bucket> {
+    Bucket::from_key(key, tz, interval, Some(week_start))
+        .map_err(|e| CliError::input(e.to_string()))
+}
+
+fn write_bucket(
+    bucket: &Bucket,
+    output_format: OutputFormat,
+    mut out: impl Write,
+) -> CliResult<()> {
+    match output_format {
+        OutputFormat::Json => {
+            let json = serde_json::to_string(bucket)
+                .map_err(|e| CliError::runtime(format!("Failed to serialize JSON: {}", e)))?;
+            writeln!(out, "{}", json).map_err(write_error)
+        }
+        OutputFormat::Text => writeln!(
+            out,
+            "{}: {} to {}",
+            Style::stdout().bucket_key(&bucket.key, &bucket.start_local, &bucket.end_local),
+            bucket.start_local,
+            bucket.end_local
+        )
+        .map_err(write_error),
+    }
+}
@@ -0,0 +1,56 @@
+use std::io::Write;
+use std::process::ExitCode;
+
+use tzbucket_core::{BucketKey, bucket_for_key};
+
+use crate::cli::KeysArgs;
+use crate::error::{CliError, CliResult, EXIT_SUCCESS, OutputFormat};
+use crate::range_cmd::generate_buckets_in_range;
+use crate::shared::{
+    parse_interval, parse_rfc3339_to_utc, parse_tz_or_input_error, parse_week_start, write_error,
+};
+
+pub fn run_keys(
+    args: KeysArgs,
+    output_format: OutputFormat,
+    mut out: impl Write,
+) -> CliResult<ExitCode> {
+    let tz = parse_tz_or_input_error(&args.tz)?;
+    let interval = parse_interval(&args.interval)?;
+    let week_start = parse_week_start(&args.week_start)?;
+
+    let from = bucket_for_key(&args.from, tz, interval, Some(week_start))
+        .map_err(|e| CliError::input(e.to_string()))?;
+    let to = bucket_for_key(&args.to, tz, interval, Some(week_start))
+        .map_err(|e| CliError::input(e.to_string()))?;
+
+    let start_utc = parse_rfc3339_to_utc(&from.start_utc)?;
+    let end_utc = parse_rfc3339_to_utc(&to.end_utc)?;
+    if start_utc >= end_utc {
+        return Err(CliError::input(format!(
+            "Invalid key range: from '{}' must not be later than to '{}'",
+            args.from, args.to
+        )));
+    }
+
+    let keys: Vec<BucketKey> =
+        generate_buckets_in_range(start_utc, end_utc, tz, interval, week_start, None)?
+            .into_iter()
+            .map(|bucket| bucket.key)
+            .collect();
+
+    match output_format {
+        OutputFormat::Json => {
+            let json = serde_json::to_string(&keys)
+                .map_err(|e| CliError::runtime(format!("Failed to serialize JSON: {}", e)))?;
+            writeln!(out, "{}", json).map_err(write_error)?;
+        }
+        OutputFormat::Text => {
+            for key in keys {
+                writeln!(out, "{}", key).map_err(write_error)?;
+            }
+        }
+    }
+
+    Ok(ExitCode::from(EXIT_SUCCESS))
+}
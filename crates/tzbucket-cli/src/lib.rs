@@ -0,0 +1,283 @@
+//! The `tzbucket` command line, as a library.
+//!
+//! The `tzbucket` binary is a thin wrapper around [`main`]. Other binaries
+//! can embed the same subcommands:
+//!
+//! - [`cli`] holds the clap definitions. [`cli::Cli`] can be a subcommand of
+//!   a larger tool, and [`run`] dispatches it exactly like `tzbucket` does.
+//! - [`run_bucket`], [`run_range`] and [`run_explain`] run one command from
+//!   its arguments, writing the output to any [`Write`](std::io::Write) and
+//!   returning the exit code or a [`CliError`](error::CliError). The
+//!   arguments can be built in code (see [`cli`]).
+//! - [`error`] maps errors to exit codes and renders the JSON or text error
+//!   envelope ([`error::render_error`]).
+//! - [`input`] is the line reader and the `--input-mode` decoders.
+//!
+//! ```no_run
+//! use std::process::ExitCode;
+//!
+//! use clap::Parser;
+//!
+//! #[derive(Parser)]
+//! enum Multitool {
+//!     /// DST-safe time bucketing
+//!     Tz(tzbucket_cli::cli::Cli),
+//!     // ... the team's own tools
+//! }
+//!
+//! fn main() -> ExitCode {
+//!     match Multitool::parse() {
+//!         Multitool::Tz(cli) => tzbucket_cli::run(cli, std::io::stdout().lock()),
+//!     }
+//! }
+//! ```
+//!
+//! Binaries can also register extra input decoders with
+//! [`input::register_decoder`] before dispatching, making them available to
+//! `--input-mode`:
+//!
+//! ```no_run
+//! use std::process::ExitCode;
+//!
+//! use tzbucket_cli::error::CliResult;
+//! use tzbucket_cli::input::{InputDecoder, RawRecord, register_decoder};
+//!
+//! /// `--input-mode syslog`: the timestamp is the first field.
+//! struct Syslog;
+//!
+//! impl InputDecoder for Syslog {
+//!     fn decode(&mut self, line: &str) -> CliResult<Option<RawRecord>> {
+//!         Ok(line.split_whitespace().next().map(RawRecord::new))
+//!     }
+//! }
+//!
+//! fn main() -> ExitCode {
+//!     register_decoder("syslog", |_| Ok(Box::new(Syslog)));
+//!     tzbucket_cli::main()
+//! }
+//! ```
+
+use std::io::{self, Write};
+use std::process::ExitCode;
+
+use clap::Parser;
+
+mod annotate;
+mod bucket_cmd;
+mod build_index_cmd;
+pub mod cli;
+mod completeness;
+mod count_cmd;
+mod diff_cmd;
+mod difftest_cmd;
+pub mod error;
+mod explain_cmd;
+pub mod input;
+mod key_cmd;
+mod keys_cmd;
+mod matrix_cmd;
+mod now_cmd;
+mod offsets_cmd;
+mod output;
+mod plan_cmd;
+mod range_cmd;
+mod report_cmd;
+mod resolve_cmd;
+mod rollup_cmd;
+mod same_rules_cmd;
+#[cfg(feature = "schemars")]
+mod schema_cmd;
+mod selftest_cmd;
+mod shard;
+mod shared;
+mod shift_cmd;
+mod skipped;
+mod span_cmd;
+mod style;
+mod summary;
+mod tzimpact_cmd;
+mod vectors_cmd;
+
+pub use bucket_cmd::run_bucket;
+use build_index_cmd::run_build_index;
+use cli::{Cli, Commands};
+use count_cmd::run_count;
+use diff_cmd::run_diff_output;
+use difftest_cmd::run_difftest;
+use error::{CliResult, OutputFormat, output_format_hint, parse_output_format, render_error};
+pub use explain_cmd::run_explain;
+use key_cmd::run_key;
+use keys_cmd::run_keys;
+use matrix_cmd::run_matrix;
+use now_cmd::run_now;
+use offsets_cmd::run_offsets;
+use output::Output;
+use plan_cmd::run_plan;
+pub use range_cmd::run_range;
+use report_cmd::run_report;
+use resolve_cmd::run_resolve;
+use rollup_cmd::run_rollup;
+use same_rules_cmd::run_same_rules;
+#[cfg(feature = "schemars")]
+use schema_cmd::run_schema;
+use selftest_cmd::run_selftest;
+use shared::clock_from_override;
+use shift_cmd::run_shift;
+use span_cmd::run_span;
+use style::{parse_color_choice, set_color_choice, set_output_to_file};
+use tzimpact_cmd::run_tzimpact;
+use vectors_cmd::run_vectors;
+
+/// Parse the command line, run the subcommand and return its exit code.
+pub fn main() -> ExitCode {
+    run(Cli::parse(), io::stdout().lock())
+}
+
+/// Run a parsed command line: apply the global options, dispatch the
+/// subcommand and render any error in its output format.
+///
+/// Output meant for stdout goes to `stdout` unless `--output` names a file;
+/// errors and warnings still go to stderr.
+pub fn run(cli: Cli, stdout: impl Write) -> ExitCode {
+    match parse_color_choice(&cli.color) {
+        Ok(choice) => set_color_choice(choice),
+        Err(err) => return render_error(&err, OutputFormat::Text),
+    }
+    let out = Output::new(cli.output.as_deref(), cli.append, stdout);
+    if out.is_file() {
+        set_output_to_file();
+    }
+    let now_override = cli.now_override.as_deref();
+    let clock = || clock_from_override(now_override);
+
+    match cli.command {
+        Commands::Bucket(args) => with_output(args.output_format, out, |out| {
+            run_bucket(args, clock()?.as_ref(), out)
+        }),
+        Commands::Range(args) => with_output(args.output_format, out, |out| {
+            run_range(args, clock()?.as_ref(), out)
+        }),
+        Commands::Explain(args) => {
+            with_output(args.output_format, out, |out| run_explain(args, out))
+        }
+        Commands::Now(args) => {
+            with_output_format(args.output_format.clone(), out, |output_format, out| {
+                run_now(args, output_format, clock()?.as_ref(), out)
+            })
+        }
+        Commands::Matrix(args) => {
+            with_output_format(args.output_format.clone(), out, |output_format, out| {
+                run_matrix(args, output_format, clock()?.as_ref(), out)
+            })
+        }
+        // `offsets` also accepts `csv`; errors render as text for it.
+        Commands::Offsets(args) => {
+            with_output(output_format_hint(&args.output_format), out, |out| {
+                run_offsets(args, out)
+            })
+        }
+        Commands::SameRules(args) => {
+            with_output_format(args.output_format.clone(), out, |output_format, out| {
+                run_same_rules(args, output_format, out)
+            })
+        }
+        Commands::Key(args) => {
+            with_output_format(args.output_format.clone(), out, |output_format, out| {
+                run_key(args, output_format, out)
+            })
+        }
+        Commands::Shift(args) => {
+            with_output_format(args.output_format.clone(), out, |output_format, out| {
+                run_shift(args, output_format, out)
+            })
+        }
+        Commands::Span(args) => {
+            with_output_format(args.output_format.clone(), out, |output_format, out| {
+                run_span(args, output_format, out)
+            })
+        }
+        Commands::Keys(args) => {
+            with_output_format(args.output_format.clone(), out, |output_format, out| {
+                run_keys(args, output_format, out)
+            })
+        }
+        // `resolve` also accepts `csv`; errors render as text for it.
+        Commands::Resolve(args) => {
+            with_output(output_format_hint(&args.output_format), out, |out| {
+                run_resolve(args, out)
+            })
+        }
+        // `count` also accepts `calendar` and `vega`; errors render as text for them.
+        Commands::Count(args) => with_output(output_format_hint(&args.output_format), out, |out| {
+            run_count(args, out)
+        }),
+        Commands::DiffOutput(args) => {
+            with_output_format(args.output_format.clone(), out, |output_format, out| {
+                run_diff_output(args, output_format, out)
+            })
+        }
+        Commands::Tzimpact(args) => {
+            with_output_format(args.output_format.clone(), out, |output_format, out| {
+                run_tzimpact(args, output_format, out)
+            })
+        }
+        Commands::Rollup(args) => {
+            with_output_format(args.output_format.clone(), out, |output_format, out| {
+                run_rollup(args, output_format, out)
+            })
+        }
+        Commands::Plan(args) => {
+            with_output_format(args.output_format.clone(), out, |output_format, out| {
+                run_plan(args, output_format, out)
+            })
+        }
+        // The report is HTML; errors render as text.
+        Commands::Report(args) => with_output(OutputFormat::Text, out, |out| run_report(args, out)),
+        Commands::BuildIndex(args) => {
+            with_output(OutputFormat::Text, out, |out| run_build_index(args, out))
+        }
+        Commands::Vectors(args) => {
+            with_output(OutputFormat::Json, out, |out| run_vectors(args, out))
+        }
+        Commands::Selftest(args) => {
+            with_output_format(args.output_format.clone(), out, |output_format, out| {
+                run_selftest(args, output_format, out)
+            })
+        }
+        Commands::Difftest(args) => {
+            with_output_format(args.output_format.clone(), out, |output_format, out| {
+                run_difftest(args, output_format, out)
+            })
+        }
+        #[cfg(feature = "schemars")]
+        Commands::Schema(args) => with_output(OutputFormat::Json, out, |out| run_schema(args, out)),
+    }
+}
+
+/// Run a command against `out` and commit its output if it succeeds. A
+/// failed command discards its output, leaving an existing `--output` file
+/// untouched, and its error is rendered in `error_format`.
+fn with_output<W: Write>(
+    error_format: OutputFormat,
+    mut out: Output<W>,
+    command: impl FnOnce(&mut Output<W>) -> CliResult<ExitCode>,
+) -> ExitCode {
+    let result = command(&mut out).and_then(|code| {
+        out.commit()?;
+        Ok(code)
+    });
+    result.unwrap_or_else(|err| render_error(&err, error_format))
+}
+
+/// [`with_output`] for a command whose `--output-format` is still a string:
+/// the command gets it parsed, and errors (including an invalid format)
+/// render as JSON if it reads `json`, as text otherwise.
+fn with_output_format<W: Write>(
+    output_format: String,
+    out: Output<W>,
+    command: impl FnOnce(OutputFormat, &mut Output<W>) -> CliResult<ExitCode>,
+) -> ExitCode {
+    with_output(output_format_hint(&output_format), out, |out| {
+        command(parse_output_format(&output_format)?, out)
+    })
+}
@@ -0,0 +1,117 @@
+use std::io::Write;
+use std::process::ExitCode;
+
+use chrono::Offset;
+use serde::Serialize;
+use tzbucket_core::tz::{format_rfc3339, format_rfc3339_utc};
+use tzbucket_core::{BucketKey, Clock, Interval, TimestampFormat, compute_bucket, parse_timestamp};
+
+use crate::cli::MatrixArgs;
+use crate::error::{CliError, CliResult, EXIT_SUCCESS, OutputFormat};
+use crate::input::open_input;
+use crate::shared::{parse_interval, parse_tz_or_input_error, parse_week_start, write_error};
+
+/// Run `matrix`: the local time, offset and bucket of one instant in every
+/// zone of `--tz-list`, in list order.
+///
+/// `clock` supplies the instant when `--at` is not given.
+pub fn run_matrix(
+    args: MatrixArgs,
+    output_format: OutputFormat,
+    clock: &dyn Clock,
+    mut out: impl Write,
+) -> CliResult<ExitCode> {
+    let interval = parse_interval(&args.interval)?;
+    let week_start = parse_week_start(&args.week_start)?;
+    let at = match &args.at {
+        Some(at) => parse_timestamp(at, TimestampFormat::Rfc3339)
+            .map_err(|e| CliError::input(format!("Invalid --at timestamp: {}", e)))?,
+        None => clock.now(),
+    };
+
+    let mut zones = Vec::new();
+    let mut lines = open_input(&args.tz_list)?;
+    while let Some((line_number, line)) = lines.next_numbered_line()? {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let tz = parse_tz_or_input_error(line)
+            .map_err(|e| CliError::input(format!("{}:{}: {}", args.tz_list, line_number, e)))?;
+        let local = at.with_timezone(&tz);
+        let bucket = compute_bucket(at, tz, interval, Some(week_start));
+        zones.push(MatrixRow {
+            tz: tz.to_string(),
+            local_time: format_rfc3339(&local),
+            offset: local.offset().fix().to_string(),
+            key: bucket.key,
+            start_utc: bucket.start_utc,
+            end_utc: bucket.end_utc,
+        });
+    }
+    if zones.is_empty() {
+        return Err(CliError::input(format!(
+            "Zone list '{}' names no zones",
+            args.tz_list
+        )));
+    }
+
+    let matrix = Matrix {
+        at_utc: format_rfc3339_utc(&at),
+        interval,
+        zones,
+    };
+    match output_format {
+        OutputFormat::Json => {
+            let json = serde_json::to_string_pretty(&matrix)
+                .map_err(|e| CliError::runtime(format!("Failed to serialize JSON: {}", e)))?;
+            writeln!(out, "{}", json).map_err(write_error)?;
+        }
+        OutputFormat::Text => write_table(&matrix, &mut out).map_err(write_error)?,
+    }
+
+    Ok(ExitCode::from(EXIT_SUCCESS))
+}
+
+/// One row per zone, columns padded to the widest entry.
+fn write_table(matrix: &Matrix, out: &mut impl Write) -> std::io::Result<()> {
+    let tz_width = matrix
+        .zones
+        .iter()
+        .map(|row| row.tz.len())
+        .max()
+        .unwrap_or(0);
+    let time_width = matrix
+        .zones
+        .iter()
+        .map(|row| row.local_time.len())
+        .max()
+        .unwrap_or(0);
+    for row in &matrix.zones {
+        writeln!(
+            out,
+            "{:<tz_width$}  {:<time_width$}  {}  {}",
+            row.tz, row.local_time, row.offset, row.key
+        )?;
+    }
+    Ok(())
+}
+
+#[derive(Debug, Serialize)]
+struct Matrix {
+    at_utc: String,
+    interval: Interval,
+    zones: Vec<MatrixRow>,
+}
+
+#[derive(Debug, Serialize)]
+struct MatrixRow {
+    tz: String,
+    /// `at` in the zone's local time, with offset.
+    local_time: String,
+    /// UTC offset at `at`.
+    offset: String,
+    /// Key of the bucket containing `at` in this zone.
+    key: BucketKey,
+    start_utc: String,
+    end_utc: String,
+}
@@ -0,0 +1,54 @@
+use std::io::Write;
+use std::process::ExitCode;
+
+use tzbucket_core::{Clock, Interval, compute_current_bucket};
+
+use crate::cli::NowArgs;
+use crate::error::{CliError, CliResult, EXIT_SUCCESS, OutputFormat};
+use crate::shared::{
+    Versioned, parse_anchored_interval, parse_schema_version, parse_tz_or_input_error,
+    parse_week_options, write_error,
+};
+use crate::style::Style;
+
+pub fn run_now(
+    args: NowArgs,
+    output_format: OutputFormat,
+    clock: &dyn Clock,
+    mut out: impl Write,
+) -> CliResult<ExitCode> {
+    let tz = parse_tz_or_input_error(&args.tz)?;
+    let interval = parse_anchored_interval(&args.interval, args.anchor.as_deref())?;
+    let (week_start, week_key) = parse_week_options(
+        &args.week_start,
+        &args.week_key,
+        args.week_scheme.as_deref(),
+    )?;
+    let schema_version = parse_schema_version(&args.schema_version)?;
+
+    let mut result = compute_current_bucket(clock, tz, interval, Some(week_start));
+    if interval == Interval::Week {
+        result.bucket = result.bucket.with_week_key(week_start, week_key);
+    }
+
+    match output_format {
+        OutputFormat::Json => {
+            let json = serde_json::to_string(&Versioned::new(&result, schema_version))
+                .map_err(|e| CliError::runtime(format!("Failed to serialize JSON: {}", e)))?;
+            writeln!(out, "{}", json).map_err(write_error)?;
+        }
+        OutputFormat::Text => {
+            let bucket = &result.bucket;
+            writeln!(
+                out,
+                "{} -> {} to {}",
+                Style::stdout().bucket_key(&bucket.key, &bucket.start_local, &bucket.end_local),
+                bucket.start_local,
+                bucket.end_local
+            )
+            .map_err(write_error)?;
+        }
+    }
+
+    Ok(ExitCode::from(EXIT_SUCCESS))
+}
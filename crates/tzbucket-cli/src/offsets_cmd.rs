@@ -0,0 +1,92 @@
+use std::io::Write;
+use std::process::ExitCode;
+
+use chrono_tz::Tz;
+use serde::Serialize;
+use tzbucket_core::tz::{OffsetPeriod, format_rfc3339_utc, offset_history};
+
+use crate::cli::OffsetsArgs;
+use crate::error::{CliError, CliResult, EXIT_SUCCESS};
+use crate::shared::{json_line, parse_tz_or_input_error, parse_year_range, write_error};
+
+/// Output layouts supported by `offsets` (CSV is specific to this command).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OffsetsFormat {
+    Json,
+    Csv,
+    Text,
+}
+
+const CSV_HEADER: &str = "tz,effective_from_utc,offset,abbrev,is_dst";
+
+/// Run `offsets`: one row per period of constant UTC offset, abbreviation
+/// and DST flag in `--tz` between `--from` and `--to`.
+pub fn run_offsets(args: OffsetsArgs, mut out: impl Write) -> CliResult<ExitCode> {
+    let format = parse_offsets_format(&args.output_format)?;
+    let tz = parse_tz_or_input_error(&args.tz)?;
+    let (from, to) = parse_year_range(&args.from, &args.to)?;
+
+    if format == OffsetsFormat::Csv {
+        writeln!(out, "{}", CSV_HEADER).map_err(write_error)?;
+    }
+    for period in offset_history(tz, from, to) {
+        let record = OffsetRecord::new(tz, &period);
+        match format {
+            OffsetsFormat::Json => {
+                let json = json_line(&record)?;
+                writeln!(out, "{}", json).map_err(write_error)?;
+            }
+            OffsetsFormat::Csv => writeln!(
+                out,
+                "{},{},{},{},{}",
+                record.tz, record.effective_from_utc, record.offset, record.abbrev, record.is_dst
+            )
+            .map_err(write_error)?,
+            OffsetsFormat::Text => writeln!(
+                out,
+                "{}  {}  {}{}",
+                record.effective_from_utc,
+                record.offset,
+                record.abbrev,
+                if record.is_dst { " (DST)" } else { "" }
+            )
+            .map_err(write_error)?,
+        }
+    }
+
+    Ok(ExitCode::from(EXIT_SUCCESS))
+}
+
+fn parse_offsets_format(s: &str) -> CliResult<OffsetsFormat> {
+    match s.to_lowercase().as_str() {
+        "json" => Ok(OffsetsFormat::Json),
+        "csv" => Ok(OffsetsFormat::Csv),
+        "text" => Ok(OffsetsFormat::Text),
+        _ => Err(CliError::input(format!(
+            "Invalid output_format '{}'. Expected: json, csv, text",
+            s
+        ))),
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct OffsetRecord {
+    tz: String,
+    /// First instant of the period; the first record starts at `--from`.
+    effective_from_utc: String,
+    offset: String,
+    abbrev: String,
+    is_dst: bool,
+}
+
+impl OffsetRecord {
+    fn new(tz: Tz, period: &OffsetPeriod) -> Self {
+        Self {
+            tz: tz.to_string(),
+            effective_from_utc: format_rfc3339_utc(&period.from_utc),
+            offset: period.offset.to_string(),
+            abbrev: period.abbreviation.clone(),
+            is_dst: period.is_dst,
+        }
+    }
+}
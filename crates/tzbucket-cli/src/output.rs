@@ -0,0 +1,135 @@
+//! Command output: stdout (or the writer standing in for it), or the file
+//! named by the global `--output`.
+//!
+//! A file is written to a temporary file next to it and renamed over the
+//! target only when the command succeeds, so a run that fails or is killed
+//! leaves the previous file intact rather than half-written. With `--append`
+//! the temporary file starts as a copy of the existing one.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
+
+use crate::error::{CliError, CliResult};
+
+/// Where a command writes its output.
+pub enum Output<W> {
+    Stdout(W),
+    File(AtomicFile),
+}
+
+impl<W: Write> Output<W> {
+    /// `stdout`, or `path` (unless it is `-`) replaced atomically on
+    /// [`commit`](Self::commit).
+    pub fn new(path: Option<&str>, append: bool, stdout: W) -> Self {
+        match path {
+            Some(path) if path != "-" => Output::File(AtomicFile::new(path, append)),
+            _ => Output::Stdout(stdout),
+        }
+    }
+
+    /// Whether output goes to a file rather than stdout.
+    pub fn is_file(&self) -> bool {
+        matches!(self, Output::File(_))
+    }
+
+    /// Finish a successful run: flush stdout, or move the file into place.
+    pub fn commit(self) -> CliResult<()> {
+        match self {
+            Output::Stdout(mut stdout) => stdout
+                .flush()
+                .map_err(|e| CliError::runtime(format!("Failed to write output: {}", e))),
+            Output::File(file) => file.commit(),
+        }
+    }
+}
+
+impl<W: Write> Write for Output<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Output::Stdout(stdout) => stdout.write(buf),
+            Output::File(file) => file.writer()?.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Output::Stdout(stdout) => stdout.flush(),
+            Output::File(file) => file.writer()?.flush(),
+        }
+    }
+}
+
+/// A file written through a temporary sibling and renamed into place.
+///
+/// The temporary file is created on the first write, so that errors surface
+/// from the command (in its output format), and removed on drop unless
+/// committed.
+pub struct AtomicFile {
+    path: PathBuf,
+    temp: PathBuf,
+    append: bool,
+    writer: Option<BufWriter<File>>,
+}
+
+impl AtomicFile {
+    fn new(path: &str, append: bool) -> Self {
+        let path = PathBuf::from(path);
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let temp = path.with_file_name(format!(".{}.{}.tmp", name, std::process::id()));
+        Self {
+            path,
+            temp,
+            append,
+            writer: None,
+        }
+    }
+
+    fn writer(&mut self) -> io::Result<&mut BufWriter<File>> {
+        if self.writer.is_none() {
+            let file = self.create().map_err(|e| {
+                io::Error::new(
+                    e.kind(),
+                    format!("cannot create '{}': {}", self.temp.display(), e),
+                )
+            })?;
+            self.writer = Some(BufWriter::new(file));
+        }
+        Ok(self.writer.as_mut().expect("writer was just created"))
+    }
+
+    fn create(&self) -> io::Result<File> {
+        if self.append && self.path.exists() {
+            fs::copy(&self.path, &self.temp)?;
+            OpenOptions::new().append(true).open(&self.temp)
+        } else {
+            File::create(&self.temp)
+        }
+    }
+
+    fn commit(mut self) -> CliResult<()> {
+        self.persist().map_err(|e| {
+            CliError::runtime(format!("Failed to write '{}': {}", self.path.display(), e))
+        })
+    }
+
+    fn persist(&mut self) -> io::Result<()> {
+        let writer = self.writer()?;
+        writer.flush()?;
+        writer.get_ref().sync_all()?;
+        fs::rename(&self.temp, &self.path)?;
+        self.writer = None;
+        Ok(())
+    }
+}
+
+impl Drop for AtomicFile {
+    fn drop(&mut self) {
+        if self.writer.take().is_some() {
+            let _ = fs::remove_file(&self.temp);
+        }
+    }
+}
@@ -0,0 +1,250 @@
+use std::fs::File;
+use std::io::{BufReader, Write};
+use std::process::ExitCode;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tzbucket_core::tz::format_rfc3339_utc;
+
+use crate::cli::PlanArgs;
+use crate::error::{CliError, CliResult, EXIT_SUCCESS, OutputFormat};
+use crate::input::LineReader;
+use crate::shared::{parse_rfc3339_to_utc, write_error};
+
+pub fn run_plan(
+    args: PlanArgs,
+    output_format: OutputFormat,
+    mut out: impl Write,
+) -> CliResult<ExitCode> {
+    if args.target_rows_per_partition == 0 {
+        return Err(CliError::input(
+            "Invalid --target-rows-per-partition '0'. Expected: a positive integer",
+        ));
+    }
+
+    let rows = read_counts(&args.counts)?;
+    let plan = PartitionPlan {
+        target_rows_per_partition: args.target_rows_per_partition,
+        partitions: plan_partitions(&rows, args.target_rows_per_partition),
+    };
+
+    match output_format {
+        OutputFormat::Json => {
+            let json = serde_json::to_string_pretty(&plan)
+                .map_err(|e| CliError::runtime(format!("Failed to serialize JSON: {}", e)))?;
+            writeln!(out, "{}", json).map_err(write_error)?;
+        }
+        OutputFormat::Text => {
+            for partition in &plan.partitions {
+                let split = partition
+                    .split
+                    .as_ref()
+                    .map(|s| format!(" (split {}/{})", s.part, s.of))
+                    .unwrap_or_default();
+                writeln!(
+                    out,
+                    "{} to {}: {} rows [{}]{}",
+                    partition.start_utc,
+                    partition.end_utc,
+                    partition.estimated_rows,
+                    partition.buckets.join(", "),
+                    split
+                )
+                .map_err(write_error)?;
+            }
+        }
+    }
+
+    Ok(ExitCode::from(EXIT_SUCCESS))
+}
+
+/// One bucket row of the counts CSV.
+struct BucketCount {
+    key: String,
+    start_utc: DateTime<Utc>,
+    end_utc: DateTime<Utc>,
+    count: u64,
+}
+
+#[derive(Debug, Serialize)]
+struct PartitionPlan {
+    target_rows_per_partition: u64,
+    partitions: Vec<Partition>,
+}
+
+#[derive(Debug, Serialize)]
+struct Partition {
+    start_utc: String,
+    end_utc: String,
+    estimated_rows: u64,
+    buckets: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    split: Option<Split>,
+}
+
+#[derive(Debug, Serialize)]
+struct Split {
+    part: u64,
+    of: u64,
+}
+
+/// Read `start_utc,end_utc,count` rows (plus an optional `key` column) from a
+/// CSV file with a header line. Rows are returned sorted by start.
+fn read_counts(path: &str) -> CliResult<Vec<BucketCount>> {
+    let file = File::open(path)
+        .map_err(|e| CliError::runtime(format!("Failed to open file '{}': {}", path, e)))?;
+    let mut lines = LineReader::new(BufReader::new(file));
+
+    let header = match lines.next_line()? {
+        Some(line) => line.to_string(),
+        None => return Err(CliError::input(format!("{}: missing CSV header", path))),
+    };
+    let columns: Vec<&str> = header.split(',').map(str::trim).collect();
+    let column = |name: &str| columns.iter().position(|c| *c == name);
+    let (Some(start_col), Some(end_col), Some(count_col)) =
+        (column("start_utc"), column("end_utc"), column("count"))
+    else {
+        return Err(CliError::input(format!(
+            "{}: CSV header must contain start_utc, end_utc and count",
+            path
+        )));
+    };
+    let key_col = column("key");
+
+    let mut rows = Vec::new();
+    while let Some((line_number, line)) = lines.next_numbered_line()? {
+        if line.is_empty() {
+            continue;
+        }
+
+        let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+        let field = |col: usize, name: &str| {
+            fields.get(col).copied().ok_or_else(|| {
+                CliError::input(format!(
+                    "{}:{}: missing column '{}'",
+                    path, line_number, name
+                ))
+            })
+        };
+        let timestamp = |col: usize, name: &str| {
+            let value = field(col, name)?;
+            parse_rfc3339_to_utc(value).map_err(|_| {
+                CliError::input(format!(
+                    "{}:{}: invalid {} '{}'",
+                    path, line_number, name, value
+                ))
+            })
+        };
+
+        let start_utc = timestamp(start_col, "start_utc")?;
+        let end_utc = timestamp(end_col, "end_utc")?;
+        let count_text = field(count_col, "count")?;
+        let count = count_text.parse::<u64>().map_err(|_| {
+            CliError::input(format!(
+                "{}:{}: invalid count '{}'",
+                path, line_number, count_text
+            ))
+        })?;
+        if end_utc <= start_utc {
+            return Err(CliError::input(format!(
+                "{}:{}: end_utc must be later than start_utc",
+                path, line_number
+            )));
+        }
+        let key = match key_col {
+            Some(col) => field(col, "key")?.to_string(),
+            None => format_rfc3339_utc(&start_utc),
+        };
+
+        rows.push(BucketCount {
+            key,
+            start_utc,
+            end_utc,
+            count,
+        });
+    }
+
+    rows.sort_by_key(|row| row.start_utc);
+    if let Some(pair) = rows
+        .windows(2)
+        .find(|pair| pair[1].start_utc < pair[0].end_utc)
+    {
+        return Err(CliError::input(format!(
+            "{}: buckets '{}' and '{}' overlap",
+            path, pair[0].key, pair[1].key
+        )));
+    }
+
+    Ok(rows)
+}
+
+/// Greedily merge adjacent buckets up to `target` rows; split buckets above
+/// `target` into equal-duration parts, assuming rows are spread evenly.
+///
+/// Buckets separated by a gap are never merged, so every partition covers a
+/// contiguous UTC range.
+fn plan_partitions(rows: &[BucketCount], target: u64) -> Vec<Partition> {
+    let mut partitions = Vec::new();
+    let mut current: Option<Partition> = None;
+    let mut current_end = None;
+
+    for row in rows {
+        if row.count > target {
+            partitions.extend(current.take());
+            partitions.extend(split_bucket(row, target));
+            continue;
+        }
+
+        if let Some(partition) = &current
+            && (current_end != Some(row.start_utc) || partition.estimated_rows + row.count > target)
+        {
+            partitions.extend(current.take());
+        }
+
+        let partition = current.get_or_insert_with(|| Partition {
+            start_utc: format_rfc3339_utc(&row.start_utc),
+            end_utc: String::new(),
+            estimated_rows: 0,
+            buckets: Vec::new(),
+            split: None,
+        });
+        partition.end_utc = format_rfc3339_utc(&row.end_utc);
+        partition.estimated_rows += row.count;
+        partition.buckets.push(row.key.clone());
+        current_end = Some(row.end_utc);
+    }
+
+    partitions.extend(current);
+    partitions
+}
+
+/// Parts never get shorter than one second, so a bucket is split into at
+/// most as many parts as it has seconds.
+fn split_bucket(row: &BucketCount, target: u64) -> Vec<Partition> {
+    let seconds = (row.end_utc - row.start_utc).num_seconds();
+    let parts = row.count.div_ceil(target).min(seconds.max(1) as u64);
+    let boundary = |part: u64| {
+        let offset = (i128::from(seconds) * i128::from(part) / i128::from(parts)) as i64;
+        row.start_utc + chrono::Duration::seconds(offset)
+    };
+
+    (0..parts)
+        .map(|part| {
+            let end = if part + 1 == parts {
+                row.end_utc
+            } else {
+                boundary(part + 1)
+            };
+            Partition {
+                start_utc: format_rfc3339_utc(&boundary(part)),
+                end_utc: format_rfc3339_utc(&end),
+                estimated_rows: row.count / parts + u64::from(part < row.count % parts),
+                buckets: vec![row.key.clone()],
+                split: Some(Split {
+                    part: part + 1,
+                    of: parts,
+                }),
+            }
+        })
+        .collect()
+}
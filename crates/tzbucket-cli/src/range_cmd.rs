@@ -1,24 +1,39 @@
+use std::io::Write;
 use std::process::ExitCode;
 
-use chrono::{DateTime, Datelike, TimeZone, Utc};
+use chrono::{DateTime, TimeDelta, Utc};
 use chrono_tz::Tz;
 use serde::Serialize;
-use tzbucket_core::{Interval, TimestampFormat, WeekStart, compute_bucket, parse_timestamp};
+use tzbucket_core::{
+    Bucket, BucketKey, BucketSeries, BucketSpec, Clock, DstInfo, Interval, MonthClamp, WeekNumbers,
+    WeekStart, buckets_between, compute_bucket,
+};
 
 use crate::cli::RangeArgs;
 use crate::error::{CliError, CliResult, EXIT_SUCCESS, OutputFormat};
 use crate::shared::{
-    parse_interval, parse_rfc3339_to_utc, parse_tz_or_input_error, parse_week_start,
+    DayOffset, RangeBound, Versioned, check_day_offset, check_legacy_tz, parse_rfc3339_to_utc,
+    week_options, write_error,
 };
+use crate::style::Style;
 
-pub fn run_range(args: RangeArgs, output_format: OutputFormat) -> CliResult<ExitCode> {
-    let tz = parse_tz_or_input_error(&args.tz)?;
-    let interval = parse_interval(&args.interval)?;
-    let week_start = parse_week_start(&args.week_start)?;
+/// Run `range`: write every bucket intersecting `[args.start, args.end)`,
+/// or the plan with `--dry-run`, to `out`.
+///
+/// `clock` is the current time for `humantime` bounds such as `2 weeks ago`.
+pub fn run_range(args: RangeArgs, clock: &dyn Clock, mut out: impl Write) -> CliResult<ExitCode> {
+    let output_format = args.output_format;
+    let tz = args.tz;
+    let canonical_tz = check_legacy_tz(tz, args.strict_tz, output_format)?;
+    let interval = args.interval.anchored(args.anchor)?;
+    let (week_start, week_key) = week_options(args.week_start, args.week_key, args.week_scheme);
+    let label = args.label;
+    let schema_version = args.schema_version;
+    let day_offset = check_day_offset(args.day_offset, args.policy(), interval)?;
 
-    let start_utc = parse_timestamp(&args.start, TimestampFormat::Rfc3339)
+    let (start_utc, start_format) = resolve_bound(&args.start, tz, clock)
         .map_err(|e| CliError::input(format!("Invalid start timestamp: {}", e)))?;
-    let end_utc = parse_timestamp(&args.end, TimestampFormat::Rfc3339)
+    let (end_utc, end_format) = resolve_bound(&args.end, tz, clock)
         .map_err(|e| CliError::input(format!("Invalid end timestamp: {}", e)))?;
 
     if start_utc >= end_utc {
@@ -28,158 +43,308 @@ pub fn run_range(args: RangeArgs, output_format: OutputFormat) -> CliResult<Exit
         )));
     }
 
-    let buckets = generate_buckets_in_range(start_utc, end_utc, tz, interval, week_start)?;
-
-    match output_format {
-        OutputFormat::Json => {
-            let json = serde_json::to_string_pretty(&buckets)
-                .map_err(|e| CliError::runtime(format!("Failed to serialize JSON: {}", e)))?;
-            println!("{}", json);
+    let decorate = |mut bucket: Bucket| {
+        if args.week_numbers && interval == Interval::Week {
+            bucket = bucket.with_week_numbers();
         }
-        OutputFormat::Text => {
-            for bucket in buckets {
-                println!(
-                    "{}: {} to {}",
-                    bucket.key, bucket.start_local, bucket.end_local
-                );
-            }
+        if interval == Interval::Week {
+            bucket = bucket.with_week_key(week_start, week_key);
+        }
+        if let Some(point) = label {
+            bucket = bucket.with_label(point);
+        }
+        if args.dst_info {
+            bucket = bucket.with_dst_info(tz);
         }
+        if args.inclusive_end {
+            bucket = bucket.with_inclusive_end(tz);
+        }
+        Versioned::new(RangeBucket::from(bucket), schema_version)
+    };
+
+    if args.dry_run {
+        let (first, last, bucket_count) =
+            plan_range(start_utc, end_utc, tz, interval, week_start, day_offset)?;
+        let first = decorate(first);
+        let plan = RangePlan {
+            tz: tz.to_string(),
+            canonical_tz: canonical_tz.map(|tz| tz.to_string()),
+            interval,
+            start_format,
+            end_format,
+            bucket_count,
+            first_start_utc: first.record.start_utc.clone(),
+            last_end_utc: last.end_utc,
+            estimated_output_bytes: estimate_output_bytes(&first, bucket_count, output_format)?,
+        };
+        write_plan(&plan, output_format, &mut out)?;
+    } else {
+        let buckets: Vec<Versioned<RangeBucket>> =
+            generate_buckets_in_range(start_utc, end_utc, tz, interval, week_start, day_offset)?
+                .into_iter()
+                .map(decorate)
+                .collect();
+        let output = render_buckets(&buckets, output_format)?;
+        out.write_all(output.as_bytes()).map_err(write_error)?;
     }
+    out.flush().map_err(write_error)?;
 
     Ok(ExitCode::from(EXIT_SUCCESS))
 }
 
-#[derive(Debug, Serialize)]
-struct RangeBucket {
-    key: String,
-    start_local: String,
-    end_local: String,
-    start_utc: String,
-    end_utc: String,
+/// How a range bound was parsed, reported by `--dry-run`.
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum BoundFormat {
+    Rfc3339,
+    #[cfg(feature = "humantime")]
+    Humantime,
+}
+
+impl std::fmt::Display for BoundFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            BoundFormat::Rfc3339 => "rfc3339",
+            #[cfg(feature = "humantime")]
+            BoundFormat::Humantime => "humantime",
+        })
+    }
+}
+
+/// The instant of a range bound, evaluating a `humantime` expression in `tz`
+/// at the current time.
+fn resolve_bound(
+    bound: &RangeBound,
+    tz: Tz,
+    clock: &dyn Clock,
+) -> tzbucket_core::Result<(DateTime<Utc>, BoundFormat)> {
+    #[cfg(not(feature = "humantime"))]
+    let _ = (tz, clock);
+    match bound {
+        RangeBound::Instant(at) => Ok((*at, BoundFormat::Rfc3339)),
+        #[cfg(feature = "humantime")]
+        RangeBound::Human(expr) => tzbucket_core::human::parse_human(expr, tz, clock.now())
+            .map(|at| (at, BoundFormat::Humantime)),
+    }
 }
 
-fn generate_buckets_in_range(
+/// The first and last bucket of the range and how many there are, without
+/// generating the ones in between: sub-day intervals, whose buckets a
+/// transition can skip or repeat, are stepped through; days and longer are
+/// counted by key position with `buckets_between`, which steps over skipped
+/// days and counts a 23- or 25-hour day once.
+fn plan_range(
     start_utc: DateTime<Utc>,
     end_utc: DateTime<Utc>,
     tz: Tz,
     interval: Interval,
     week_start: WeekStart,
-) -> CliResult<Vec<RangeBucket>> {
-    let mut buckets = Vec::new();
+    day_offset: Option<DayOffset>,
+) -> CliResult<(Bucket, Bucket, usize)> {
+    // The last bucket is the one containing the last instant before the end.
+    let last_instant = end_utc - TimeDelta::nanoseconds(1);
+    let spec = BucketSpec::new(tz, interval).with_week_start(week_start);
+    let (first, last) = match day_offset {
+        Some(offset) => (
+            offset.bucket(start_utc, tz, interval, week_start)?,
+            offset.bucket(last_instant, tz, interval, week_start)?,
+        ),
+        None => (
+            compute_bucket(start_utc, tz, interval, Some(week_start)),
+            compute_bucket(last_instant, tz, interval, Some(week_start)),
+        ),
+    };
 
-    let start_local = start_utc.with_timezone(&tz);
-    let end_local = end_utc.with_timezone(&tz);
+    let count = match interval {
+        Interval::Minutes(_) | Interval::Hour | Interval::Hours(_) => {
+            BucketSeries::new(start_utc, &spec).until(end_utc).count()
+        }
+        _ => {
+            let between = buckets_between(&first.key, &last.key, tz, interval, Some(week_start))
+                .map_err(|e| CliError::runtime(e.to_string()))?;
+            between as usize + 1
+        }
+    };
 
-    match interval {
-        Interval::Day => {
-            let mut current_date = start_local.date_naive();
-            let end_date = end_local.date_naive();
+    Ok((first, last, count))
+}
 
-            while current_date <= end_date {
-                let bucket = compute_bucket_for_date(current_date, tz, interval, week_start)?;
-                let bucket_start_utc = parse_rfc3339_to_utc(&bucket.start_utc)?;
-                let bucket_end_utc = parse_rfc3339_to_utc(&bucket.end_utc)?;
+/// The size of the rendered range: the first bucket's record size times the
+/// count plus the JSON array framing. Exact unless record widths vary within
+/// the range (week numbers passing 9, `dst_change` flipping).
+fn estimate_output_bytes(
+    first: &Versioned<RangeBucket>,
+    count: usize,
+    output_format: OutputFormat,
+) -> CliResult<usize> {
+    let single = render_buckets(std::slice::from_ref(first), output_format)?.len();
+    Ok(match output_format {
+        OutputFormat::Text => single * count,
+        // "[\n", the records separated by ",\n", then "\n]\n".
+        OutputFormat::Json => {
+            let framing = "[\n\n]\n".len();
+            framing + (single - framing) * count + ",\n".len() * (count - 1)
+        }
+    })
+}
 
-                if bucket_start_utc < end_utc && bucket_end_utc > start_utc {
-                    buckets.push(bucket);
-                }
+fn render_buckets(
+    buckets: &[Versioned<RangeBucket>],
+    output_format: OutputFormat,
+) -> CliResult<String> {
+    let mut output = String::new();
 
-                current_date += chrono::Duration::days(1);
-            }
+    match output_format {
+        OutputFormat::Json => {
+            let json = serde_json::to_string_pretty(buckets)
+                .map_err(|e| CliError::runtime(format!("Failed to serialize JSON: {}", e)))?;
+            output.push_str(&json);
+            output.push('\n');
         }
-        Interval::Week => {
-            let mut current_date = start_local.date_naive();
-            let end_date = end_local.date_naive();
-
-            let weekday = current_date.weekday();
-            let days_from_week_start = match week_start {
-                WeekStart::Monday => weekday.num_days_from_monday() as i64,
-                WeekStart::Sunday => weekday.num_days_from_sunday() as i64,
-            };
-            current_date -= chrono::Duration::days(days_from_week_start);
-
-            while current_date <= end_date {
-                let bucket = compute_bucket_for_date(current_date, tz, interval, week_start)?;
-                let bucket_start_utc = parse_rfc3339_to_utc(&bucket.start_utc)?;
-                let bucket_end_utc = parse_rfc3339_to_utc(&bucket.end_utc)?;
-
-                if bucket_start_utc < end_utc
-                    && bucket_end_utc > start_utc
-                    && !buckets.iter().any(|b: &RangeBucket| b.key == bucket.key)
-                {
-                    buckets.push(bucket);
-                }
-
-                current_date += chrono::Duration::weeks(1);
+        OutputFormat::Text => {
+            let style = Style::stdout();
+            for Versioned { record: bucket, .. } in buckets {
+                output.push_str(&format!(
+                    "{}: {} to {}\n",
+                    style.bucket_key(&bucket.key, &bucket.start_local, &bucket.end_local),
+                    bucket.start_local,
+                    bucket.end_local
+                ));
             }
         }
-        Interval::Month => {
-            let mut current_date = start_local.date_naive();
-            let end_date = end_local.date_naive();
-
-            current_date =
-                chrono::NaiveDate::from_ymd_opt(current_date.year(), current_date.month(), 1)
-                    .ok_or_else(|| CliError::runtime("Could not construct month start date"))?;
-
-            while current_date <= end_date {
-                let bucket = compute_bucket_for_date(current_date, tz, interval, week_start)?;
-                let bucket_start_utc = parse_rfc3339_to_utc(&bucket.start_utc)?;
-                let bucket_end_utc = parse_rfc3339_to_utc(&bucket.end_utc)?;
-
-                if bucket_start_utc < end_utc
-                    && bucket_end_utc > start_utc
-                    && !buckets.iter().any(|b: &RangeBucket| b.key == bucket.key)
-                {
-                    buckets.push(bucket);
-                }
-
-                let year = current_date.year();
-                let month = current_date.month();
-                current_date = if month == 12 {
-                    chrono::NaiveDate::from_ymd_opt(year + 1, 1, 1)
-                        .ok_or_else(|| CliError::runtime("Could not construct next month date"))?
-                } else {
-                    chrono::NaiveDate::from_ymd_opt(year, month + 1, 1)
-                        .ok_or_else(|| CliError::runtime("Could not construct next month date"))?
-                };
-            }
+    }
+
+    Ok(output)
+}
+
+fn write_plan(
+    plan: &RangePlan,
+    output_format: OutputFormat,
+    out: &mut impl Write,
+) -> CliResult<()> {
+    let mut text = String::new();
+    match output_format {
+        OutputFormat::Json => {
+            let json = serde_json::to_string_pretty(plan)
+                .map_err(|e| CliError::runtime(format!("Failed to serialize JSON: {}", e)))?;
+            text.push_str(&json);
+            text.push('\n');
+        }
+        OutputFormat::Text => {
+            text.push_str(&format!("Timezone: {}\n", plan.tz));
+            text.push_str(&format!("Interval: {}\n", plan.interval));
+            text.push_str(&format!(
+                "Input format: start {}, end {}\n",
+                plan.start_format, plan.end_format
+            ));
+            text.push_str(&format!("Buckets: {}\n", plan.bucket_count));
+            text.push_str(&format!("First start (UTC): {}\n", plan.first_start_utc));
+            text.push_str(&format!("Last end (UTC): {}\n", plan.last_end_utc));
+            text.push_str(&format!(
+                "Estimated output size: {} bytes\n",
+                plan.estimated_output_bytes
+            ));
         }
     }
 
-    buckets.sort_by(|a, b| a.start_utc.cmp(&b.start_utc));
+    out.write_all(text.as_bytes()).map_err(write_error)
+}
 
-    Ok(buckets)
+#[derive(Debug, Serialize)]
+struct RangePlan {
+    tz: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    canonical_tz: Option<String>,
+    interval: Interval,
+    start_format: BoundFormat,
+    end_format: BoundFormat,
+    bucket_count: usize,
+    first_start_utc: String,
+    last_end_utc: String,
+    estimated_output_bytes: usize,
+}
+
+#[derive(Debug, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub(crate) struct RangeBucket {
+    /// Bucket key.
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
+    key: BucketKey,
+    /// Bucket start in local time with offset.
+    start_local: String,
+    /// Bucket end in local time with offset.
+    end_local: String,
+    /// Bucket start in UTC.
+    start_utc: String,
+    /// Bucket end in UTC.
+    end_utc: String,
+    /// Elapsed seconds from start to end (82800 for a 23-hour day).
+    duration_seconds: i64,
+    /// Week numbers of the bucket start (`--week-numbers`).
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    week_numbers: Option<WeekNumbers>,
+    /// Clamp policy applied to a trailing month anchor.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    month_clamp: Option<MonthClamp>,
+    /// Label instant in UTC (`--label`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    label_ts: Option<String>,
+    /// Boundary offsets and whether DST changes inside (`--dst-info`).
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    dst_info: Option<DstInfo>,
 }
 
-fn compute_bucket_for_date(
-    date: chrono::NaiveDate,
+impl From<Bucket> for RangeBucket {
+    fn from(bucket: Bucket) -> Self {
+        Self {
+            key: bucket.key,
+            start_local: bucket.start_local,
+            end_local: bucket.end_local,
+            start_utc: bucket.start_utc,
+            end_utc: bucket.end_utc,
+            duration_seconds: bucket.duration_seconds,
+            week_numbers: bucket.week_numbers,
+            month_clamp: bucket.month_clamp,
+            label_ts: bucket.label_ts,
+            dst_info: bucket.dst_info,
+        }
+    }
+}
+
+/// Consecutive buckets from the one containing `start_utc` until the range
+/// end. With `day_offset`, days start at that local time instead of
+/// midnight.
+pub(crate) fn generate_buckets_in_range(
+    start_utc: DateTime<Utc>,
+    end_utc: DateTime<Utc>,
     tz: Tz,
     interval: Interval,
     week_start: WeekStart,
-) -> CliResult<RangeBucket> {
-    let midnight = date.and_hms_opt(0, 0, 0).ok_or_else(|| {
-        CliError::runtime(format!("Could not construct midnight for date {}", date))
-    })?;
-
-    let local_result = tz.from_local_datetime(&midnight);
-    let instant = local_result
-        .single()
-        .or_else(|| local_result.earliest())
-        .map(|dt| dt.with_timezone(&Utc))
-        .ok_or_else(|| {
-            CliError::runtime(format!(
-                "Could not resolve local midnight for date {}",
-                date
-            ))
-        })?;
-
-    let bucket = compute_bucket(instant, tz, interval, Some(week_start));
-
-    Ok(RangeBucket {
-        key: bucket.key,
-        start_local: bucket.start_local,
-        end_local: bucket.end_local,
-        start_utc: bucket.start_utc,
-        end_utc: bucket.end_utc,
-    })
+    day_offset: Option<DayOffset>,
+) -> CliResult<Vec<Bucket>> {
+    let Some(offset) = day_offset else {
+        let spec = BucketSpec::new(tz, interval).with_week_start(week_start);
+        return Ok(BucketSeries::new(start_utc, &spec).until(end_utc).collect());
+    };
+
+    // Day-offset boundaries can fail on a policy, so they are walked here;
+    // each next bucket is the one containing the previous end.
+    let mut buckets = Vec::new();
+    let mut cursor = start_utc;
+    while cursor < end_utc {
+        let bucket = offset.bucket(cursor, tz, interval, week_start)?;
+        let bucket_end_utc = parse_rfc3339_to_utc(&bucket.end_utc)?;
+
+        if bucket_end_utc <= cursor {
+            return Err(CliError::runtime(format!(
+                "Bucket '{}' does not advance past {}",
+                bucket.key, bucket.end_utc
+            )));
+        }
+
+        buckets.push(bucket);
+        cursor = bucket_end_utc;
+    }
+
+    Ok(buckets)
 }
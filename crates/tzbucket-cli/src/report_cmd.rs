@@ -0,0 +1,385 @@
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::io::Write;
+use std::process::ExitCode;
+
+use chrono::{DateTime, Duration, FixedOffset, Offset, TimeZone, Utc};
+use chrono_tz::Tz;
+use tzbucket_core::tz::{format_rfc3339_utc, offset_transitions};
+use tzbucket_core::{
+    Bucket, Interval, TimestampFormat, WeekStart, compute_bucket, parse_timestamp,
+};
+
+use crate::cli::ReportArgs;
+use crate::error::{CliError, CliResult, EXIT_SUCCESS};
+use crate::input::{InputDecoder, open_input, parse_input_mode};
+use crate::range_cmd::generate_buckets_in_range;
+use crate::shared::{
+    format_rfc3339, parse_format, parse_interval, parse_rfc3339_to_utc, parse_tz_or_input_error,
+    parse_week_start, write_error,
+};
+
+pub fn run_report(args: ReportArgs, mut out: impl Write) -> CliResult<ExitCode> {
+    let tz = parse_tz_or_input_error(&args.tz)?;
+    let interval = parse_interval(&args.interval)?;
+    let week_start = parse_week_start(&args.week_start)?;
+    let timestamp_format = parse_format(&args.format)?;
+    let mut decoder = parse_input_mode(&args.input_mode)?;
+
+    let start_utc = parse_timestamp(&args.start, TimestampFormat::Rfc3339)
+        .map_err(|e| CliError::input(format!("Invalid start timestamp: {}", e)))?;
+    let end_utc = parse_timestamp(&args.end, TimestampFormat::Rfc3339)
+        .map_err(|e| CliError::input(format!("Invalid end timestamp: {}", e)))?;
+
+    if start_utc >= end_utc {
+        return Err(CliError::input(format!(
+            "Invalid range: start '{}' must be earlier than end '{}'",
+            args.start, args.end
+        )));
+    }
+
+    let buckets = generate_buckets_in_range(start_utc, end_utc, tz, interval, week_start, None)?;
+    let transitions: Vec<Transition> = offset_transitions(tz, start_utc, end_utc)
+        .into_iter()
+        .map(|at| Transition::new(tz, at))
+        .collect();
+
+    let mut rows = Vec::with_capacity(buckets.len());
+    for bucket in buckets {
+        let start = parse_rfc3339_to_utc(&bucket.start_utc)?;
+        let end = parse_rfc3339_to_utc(&bucket.end_utc)?;
+        let transitions = transitions
+            .iter()
+            .filter(|t| start <= t.at && t.at < end)
+            .cloned()
+            .collect();
+        rows.push(ReportRow {
+            bucket,
+            seconds: (end - start).num_seconds(),
+            count: None,
+            transitions,
+        });
+    }
+
+    let outside = match &args.input {
+        Some(input) => Some(count_events(
+            input,
+            decoder.as_mut(),
+            timestamp_format,
+            tz,
+            interval,
+            week_start,
+            &mut rows,
+        )?),
+        None => None,
+    };
+
+    let report = Report {
+        tz,
+        interval,
+        start: start_utc,
+        end: end_utc,
+        rows,
+        transitions,
+        outside,
+    };
+    write!(out, "{}", report.render()).map_err(write_error)?;
+
+    Ok(ExitCode::from(EXIT_SUCCESS))
+}
+
+/// Count input timestamps into `rows`; returns how many fell outside them.
+fn count_events(
+    input: &str,
+    decoder: &mut dyn InputDecoder,
+    format: TimestampFormat,
+    tz: Tz,
+    interval: Interval,
+    week_start: WeekStart,
+    rows: &mut [ReportRow],
+) -> CliResult<u64> {
+    let mut lines = open_input(input)?;
+
+    let index: HashMap<String, usize> = rows
+        .iter()
+        .enumerate()
+        .map(|(i, row)| (row.bucket.start_utc.clone(), i))
+        .collect();
+    for row in rows.iter_mut() {
+        row.count = Some(0);
+    }
+
+    let mut outside = 0;
+    while let Some(trimmed) = lines.next_line()? {
+        if trimmed.is_empty() {
+            continue;
+        }
+        let Some(record) = decoder.decode(trimmed)? else {
+            continue;
+        };
+
+        let instant = parse_timestamp(&record.ts_str, format)
+            .map_err(|e| CliError::input(format!("Error processing '{}': {}", record.ts_str, e)))?;
+        let bucket = compute_bucket(instant, tz, interval, Some(week_start));
+        match index.get(&bucket.start_utc) {
+            Some(&i) => *rows[i].count.get_or_insert(0) += 1,
+            None => outside += 1,
+        }
+    }
+
+    Ok(outside)
+}
+
+/// A UTC offset change, with the wall clock on either side of it.
+#[derive(Debug, Clone)]
+struct Transition {
+    at: DateTime<Utc>,
+    before: FixedOffset,
+    after: FixedOffset,
+}
+
+impl Transition {
+    fn new(tz: Tz, at: DateTime<Utc>) -> Self {
+        let offset_at = |t: DateTime<Utc>| tz.offset_from_utc_datetime(&t.naive_utc()).fix();
+        Self {
+            at,
+            before: offset_at(at - Duration::seconds(1)),
+            after: offset_at(at),
+        }
+    }
+
+    /// e.g. `2026-03-29T02:00:00+01:00 → 2026-03-29T03:00:00+02:00`
+    fn wall_clock(&self) -> String {
+        format!(
+            "{} → {}",
+            format_rfc3339(&self.at.with_timezone(&self.before)),
+            format_rfc3339(&self.at.with_timezone(&self.after))
+        )
+    }
+
+    /// e.g. `+01:00 → +02:00 (+1h)`
+    fn shift(&self) -> String {
+        let delta = self.after.local_minus_utc() - self.before.local_minus_utc();
+        format!(
+            "{} → {} ({}{})",
+            self.before,
+            self.after,
+            if delta < 0 { "-" } else { "+" },
+            format_duration(i64::from(delta.abs()))
+        )
+    }
+}
+
+struct ReportRow {
+    bucket: Bucket,
+    seconds: i64,
+    /// Events in the bucket; `None` without `--input`.
+    count: Option<u64>,
+    transitions: Vec<Transition>,
+}
+
+struct Report {
+    tz: Tz,
+    interval: Interval,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    rows: Vec<ReportRow>,
+    transitions: Vec<Transition>,
+    /// Events outside the reported buckets; `None` without `--input`.
+    outside: Option<u64>,
+}
+
+const CHART_WIDTH: f64 = 720.0;
+const CHART_HEIGHT: f64 = 200.0;
+
+const STYLE: &str = "body { font-family: sans-serif; margin: 2em; color: #222; }
+table { border-collapse: collapse; }
+th, td { border: 1px solid #ccc; padding: 0.25em 0.5em; text-align: left; }
+tr.dst td { background: #fff3cd; }
+rect.bar { fill: #4a7ab5; }
+rect.bar.dst { fill: #d9822b; }
+";
+
+impl Report {
+    /// A self-contained HTML page: summary, DST transitions, an SVG bar chart
+    /// and the bucket table. Writing to a `String` cannot fail.
+    fn render(&self) -> String {
+        let title = format!(
+            "tzbucket report: {} per {}, {} to {}",
+            self.tz,
+            self.interval,
+            format_rfc3339_utc(&self.start),
+            format_rfc3339_utc(&self.end)
+        );
+
+        let mut html = String::new();
+        html.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+        let _ = writeln!(html, "<title>{}</title>", escape_html(&title));
+        let _ = writeln!(html, "<style>\n{}</style>\n</head>\n<body>", STYLE);
+        let _ = writeln!(html, "<h1>{}</h1>", escape_html(&title));
+
+        self.render_summary(&mut html);
+        self.render_transitions(&mut html);
+        self.render_chart(&mut html);
+        self.render_table(&mut html);
+
+        html.push_str("</body>\n</html>\n");
+        html
+    }
+
+    fn render_summary(&self, html: &mut String) {
+        html.push_str("<h2>Summary</h2>\n<ul>\n");
+        let _ = writeln!(html, "<li>Timezone: {}</li>", escape_html(self.tz.name()));
+        let _ = writeln!(html, "<li>Interval: {}</li>", self.interval);
+        let _ = writeln!(html, "<li>Buckets: {}</li>", self.rows.len());
+        if let Some(outside) = self.outside {
+            let total: u64 = self.rows.iter().filter_map(|row| row.count).sum();
+            let _ = writeln!(html, "<li>Events: {}</li>", total);
+            let _ = writeln!(html, "<li>Events outside the range: {}</li>", outside);
+        }
+        let _ = writeln!(
+            html,
+            "<li>Offset transitions: {}</li>",
+            self.transitions.len()
+        );
+        html.push_str("</ul>\n");
+    }
+
+    fn render_transitions(&self, html: &mut String) {
+        html.push_str("<h2>DST transitions</h2>\n");
+        if self.transitions.is_empty() {
+            html.push_str("<p>No offset changes in this range.</p>\n");
+            return;
+        }
+
+        html.push_str("<table>\n<tr><th>UTC</th><th>Offset</th><th>Local wall clock</th></tr>\n");
+        for transition in &self.transitions {
+            let _ = writeln!(
+                html,
+                "<tr><td>{}</td><td>{}</td><td>{}</td></tr>",
+                format_rfc3339_utc(&transition.at),
+                transition.shift(),
+                transition.wall_clock()
+            );
+        }
+        html.push_str("</table>\n");
+    }
+
+    /// Bars are event counts with `--input`, bucket lengths in hours without.
+    /// Buckets containing a transition are highlighted.
+    fn render_chart(&self, html: &mut String) {
+        let value = |row: &ReportRow| match row.count {
+            Some(count) => count as f64,
+            None => row.seconds as f64 / 3600.0,
+        };
+        let (heading, unit) = match self.outside {
+            Some(_) => ("Events per bucket", "events"),
+            None => ("Bucket length", "hours"),
+        };
+        let max = self.rows.iter().map(value).fold(0.0, f64::max);
+        let width = CHART_WIDTH / self.rows.len().max(1) as f64;
+
+        let _ = writeln!(html, "<h2>{}</h2>", heading);
+        let _ = writeln!(
+            html,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">",
+            w = CHART_WIDTH,
+            h = CHART_HEIGHT
+        );
+        for (i, row) in self.rows.iter().enumerate() {
+            let v = value(row);
+            let height = if max > 0.0 {
+                v / max * CHART_HEIGHT
+            } else {
+                0.0
+            };
+            let class = if row.transitions.is_empty() {
+                "bar"
+            } else {
+                "bar dst"
+            };
+            let _ = writeln!(
+                html,
+                "<rect class=\"{}\" x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\"><title>{}: {} {}</title></rect>",
+                class,
+                i as f64 * width,
+                CHART_HEIGHT - height,
+                (width - 1.0).max(0.5),
+                height,
+                escape_html(&row.bucket.key),
+                format_value(v),
+                unit
+            );
+        }
+        html.push_str("</svg>\n");
+    }
+
+    fn render_table(&self, html: &mut String) {
+        html.push_str("<h2>Buckets</h2>\n<table>\n<tr><th>Key</th><th>Start (local)</th><th>End (local)</th><th>Start (UTC)</th><th>End (UTC)</th><th>Length</th>");
+        if self.outside.is_some() {
+            html.push_str("<th>Events</th>");
+        }
+        html.push_str("<th>DST</th></tr>\n");
+
+        for row in &self.rows {
+            let bucket = &row.bucket;
+            let class = if row.transitions.is_empty() {
+                ""
+            } else {
+                " class=\"dst\""
+            };
+            let _ = write!(
+                html,
+                "<tr{}><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td>",
+                class,
+                escape_html(&bucket.key),
+                escape_html(&bucket.start_local),
+                escape_html(&bucket.end_local),
+                escape_html(&bucket.start_utc),
+                escape_html(&bucket.end_utc),
+                format_duration(row.seconds)
+            );
+            if let Some(count) = row.count {
+                let _ = write!(html, "<td>{}</td>", count);
+            }
+            let note: Vec<String> = row.transitions.iter().map(Transition::shift).collect();
+            let _ = writeln!(html, "<td>{}</td></tr>", note.join("; "));
+        }
+        html.push_str("</table>\n");
+    }
+}
+
+/// Compact duration: `23h`, `1h 30m`, `90s`.
+fn format_duration(seconds: i64) -> String {
+    let (hours, minutes) = (seconds / 3600, seconds % 3600 / 60);
+    match (seconds % 60, minutes) {
+        (0, 0) => format!("{}h", hours),
+        (0, _) if hours == 0 => format!("{}m", minutes),
+        (0, _) => format!("{}h {}m", hours, minutes),
+        _ => format!("{}s", seconds),
+    }
+}
+
+/// Whole numbers without decimals, anything else to two places.
+fn format_value(value: f64) -> String {
+    if value.fract() == 0.0 {
+        format!("{}", value)
+    } else {
+        format!("{:.2}", value)
+    }
+}
+
+fn escape_html(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
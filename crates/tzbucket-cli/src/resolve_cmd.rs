@@ -0,0 +1,138 @@
+use std::io::Write;
+use std::process::ExitCode;
+
+use serde::Serialize;
+use tzbucket_core::{LocalResolution, Policy, RoundTrip, resolve_local};
+
+use crate::cli::ResolveArgs;
+use crate::error::{CliError, CliResult, EXIT_SUCCESS};
+use crate::input::open_input;
+use crate::shared::{
+    json_line, parse_ambiguous_policy, parse_local_time, parse_nonexistent_policy,
+    parse_tz_or_input_error, resolve_error_to_cli, write_error,
+};
+use crate::skipped::LineErrors;
+
+/// Output layouts supported by `resolve` (CSV is specific to this command).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ResolveFormat {
+    Json,
+    Csv,
+    Text,
+}
+
+const CSV_HEADER: &str = "local,tz,status,policy,utc,offset";
+
+pub fn run_resolve(args: ResolveArgs, mut out: impl Write) -> CliResult<ExitCode> {
+    let format = parse_resolve_format(&args.output_format)?;
+    let tz = parse_tz_or_input_error(&args.tz)?;
+    let policy = Policy {
+        nonexistent: parse_nonexistent_policy(&args.policy_nonexistent)?,
+        ambiguous: parse_ambiguous_policy(&args.policy_ambiguous)?,
+    };
+
+    let mut errors = LineErrors::open(args.errors_to.as_deref())?;
+    let mut lines = open_input(&args.input)?;
+
+    if format == ResolveFormat::Csv {
+        if args.verify_roundtrip {
+            writeln!(out, "{},roundtrip", CSV_HEADER).map_err(write_error)?;
+        } else {
+            writeln!(out, "{}", CSV_HEADER).map_err(write_error)?;
+        }
+    }
+
+    while let Some((line_number, trimmed)) = lines.next_numbered_line()? {
+        if trimmed.is_empty() {
+            continue;
+        }
+
+        let resolved = parse_local_time(trimmed).and_then(|local| {
+            resolve_local(local, tz, policy).map_err(|e| resolve_error_to_cli(e, local, tz))
+        });
+        let Some(resolved) = errors.check(line_number, trimmed, resolved)? else {
+            continue;
+        };
+        let mut record = ResolveRecord::new(&resolved);
+        if args.verify_roundtrip {
+            record.roundtrip = Some(resolved.roundtrip());
+        }
+
+        match format {
+            ResolveFormat::Json => {
+                let json = json_line(&record)?;
+                writeln!(out, "{}", json).map_err(write_error)?;
+            }
+            ResolveFormat::Csv => {
+                let mut row = format!(
+                    "{},{},{},{},{},{}",
+                    record.local,
+                    record.tz,
+                    record.status,
+                    record.policy.unwrap_or_default(),
+                    record.utc,
+                    record.offset
+                );
+                if let Some(roundtrip) = record.roundtrip {
+                    row.push_str(&format!(",{}", roundtrip));
+                }
+                writeln!(out, "{}", row).map_err(write_error)?;
+            }
+            ResolveFormat::Text => match record.roundtrip {
+                Some(roundtrip) => writeln!(
+                    out,
+                    "{} -> {} ({}, {}, roundtrip {})",
+                    record.local, record.utc, record.offset, record.status, roundtrip
+                )
+                .map_err(write_error)?,
+                None => writeln!(
+                    out,
+                    "{} -> {} ({}, {})",
+                    record.local, record.utc, record.offset, record.status
+                )
+                .map_err(write_error)?,
+            },
+        }
+    }
+
+    Ok(ExitCode::from(EXIT_SUCCESS))
+}
+
+fn parse_resolve_format(s: &str) -> CliResult<ResolveFormat> {
+    match s.to_lowercase().as_str() {
+        "json" => Ok(ResolveFormat::Json),
+        "csv" => Ok(ResolveFormat::Csv),
+        "text" => Ok(ResolveFormat::Text),
+        _ => Err(CliError::input(format!(
+            "Invalid output_format '{}'. Expected: json, csv, text",
+            s
+        ))),
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct ResolveRecord {
+    local: String,
+    tz: String,
+    status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    policy: Option<String>,
+    utc: String,
+    offset: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    roundtrip: Option<RoundTrip>,
+}
+
+impl ResolveRecord {
+    fn new(resolved: &LocalResolution) -> Self {
+        Self {
+            local: resolved.local.format("%Y-%m-%dT%H:%M:%S").to_string(),
+            tz: resolved.instant.timezone().to_string(),
+            status: resolved.status.to_string(),
+            policy: resolved.applied.map(|applied| applied.to_string()),
+            utc: tzbucket_core::tz::format_rfc3339_utc(&resolved.utc()),
+            offset: resolved.instant.format("%:z").to_string(),
+            roundtrip: None,
+        }
+    }
+}
@@ -0,0 +1,336 @@
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::process::ExitCode;
+
+use chrono::{DateTime, Duration, Utc};
+use chrono_tz::Tz;
+use serde::Serialize;
+use tzbucket_core::navigate::bucket_for_key;
+use tzbucket_core::{
+    Attribution, Bucket, BucketKey, Interval, WeekStart, attribute, compute_bucket,
+};
+
+use crate::cli::RollupArgs;
+use crate::error::{CliError, CliResult, EXIT_SUCCESS, OutputFormat};
+use crate::input::open_input;
+use crate::shared::{
+    parse_interval, parse_interval_list, parse_rfc3339_to_utc, parse_tz_or_input_error,
+    parse_week_start, write_error,
+};
+
+pub fn run_rollup(
+    args: RollupArgs,
+    output_format: OutputFormat,
+    mut out: impl Write,
+) -> CliResult<ExitCode> {
+    let tz = parse_tz_or_input_error(&args.tz)?;
+    let interval = parse_interval(&args.interval)?;
+    let targets = parse_interval_list(&args.to, None)?;
+    let week_start = parse_week_start(&args.week_start)?;
+    let attribution = parse_attribution(&args.attribution)?;
+
+    let children = read_children(&args.input, tz, interval, week_start)?;
+    let rollups = targets
+        .iter()
+        .map(|&target| roll_up(&children, interval, target, tz, week_start, attribution))
+        .collect::<CliResult<Vec<_>>>()?;
+    let report = RollupReport {
+        interval,
+        attribution: attribution.map_or_else(|| "error".to_string(), |a| a.to_string()),
+        rollups,
+    };
+
+    match output_format {
+        OutputFormat::Json => {
+            let json = serde_json::to_string_pretty(&report)
+                .map_err(|e| CliError::runtime(format!("Failed to serialize JSON: {}", e)))?;
+            writeln!(out, "{}", json).map_err(write_error)?;
+        }
+        OutputFormat::Text => {
+            for rollup in &report.rollups {
+                for parent in &rollup.buckets {
+                    writeln!(
+                        out,
+                        "{} {}: {} from {} {} buckets{}",
+                        rollup.interval,
+                        parent.key,
+                        parent.count,
+                        parent.children,
+                        report.interval,
+                        if parent.complete { "" } else { " (incomplete)" }
+                    )
+                    .map_err(write_error)?;
+                }
+            }
+        }
+    }
+
+    Ok(ExitCode::from(EXIT_SUCCESS))
+}
+
+/// Parse `--attribution`; `error` (no policy) is `None`.
+fn parse_attribution(s: &str) -> CliResult<Option<Attribution>> {
+    match s.to_lowercase().as_str() {
+        "error" => Ok(None),
+        "start" => Ok(Some(Attribution::Start)),
+        "end" => Ok(Some(Attribution::End)),
+        "majority" => Ok(Some(Attribution::Majority)),
+        "split" => Ok(Some(Attribution::Split)),
+        _ => Err(CliError::input(format!(
+            "Invalid attribution '{}'. Expected: error, start, end, majority, split",
+            s
+        ))),
+    }
+}
+
+/// One input aggregate with its bucket boundaries.
+struct Child {
+    key: String,
+    start_utc: DateTime<Utc>,
+    end_utc: DateTime<Utc>,
+    count: u64,
+}
+
+/// Read `key,count` aggregates of `interval`: a CSV file with a header
+/// naming (at least) `key` and `count`, or a JSON array of objects with
+/// those fields such as `count` output. Returned sorted by start.
+fn read_children(
+    path: &str,
+    tz: Tz,
+    interval: Interval,
+    week_start: WeekStart,
+) -> CliResult<Vec<Child>> {
+    let mut lines = open_input(path)?;
+    let mut text = Vec::new();
+    while let Some((line_number, line)) = lines.next_numbered_line()? {
+        if !line.is_empty() {
+            text.push((line_number, line.to_string()));
+        }
+    }
+
+    let rows: Vec<(String, String, u64)> = match text.first() {
+        Some((_, first)) if first.starts_with('[') => {
+            let json: String = text.iter().map(|(_, line)| line.as_str()).collect();
+            let records: Vec<AggregateRecord> = serde_json::from_str(&json)
+                .map_err(|e| CliError::input(format!("{}: invalid JSON: {}", path, e)))?;
+            records
+                .into_iter()
+                .map(|record| (path.to_string(), record.key, record.count))
+                .collect()
+        }
+        Some((_, header)) => {
+            let columns: Vec<&str> = header.split(',').map(str::trim).collect();
+            let column = |name: &str| columns.iter().position(|c| *c == name);
+            let (Some(key_col), Some(count_col)) = (column("key"), column("count")) else {
+                return Err(CliError::input(format!(
+                    "{}: CSV header must contain key and count",
+                    path
+                )));
+            };
+            text[1..]
+                .iter()
+                .map(|(line_number, line)| {
+                    let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+                    let (Some(key), Some(count)) = (fields.get(key_col), fields.get(count_col))
+                    else {
+                        return Err(CliError::input(format!(
+                            "{}:{}: missing key or count column",
+                            path, line_number
+                        )));
+                    };
+                    let count = count.parse::<u64>().map_err(|_| {
+                        CliError::input(format!(
+                            "{}:{}: invalid count '{}'",
+                            path, line_number, count
+                        ))
+                    })?;
+                    Ok((format!("{}:{}", path, line_number), key.to_string(), count))
+                })
+                .collect::<CliResult<_>>()?
+        }
+        None => Vec::new(),
+    };
+
+    let mut children = Vec::with_capacity(rows.len());
+    for (location, key, count) in rows {
+        let bucket = bucket_for_key(&key, tz, interval, Some(week_start))
+            .map_err(|e| CliError::input(format!("{}: {}", location, e)))?;
+        children.push(Child {
+            key,
+            start_utc: parse_rfc3339_to_utc(&bucket.start_utc)?,
+            end_utc: parse_rfc3339_to_utc(&bucket.end_utc)?,
+            count,
+        });
+    }
+    children.sort_by_key(|child| child.start_utc);
+    if let Some(pair) = children
+        .windows(2)
+        .find(|pair| pair[1].start_utc < pair[0].end_utc)
+    {
+        return Err(CliError::input(format!(
+            "{}: bucket '{}' appears twice",
+            path, pair[0].key
+        )));
+    }
+
+    Ok(children)
+}
+
+/// The fields of a `count` JSON record that a rollup reads.
+#[derive(serde::Deserialize)]
+struct AggregateRecord {
+    key: String,
+    count: u64,
+}
+
+/// Sum `children` into the `target` buckets they fall in.
+fn roll_up(
+    children: &[Child],
+    interval: Interval,
+    target: Interval,
+    tz: Tz,
+    week_start: WeekStart,
+    attribution: Option<Attribution>,
+) -> CliResult<Rollup> {
+    // Keyed by `start_utc`, which sorts chronologically as text.
+    let mut parents: BTreeMap<String, Parent> = BTreeMap::new();
+    let parent_of = |parents: &mut BTreeMap<String, Parent>, instant| -> CliResult<String> {
+        let bucket = compute_bucket(instant, tz, target, Some(week_start));
+        let key = bucket.start_utc.clone();
+        if !parents.contains_key(&key) {
+            parents.insert(key.clone(), Parent::new(bucket)?);
+        }
+        Ok(key)
+    };
+
+    for child in children {
+        let first = parent_of(&mut parents, child.start_utc)?;
+        let last = parent_of(&mut parents, child.end_utc - Duration::milliseconds(1))?;
+        if first == last {
+            let parent = parents.get_mut(&first).expect("parent inserted above");
+            parent.count += child.count;
+            parent.children += 1;
+            parent.cover(child);
+            continue;
+        }
+        if parents[&first].end_utc != parents[&last].start_utc {
+            return Err(CliError::input(format!(
+                "Invalid rollup: {} bucket '{}' spans more than two {} buckets. \
+                 Expected: --to intervals coarser than --interval",
+                interval, child.key, target
+            )));
+        }
+        let Some(attribution) = attribution else {
+            return Err(CliError::input(format!(
+                "{} bucket '{}' crosses the {} boundary at {}. \
+                 Use --attribution=start, end, majority or split to assign it.",
+                interval, child.key, target, parents[&last].bucket.start_local
+            )));
+        };
+
+        let local = |instant: DateTime<Utc>| instant.with_timezone(&tz).naive_local();
+        let (start, boundary, end) = (
+            local(child.start_utc),
+            local(parents[&last].start_utc),
+            local(child.end_utc),
+        );
+        let shares = attribute(child.count, start, boundary, end, attribution);
+        // Which parents the child counts in, even when its count is zero.
+        let credited = match attribution {
+            Attribution::Split => (true, true),
+            _ => {
+                let to_first = attribute(1, start, boundary, end, attribution).0 == 1;
+                (to_first, !to_first)
+            }
+        };
+        for (key, share, credited) in [(first, shares.0, credited.0), (last, shares.1, credited.1)]
+        {
+            let parent = parents.get_mut(&key).expect("parent inserted above");
+            parent.count += share;
+            parent.children += u64::from(credited);
+            // A crossing child covers part of both parents, whichever counts it.
+            parent.cover(child);
+        }
+    }
+
+    Ok(Rollup {
+        interval: target,
+        buckets: parents
+            .into_values()
+            .filter(|parent| parent.children > 0)
+            .map(Parent::into_record)
+            .collect(),
+    })
+}
+
+/// A target bucket while rolling up.
+struct Parent {
+    bucket: Bucket,
+    start_utc: DateTime<Utc>,
+    end_utc: DateTime<Utc>,
+    count: u64,
+    children: u64,
+    /// UTC time covered by input buckets, clipped to this bucket.
+    covered: Duration,
+}
+
+impl Parent {
+    fn new(bucket: Bucket) -> CliResult<Self> {
+        Ok(Self {
+            start_utc: parse_rfc3339_to_utc(&bucket.start_utc)?,
+            end_utc: parse_rfc3339_to_utc(&bucket.end_utc)?,
+            bucket,
+            count: 0,
+            children: 0,
+            covered: Duration::zero(),
+        })
+    }
+
+    fn cover(&mut self, child: &Child) {
+        let start = child.start_utc.max(self.start_utc);
+        let end = child.end_utc.min(self.end_utc);
+        if end > start {
+            self.covered += end - start;
+        }
+    }
+
+    fn into_record(self) -> RollupBucket {
+        RollupBucket {
+            complete: self.covered == self.end_utc - self.start_utc,
+            key: self.bucket.key,
+            start_local: self.bucket.start_local,
+            end_local: self.bucket.end_local,
+            start_utc: self.bucket.start_utc,
+            end_utc: self.bucket.end_utc,
+            count: self.count,
+            children: self.children,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct RollupReport {
+    interval: Interval,
+    attribution: String,
+    rollups: Vec<Rollup>,
+}
+
+#[derive(Debug, Serialize)]
+struct Rollup {
+    interval: Interval,
+    buckets: Vec<RollupBucket>,
+}
+
+#[derive(Debug, Serialize)]
+struct RollupBucket {
+    key: BucketKey,
+    start_local: String,
+    end_local: String,
+    start_utc: String,
+    end_utc: String,
+    count: u64,
+    /// Input buckets counted (wholly or in part) in this bucket.
+    children: u64,
+    /// Whether the input buckets cover this bucket without gaps.
+    complete: bool,
+}
@@ -0,0 +1,97 @@
+use std::io::Write;
+use std::process::ExitCode;
+
+use chrono::{DateTime, Offset, TimeZone, Utc};
+use chrono_tz::Tz;
+use serde::Serialize;
+use tzbucket_core::tz::{first_offset_difference, format_rfc3339_utc};
+
+use crate::cli::SameRulesArgs;
+use crate::error::{CliError, CliResult, EXIT_RUNTIME_ERROR, EXIT_SUCCESS, OutputFormat};
+use crate::shared::{parse_tz_or_input_error, parse_year_range, write_error};
+
+/// Run `same-rules`: whether two zones keep the same UTC offset at every
+/// instant between `--from` and `--to`.
+///
+/// Exits `3` when they differ, so scripts can gate on the result.
+pub fn run_same_rules(
+    args: SameRulesArgs,
+    output_format: OutputFormat,
+    mut out: impl Write,
+) -> CliResult<ExitCode> {
+    let first = parse_tz_or_input_error(&args.first)?;
+    let second = parse_tz_or_input_error(&args.second)?;
+    let (from, to) = parse_year_range(&args.from, &args.to)?;
+
+    let difference = first_offset_difference(first, second, from..to).map(|at| Difference {
+        at_utc: format_rfc3339_utc(&at),
+        first_offset: offset_at(first, at),
+        second_offset: offset_at(second, at),
+    });
+    let report = SameRulesReport {
+        first: first.to_string(),
+        second: second.to_string(),
+        from_utc: format_rfc3339_utc(&from),
+        to_utc: format_rfc3339_utc(&to),
+        same: difference.is_none(),
+        first_difference: difference,
+    };
+
+    match output_format {
+        OutputFormat::Json => {
+            let json = serde_json::to_string_pretty(&report)
+                .map_err(|e| CliError::runtime(format!("Failed to serialize JSON: {}", e)))?;
+            writeln!(out, "{}", json).map_err(write_error)?;
+        }
+        OutputFormat::Text => match &report.first_difference {
+            None => writeln!(
+                out,
+                "same: {} and {} have identical UTC offsets from {} to {}",
+                report.first, report.second, report.from_utc, report.to_utc
+            )
+            .map_err(write_error)?,
+            Some(difference) => writeln!(
+                out,
+                "different: {} is {} but {} is {} at {}",
+                report.first,
+                difference.first_offset,
+                report.second,
+                difference.second_offset,
+                difference.at_utc
+            )
+            .map_err(write_error)?,
+        },
+    }
+
+    if report.same {
+        Ok(ExitCode::from(EXIT_SUCCESS))
+    } else {
+        Ok(ExitCode::from(EXIT_RUNTIME_ERROR))
+    }
+}
+
+fn offset_at(tz: Tz, at: DateTime<Utc>) -> String {
+    tz.offset_from_utc_datetime(&at.naive_utc())
+        .fix()
+        .to_string()
+}
+
+#[derive(Debug, Serialize)]
+struct SameRulesReport {
+    first: String,
+    second: String,
+    from_utc: String,
+    /// Exclusive end of the compared range.
+    to_utc: String,
+    same: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    first_difference: Option<Difference>,
+}
+
+/// The earliest instant at which the two zones disagree.
+#[derive(Debug, Serialize)]
+struct Difference {
+    at_utc: String,
+    first_offset: String,
+    second_offset: String,
+}
@@ -0,0 +1,36 @@
+use std::io::Write;
+use std::process::ExitCode;
+
+use schemars::schema_for;
+use tzbucket_core::BucketResult;
+
+use crate::annotate::TransitionRecord;
+use crate::cli::SchemaArgs;
+use crate::error::{CliError, CliResult, EXIT_SUCCESS, ErrorOutput};
+use crate::explain_cmd::ExplainResult;
+use crate::range_cmd::RangeBucket;
+use crate::shared::{Versioned, write_error};
+use crate::skipped::SkippedLine;
+
+pub fn run_schema(args: SchemaArgs, mut out: impl Write) -> CliResult<ExitCode> {
+    let schema = match args.output_type.to_lowercase().as_str() {
+        "bucket" => schema_for!(Versioned<BucketResult>),
+        "range" => schema_for!(Vec<Versioned<RangeBucket>>),
+        "explain" => schema_for!(Versioned<ExplainResult>),
+        "error" => schema_for!(ErrorOutput),
+        "skipped" => schema_for!(SkippedLine<'static>),
+        "transition" => schema_for!(Versioned<TransitionRecord>),
+        _ => {
+            return Err(CliError::input(format!(
+                "Invalid schema type '{}'. Expected: bucket, range, explain, error, skipped, transition",
+                args.output_type
+            )));
+        }
+    };
+
+    let json = serde_json::to_string_pretty(&schema)
+        .map_err(|e| CliError::runtime(format!("Failed to serialize JSON: {}", e)))?;
+    writeln!(out, "{}", json).map_err(write_error)?;
+
+    Ok(ExitCode::from(EXIT_SUCCESS))
+}
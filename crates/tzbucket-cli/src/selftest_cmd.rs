@@ -0,0 +1,288 @@
+use std::io::Write;
+use std::process::ExitCode;
+
+use serde::Serialize;
+use tzbucket_core::{BucketKey, TimestampFormat, compute_bucket_from_string};
+
+use crate::cli::SelftestArgs;
+use crate::error::{CliError, CliResult, EXIT_RUNTIME_ERROR, EXIT_SUCCESS, OutputFormat};
+use crate::shared::write_error;
+use crate::vectors_cmd::{CASES, CaseSpec};
+
+/// Known-good bucket of a conformance case, independent of the linked
+/// tzdata. The local boundaries carry their offsets, so they also pin the
+/// UTC boundaries.
+struct Expected {
+    id: &'static str,
+    key: &'static str,
+    start_local: &'static str,
+    end_local: &'static str,
+}
+
+const fn expect(
+    id: &'static str,
+    key: &'static str,
+    start_local: &'static str,
+    end_local: &'static str,
+) -> Expected {
+    Expected {
+        id,
+        key,
+        start_local,
+        end_local,
+    }
+}
+
+/// One entry per case in [`CASES`], matched by id.
+const EXPECTED: &[Expected] = &[
+    expect(
+        "berlin-gap-day",
+        "2026-03-29",
+        "2026-03-29T00:00:00+01:00",
+        "2026-03-30T00:00:00+02:00",
+    ),
+    expect(
+        "berlin-overlap-first",
+        "2026-10-25",
+        "2026-10-25T00:00:00+02:00",
+        "2026-10-26T00:00:00+01:00",
+    ),
+    expect(
+        "berlin-overlap-second",
+        "2026-10-25",
+        "2026-10-25T00:00:00+02:00",
+        "2026-10-26T00:00:00+01:00",
+    ),
+    expect(
+        "newyork-gap-day",
+        "2026-03-08",
+        "2026-03-08T00:00:00-05:00",
+        "2026-03-09T00:00:00-04:00",
+    ),
+    expect(
+        "newyork-overlap-day",
+        "2026-11-01",
+        "2026-11-01T00:00:00-04:00",
+        "2026-11-02T00:00:00-05:00",
+    ),
+    expect(
+        "saopaulo-gap-midnight",
+        "2018-11-04",
+        "2018-11-04T01:00:00-02:00",
+        "2018-11-05T00:00:00-02:00",
+    ),
+    expect(
+        "havana-overlap-midnight",
+        "2026-11-01",
+        "2026-11-01T00:00:00-04:00",
+        "2026-11-02T00:00:00-05:00",
+    ),
+    expect(
+        "lordhowe-half-hour-gap",
+        "2026-10-04",
+        "2026-10-04T00:00:00+10:30",
+        "2026-10-05T00:00:00+11:00",
+    ),
+    expect(
+        "apia-skipped-day",
+        "2011-12-29",
+        "2011-12-29T00:00:00-10:00",
+        "2011-12-31T00:00:00+14:00",
+    ),
+    expect(
+        "berlin-week-dst",
+        "2026-03-23",
+        "2026-03-23T00:00:00+01:00",
+        "2026-03-30T00:00:00+02:00",
+    ),
+    expect(
+        "berlin-week-sunday-start",
+        "2026-03-29",
+        "2026-03-29T00:00:00+01:00",
+        "2026-04-05T00:00:00+02:00",
+    ),
+    expect(
+        "utc-week-year-crossing",
+        "2025-12-29",
+        "2025-12-29T00:00:00+00:00",
+        "2026-01-05T00:00:00+00:00",
+    ),
+    expect(
+        "berlin-month-last-second",
+        "2026-03",
+        "2026-03-01T00:00:00+01:00",
+        "2026-04-01T00:00:00+02:00",
+    ),
+    expect(
+        "berlin-month-first-second",
+        "2026-04",
+        "2026-04-01T00:00:00+02:00",
+        "2026-05-01T00:00:00+02:00",
+    ),
+    expect(
+        "berlin-month-year-rollover",
+        "2027-01",
+        "2027-01-01T00:00:00+01:00",
+        "2027-02-01T00:00:00+01:00",
+    ),
+    expect(
+        "utc-leap-day",
+        "2024-02-29",
+        "2024-02-29T00:00:00+00:00",
+        "2024-03-01T00:00:00+00:00",
+    ),
+    expect(
+        "berlin-leap-february",
+        "2024-02",
+        "2024-02-01T00:00:00+01:00",
+        "2024-03-01T00:00:00+01:00",
+    ),
+    expect(
+        "utc-century-leap-2000",
+        "2000-02-29",
+        "2000-02-29T00:00:00+00:00",
+        "2000-03-01T00:00:00+00:00",
+    ),
+    expect(
+        "utc-century-non-leap-2100",
+        "2100-02",
+        "2100-02-01T00:00:00+00:00",
+        "2100-03-01T00:00:00+00:00",
+    ),
+];
+
+pub fn run_selftest(
+    _args: SelftestArgs,
+    output_format: OutputFormat,
+    mut out: impl Write,
+) -> CliResult<ExitCode> {
+    let checks = CASES
+        .iter()
+        .map(|spec| {
+            let expected = EXPECTED.iter().find(|e| e.id == spec.id).ok_or_else(|| {
+                CliError::runtime(format!("No expectation for case '{}'", spec.id))
+            })?;
+            Ok(run_check(spec, expected))
+        })
+        .collect::<CliResult<Vec<_>>>()?;
+
+    let failed = checks.iter().filter(|c| !c.passed).count();
+    let report = SelftestReport {
+        tzdb_version: chrono_tz::IANA_TZDB_VERSION,
+        passed: checks.len() - failed,
+        failed,
+        checks,
+    };
+
+    match output_format {
+        OutputFormat::Json => {
+            let json = serde_json::to_string_pretty(&report)
+                .map_err(|e| CliError::runtime(format!("Failed to serialize JSON: {}", e)))?;
+            writeln!(out, "{}", json).map_err(write_error)?;
+        }
+        OutputFormat::Text => {
+            for check in &report.checks {
+                match (&check.error, &check.actual) {
+                    (Some(error), _) => {
+                        writeln!(out, "FAIL {}: {}", check.id, error).map_err(write_error)?
+                    }
+                    (None, Some(actual)) => writeln!(
+                        out,
+                        "FAIL {}: expected {} [{}, {}), got {} [{}, {})",
+                        check.id,
+                        check.expected.key,
+                        check.expected.start_local,
+                        check.expected.end_local,
+                        actual.key,
+                        actual.start_local,
+                        actual.end_local
+                    )
+                    .map_err(write_error)?,
+                    (None, None) => writeln!(out, "ok   {}", check.id).map_err(write_error)?,
+                }
+            }
+            writeln!(
+                out,
+                "{} passed, {} failed (tzdata {})",
+                report.passed, report.failed, report.tzdb_version
+            )
+            .map_err(write_error)?;
+        }
+    }
+
+    if report.failed > 0 {
+        Ok(ExitCode::from(EXIT_RUNTIME_ERROR))
+    } else {
+        Ok(ExitCode::from(EXIT_SUCCESS))
+    }
+}
+
+fn run_check(spec: &CaseSpec, expected: &Expected) -> Check {
+    let expected = BucketSummary {
+        key: BucketKey::from(expected.key),
+        start_local: expected.start_local.to_string(),
+        end_local: expected.end_local.to_string(),
+    };
+    let result = compute_bucket_from_string(
+        spec.ts,
+        TimestampFormat::Rfc3339,
+        spec.tz,
+        spec.interval,
+        Some(spec.week_start),
+    );
+
+    match result {
+        Ok(result) => {
+            let actual = BucketSummary {
+                key: result.bucket.key,
+                start_local: result.bucket.start_local,
+                end_local: result.bucket.end_local,
+            };
+            let passed = actual == expected;
+            Check {
+                id: spec.id,
+                category: spec.category,
+                passed,
+                expected,
+                actual: (!passed).then_some(actual),
+                error: None,
+            }
+        }
+        Err(e) => Check {
+            id: spec.id,
+            category: spec.category,
+            passed: false,
+            expected,
+            actual: None,
+            error: Some(e.to_string()),
+        },
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct SelftestReport {
+    tzdb_version: &'static str,
+    passed: usize,
+    failed: usize,
+    checks: Vec<Check>,
+}
+
+#[derive(Debug, Serialize)]
+struct Check {
+    id: &'static str,
+    category: &'static str,
+    passed: bool,
+    expected: BucketSummary,
+    /// The computed bucket, present only when it differs from `expected`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    actual: Option<BucketSummary>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct BucketSummary {
+    key: BucketKey,
+    start_local: String,
+    end_local: String,
+}
@@ -0,0 +1,150 @@
+//! Output sharding for `bucket --output-dir`.
+//!
+//! [`ShardWriter`] writes each record line into a file named after its
+//! bucket key (`out/2026-03-29.ndjson`), starting a numbered part
+//! (`2026-03-29.1.ndjson`, ...) once a file would exceed the rotation size,
+//! and records every file in `manifest.json` when finished.
+
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use crate::error::{CliError, CliResult};
+
+/// Name of the manifest written next to the shard files.
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// Shard files of one output directory, keyed by bucket key.
+pub struct ShardWriter {
+    dir: PathBuf,
+    extension: &'static str,
+    rotate_size: Option<u64>,
+    shards: BTreeMap<String, Shard>,
+}
+
+struct Shard {
+    writer: BufWriter<File>,
+    files: Vec<ShardFile>,
+}
+
+/// One file of a shard, as listed in the manifest.
+#[derive(Debug, Serialize)]
+struct ShardFile {
+    path: String,
+    records: u64,
+    bytes: u64,
+}
+
+#[derive(Debug, Serialize)]
+struct ManifestEntry<'a> {
+    key: &'a str,
+    files: &'a [ShardFile],
+}
+
+#[derive(Debug, Serialize)]
+struct Manifest<'a> {
+    shards: Vec<ManifestEntry<'a>>,
+}
+
+impl ShardWriter {
+    /// Create `dir` if needed. Files are named `<key>.<extension>`; existing
+    /// files of the same name are overwritten.
+    pub fn create(dir: &str, extension: &'static str, rotate_size: Option<u64>) -> CliResult<Self> {
+        fs::create_dir_all(dir).map_err(|e| {
+            CliError::runtime(format!("Failed to create directory '{}': {}", dir, e))
+        })?;
+        Ok(Self {
+            dir: PathBuf::from(dir),
+            extension,
+            rotate_size,
+            shards: BTreeMap::new(),
+        })
+    }
+
+    /// Append `line` (without newline) to the shard of `key`.
+    pub fn write(&mut self, key: &str, line: &str) -> CliResult<()> {
+        let len = line.len() as u64 + 1;
+
+        if !self.shards.contains_key(key) {
+            let (writer, file) = self.open(key, 0)?;
+            let shard = Shard {
+                writer,
+                files: vec![file],
+            };
+            self.shards.insert(key.to_string(), shard);
+        }
+        let rotate = {
+            let current = self.shards[key].files.last().expect("shard has a file");
+            self.rotate_size
+                .is_some_and(|limit| current.bytes > 0 && current.bytes + len > limit)
+        };
+        if rotate {
+            let part = self.shards[key].files.len();
+            let (writer, file) = self.open(key, part)?;
+            let shard = self.shards.get_mut(key).expect("inserted above");
+            flush(&mut shard.writer)?;
+            shard.writer = writer;
+            shard.files.push(file);
+        }
+
+        let shard = self.shards.get_mut(key).expect("inserted above");
+        writeln!(shard.writer, "{}", line)
+            .map_err(|e| CliError::runtime(format!("Failed to write shard '{}': {}", key, e)))?;
+        let file = shard.files.last_mut().expect("shard has a file");
+        file.records += 1;
+        file.bytes += len;
+        Ok(())
+    }
+
+    /// Flush every shard and write the manifest.
+    pub fn finish(mut self) -> CliResult<()> {
+        for shard in self.shards.values_mut() {
+            flush(&mut shard.writer)?;
+        }
+
+        let manifest = Manifest {
+            shards: self
+                .shards
+                .iter()
+                .map(|(key, shard)| ManifestEntry {
+                    key,
+                    files: &shard.files,
+                })
+                .collect(),
+        };
+        let json = serde_json::to_string_pretty(&manifest)
+            .map_err(|e| CliError::runtime(format!("Failed to serialize JSON: {}", e)))?;
+        let path = self.dir.join(MANIFEST_FILE);
+        fs::write(&path, json + "\n")
+            .map_err(|e| CliError::runtime(format!("Failed to write '{}': {}", path.display(), e)))
+    }
+
+    fn open(&self, key: &str, part: usize) -> CliResult<(BufWriter<File>, ShardFile)> {
+        // Fixed-interval keys are RFC3339 instants; ':' is not portable in
+        // file names.
+        let stem = key.replace(':', "-");
+        let name = match part {
+            0 => format!("{}.{}", stem, self.extension),
+            _ => format!("{}.{}.{}", stem, part, self.extension),
+        };
+        let path = self.dir.join(&name);
+        let file = File::create(&path).map_err(|e| {
+            CliError::runtime(format!("Failed to create file '{}': {}", path.display(), e))
+        })?;
+        let entry = ShardFile {
+            path: name,
+            records: 0,
+            bytes: 0,
+        };
+        Ok((BufWriter::new(file), entry))
+    }
+}
+
+fn flush(writer: &mut BufWriter<File>) -> CliResult<()> {
+    writer
+        .flush()
+        .map_err(|e| CliError::runtime(format!("Failed to write shard: {}", e)))
+}
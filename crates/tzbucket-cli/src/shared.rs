@@ -1,21 +1,207 @@
-use chrono::{DateTime, TimeZone};
+use std::fmt;
 
-use crate::error::{CliError, CliResult};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+
+use crate::error::{CliError, CliResult, OutputFormat, render_warning};
 use chrono_tz::Tz;
-use tzbucket_core::{AmbiguousPolicy, Interval, NonexistentPolicy, TimestampFormat, WeekStart};
+use serde::Serialize;
+use serde::ser::{self, Serializer};
+use tzbucket_core::tz::canonical_tz;
+use tzbucket_core::{
+    AmbiguousPolicy, Bucket, Clock, FixedClock, Interval, LabelPoint, MonthClamp,
+    NonexistentPolicy, OutputSchemaVersion, Policy, SystemClock, TimestampFormat, TzBucketError,
+    WeekKeyFormat, WeekScheme, WeekStart, compute_bucket_with_day_offset,
+};
 
 pub fn parse_interval(s: &str) -> CliResult<Interval> {
-    match s.to_lowercase().as_str() {
-        "day" => Ok(Interval::Day),
-        "week" => Ok(Interval::Week),
-        "month" => Ok(Interval::Month),
-        _ => Err(CliError::input(format!(
-            "Invalid interval '{}'. Expected: day, week, month",
-            s
-        ))),
+    let lower = s.to_lowercase();
+    match lower.as_str() {
+        "hour" => return Ok(Interval::Hour),
+        "day" => return Ok(Interval::Day),
+        "week" => return Ok(Interval::Week),
+        "month" => return Ok(Interval::Month),
+        "quarter" => return Ok(Interval::Quarter),
+        "year" => return Ok(Interval::Year),
+        _ => {}
+    }
+
+    if let Some(spec) = lower.strip_prefix("trailing_month:") {
+        let (anchor, clamp) = match spec.split_once(':') {
+            Some((anchor, "roll_forward")) => (anchor, Some(MonthClamp::RollForward)),
+            Some((anchor, "previous_valid_day")) => (anchor, Some(MonthClamp::PreviousValidDay)),
+            Some(_) => (spec, None),
+            None => (spec, Some(MonthClamp::PreviousValidDay)),
+        };
+        if let Some(clamp) = clamp
+            && let Ok(anchor_day) = anchor.parse::<u8>()
+            && (1..=31).contains(&anchor_day)
+        {
+            return Ok(Interval::TrailingMonth { anchor_day, clamp });
+        }
+    }
+
+    if let Some(month) = lower.strip_prefix("fiscal_quarter:")
+        && let Ok(start_month) = month.parse::<u8>()
+        && (1..=12).contains(&start_month)
+    {
+        return Ok(Interval::FiscalQuarter { start_month });
+    }
+
+    if let Some((n, anchor)) = lower.split_once("d:")
+        && let Ok(n) = n.parse::<u32>()
+        && n > 0
+        && let Ok(anchor) = NaiveDate::parse_from_str(anchor, "%Y-%m-%d")
+    {
+        return Ok(Interval::Days { n, anchor });
+    }
+
+    if let Some(hours) = lower.strip_suffix('h')
+        && let Ok(hours) = hours.parse::<u32>()
+        && (1..=24).contains(&hours)
+        && 24 % hours == 0
+    {
+        return Ok(Interval::Hours(hours));
+    }
+
+    if let Some(minutes) = lower.strip_suffix('m')
+        && let Ok(minutes) = minutes.parse::<u32>()
+        && (1..=1440).contains(&minutes)
+        && 1440 % minutes == 0
+    {
+        return Ok(Interval::Minutes(minutes));
+    }
+
+    if let Some(seconds) = lower.strip_prefix("fixed:")
+        && let Ok(seconds) = seconds.parse::<u64>()
+        && (1..=MAX_FIXED_SECONDS).contains(&seconds)
+    {
+        return Ok(Interval::FixedSeconds(seconds));
+    }
+
+    Err(CliError::input(format!(
+        "Invalid interval '{}'. Expected: <minutes>m (dividing 1440, e.g. 15m), hour, <hours>h (dividing 24, e.g. 6h), day, <days>d:<YYYY-MM-DD> (or <days>d with --anchor), week, month, quarter, fiscal_quarter:<start month 1-12> (e.g. fiscal_quarter:10), year, trailing_month:<1-31>[:previous_valid_day|:roll_forward], fixed:<1-{}>",
+        s, MAX_FIXED_SECONDS
+    )))
+}
+
+/// One `--interval` value: an interval, or a bare `<days>d` that `--anchor`
+/// completes to `<days>d:<anchor>`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IntervalArg {
+    Interval(Interval),
+    Days(u32),
+}
+
+impl fmt::Display for IntervalArg {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IntervalArg::Interval(interval) => interval.fmt(f),
+            IntervalArg::Days(n) => write!(f, "{}d", n),
+        }
+    }
+}
+
+impl From<Interval> for IntervalArg {
+    fn from(interval: Interval) -> Self {
+        IntervalArg::Interval(interval)
+    }
+}
+
+impl IntervalArg {
+    /// The interval, completing a bare `<days>d` with `anchor`.
+    pub fn anchored(self, anchor: Option<NaiveDate>) -> CliResult<Interval> {
+        match (self, anchor) {
+            (IntervalArg::Interval(interval), None) => Ok(interval),
+            (IntervalArg::Days(n), Some(anchor)) => Ok(Interval::Days { n, anchor }),
+            (IntervalArg::Interval(interval), Some(_)) => Err(CliError::input(format!(
+                "Invalid interval '{}' for --anchor. Expected: <days>d (e.g. 14d)",
+                interval
+            ))),
+            (IntervalArg::Days(n), None) => Err(CliError::input(format!(
+                "Invalid interval '{}d'. Expected: <days>d:<YYYY-MM-DD>, or <days>d with --anchor",
+                n
+            ))),
+        }
+    }
+}
+
+pub fn parse_interval_arg(s: &str) -> CliResult<IntervalArg> {
+    let s = s.trim();
+    if let Some(n) = s.strip_suffix(['d', 'D'])
+        && let Ok(n) = n.parse::<u32>()
+        && n > 0
+    {
+        return Ok(IntervalArg::Days(n));
+    }
+    parse_interval(s).map(IntervalArg::Interval)
+}
+
+pub fn parse_anchor(s: &str) -> CliResult<NaiveDate> {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d")
+        .map_err(|_| CliError::input(format!("Invalid anchor '{}'. Expected: YYYY-MM-DD", s)))
+}
+
+/// Parse `--interval` together with `--anchor`, the date that completes a
+/// bare `<days>d` interval to `<days>d:<anchor>`.
+pub fn parse_anchored_interval(s: &str, anchor: Option<&str>) -> CliResult<Interval> {
+    let anchor = anchor.map(parse_anchor).transpose()?;
+    parse_interval_arg(s)?.anchored(anchor)
+}
+
+/// Parse a comma-separated `--interval` list (`day,week,month`), applying
+/// `--anchor` to its `<N>d` entries.
+pub fn parse_interval_list(s: &str, anchor: Option<&str>) -> CliResult<Vec<Interval>> {
+    let anchor = anchor.map(parse_anchor).transpose()?;
+    let list = s
+        .split(',')
+        .map(parse_interval_arg)
+        .collect::<CliResult<Vec<_>>>()?;
+    anchor_intervals(&list, anchor)
+}
+
+/// The intervals of an `--interval` list, applying `--anchor` to its `<N>d`
+/// entries. An anchor without such an entry and a repeated interval are
+/// errors.
+pub fn anchor_intervals(
+    list: &[IntervalArg],
+    anchor: Option<NaiveDate>,
+) -> CliResult<Vec<Interval>> {
+    let mut intervals = Vec::new();
+    for &arg in list {
+        let interval = match arg {
+            IntervalArg::Interval(interval) => interval,
+            IntervalArg::Days(_) => arg.anchored(anchor)?,
+        };
+        if intervals.contains(&interval) {
+            return Err(CliError::input(format!(
+                "Invalid interval list '{}'. '{}' appears twice",
+                format_interval_list(list),
+                arg
+            )));
+        }
+        intervals.push(interval);
+    }
+    let anchored = list.iter().any(|arg| matches!(arg, IntervalArg::Days(_)));
+    if anchor.is_some() && !anchored {
+        return Err(CliError::input(format!(
+            "Invalid interval '{}' for --anchor. Expected: <days>d (e.g. 14d)",
+            format_interval_list(list)
+        )));
     }
+    Ok(intervals)
 }
 
+/// An `--interval` list in its command-line form (`day,week,month`).
+pub fn format_interval_list(list: &[IntervalArg]) -> String {
+    list.iter()
+        .map(|arg| arg.to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Upper bound for `fixed:<seconds>` windows (366 days).
+const MAX_FIXED_SECONDS: u64 = 366 * 86_400;
+
 pub fn parse_week_start(s: &str) -> CliResult<WeekStart> {
     match s.to_lowercase().as_str() {
         "monday" => Ok(WeekStart::Monday),
@@ -27,6 +213,543 @@ pub fn parse_week_start(s: &str) -> CliResult<WeekStart> {
     }
 }
 
+pub fn parse_week_key(s: &str) -> CliResult<WeekKeyFormat> {
+    match s.to_lowercase().as_str() {
+        "start_date" => Ok(WeekKeyFormat::StartDate),
+        "iso" => Ok(WeekKeyFormat::Iso),
+        "mmwr" => Ok(WeekKeyFormat::Mmwr),
+        "broadcast" => Ok(WeekKeyFormat::Broadcast),
+        _ => Err(CliError::input(format!(
+            "Invalid week_key '{}'. Expected: start_date, iso, mmwr, broadcast",
+            s
+        ))),
+    }
+}
+
+pub fn parse_week_scheme(s: &str) -> CliResult<WeekScheme> {
+    match s.to_lowercase().as_str() {
+        "iso" => Ok(WeekScheme::Iso),
+        "mmwr" => Ok(WeekScheme::Mmwr),
+        "broadcast" => Ok(WeekScheme::Broadcast),
+        _ => Err(CliError::input(format!(
+            "Invalid week_scheme '{}'. Expected: iso, mmwr, broadcast",
+            s
+        ))),
+    }
+}
+
+/// Week start and key format from `--week-start` and `--week-key`, or from
+/// `--week-scheme`, which sets both.
+pub fn week_options(
+    week_start: WeekStart,
+    week_key: WeekKeyFormat,
+    week_scheme: Option<WeekScheme>,
+) -> (WeekStart, WeekKeyFormat) {
+    match week_scheme {
+        Some(scheme) => (scheme.week_start(), scheme.week_key()),
+        None => (week_start, week_key),
+    }
+}
+
+/// [`week_options`] from their command-line values.
+pub fn parse_week_options(
+    week_start: &str,
+    week_key: &str,
+    week_scheme: Option<&str>,
+) -> CliResult<(WeekStart, WeekKeyFormat)> {
+    match week_scheme {
+        Some(scheme) => {
+            let scheme = parse_week_scheme(scheme)?;
+            Ok((scheme.week_start(), scheme.week_key()))
+        }
+        None => Ok((parse_week_start(week_start)?, parse_week_key(week_key)?)),
+    }
+}
+
+pub fn parse_label_point(s: &str) -> CliResult<LabelPoint> {
+    match s.to_lowercase().as_str() {
+        "start" => Ok(LabelPoint::Start),
+        "end" => Ok(LabelPoint::End),
+        "midpoint" => Ok(LabelPoint::Midpoint),
+        _ => Err(CliError::input(format!(
+            "Invalid label point '{}'. Expected: start, end, midpoint",
+            s
+        ))),
+    }
+}
+
+pub fn parse_schema_version(s: &str) -> CliResult<OutputSchemaVersion> {
+    match s {
+        "1" => Ok(OutputSchemaVersion::V1),
+        "2" => Ok(OutputSchemaVersion::V2),
+        _ => Err(CliError::input(format!(
+            "Invalid schema_version '{}'. Expected: 1, 2",
+            s
+        ))),
+    }
+}
+
+// A JSON output record in the shape of the requested schema version:
+// version 1 records keep the shape they had before versioning (without
+// `schema_version` and the bucket fields added since), later versions lead
+// with a `schema_version` field. Plain comments keep the record's own
+// description in the generated JSON Schema.
+#[derive(Debug)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schemars", schemars(rename = "{T}"))]
+pub struct Versioned<T> {
+    /// Output schema version (only in version 2 output).
+    #[cfg_attr(
+        feature = "schemars",
+        serde(default, skip_serializing_if = "Option::is_none"),
+        schemars(with = "OutputSchemaVersion")
+    )]
+    pub schema_version: Option<OutputSchemaVersion>,
+    #[cfg_attr(feature = "schemars", serde(flatten))]
+    pub record: T,
+}
+
+impl<T> Versioned<T> {
+    pub fn new(record: T, version: OutputSchemaVersion) -> Self {
+        Self {
+            schema_version: version.is_embedded().then_some(version),
+            record,
+        }
+    }
+}
+
+impl<T: Serialize> Serialize for Versioned<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct Embedded<'a, T> {
+            schema_version: OutputSchemaVersion,
+            #[serde(flatten)]
+            record: &'a T,
+        }
+
+        match self.schema_version {
+            Some(schema_version) => Embedded {
+                schema_version,
+                record: &self.record,
+            }
+            .serialize(serializer),
+            None => self.record.serialize(V1Shape(serializer)),
+        }
+    }
+}
+
+/// Fields added to output records after version 1, left out of version 1
+/// records wherever they occur.
+const FIELDS_AFTER_V1: &[&str] = &["duration_seconds"];
+
+/// A serializer passing everything through to the wrapped one except the
+/// struct and map entries named in [`FIELDS_AFTER_V1`], at any depth. Other
+/// fields keep their order, so version 1 output stays byte-identical.
+struct V1Shape<S>(S);
+
+/// A value serialized through [`V1Shape`].
+struct InV1Shape<'a, T: ?Sized>(&'a T);
+
+impl<T: Serialize + ?Sized> Serialize for InV1Shape<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(V1Shape(serializer))
+    }
+}
+
+fn is_after_v1<K: Serialize + ?Sized>(key: &K) -> bool {
+    serde_json::to_value(key)
+        .ok()
+        .and_then(|key| key.as_str().map(|key| FIELDS_AFTER_V1.contains(&key)))
+        .unwrap_or(false)
+}
+
+macro_rules! forward_primitives {
+    ($($method:ident($ty:ty)),* $(,)?) => {
+        $(fn $method(self, v: $ty) -> Result<S::Ok, S::Error> {
+            self.0.$method(v)
+        })*
+    };
+}
+
+impl<S: Serializer> Serializer for V1Shape<S> {
+    type Ok = S::Ok;
+    type Error = S::Error;
+    type SerializeSeq = V1Shape<S::SerializeSeq>;
+    type SerializeTuple = V1Shape<S::SerializeTuple>;
+    type SerializeTupleStruct = V1Shape<S::SerializeTupleStruct>;
+    type SerializeTupleVariant = V1Shape<S::SerializeTupleVariant>;
+    type SerializeMap = V1Shape<S::SerializeMap>;
+    type SerializeStruct = V1Shape<S::SerializeStruct>;
+    type SerializeStructVariant = V1Shape<S::SerializeStructVariant>;
+
+    forward_primitives!(
+        serialize_bool(bool),
+        serialize_i8(i8),
+        serialize_i16(i16),
+        serialize_i32(i32),
+        serialize_i64(i64),
+        serialize_i128(i128),
+        serialize_u8(u8),
+        serialize_u16(u16),
+        serialize_u32(u32),
+        serialize_u64(u64),
+        serialize_u128(u128),
+        serialize_f32(f32),
+        serialize_f64(f64),
+        serialize_char(char),
+        serialize_str(&str),
+        serialize_bytes(&[u8]),
+        serialize_unit_struct(&'static str),
+    );
+
+    fn serialize_none(self) -> Result<S::Ok, S::Error> {
+        self.0.serialize_none()
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<S::Ok, S::Error> {
+        self.0.serialize_some(&InV1Shape(value))
+    }
+
+    fn serialize_unit(self) -> Result<S::Ok, S::Error> {
+        self.0.serialize_unit()
+    }
+
+    fn serialize_unit_variant(
+        self,
+        name: &'static str,
+        index: u32,
+        variant: &'static str,
+    ) -> Result<S::Ok, S::Error> {
+        self.0.serialize_unit_variant(name, index, variant)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<S::Ok, S::Error> {
+        self.0.serialize_newtype_struct(name, &InV1Shape(value))
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<S::Ok, S::Error> {
+        self.0
+            .serialize_newtype_variant(name, index, variant, &InV1Shape(value))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, S::Error> {
+        self.0.serialize_seq(len).map(V1Shape)
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, S::Error> {
+        self.0.serialize_tuple(len).map(V1Shape)
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, S::Error> {
+        self.0.serialize_tuple_struct(name, len).map(V1Shape)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, S::Error> {
+        self.0
+            .serialize_tuple_variant(name, index, variant, len)
+            .map(V1Shape)
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, S::Error> {
+        self.0.serialize_map(len).map(V1Shape)
+    }
+
+    fn serialize_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct, S::Error> {
+        self.0.serialize_struct(name, len).map(V1Shape)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStructVariant, S::Error> {
+        self.0
+            .serialize_struct_variant(name, index, variant, len)
+            .map(V1Shape)
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.0.is_human_readable()
+    }
+}
+
+impl<S: ser::SerializeSeq> ser::SerializeSeq for V1Shape<S> {
+    type Ok = S::Ok;
+    type Error = S::Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), S::Error> {
+        self.0.serialize_element(&InV1Shape(value))
+    }
+
+    fn end(self) -> Result<S::Ok, S::Error> {
+        self.0.end()
+    }
+}
+
+impl<S: ser::SerializeTuple> ser::SerializeTuple for V1Shape<S> {
+    type Ok = S::Ok;
+    type Error = S::Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), S::Error> {
+        self.0.serialize_element(&InV1Shape(value))
+    }
+
+    fn end(self) -> Result<S::Ok, S::Error> {
+        self.0.end()
+    }
+}
+
+impl<S: ser::SerializeTupleStruct> ser::SerializeTupleStruct for V1Shape<S> {
+    type Ok = S::Ok;
+    type Error = S::Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), S::Error> {
+        self.0.serialize_field(&InV1Shape(value))
+    }
+
+    fn end(self) -> Result<S::Ok, S::Error> {
+        self.0.end()
+    }
+}
+
+impl<S: ser::SerializeTupleVariant> ser::SerializeTupleVariant for V1Shape<S> {
+    type Ok = S::Ok;
+    type Error = S::Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), S::Error> {
+        self.0.serialize_field(&InV1Shape(value))
+    }
+
+    fn end(self) -> Result<S::Ok, S::Error> {
+        self.0.end()
+    }
+}
+
+impl<S: ser::SerializeMap> ser::SerializeMap for V1Shape<S> {
+    type Ok = S::Ok;
+    type Error = S::Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), S::Error> {
+        self.0.serialize_key(&InV1Shape(key))
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), S::Error> {
+        self.0.serialize_value(&InV1Shape(value))
+    }
+
+    // Structs with flattened fields (such as `Bucket`) serialize as maps,
+    // one entry per field.
+    fn serialize_entry<K: Serialize + ?Sized, V: Serialize + ?Sized>(
+        &mut self,
+        key: &K,
+        value: &V,
+    ) -> Result<(), S::Error> {
+        if is_after_v1(key) {
+            return Ok(());
+        }
+        self.0.serialize_entry(&InV1Shape(key), &InV1Shape(value))
+    }
+
+    fn end(self) -> Result<S::Ok, S::Error> {
+        self.0.end()
+    }
+}
+
+impl<S: ser::SerializeStruct> ser::SerializeStruct for V1Shape<S> {
+    type Ok = S::Ok;
+    type Error = S::Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), S::Error> {
+        if FIELDS_AFTER_V1.contains(&key) {
+            return self.0.skip_field(key);
+        }
+        self.0.serialize_field(key, &InV1Shape(value))
+    }
+
+    fn end(self) -> Result<S::Ok, S::Error> {
+        self.0.end()
+    }
+}
+
+impl<S: ser::SerializeStructVariant> ser::SerializeStructVariant for V1Shape<S> {
+    type Ok = S::Ok;
+    type Error = S::Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), S::Error> {
+        if FIELDS_AFTER_V1.contains(&key) {
+            return self.0.skip_field(key);
+        }
+        self.0.serialize_field(key, &InV1Shape(value))
+    }
+
+    fn end(self) -> Result<S::Ok, S::Error> {
+        self.0.end()
+    }
+}
+
+/// What `bucket --output-dir` shards records by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShardBy {
+    Key,
+}
+
+pub fn parse_shard_by(s: &str) -> CliResult<ShardBy> {
+    match s {
+        "key" => Ok(ShardBy::Key),
+        _ => Err(CliError::input(format!(
+            "Invalid shard_by '{}'. Expected: key",
+            s
+        ))),
+    }
+}
+
+/// What `bucket --future-policy` does with instants after the current time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FuturePolicy {
+    /// Bucket them like any other instant.
+    Allow,
+    /// Fail the line.
+    Reject,
+    /// Bucket them as the current time.
+    ClampNow,
+}
+
+pub fn parse_future_policy(s: &str) -> CliResult<FuturePolicy> {
+    match s.to_lowercase().as_str() {
+        "allow" => Ok(FuturePolicy::Allow),
+        "reject" => Ok(FuturePolicy::Reject),
+        "clamp_now" | "clamp-now" => Ok(FuturePolicy::ClampNow),
+        _ => Err(CliError::input(format!(
+            "Invalid future_policy '{}'. Expected: allow, reject, clamp_now",
+            s
+        ))),
+    }
+}
+
+/// Parse a byte size such as `1048576`, `64K`, `512M` or `2G` (powers of 1024).
+pub fn parse_size(s: &str) -> CliResult<u64> {
+    let invalid = || {
+        CliError::input(format!(
+            "Invalid size '{}'. Expected: positive byte count, optionally with K, M or G suffix",
+            s
+        ))
+    };
+    let (digits, unit) = match s.char_indices().last() {
+        Some((i, 'K' | 'k')) => (&s[..i], 1 << 10),
+        Some((i, 'M' | 'm')) => (&s[..i], 1 << 20),
+        Some((i, 'G' | 'g')) => (&s[..i], 1 << 30),
+        _ => (s, 1),
+    };
+    digits
+        .parse::<u64>()
+        .ok()
+        .and_then(|n| n.checked_mul(unit))
+        .filter(|&bytes| bytes > 0)
+        .ok_or_else(invalid)
+}
+
+/// Parse a non-negative duration such as `90s`, `5m`, `2h` or `1d`.
+pub fn parse_duration(s: &str) -> CliResult<chrono::Duration> {
+    let invalid = || {
+        CliError::input(format!(
+            "Invalid duration '{}'. Expected: number with s, m, h or d suffix (e.g. 5m)",
+            s
+        ))
+    };
+    let (digits, unit_seconds) = match s.char_indices().last() {
+        Some((i, 's')) => (&s[..i], 1),
+        Some((i, 'm')) => (&s[..i], 60),
+        Some((i, 'h')) => (&s[..i], 3600),
+        Some((i, 'd')) => (&s[..i], 86_400),
+        _ => return Err(invalid()),
+    };
+    digits
+        .parse::<i64>()
+        .ok()
+        .filter(|&n| n >= 0)
+        .and_then(|n| n.checked_mul(unit_seconds))
+        .and_then(chrono::Duration::try_seconds)
+        .ok_or_else(invalid)
+}
+
+/// Parse a signed clock offset `[+|-]HH:MM:SS` (e.g. `+00:02:30`).
+pub fn parse_skew(s: &str) -> CliResult<chrono::Duration> {
+    let invalid = || {
+        CliError::input(format!(
+            "Invalid skew '{}'. Expected: [+|-]HH:MM:SS (e.g. +00:02:30)",
+            s
+        ))
+    };
+    let (sign, rest) = match s.as_bytes().first() {
+        Some(b'-') => (-1, &s[1..]),
+        Some(b'+') => (1, &s[1..]),
+        _ => (1, s),
+    };
+    let parts: Vec<&str> = rest.split(':').collect();
+    let [hours, minutes, seconds] = parts.as_slice() else {
+        return Err(invalid());
+    };
+    let field = |text: &str, max: i64| {
+        text.parse::<i64>()
+            .ok()
+            .filter(|n| text.len() == 2 && (0..=max).contains(n))
+    };
+    match (field(hours, 99), field(minutes, 59), field(seconds, 59)) {
+        (Some(h), Some(m), Some(sec)) => {
+            Ok(chrono::Duration::seconds(sign * (h * 3600 + m * 60 + sec)))
+        }
+        _ => Err(invalid()),
+    }
+}
+
+/// Format a duration as `[+|-]HH:MM:SS`, the inverse of [`parse_skew`].
+pub fn format_skew(skew: chrono::Duration) -> String {
+    let seconds = skew.num_seconds();
+    let sign = if seconds < 0 { '-' } else { '+' };
+    let seconds = seconds.abs();
+    format!(
+        "{}{:02}:{:02}:{:02}",
+        sign,
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
 pub fn parse_format(s: &str) -> CliResult<TimestampFormat> {
     match s.to_lowercase().as_str() {
         "epoch_ms" => Ok(TimestampFormat::EpochMs),
@@ -62,12 +785,88 @@ pub fn parse_ambiguous_policy(s: &str) -> CliResult<AmbiguousPolicy> {
     }
 }
 
+/// A `range --start`/`--end` bound.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RangeBound {
+    /// An RFC3339 instant.
+    Instant(DateTime<Utc>),
+    /// A `date -d`-style expression such as `2 weeks ago` (`humantime`
+    /// feature), evaluated in the range's zone against the clock when the
+    /// command runs.
+    #[cfg(feature = "humantime")]
+    Human(String),
+}
+
+impl fmt::Display for RangeBound {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RangeBound::Instant(at) => {
+                f.write_str(&at.to_rfc3339_opts(chrono::SecondsFormat::AutoSi, true))
+            }
+            #[cfg(feature = "humantime")]
+            RangeBound::Human(expr) => f.write_str(expr),
+        }
+    }
+}
+
+impl From<DateTime<Utc>> for RangeBound {
+    fn from(at: DateTime<Utc>) -> Self {
+        RangeBound::Instant(at)
+    }
+}
+
+/// Parse a range bound: RFC3339, or with the `humantime` feature an
+/// expression. Expressions are only checked here, in UTC at the system time;
+/// errors report the strict parse.
+pub fn parse_range_bound(s: &str) -> CliResult<RangeBound> {
+    match tzbucket_core::parse_timestamp(s, TimestampFormat::Rfc3339) {
+        Ok(at) => Ok(RangeBound::Instant(at)),
+        #[cfg(feature = "humantime")]
+        Err(_) if tzbucket_core::human::parse_human(s, chrono_tz::UTC, Utc::now()).is_ok() => {
+            Ok(RangeBound::Human(s.to_string()))
+        }
+        Err(e) => Err(CliError::input(e.to_string())),
+    }
+}
+
 pub fn parse_rfc3339_to_utc(s: &str) -> CliResult<DateTime<chrono::Utc>> {
     DateTime::parse_from_rfc3339(s)
         .map(|dt| dt.with_timezone(&chrono::Utc))
         .map_err(|e| CliError::runtime(format!("Failed to parse RFC3339 '{}': {}", s, e)))
 }
 
+/// Parse a `--from`/`--to` pair of years or RFC3339 instants into a
+/// half-open UTC range. A year stands for January 1 of that year, or of the
+/// next one for `--to`, so `--from 2020 --to 2030` covers both years.
+pub fn parse_year_range(from: &str, to: &str) -> CliResult<(DateTime<Utc>, DateTime<Utc>)> {
+    let start = parse_year_bound(from, "from", false)?;
+    let end = parse_year_bound(to, "to", true)?;
+    if start >= end {
+        return Err(CliError::input(format!(
+            "Invalid range: --from {} is not before --to {}",
+            from, to
+        )));
+    }
+    Ok((start, end))
+}
+
+fn parse_year_bound(s: &str, flag: &str, end: bool) -> CliResult<DateTime<Utc>> {
+    if let Ok(year) = s.parse::<i32>() {
+        let year = if end { year.checked_add(1) } else { Some(year) };
+        if let Some(start) = year.and_then(|y| Utc.with_ymd_and_hms(y, 1, 1, 0, 0, 0).single()) {
+            return Ok(start);
+        }
+    }
+    DateTime::parse_from_rfc3339(s)
+        .map(|dt| dt.with_timezone(&Utc))
+        .map_err(|_| {
+            CliError::input(format!(
+                "Invalid --{} '{}'. Expected: a year (e.g. 1970) or an RFC3339 timestamp",
+                flag, s
+            ))
+        })
+}
+
 pub fn format_rfc3339<T: TimeZone>(dt: &DateTime<T>) -> String
 where
     T::Offset: std::fmt::Display,
@@ -79,3 +878,231 @@ pub fn parse_tz_or_input_error(name: &str) -> CliResult<Tz> {
     tzbucket_core::tz::parse_tz(name)
         .map_err(|e| CliError::input(format!("Invalid timezone '{}': {}", name, e)))
 }
+
+/// The zones to bucket in: every `--tz`, the zone at the `--lat`/`--lon`
+/// `location`, or with `--country` the country's zones from `zone.tab`. A
+/// country with several zones is an error listing them unless `all_zones`
+/// asks for every one.
+pub fn resolve_zones(
+    tz: &[Tz],
+    country: Option<&str>,
+    all_zones: bool,
+    location: Option<(f64, f64)>,
+) -> CliResult<Vec<Tz>> {
+    if let Some((lat, lon)) = location {
+        return Ok(vec![zone_for_location(lat, lon)?]);
+    }
+    let Some(country) = country else {
+        return Ok(tz.to_vec());
+    };
+    let zones = zones_for_country(country)?;
+    match zones.as_slice() {
+        [] => Err(CliError::input(format!(
+            "Invalid country '{}'. Expected: an ISO 3166 country code listed in zone.tab",
+            country
+        ))),
+        [_] => Ok(zones),
+        _ if all_zones => Ok(zones),
+        _ => {
+            let names: Vec<String> = zones.iter().map(|tz| tz.to_string()).collect();
+            Err(CliError::input(format!(
+                "Country '{}' has {} zones: {}. Use --tz with one of them, or --all-zones.",
+                country,
+                zones.len(),
+                names.join(", ")
+            )))
+        }
+    }
+}
+
+/// Warn on stderr about a legacy zone name such as `US/Eastern`, or reject it
+/// when `strict`. Returns the current name to report as `canonical_tz`.
+pub fn check_legacy_tz(tz: Tz, strict: bool, output_format: OutputFormat) -> CliResult<Option<Tz>> {
+    let Some(canonical) = canonical_tz(tz) else {
+        return Ok(None);
+    };
+    let message = format!("Timezone '{}' is a legacy name for '{}'", tz, canonical);
+    if strict {
+        return Err(CliError::input(format!("{} (--strict-tz)", message)));
+    }
+    render_warning(
+        message,
+        "legacy_tz",
+        LegacyTz {
+            tz: tz.to_string(),
+            canonical_tz: canonical.to_string(),
+        },
+        output_format,
+    );
+    Ok(Some(canonical))
+}
+
+#[derive(Debug, Serialize)]
+struct LegacyTz {
+    tz: String,
+    canonical_tz: String,
+}
+
+#[cfg(feature = "zone-tab")]
+fn zones_for_country(country: &str) -> CliResult<Vec<Tz>> {
+    Ok(tzbucket_core::tz::zones_for_country(country))
+}
+
+#[cfg(not(feature = "zone-tab"))]
+fn zones_for_country(_country: &str) -> CliResult<Vec<Tz>> {
+    Err(CliError::input(
+        "--country requires tzbucket built with the zone-tab feature",
+    ))
+}
+
+#[cfg(feature = "tzf-rs")]
+fn zone_for_location(lat: f64, lon: f64) -> CliResult<Tz> {
+    tzbucket_core::tz::tz_for_location(lat, lon)
+        .map_err(|e| CliError::input(format!("Invalid location '{}, {}': {}", lat, lon, e)))
+}
+
+#[cfg(not(feature = "tzf-rs"))]
+fn zone_for_location(_lat: f64, _lon: f64) -> CliResult<Tz> {
+    Err(CliError::input(
+        "--lat/--lon requires tzbucket built with the tzf-rs feature",
+    ))
+}
+
+/// Clock for commands that read "now": the system clock, or a fixed instant
+/// from the global `--now-override` flag.
+pub fn clock_from_override(now_override: Option<&str>) -> CliResult<Box<dyn Clock>> {
+    match now_override {
+        None => Ok(Box::new(SystemClock)),
+        Some(s) => DateTime::parse_from_rfc3339(s)
+            .map(|dt| Box::new(FixedClock(dt.with_timezone(&chrono::Utc))) as Box<dyn Clock>)
+            .map_err(|e| CliError::input(format!("Invalid now_override '{}': {}", s, e))),
+    }
+}
+
+/// `--day-offset`: the local time buckets start at, and the policies for
+/// boundaries where that time is skipped or repeated.
+#[derive(Debug, Clone, Copy)]
+pub struct DayOffset {
+    pub time: NaiveTime,
+    pub policy: Policy,
+}
+
+impl DayOffset {
+    /// The bucket of `instant` with days starting at the offset.
+    pub fn bucket(
+        self,
+        instant: DateTime<Utc>,
+        tz: Tz,
+        interval: Interval,
+        week_start: WeekStart,
+    ) -> CliResult<Bucket> {
+        compute_bucket_with_day_offset(
+            instant,
+            tz,
+            interval,
+            Some(week_start),
+            self.time,
+            self.policy,
+        )
+        .map_err(|err| match err {
+            TzBucketError::NonexistentTime { local, .. }
+            | TzBucketError::AmbiguousTime { local, .. } => resolve_error_to_cli(err, local, tz),
+            other => CliError::input(other.to_string()),
+        })
+    }
+}
+
+/// Parse a `--day-offset` time: `HH:MM` or `HH:MM:SS`.
+pub fn parse_day_offset_time(s: &str) -> CliResult<NaiveTime> {
+    NaiveTime::parse_from_str(s, "%H:%M:%S")
+        .or_else(|_| NaiveTime::parse_from_str(s, "%H:%M"))
+        .map_err(|_| {
+            CliError::input(format!(
+                "Invalid day offset '{}'. Expected: HH:MM or HH:MM:SS",
+                s
+            ))
+        })
+}
+
+/// The `--day-offset` with its `--policy-nonexistent` and
+/// `--policy-ambiguous`, checking that `interval` is made of whole days.
+pub fn check_day_offset(
+    time: Option<NaiveTime>,
+    policy: Policy,
+    interval: Interval,
+) -> CliResult<Option<DayOffset>> {
+    let Some(time) = time else {
+        return Ok(None);
+    };
+    if matches!(
+        interval,
+        Interval::Minutes(_) | Interval::Hour | Interval::Hours(_) | Interval::FixedSeconds(_)
+    ) {
+        return Err(CliError::input(format!(
+            "Invalid interval '{}' for --day-offset. Expected an interval of whole days: day, <N>d:<anchor>, week, month, quarter, fiscal_quarter:<M>, year, trailing_month:<N>",
+            interval
+        )));
+    }
+    Ok(Some(DayOffset { time, policy }))
+}
+
+pub fn parse_local_time(s: &str) -> CliResult<NaiveDateTime> {
+    let formats = [
+        "%Y-%m-%dT%H:%M:%S",
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%dT%H:%M",
+        "%Y-%m-%d %H:%M",
+    ];
+
+    for fmt in &formats {
+        if let Ok(dt) = NaiveDateTime::parse_from_str(s, fmt) {
+            return Ok(dt);
+        }
+    }
+
+    Err(CliError::input(format!(
+        "Invalid local time format '{}'. Expected: YYYY-MM-DDTHH:MM:SS",
+        s
+    )))
+}
+
+/// Map a failed write of command output to a runtime error.
+pub fn write_error(err: std::io::Error) -> CliError {
+    CliError::runtime(format!("Failed to write output: {}", err))
+}
+
+/// Encode one NDJSON record as compact JSON. With the `simd-json` feature the
+/// record is encoded by simd-json instead of serde_json; the bytes are the same.
+pub fn json_line<T: Serialize + ?Sized>(record: &T) -> CliResult<String> {
+    #[cfg(feature = "simd-json")]
+    let json = simd_json::serde::to_string(record).map_err(|e| e.to_string());
+    #[cfg(not(feature = "simd-json"))]
+    let json = serde_json::to_string(record).map_err(|e| e.to_string());
+    json.map_err(|e| CliError::runtime(format!("Failed to serialize JSON: {}", e)))
+}
+
+/// Map a core resolution error to a CLI error, pointing policy errors at the
+/// flag that resolves them.
+pub fn resolve_error_to_cli(err: TzBucketError, local: NaiveDateTime, tz: Tz) -> CliError {
+    match err {
+        TzBucketError::AmbiguousTime { .. } => CliError::policy(
+            format!(
+                "Ambiguous time '{}' in timezone '{}'. Occurs twice due to DST fall back. \
+                 Use --policy-ambiguous=first or --policy-ambiguous=second to resolve.",
+                local.format("%Y-%m-%dT%H:%M:%S"),
+                tz
+            ),
+            "ambiguous",
+        ),
+        TzBucketError::NonexistentTime { .. } => CliError::policy(
+            format!(
+                "Nonexistent time '{}' in timezone '{}'. Skipped due to DST spring forward. \
+                 Use --policy-nonexistent=shift_forward to resolve.",
+                local.format("%Y-%m-%dT%H:%M:%S"),
+                tz
+            ),
+            "nonexistent",
+        ),
+        other => CliError::runtime(other.to_string()),
+    }
+}
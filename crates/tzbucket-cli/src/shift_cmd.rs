@@ -0,0 +1,50 @@
+use std::io::Write;
+use std::process::ExitCode;
+
+use tzbucket_core::{TimestampFormat, parse_timestamp, shift_bucket, shift_bucket_at};
+
+use crate::cli::ShiftArgs;
+use crate::error::{CliError, CliResult, EXIT_SUCCESS, OutputFormat};
+use crate::shared::{parse_interval, parse_tz_or_input_error, parse_week_start, write_error};
+use crate::style::Style;
+
+pub fn run_shift(
+    args: ShiftArgs,
+    output_format: OutputFormat,
+    mut out: impl Write,
+) -> CliResult<ExitCode> {
+    let tz = parse_tz_or_input_error(&args.tz)?;
+    let interval = parse_interval(&args.interval)?;
+    let week_start = parse_week_start(&args.week_start)?;
+
+    let bucket = match (&args.key, &args.at) {
+        (Some(key), _) => shift_bucket(key, tz, interval, Some(week_start), args.by),
+        (None, Some(at)) => {
+            let at = parse_timestamp(at, TimestampFormat::Rfc3339)
+                .map_err(|e| CliError::input(format!("Invalid --at timestamp: {}", e)))?;
+            shift_bucket_at(at, tz, interval, Some(week_start), args.by)
+        }
+        (None, None) => return Err(CliError::input("Either --key or --at is required")),
+    }
+    .map_err(|e| CliError::input(e.to_string()))?;
+
+    match output_format {
+        OutputFormat::Json => {
+            let json = serde_json::to_string(&bucket)
+                .map_err(|e| CliError::runtime(format!("Failed to serialize JSON: {}", e)))?;
+            writeln!(out, "{}", json).map_err(write_error)?;
+        }
+        OutputFormat::Text => {
+            writeln!(
+                out,
+                "{}: {} to {}",
+                Style::stdout().bucket_key(&bucket.key, &bucket.start_local, &bucket.end_local),
+                bucket.start_local,
+                bucket.end_local
+            )
+            .map_err(write_error)?;
+        }
+    }
+
+    Ok(ExitCode::from(EXIT_SUCCESS))
+}
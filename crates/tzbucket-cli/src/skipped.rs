@@ -0,0 +1,78 @@
+//! `--errors-to`: where the line-oriented commands report the input lines
+//! they skip.
+//!
+//! Without the flag, the first bad line aborts the command. With it, a line
+//! whose timestamp cannot be decoded, parsed or resolved is skipped and
+//! reported as one NDJSON [`SkippedLine`] record, so a long stream survives
+//! a few malformed lines. Write failures still abort.
+
+use std::fs::File;
+use std::io::{self, Write};
+
+use serde::Serialize;
+
+use crate::error::{CliError, CliResult, EXIT_INPUT_ERROR};
+use crate::shared::json_line;
+
+/// A skipped input line, as written to the `--errors-to` target.
+#[derive(Debug, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub(crate) struct SkippedLine<'a> {
+    /// 1-based line number in the input.
+    line: usize,
+    /// The line, trimmed.
+    input: &'a str,
+    /// Why the line was skipped.
+    error: String,
+}
+
+/// Decides per line whether an input error aborts the command or skips the
+/// line.
+pub struct LineErrors {
+    target: Option<Box<dyn Write>>,
+}
+
+impl LineErrors {
+    /// Parse `--errors-to`: `stderr`, `null`, or a file path (created or
+    /// truncated). `None` aborts on the first bad line.
+    pub fn open(errors_to: Option<&str>) -> CliResult<Self> {
+        let target: Option<Box<dyn Write>> = match errors_to {
+            None => None,
+            Some("stderr") => Some(Box::new(io::stderr())),
+            Some("null") => Some(Box::new(io::sink())),
+            Some(path) => {
+                let file = File::create(path).map_err(|e| {
+                    CliError::runtime(format!("Failed to create '{}': {}", path, e))
+                })?;
+                Some(Box::new(file))
+            }
+        };
+        Ok(Self { target })
+    }
+
+    /// The value of a processed line, or `None` if it failed with an input
+    /// error and was reported to the `--errors-to` target.
+    pub fn check<T>(
+        &mut self,
+        line: usize,
+        input: &str,
+        result: CliResult<T>,
+    ) -> CliResult<Option<T>> {
+        match (result, &mut self.target) {
+            (Ok(value), _) => Ok(Some(value)),
+            (Err(err), Some(target)) if err.exit_code() == EXIT_INPUT_ERROR => {
+                let record = SkippedLine {
+                    line,
+                    input,
+                    error: err.to_string(),
+                };
+                let json = json_line(&record)?;
+                writeln!(target, "{}", json).map_err(|e| {
+                    CliError::runtime(format!("Failed to write skipped line: {}", e))
+                })?;
+                Ok(None)
+            }
+            (Err(err), _) => Err(err),
+        }
+    }
+}
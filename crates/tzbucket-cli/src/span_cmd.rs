@@ -0,0 +1,85 @@
+use std::io::Write;
+use std::process::ExitCode;
+
+use chrono_tz::Tz;
+use serde::Serialize;
+use tzbucket_core::{
+    Interval, TimestampFormat, WeekStart, buckets_between, compute_bucket, parse_timestamp,
+};
+
+use crate::cli::SpanArgs;
+use crate::error::{CliError, CliResult, EXIT_SUCCESS, OutputFormat};
+use crate::shared::{parse_interval, parse_tz_or_input_error, parse_week_start, write_error};
+
+pub fn run_span(
+    args: SpanArgs,
+    output_format: OutputFormat,
+    mut out: impl Write,
+) -> CliResult<ExitCode> {
+    let tz = parse_tz_or_input_error(&args.tz)?;
+    let interval = parse_interval(&args.interval)?;
+    let week_start = parse_week_start(&args.week_start)?;
+
+    let from_key = resolve_key(
+        args.from_key,
+        args.from_at,
+        "--from",
+        tz,
+        interval,
+        week_start,
+    )?;
+    let to_key = resolve_key(args.to_key, args.to_at, "--to", tz, interval, week_start)?;
+
+    let intervals = buckets_between(&from_key, &to_key, tz, interval, Some(week_start))
+        .map_err(|e| CliError::input(e.to_string()))?;
+
+    match output_format {
+        OutputFormat::Json => {
+            let span = Span {
+                from_key: &from_key,
+                to_key: &to_key,
+                interval,
+                intervals,
+            };
+            let json = serde_json::to_string(&span)
+                .map_err(|e| CliError::runtime(format!("Failed to serialize JSON: {}", e)))?;
+            writeln!(out, "{}", json).map_err(write_error)?;
+        }
+        OutputFormat::Text => writeln!(out, "{}", intervals).map_err(write_error)?,
+    }
+
+    Ok(ExitCode::from(EXIT_SUCCESS))
+}
+
+/// The given `<flag>-key`, or the key of the bucket containing `<flag>-at`.
+fn resolve_key(
+    key: Option<String>,
+    at: Option<String>,
+    flag: &str,
+    tz: Tz,
+    interval: Interval,
+    week_start: WeekStart,
+) -> CliResult<String> {
+    match (key, at) {
+        (Some(key), _) => Ok(key),
+        (None, Some(at)) => {
+            let at = parse_timestamp(&at, TimestampFormat::Rfc3339)
+                .map_err(|e| CliError::input(format!("Invalid {}-at timestamp: {}", flag, e)))?;
+            Ok(compute_bucket(at, tz, interval, Some(week_start))
+                .key
+                .to_string())
+        }
+        (None, None) => Err(CliError::input(format!(
+            "Either {}-key or {}-at is required",
+            flag, flag
+        ))),
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct Span<'a> {
+    from_key: &'a str,
+    to_key: &'a str,
+    interval: Interval,
+    intervals: i64,
+}
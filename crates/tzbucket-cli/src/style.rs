@@ -0,0 +1,121 @@
+//! Terminal styling of text output (global `--color` flag).
+//!
+//! `auto` colors a stream only when it is a terminal and `NO_COLOR` is unset
+//! or empty; `always` and `never` override both. Bucket keys are bold cyan,
+//! keys of buckets that span a UTC offset change (23h/25h days) bold yellow,
+//! the `Warning:` prefix bold yellow and the `Error:` prefix bold red.
+
+use std::io::IsTerminal;
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::error::{CliError, CliResult};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorChoice {
+    Auto,
+    Always,
+    Never,
+}
+
+pub fn parse_color_choice(s: &str) -> CliResult<ColorChoice> {
+    match s.to_lowercase().as_str() {
+        "auto" => Ok(ColorChoice::Auto),
+        "always" => Ok(ColorChoice::Always),
+        "never" => Ok(ColorChoice::Never),
+        _ => Err(CliError::input(format!(
+            "Invalid color '{}'. Expected: auto, always, never",
+            s
+        ))),
+    }
+}
+
+static COLOR_CHOICE: OnceLock<ColorChoice> = OnceLock::new();
+
+/// Set the process-wide color choice; later calls are ignored.
+pub fn set_color_choice(choice: ColorChoice) {
+    let _ = COLOR_CHOICE.set(choice);
+}
+
+static OUTPUT_TO_FILE: AtomicBool = AtomicBool::new(false);
+
+/// Mark command output as going to an `--output` file rather than stdout;
+/// `auto` then leaves it plain.
+pub fn set_output_to_file() {
+    OUTPUT_TO_FILE.store(true, Ordering::Relaxed);
+}
+
+const RESET: &str = "\x1b[0m";
+const KEY: &str = "\x1b[1;36m";
+const OFFSET_CHANGE: &str = "\x1b[1;33m";
+const WARNING: &str = "\x1b[1;33m";
+const ERROR: &str = "\x1b[1;31m";
+
+/// Styling for one output stream; plain when colors are off.
+#[derive(Debug, Clone, Copy)]
+pub struct Style {
+    enabled: bool,
+}
+
+impl Style {
+    /// Styling for command output (stdout, or the `--output` file) under
+    /// the color choice.
+    pub fn stdout() -> Self {
+        Self::for_stream(std::io::stdout().is_terminal() && !OUTPUT_TO_FILE.load(Ordering::Relaxed))
+    }
+
+    /// Styling for stderr under the color choice.
+    pub fn stderr() -> Self {
+        Self::for_stream(std::io::stderr().is_terminal())
+    }
+
+    /// No styling, e.g. for output written to files.
+    pub fn plain() -> Self {
+        Self { enabled: false }
+    }
+
+    fn for_stream(is_terminal: bool) -> Self {
+        let enabled = match COLOR_CHOICE.get().copied().unwrap_or(ColorChoice::Auto) {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => {
+                is_terminal && std::env::var_os("NO_COLOR").is_none_or(|v| v.is_empty())
+            }
+        };
+        Self { enabled }
+    }
+
+    /// A bucket key, highlighted if the bucket's local boundaries carry
+    /// different UTC offsets.
+    pub fn bucket_key(self, key: &str, start_local: &str, end_local: &str) -> String {
+        if offset_of(start_local) != offset_of(end_local) {
+            self.paint(OFFSET_CHANGE, key)
+        } else {
+            self.paint(KEY, key)
+        }
+    }
+
+    pub fn warning(self, text: &str) -> String {
+        self.paint(WARNING, text)
+    }
+
+    pub fn error(self, text: &str) -> String {
+        self.paint(ERROR, text)
+    }
+
+    fn paint(self, code: &str, text: &str) -> String {
+        if self.enabled {
+            format!("{}{}{}", code, text, RESET)
+        } else {
+            text.to_string()
+        }
+    }
+}
+
+/// The `+HH:MM`/`-HH:MM`/`Z` suffix of an RFC3339 local time.
+fn offset_of(rfc3339: &str) -> &str {
+    match rfc3339.rfind(['+', '-', 'Z']) {
+        Some(i) => &rfc3339[i..],
+        None => "",
+    }
+}
@@ -0,0 +1,67 @@
+//! The `--summary` footer of `bucket` and `count` text output.
+
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use tzbucket_core::Bucket;
+use tzbucket_core::tz::{format_rfc3339_utc, offset_transitions};
+
+use crate::error::CliResult;
+use crate::shared::parse_rfc3339_to_utc;
+
+/// Totals over the records of one run.
+pub struct Summary {
+    tz: Tz,
+    lines: u64,
+    /// Whether each distinct bucket (by `start_utc`) spans a UTC offset change.
+    buckets: BTreeMap<String, bool>,
+    earliest: Option<DateTime<Utc>>,
+    latest: Option<DateTime<Utc>>,
+}
+
+impl Summary {
+    pub fn new(tz: Tz) -> Self {
+        Self {
+            tz,
+            lines: 0,
+            buckets: BTreeMap::new(),
+            earliest: None,
+            latest: None,
+        }
+    }
+
+    /// Count one input line, bucketed as `instant` into `bucket` (with its
+    /// half-open boundaries).
+    pub fn record(&mut self, instant: DateTime<Utc>, bucket: &Bucket) -> CliResult<()> {
+        self.lines += 1;
+        self.earliest = Some(self.earliest.map_or(instant, |t| t.min(instant)));
+        self.latest = Some(self.latest.map_or(instant, |t| t.max(instant)));
+
+        if !self.buckets.contains_key(&bucket.start_utc) {
+            let start = parse_rfc3339_to_utc(&bucket.start_utc)?;
+            let end = parse_rfc3339_to_utc(&bucket.end_utc)?;
+            let dst_affected = !offset_transitions(self.tz, start, end).is_empty();
+            self.buckets.insert(bucket.start_utc.clone(), dst_affected);
+        }
+        Ok(())
+    }
+
+    /// Write the footer, separated from the records by a blank line.
+    pub fn write(&self, mut out: impl Write) -> io::Result<()> {
+        let format =
+            |t: Option<DateTime<Utc>>| t.map_or("-".to_string(), |t| format_rfc3339_utc(&t));
+
+        writeln!(out)?;
+        writeln!(out, "Lines: {}", self.lines)?;
+        writeln!(out, "Buckets: {}", self.buckets.len())?;
+        writeln!(
+            out,
+            "DST-affected buckets: {}",
+            self.buckets.values().filter(|&&affected| affected).count()
+        )?;
+        writeln!(out, "Earliest: {}", format(self.earliest))?;
+        writeln!(out, "Latest: {}", format(self.latest))
+    }
+}
@@ -0,0 +1,341 @@
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::process::ExitCode;
+
+use chrono::NaiveDate;
+use serde::Serialize;
+use tz::TimeZone;
+use tzbucket_core::{
+    BucketKey, Interval, TimestampFormat, WeekStart, bucket_dates, parse_timestamp,
+};
+
+use crate::cli::TzImpactArgs;
+use crate::error::{CliError, CliResult, EXIT_SUCCESS, OutputFormat};
+use crate::shared::{parse_interval, parse_week_start, write_error};
+
+pub fn run_tzimpact(
+    args: TzImpactArgs,
+    output_format: OutputFormat,
+    mut out: impl Write,
+) -> CliResult<ExitCode> {
+    let interval = parse_interval(&args.interval)?;
+    let week_start = parse_week_start(&args.week_start)?;
+    match interval {
+        Interval::FixedSeconds(_) => {
+            return Err(CliError::input(format!(
+                "Invalid interval '{}' for tzimpact: fixed windows do not depend on tzdata",
+                args.interval
+            )));
+        }
+        Interval::Minutes(_) | Interval::Hour | Interval::Hours(_) => {
+            return Err(CliError::input(format!(
+                "Invalid interval '{}' for tzimpact. Expected: day, <N>d:<anchor>, week, month, quarter, fiscal_quarter:<start month 1-12>, year, trailing_month:<1-31>",
+                args.interval
+            )));
+        }
+        _ => {}
+    }
+
+    let start = parse_timestamp(&args.start, TimestampFormat::Rfc3339)
+        .map_err(|e| CliError::input(format!("Invalid start timestamp: {}", e)))?
+        .timestamp();
+    let end = parse_timestamp(&args.end, TimestampFormat::Rfc3339)
+        .map_err(|e| CliError::input(format!("Invalid end timestamp: {}", e)))?
+        .timestamp();
+
+    if start >= end {
+        return Err(CliError::input(format!(
+            "Invalid range: start '{}' must be earlier than end '{}'",
+            args.start, args.end
+        )));
+    }
+
+    let zone_list = fs::read_to_string(&args.tz_list).map_err(|e| {
+        CliError::runtime(format!(
+            "Failed to read zone list '{}': {}",
+            args.tz_list, e
+        ))
+    })?;
+    let from_dir = Path::new(&args.tzdata_dir).join(&args.from_version);
+    let to_dir = Path::new(&args.tzdata_dir).join(&args.to_version);
+
+    let mut zones = Vec::new();
+    for name in zone_list
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+    {
+        let from_tz = load_tzif(&from_dir, name)?;
+        let to_tz = load_tzif(&to_dir, name)?;
+
+        let impact = match (from_tz, to_tz) {
+            (Some(from_tz), Some(to_tz)) => {
+                let spec = ImpactSpec {
+                    interval,
+                    week_start,
+                    start,
+                    end,
+                };
+                let from_buckets = VersionBuckets::build(&from_tz, &spec)?;
+                let to_buckets = VersionBuckets::build(&to_tz, &spec)?;
+                compare_versions(name, &from_buckets, &to_buckets, start, end)
+            }
+            (None, Some(_)) => ZoneImpact::status_only(name, "added"),
+            (Some(_), None) => ZoneImpact::status_only(name, "removed"),
+            (None, None) => {
+                return Err(CliError::input(format!(
+                    "Zone '{}' not found in '{}' or '{}'",
+                    name,
+                    from_dir.display(),
+                    to_dir.display()
+                )));
+            }
+        };
+
+        zones.push(impact);
+    }
+
+    let report = ImpactReport {
+        from_version: args.from_version,
+        to_version: args.to_version,
+        interval,
+        start: args.start,
+        end: args.end,
+        zones_total: zones.len(),
+        zones_changed: zones.iter().filter(|z| z.status != "unchanged").count(),
+        zones,
+    };
+
+    match output_format {
+        OutputFormat::Json => {
+            let json = serde_json::to_string_pretty(&report)
+                .map_err(|e| CliError::runtime(format!("Failed to serialize JSON: {}", e)))?;
+            writeln!(out, "{}", json).map_err(write_error)?;
+        }
+        OutputFormat::Text => {
+            for zone in &report.zones {
+                if zone.changed_buckets.is_empty() {
+                    writeln!(out, "{}: {}", zone.tz, zone.status).map_err(write_error)?;
+                } else {
+                    writeln!(
+                        out,
+                        "{}: {} ({} buckets, {} s reassigned): {}",
+                        zone.tz,
+                        zone.status,
+                        zone.changed_buckets.len(),
+                        zone.reassigned_seconds,
+                        zone.changed_buckets.join(", ")
+                    )
+                    .map_err(write_error)?;
+                }
+            }
+            writeln!(
+                out,
+                "Summary: {} of {} zones changed between {} and {}",
+                report.zones_changed, report.zones_total, report.from_version, report.to_version
+            )
+            .map_err(write_error)?;
+        }
+    }
+
+    Ok(ExitCode::from(EXIT_SUCCESS))
+}
+
+#[derive(Debug, Serialize)]
+struct ImpactReport {
+    from_version: String,
+    to_version: String,
+    interval: Interval,
+    start: String,
+    end: String,
+    zones_total: usize,
+    zones_changed: usize,
+    zones: Vec<ZoneImpact>,
+}
+
+#[derive(Debug, Serialize)]
+struct ZoneImpact {
+    tz: String,
+    status: &'static str,
+    reassigned_seconds: i64,
+    changed_buckets: Vec<BucketKey>,
+}
+
+impl ZoneImpact {
+    fn status_only(tz: &str, status: &'static str) -> Self {
+        Self {
+            tz: tz.to_string(),
+            status,
+            reassigned_seconds: 0,
+            changed_buckets: Vec::new(),
+        }
+    }
+}
+
+struct ImpactSpec {
+    interval: Interval,
+    week_start: WeekStart,
+    start: i64,
+    end: i64,
+}
+
+fn calendar_dates(
+    date: NaiveDate,
+    spec: &ImpactSpec,
+) -> CliResult<(NaiveDate, NaiveDate, BucketKey)> {
+    bucket_dates(date, spec.interval, spec.week_start).ok_or_else(|| {
+        CliError::runtime(format!(
+            "Interval '{}' has no calendar dates",
+            spec.interval
+        ))
+    })
+}
+
+/// Bucket boundaries of one zone under one tzdata version.
+///
+/// `keys[i]` covers the half-open UTC range `[boundaries[i], boundaries[i + 1])`.
+struct VersionBuckets {
+    boundaries: Vec<i64>,
+    keys: Vec<BucketKey>,
+}
+
+impl VersionBuckets {
+    fn build(tz: &TimeZone, spec: &ImpactSpec) -> CliResult<Self> {
+        // Local dates are padded by a day on each side so that every bucket
+        // touching the range is covered under any plausible UTC offset.
+        let first = date_of(spec.start - 86_400)?;
+        let last = date_of(spec.end + 86_400)?;
+
+        let (mut date, _, _) = calendar_dates(first, spec)?;
+        let mut boundaries = Vec::new();
+        let mut keys = Vec::new();
+
+        loop {
+            boundaries.push(local_midnight_to_unix(tz, date)?);
+            if date > last {
+                break;
+            }
+            let (_, next, key) = calendar_dates(date, spec)?;
+            keys.push(key);
+            date = next;
+        }
+
+        Ok(Self { boundaries, keys })
+    }
+
+    fn key_at(&self, t: i64) -> Option<&str> {
+        let index = self.boundaries.partition_point(|&b| b <= t);
+        if index == 0 {
+            return None;
+        }
+        self.keys.get(index - 1).map(BucketKey::as_str)
+    }
+
+    fn span(&self, index: usize) -> (i64, i64) {
+        (self.boundaries[index], self.boundaries[index + 1])
+    }
+}
+
+fn compare_versions(
+    name: &str,
+    from: &VersionBuckets,
+    to: &VersionBuckets,
+    start: i64,
+    end: i64,
+) -> ZoneImpact {
+    // Sweep every boundary of either version inside the range; between two
+    // consecutive points both versions assign a constant bucket key.
+    let mut points: Vec<i64> = from
+        .boundaries
+        .iter()
+        .chain(to.boundaries.iter())
+        .copied()
+        .filter(|&t| t > start && t < end)
+        .collect();
+    points.push(start);
+    points.push(end);
+    points.sort_unstable();
+    points.dedup();
+
+    let mut reassigned_seconds = 0;
+    for window in points.windows(2) {
+        if from.key_at(window[0]) != to.key_at(window[0]) {
+            reassigned_seconds += window[1] - window[0];
+        }
+    }
+
+    // Keys are derived from local dates only, so both versions share the
+    // same key sequence and can be compared index by index.
+    let mut changed_buckets = Vec::new();
+    for (index, key) in from.keys.iter().enumerate() {
+        let span = from.span(index);
+        let overlaps = span.0 < end && span.1 > start;
+        if overlaps && to.span(index) != span {
+            changed_buckets.push(key.clone());
+        }
+    }
+
+    let status = if changed_buckets.is_empty() && reassigned_seconds == 0 {
+        "unchanged"
+    } else {
+        "changed"
+    };
+
+    ZoneImpact {
+        tz: name.to_string(),
+        status,
+        reassigned_seconds,
+        changed_buckets,
+    }
+}
+
+fn load_tzif(dir: &Path, name: &str) -> CliResult<Option<TimeZone>> {
+    let path = dir.join(name);
+    if !path.is_file() {
+        return Ok(None);
+    }
+
+    let bytes = fs::read(&path)
+        .map_err(|e| CliError::runtime(format!("Failed to read '{}': {}", path.display(), e)))?;
+    TimeZone::from_tz_data(&bytes)
+        .map(Some)
+        .map_err(|e| CliError::input(format!("Invalid TZif file '{}': {}", path.display(), e)))
+}
+
+fn offset_at(tz: &TimeZone, unix_time: i64) -> CliResult<i64> {
+    tz.find_local_time_type(unix_time)
+        .map(|t| i64::from(t.ut_offset()))
+        .map_err(|e| CliError::runtime(format!("Offset lookup failed at {}: {}", unix_time, e)))
+}
+
+/// Resolve local midnight of `date` to Unix seconds, matching the core
+/// semantics: ambiguous times use the earliest occurrence, nonexistent times
+/// shift forward by the length of the gap.
+fn local_midnight_to_unix(tz: &TimeZone, date: NaiveDate) -> CliResult<i64> {
+    let local = date
+        .and_hms_opt(0, 0, 0)
+        .ok_or_else(|| CliError::runtime(format!("Could not construct midnight for {}", date)))?
+        .and_utc()
+        .timestamp();
+
+    let mut earliest: Option<i64> = None;
+    for probe in [local - 86_400, local, local + 86_400] {
+        let offset = offset_at(tz, probe)?;
+        let candidate = local - offset;
+        if offset_at(tz, candidate)? == offset {
+            earliest = Some(earliest.map_or(candidate, |e| e.min(candidate)));
+        }
+    }
+
+    match earliest {
+        Some(t) => Ok(t),
+        None => Ok(local - offset_at(tz, local - 86_400)?),
+    }
+}
+
+fn date_of(unix_time: i64) -> CliResult<NaiveDate> {
+    chrono::DateTime::from_timestamp(unix_time, 0)
+        .map(|dt| dt.date_naive())
+        .ok_or_else(|| CliError::input(format!("Timestamp out of range: {}", unix_time)))
+}
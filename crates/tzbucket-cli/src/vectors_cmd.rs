@@ -0,0 +1,274 @@
+use std::io::Write;
+use std::process::ExitCode;
+
+use serde::Serialize;
+use tzbucket_core::{
+    Bucket, InputTimestamp, Interval, TimestampFormat, WeekStart, compute_bucket_from_string,
+};
+
+use crate::cli::VectorsArgs;
+use crate::error::{CliError, CliResult, EXIT_SUCCESS};
+use crate::shared::write_error;
+
+/// Version of the vector file layout (bumped on incompatible changes).
+const VECTORS_FORMAT_VERSION: u32 = 1;
+
+/// JSON Schema describing the vector file emitted by this command.
+const VECTORS_SCHEMA: &str = include_str!("conformance_vectors.schema.json");
+
+pub fn run_vectors(args: VectorsArgs, mut out: impl Write) -> CliResult<ExitCode> {
+    if args.schema {
+        write!(out, "{}", VECTORS_SCHEMA).map_err(write_error)?;
+        return Ok(ExitCode::from(EXIT_SUCCESS));
+    }
+
+    let file = VectorFile {
+        format: "tzbucket-conformance-vectors",
+        version: VECTORS_FORMAT_VERSION,
+        generator: concat!("tzbucket ", env!("CARGO_PKG_VERSION")),
+        tzdb_version: chrono_tz::IANA_TZDB_VERSION,
+        cases: build_cases()?,
+    };
+
+    let json = serde_json::to_string_pretty(&file)
+        .map_err(|e| CliError::runtime(format!("Failed to serialize JSON: {}", e)))?;
+    writeln!(out, "{}", json).map_err(write_error)?;
+
+    Ok(ExitCode::from(EXIT_SUCCESS))
+}
+
+#[derive(Debug, Serialize)]
+struct VectorFile {
+    format: &'static str,
+    version: u32,
+    generator: &'static str,
+    tzdb_version: &'static str,
+    cases: Vec<VectorCase>,
+}
+
+#[derive(Debug, Serialize)]
+struct VectorCase {
+    id: &'static str,
+    category: &'static str,
+    description: &'static str,
+    input: InputTimestamp,
+    tz: &'static str,
+    interval: Interval,
+    week_start: WeekStart,
+    expected: Bucket,
+}
+
+pub(crate) struct CaseSpec {
+    pub id: &'static str,
+    pub category: &'static str,
+    pub description: &'static str,
+    pub ts: &'static str,
+    pub tz: &'static str,
+    pub interval: Interval,
+    pub week_start: WeekStart,
+}
+
+const fn case(
+    id: &'static str,
+    category: &'static str,
+    description: &'static str,
+    ts: &'static str,
+    tz: &'static str,
+    interval: Interval,
+) -> CaseSpec {
+    CaseSpec {
+        id,
+        category,
+        description,
+        ts,
+        tz,
+        interval,
+        week_start: WeekStart::Monday,
+    }
+}
+
+/// DST and calendar edge cases, shared with `selftest`.
+pub(crate) const CASES: &[CaseSpec] = &[
+    case(
+        "berlin-gap-day",
+        "gap",
+        "23-hour day on Europe/Berlin spring forward",
+        "2026-03-29T00:30:00Z",
+        "Europe/Berlin",
+        Interval::Day,
+    ),
+    case(
+        "berlin-overlap-first",
+        "overlap",
+        "First 02:30 on Europe/Berlin fall back (CEST)",
+        "2026-10-25T00:30:00Z",
+        "Europe/Berlin",
+        Interval::Day,
+    ),
+    case(
+        "berlin-overlap-second",
+        "overlap",
+        "Second 02:30 on Europe/Berlin fall back (CET)",
+        "2026-10-25T01:30:00Z",
+        "Europe/Berlin",
+        Interval::Day,
+    ),
+    case(
+        "newyork-gap-day",
+        "gap",
+        "23-hour day on America/New_York spring forward",
+        "2026-03-08T07:30:00Z",
+        "America/New_York",
+        Interval::Day,
+    ),
+    case(
+        "newyork-overlap-day",
+        "overlap",
+        "25-hour day on America/New_York fall back",
+        "2026-11-01T06:30:00Z",
+        "America/New_York",
+        Interval::Day,
+    ),
+    case(
+        "saopaulo-gap-midnight",
+        "gap",
+        "Local midnight skipped on America/Sao_Paulo DST start",
+        "2018-11-04T03:30:00Z",
+        "America/Sao_Paulo",
+        Interval::Day,
+    ),
+    case(
+        "havana-overlap-midnight",
+        "overlap",
+        "Local midnight repeated on America/Havana DST end",
+        "2026-11-01T05:30:00Z",
+        "America/Havana",
+        Interval::Day,
+    ),
+    case(
+        "lordhowe-half-hour-gap",
+        "gap",
+        "30-minute DST shift on Australia/Lord_Howe",
+        "2026-10-04T00:00:00Z",
+        "Australia/Lord_Howe",
+        Interval::Day,
+    ),
+    case(
+        "apia-skipped-day",
+        "gap",
+        "Pacific/Apia skipped 2011-12-30 when moving across the date line",
+        "2011-12-30T09:30:00Z",
+        "Pacific/Apia",
+        Interval::Day,
+    ),
+    case(
+        "berlin-week-dst",
+        "week-edge",
+        "Monday-start week containing the Europe/Berlin spring forward",
+        "2026-03-29T12:00:00Z",
+        "Europe/Berlin",
+        Interval::Week,
+    ),
+    CaseSpec {
+        week_start: WeekStart::Sunday,
+        ..case(
+            "berlin-week-sunday-start",
+            "week-edge",
+            "Sunday-start week beginning on the Europe/Berlin spring forward",
+            "2026-03-29T12:00:00Z",
+            "Europe/Berlin",
+            Interval::Week,
+        )
+    },
+    case(
+        "utc-week-year-crossing",
+        "week-edge",
+        "Monday-start week spanning the 2025/2026 year boundary",
+        "2026-01-01T12:00:00Z",
+        "UTC",
+        Interval::Week,
+    ),
+    case(
+        "berlin-month-last-second",
+        "month-edge",
+        "Last second of March in Europe/Berlin (CEST)",
+        "2026-03-31T21:59:59Z",
+        "Europe/Berlin",
+        Interval::Month,
+    ),
+    case(
+        "berlin-month-first-second",
+        "month-edge",
+        "First second of April in Europe/Berlin (CEST)",
+        "2026-03-31T22:00:00Z",
+        "Europe/Berlin",
+        Interval::Month,
+    ),
+    case(
+        "berlin-month-year-rollover",
+        "month-edge",
+        "UTC instant on Dec 31 that is already January in Europe/Berlin",
+        "2026-12-31T23:30:00Z",
+        "Europe/Berlin",
+        Interval::Month,
+    ),
+    case(
+        "utc-leap-day",
+        "leap-year",
+        "Leap day 2024-02-29",
+        "2024-02-29T12:00:00Z",
+        "UTC",
+        Interval::Day,
+    ),
+    case(
+        "berlin-leap-february",
+        "leap-year",
+        "29-day February 2024 in Europe/Berlin",
+        "2024-02-29T22:59:59Z",
+        "Europe/Berlin",
+        Interval::Month,
+    ),
+    case(
+        "utc-century-leap-2000",
+        "leap-year",
+        "2000 is a leap year (divisible by 400)",
+        "2000-02-29T00:00:00Z",
+        "UTC",
+        Interval::Day,
+    ),
+    case(
+        "utc-century-non-leap-2100",
+        "leap-year",
+        "2100 is not a leap year; February has 28 days",
+        "2100-02-28T23:59:59Z",
+        "UTC",
+        Interval::Month,
+    ),
+];
+
+fn build_cases() -> CliResult<Vec<VectorCase>> {
+    CASES
+        .iter()
+        .map(|spec| {
+            let result = compute_bucket_from_string(
+                spec.ts,
+                TimestampFormat::Rfc3339,
+                spec.tz,
+                spec.interval,
+                Some(spec.week_start),
+            )
+            .map_err(|e| CliError::runtime(format!("Vector '{}' failed: {}", spec.id, e)))?;
+
+            Ok(VectorCase {
+                id: spec.id,
+                category: spec.category,
+                description: spec.description,
+                input: result.input,
+                tz: spec.tz,
+                interval: spec.interval,
+                week_start: spec.week_start,
+                expected: result.bucket,
+            })
+        })
+        .collect()
+}
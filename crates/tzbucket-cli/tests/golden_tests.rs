@@ -262,6 +262,103 @@ fn test_range_berlin_march_2026() {
     assert_json_eq(&actual, &expected);
 }
 
+fn run_range_berlin_march(schema_version: &str) -> String {
+    let output = run_cli(&[
+        "range",
+        "--tz",
+        "Europe/Berlin",
+        "--start",
+        "2026-03-27T00:00:00Z",
+        "--end",
+        "2026-03-31T00:00:00Z",
+        "--schema-version",
+        schema_version,
+    ]);
+
+    assert!(
+        output.status.success(),
+        "CLI failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).expect("Output is not valid UTF-8")
+}
+
+#[test]
+fn test_range_schema_version_1_is_the_default() {
+    // Version 1 output is byte-identical to the default output and to the
+    // output from before versioning.
+    let expected = fs::read_to_string(golden_dir().join("range_berlin_march_2026.json"))
+        .expect("Failed to read golden file");
+    assert_eq!(run_range_berlin_march("1"), expected);
+}
+
+#[test]
+fn test_range_schema_version_2_adds_version_field() {
+    let expected = fs::read_to_string(golden_dir().join("v2/range_berlin_march_2026.json"))
+        .expect("Failed to read golden file");
+    assert_eq!(run_range_berlin_march("2"), expected);
+}
+
+fn run_bucket_berlin_dst_start(schema_version: Option<&str>) -> String {
+    let fixture_path = fixture_dir().join("berlin_dst_start_2026.txt");
+    let mut args = vec![
+        "bucket",
+        "--tz",
+        "Europe/Berlin",
+        "--interval",
+        "day",
+        "--format",
+        "rfc3339",
+        "--output-format",
+        "json",
+        "--input",
+        fixture_path.to_str().unwrap(),
+    ];
+    if let Some(version) = schema_version {
+        args.extend(["--schema-version", version]);
+    }
+    let output = run_cli(&args);
+
+    assert!(
+        output.status.success(),
+        "CLI failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).expect("Output is not valid UTF-8")
+}
+
+#[test]
+fn test_bucket_schema_version_1_matches_baseline_bytes() {
+    let expected = fs::read_to_string(golden_dir().join("berlin_dst_start_2026.json"))
+        .expect("Failed to read golden file");
+    assert_eq!(run_bucket_berlin_dst_start(None), expected);
+    assert_eq!(run_bucket_berlin_dst_start(Some("1")), expected);
+}
+
+#[test]
+fn test_bucket_schema_version_2_is_opt_in() {
+    let expected = fs::read_to_string(golden_dir().join("v2/berlin_dst_start_2026.json"))
+        .expect("Failed to read golden file");
+    assert_eq!(run_bucket_berlin_dst_start(Some("2")), expected);
+}
+
+#[test]
+fn test_range_rejects_unknown_schema_version() {
+    let output = run_cli(&[
+        "range",
+        "--tz",
+        "Europe/Berlin",
+        "--start",
+        "2026-03-27T00:00:00Z",
+        "--end",
+        "2026-03-31T00:00:00Z",
+        "--schema-version",
+        "3",
+    ]);
+
+    assert_eq!(output.status.code(), Some(2));
+}
+
 #[test]
 fn test_range_end_is_exclusive() {
     let output = run_cli(&[
@@ -302,64 +399,119 @@ fn test_range_end_is_exclusive() {
 }
 
 #[test]
-fn test_bucket_does_not_panic_on_nonexistent_local_midnight() {
-    let mut child = Command::new(env!("CARGO_BIN_EXE_tzbucket"))
-        .args([
-            "bucket",
-            "--tz",
-            "America/Sao_Paulo",
-            "--interval",
-            "day",
-            "--format",
-            "rfc3339",
-            "--output-format",
-            "json",
-            "--stdin",
-        ])
-        .stdin(Stdio::piped())
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .spawn()
-        .expect("Failed to spawn tzbucket");
-
-    {
-        let stdin = child.stdin.as_mut().expect("Missing stdin");
-        stdin
-            .write_all(b"2018-11-04T03:30:00Z\n")
-            .expect("Failed to write stdin");
-    }
-
-    let output = child.wait_with_output().expect("Failed to wait on child");
+fn test_range_dry_run_reports_plan() {
+    let args = [
+        "range",
+        "--tz",
+        "Europe/Berlin",
+        "--interval",
+        "day",
+        "--start",
+        "2026-03-27T00:00:00Z",
+        "--end",
+        "2026-03-31T00:00:00Z",
+        "--output-format",
+        "json",
+    ];
+    let full = run_cli(&args);
+    let mut dry_run_args = args.to_vec();
+    dry_run_args.push("--dry-run");
+    let output = run_cli(&dry_run_args);
 
-    assert_eq!(
-        output.status.code(),
-        Some(0),
-        "Expected successful exit, stderr: {}",
+    assert!(
+        output.status.success(),
+        "CLI failed: {}",
         String::from_utf8_lossy(&output.stderr)
     );
 
-    let stdout = String::from_utf8(output.stdout).expect("Output is not valid UTF-8");
-    let first_line = stdout.lines().next().expect("Expected one output line");
-    let json: serde_json::Value = serde_json::from_str(first_line).expect("Invalid JSON output");
-    assert_eq!(json["bucket"]["key"], "2018-11-04");
+    let actual = String::from_utf8(output.stdout).expect("Output is not valid UTF-8");
+    let json: serde_json::Value = serde_json::from_str(&actual).expect("Invalid JSON output");
+
+    assert_eq!(json["start_format"], "rfc3339");
+    assert_eq!(json["end_format"], "rfc3339");
+    assert_eq!(json["bucket_count"], 5);
+    assert_eq!(json["first_start_utc"], "2026-03-26T23:00:00Z");
+    assert_eq!(json["last_end_utc"], "2026-03-31T22:00:00Z");
+    assert_eq!(json["estimated_output_bytes"], full.stdout.len());
 }
 
-// =============================================================================
-// Explain Tests - Nonexistent Time
-// =============================================================================
+#[test]
+fn test_range_dry_run_counts_short_days() {
+    let cases: [(&str, &str, &str, &str, u64); 4] = [
+        // Berlin's 23-hour day alone, and with the next day.
+        (
+            "Europe/Berlin",
+            "day",
+            "2026-03-29T00:00:00+01:00",
+            "2026-03-30T00:00:00+02:00",
+            1,
+        ),
+        (
+            "Europe/Berlin",
+            "2d:2026-03-29",
+            "2026-03-29T00:00:00+01:00",
+            "2026-04-02T00:00:00+02:00",
+            2,
+        ),
+        // New York's 23-hour day in the middle of a week of days.
+        (
+            "America/New_York",
+            "day",
+            "2026-03-05T00:00:00-05:00",
+            "2026-03-12T00:00:00-04:00",
+            7,
+        ),
+        // Samoa skipped 2011-12-30: two day buckets.
+        (
+            "Pacific/Apia",
+            "day",
+            "2011-12-29T00:00:00-10:00",
+            "2012-01-01T00:00:00+14:00",
+            2,
+        ),
+    ];
+    for (tz, interval, start, end, expected) in cases {
+        let args = [
+            "range",
+            "--tz",
+            tz,
+            "--interval",
+            interval,
+            "--start",
+            start,
+            "--end",
+            end,
+        ];
+        let full: serde_json::Value = serde_json::from_slice(&run_cli(&args).stdout).unwrap();
+        let mut dry_run_args = args.to_vec();
+        dry_run_args.push("--dry-run");
+        let plan: serde_json::Value =
+            serde_json::from_slice(&run_cli(&dry_run_args).stdout).unwrap();
+
+        assert_eq!(plan["bucket_count"], expected, "{} {}", tz, interval);
+        assert_eq!(
+            full.as_array().map(Vec::len),
+            Some(expected as usize),
+            "{} {}",
+            tz,
+            interval
+        );
+    }
+}
 
 #[test]
-fn test_explain_nonexistent_shift() {
+fn test_range_week_numbers_across_year_boundary() {
     let output = run_cli(&[
-        "explain",
+        "range",
         "--tz",
         "Europe/Berlin",
-        "--local",
-        "2026-03-29T02:30:00",
-        "--policy-nonexistent",
-        "shift_forward",
-        "--output-format",
-        "json",
+        "--interval",
+        "week",
+        "--start",
+        "2025-12-29T00:00:00+01:00",
+        "--end",
+        "2026-01-05T00:00:00+01:00",
+        "--week-numbers",
     ]);
 
     assert!(
@@ -368,64 +520,71 @@ fn test_explain_nonexistent_shift() {
         String::from_utf8_lossy(&output.stderr)
     );
 
-    let actual = String::from_utf8(output.stdout).expect("Output is not valid UTF-8");
-    let expected = fs::read_to_string(golden_dir().join("explain_nonexistent_shift.json"))
-        .expect("Failed to read golden file");
+    let buckets: Vec<serde_json::Value> =
+        serde_json::from_slice(&output.stdout).expect("Output is not valid JSON");
+    assert_eq!(buckets.len(), 1);
+    assert_eq!(buckets[0]["key"], "2025-12-29");
+    assert_eq!(buckets[0]["iso_week"], 1);
+    assert_eq!(buckets[0]["iso_week_year"], 2026);
+    assert_eq!(buckets[0]["us_week_number"], 53);
+}
 
-    assert_json_eq(&actual, &expected);
+#[test]
+fn test_range_without_week_numbers_omits_fields() {
+    let output = run_cli(&[
+        "range",
+        "--tz",
+        "UTC",
+        "--interval",
+        "week",
+        "--start",
+        "2026-01-05T00:00:00Z",
+        "--end",
+        "2026-01-06T00:00:00Z",
+    ]);
+
+    let buckets: Vec<serde_json::Value> =
+        serde_json::from_slice(&output.stdout).expect("Output is not valid JSON");
+    assert!(buckets[0].get("iso_week").is_none());
 }
 
 #[test]
-fn test_explain_nonexistent_error() {
+fn test_range_label_midpoint_on_dst_day() {
     let output = run_cli(&[
-        "explain",
+        "range",
         "--tz",
         "Europe/Berlin",
-        "--local",
-        "2026-03-29T02:30:00",
-        "--policy-nonexistent",
-        "error",
-        "--output-format",
-        "json",
+        "--start",
+        "2026-03-29T00:00:00Z",
+        "--end",
+        "2026-03-29T01:00:00Z",
+        "--label",
+        "midpoint",
     ]);
 
-    // Should exit with code 2 for policy error
-    assert_eq!(
-        output.status.code(),
-        Some(2),
-        "Expected exit code 2 for nonexistent time error, got {:?}",
-        output.status.code()
-    );
-
-    // Verify error output
-    let stderr = String::from_utf8_lossy(&output.stderr);
     assert!(
-        stderr.contains("Nonexistent time"),
-        "Expected error message about nonexistent time, got: {}",
-        stderr
+        output.status.success(),
+        "CLI failed: {}",
+        String::from_utf8_lossy(&output.stderr)
     );
 
-    let error_json: serde_json::Value = serde_json::from_str(&stderr).expect("Expected JSON error");
-    assert_eq!(error_json["exit_code"], 2);
-    assert_eq!(error_json["status"], "nonexistent");
+    let buckets: Vec<serde_json::Value> =
+        serde_json::from_slice(&output.stdout).expect("Output is not valid JSON");
+    // 23-hour day: midpoint is 11.5 hours after 2026-03-28T23:00:00Z.
+    assert_eq!(buckets[0]["label_ts"], "2026-03-29T10:30:00Z");
 }
 
-// =============================================================================
-// Explain Tests - Ambiguous Time
-// =============================================================================
-
 #[test]
-fn test_explain_ambiguous_first() {
+fn test_range_inclusive_end() {
     let output = run_cli(&[
-        "explain",
+        "range",
         "--tz",
         "Europe/Berlin",
-        "--local",
-        "2026-10-25T02:30:00",
-        "--policy-ambiguous",
-        "first",
-        "--output-format",
-        "json",
+        "--start",
+        "2026-03-28T00:00:00Z",
+        "--end",
+        "2026-03-29T12:00:00Z",
+        "--inclusive-end",
     ]);
 
     assert!(
@@ -434,25 +593,43 @@ fn test_explain_ambiguous_first() {
         String::from_utf8_lossy(&output.stderr)
     );
 
-    let actual = String::from_utf8(output.stdout).expect("Output is not valid UTF-8");
-    let expected = fs::read_to_string(golden_dir().join("explain_ambiguous_first.json"))
-        .expect("Failed to read golden file");
+    let buckets: Vec<serde_json::Value> =
+        serde_json::from_slice(&output.stdout).expect("Output is not valid JSON");
+    let keys: Vec<&str> = buckets.iter().map(|b| b["key"].as_str().unwrap()).collect();
+    assert_eq!(keys, vec!["2026-03-28", "2026-03-29"]);
+    assert_eq!(buckets[1]["start_utc"], "2026-03-28T23:00:00Z");
+    assert_eq!(buckets[1]["end_local"], "2026-03-29T23:59:59.999+02:00");
+    assert_eq!(buckets[1]["end_utc"], "2026-03-29T21:59:59.999Z");
+}
 
-    assert_json_eq(&actual, &expected);
+#[test]
+fn test_invalid_label_point() {
+    let output = run_cli(&[
+        "range",
+        "--tz",
+        "UTC",
+        "--start",
+        "2026-03-29T00:00:00Z",
+        "--end",
+        "2026-03-30T00:00:00Z",
+        "--label",
+        "center",
+    ]);
+
+    assert_eq!(output.status.code(), Some(2));
 }
 
 #[test]
-fn test_explain_ambiguous_second() {
+fn test_range_nonexistent_local_midnight() {
+    // America/Sao_Paulo skipped 2018-11-04 00:00-01:00 local.
     let output = run_cli(&[
-        "explain",
+        "range",
         "--tz",
-        "Europe/Berlin",
-        "--local",
-        "2026-10-25T02:30:00",
-        "--policy-ambiguous",
-        "second",
-        "--output-format",
-        "json",
+        "America/Sao_Paulo",
+        "--start",
+        "2018-11-04T03:00:00Z",
+        "--end",
+        "2018-11-05T03:00:00Z",
     ]);
 
     assert!(
@@ -461,155 +638,3670 @@ fn test_explain_ambiguous_second() {
         String::from_utf8_lossy(&output.stderr)
     );
 
-    let actual = String::from_utf8(output.stdout).expect("Output is not valid UTF-8");
-
-    // Parse and verify the result
-    let json: serde_json::Value = serde_json::from_str(&actual).expect("Invalid JSON output");
-
-    assert_eq!(json["status"], "ambiguous");
-    assert_eq!(json["resolution"]["policy"], "second");
-    // The second occurrence should have +01:00 offset (after DST ends)
-    assert!(
-        json["resolution"]["result"]
-            .as_str()
-            .unwrap()
-            .contains("+01:00")
-    );
+    let buckets: Vec<serde_json::Value> =
+        serde_json::from_slice(&output.stdout).expect("Output is not valid JSON");
+    let keys: Vec<&str> = buckets.iter().map(|b| b["key"].as_str().unwrap()).collect();
+    assert_eq!(keys, vec!["2018-11-04", "2018-11-05"]);
+    assert_eq!(buckets[0]["start_local"], "2018-11-04T01:00:00-02:00");
+    assert_eq!(buckets[0]["end_utc"], buckets[1]["start_utc"]);
 }
 
 #[test]
-fn test_explain_ambiguous_error() {
+fn test_range_trailing_month_clamps_anchor() {
     let output = run_cli(&[
-        "explain",
+        "range",
         "--tz",
         "Europe/Berlin",
-        "--local",
-        "2026-10-25T02:30:00",
-        "--policy-ambiguous",
-        "error",
+        "--interval",
+        "trailing_month:31",
+        "--start",
+        "2026-02-01T00:00:00Z",
+        "--end",
+        "2026-03-01T00:00:00Z",
+    ]);
+
+    assert!(
+        output.status.success(),
+        "CLI failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let buckets: Vec<serde_json::Value> =
+        serde_json::from_slice(&output.stdout).expect("Output is not valid JSON");
+    let keys: Vec<&str> = buckets.iter().map(|b| b["key"].as_str().unwrap()).collect();
+    assert_eq!(keys, vec!["2026-01-31", "2026-02-28"]);
+    assert_eq!(buckets[1]["end_local"], "2026-03-31T00:00:00+02:00");
+    assert_eq!(buckets[1]["month_clamp"], "previous_valid_day");
+}
+
+#[test]
+fn test_range_trailing_month_roll_forward() {
+    let output = run_cli(&[
+        "range",
+        "--tz",
+        "UTC",
+        "--interval",
+        "trailing_month:31:roll_forward",
+        "--start",
+        "2026-02-01T00:00:00Z",
+        "--end",
+        "2026-05-15T00:00:00Z",
+    ]);
+
+    assert!(
+        output.status.success(),
+        "CLI failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let buckets: Vec<serde_json::Value> =
+        serde_json::from_slice(&output.stdout).expect("Output is not valid JSON");
+    let keys: Vec<&str> = buckets.iter().map(|b| b["key"].as_str().unwrap()).collect();
+    assert_eq!(
+        keys,
+        vec!["2026-01-31", "2026-03-01", "2026-03-31", "2026-05-01"]
+    );
+    assert_eq!(buckets[0]["month_clamp"], "roll_forward");
+    assert_eq!(buckets[3]["end_utc"], "2026-05-31T00:00:00Z");
+}
+
+#[test]
+fn test_range_fixed_seconds_windows() {
+    let output = run_cli(&[
+        "range",
+        "--tz",
+        "Europe/Berlin",
+        "--interval",
+        "fixed:3600",
+        "--start",
+        "2026-03-29T00:30:00Z",
+        "--end",
+        "2026-03-29T02:00:00Z",
+    ]);
+
+    assert!(
+        output.status.success(),
+        "CLI failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let buckets: Vec<serde_json::Value> =
+        serde_json::from_slice(&output.stdout).expect("Output is not valid JSON");
+    let keys: Vec<&str> = buckets.iter().map(|b| b["key"].as_str().unwrap()).collect();
+    assert_eq!(keys, vec!["2026-03-29T00:00:00Z", "2026-03-29T01:00:00Z",]);
+    assert_eq!(buckets[1]["start_local"], "2026-03-29T03:00:00+02:00");
+    assert_eq!(buckets[1]["end_utc"], "2026-03-29T02:00:00Z");
+}
+
+#[test]
+fn test_range_hour_buckets_across_dst_transitions() {
+    let run = |start: &str, end: &str| {
+        let output = run_cli(&[
+            "range",
+            "--tz",
+            "Europe/Berlin",
+            "--interval",
+            "hour",
+            "--start",
+            start,
+            "--end",
+            end,
+        ]);
+        assert!(
+            output.status.success(),
+            "CLI failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        let buckets: Vec<serde_json::Value> =
+            serde_json::from_slice(&output.stdout).expect("Output is not valid JSON");
+        buckets
+    };
+    let keys = |buckets: &[serde_json::Value]| -> Vec<String> {
+        buckets
+            .iter()
+            .map(|b| b["key"].as_str().unwrap().to_string())
+            .collect()
+    };
+
+    // Spring forward: local 02:00-03:00 does not exist.
+    let buckets = run("2026-03-29T00:00:00Z", "2026-03-29T02:00:00Z");
+    assert_eq!(keys(&buckets), ["2026-03-29T01", "2026-03-29T03"]);
+
+    // Fall back: local 02:00-03:00 happens twice and forms one bucket.
+    let buckets = run("2026-10-24T23:00:00Z", "2026-10-25T03:00:00Z");
+    assert_eq!(
+        keys(&buckets),
+        ["2026-10-25T01", "2026-10-25T02", "2026-10-25T03"]
+    );
+    assert_eq!(buckets[1]["start_utc"], "2026-10-25T00:00:00Z");
+    assert_eq!(buckets[1]["end_utc"], "2026-10-25T02:00:00Z");
+}
+
+#[test]
+fn test_invalid_trailing_month_anchor() {
+    let output = run_cli(&[
+        "range",
+        "--tz",
+        "UTC",
+        "--interval",
+        "trailing_month:32",
+        "--start",
+        "2026-02-01T00:00:00Z",
+        "--end",
+        "2026-03-01T00:00:00Z",
+    ]);
+
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn test_bucket_does_not_panic_on_nonexistent_local_midnight() {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_tzbucket"))
+        .args([
+            "bucket",
+            "--tz",
+            "America/Sao_Paulo",
+            "--interval",
+            "day",
+            "--format",
+            "rfc3339",
+            "--output-format",
+            "json",
+            "--stdin",
+        ])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Failed to spawn tzbucket");
+
+    {
+        let stdin = child.stdin.as_mut().expect("Missing stdin");
+        stdin
+            .write_all(b"2018-11-04T03:30:00Z\n")
+            .expect("Failed to write stdin");
+    }
+
+    let output = child.wait_with_output().expect("Failed to wait on child");
+
+    assert_eq!(
+        output.status.code(),
+        Some(0),
+        "Expected successful exit, stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let stdout = String::from_utf8(output.stdout).expect("Output is not valid UTF-8");
+    let first_line = stdout.lines().next().expect("Expected one output line");
+    let json: serde_json::Value = serde_json::from_str(first_line).expect("Invalid JSON output");
+    assert_eq!(json["bucket"]["key"], "2018-11-04");
+}
+
+#[test]
+fn test_bucket_reads_crlf_and_blank_lines() {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_tzbucket"))
+        .args([
+            "bucket",
+            "--tz",
+            "Europe/Berlin",
+            "--interval",
+            "day",
+            "--format",
+            "epoch_ms",
+            "--output-format",
+            "text",
+            "--stdin",
+        ])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Failed to spawn tzbucket");
+
+    {
+        let stdin = child.stdin.as_mut().expect("Missing stdin");
+        // CRLF endings, a blank line, padding and no trailing newline.
+        stdin
+            .write_all(b"1774739700000\r\n\r\n  1774826100000 \r\n1774912500000")
+            .expect("Failed to write stdin");
+    }
+
+    let output = child.wait_with_output().expect("Failed to wait on child");
+
+    assert!(
+        output.status.success(),
+        "CLI failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8(output.stdout).expect("Output is not valid UTF-8");
+    let keys: Vec<&str> = stdout
+        .lines()
+        .map(|line| line.split(' ').next().unwrap())
+        .collect();
+    assert_eq!(keys, ["2026-03-29", "2026-03-30", "2026-03-31"]);
+}
+
+#[test]
+fn test_bucket_output_dir_shards_by_key() {
+    let dir = std::env::temp_dir().join(format!("tzbucket_shards_{}", std::process::id()));
+    fs::remove_dir_all(&dir).ok();
+    let input = fixture_dir().join("berlin_dst_start_2026.txt");
+
+    // Each JSON record is ~300 bytes, so 700 bytes hold two per file.
+    let output = run_cli(&[
+        "bucket",
+        "--tz",
+        "Europe/Berlin",
+        "--format",
+        "rfc3339",
+        "--output-format",
+        "json",
+        "--input",
+        input.to_str().unwrap(),
+        "--output-dir",
+        dir.to_str().unwrap(),
+        "--shard-by",
+        "key",
+        "--rotate-size",
+        "700",
+    ]);
+    assert!(
+        output.status.success(),
+        "CLI failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(output.stdout.is_empty());
+
+    let manifest: serde_json::Value = serde_json::from_str(
+        &fs::read_to_string(dir.join("manifest.json")).expect("Failed to read manifest"),
+    )
+    .expect("Invalid manifest JSON");
+    let shards = manifest["shards"].as_array().unwrap();
+    let keys: Vec<&str> = shards.iter().map(|s| s["key"].as_str().unwrap()).collect();
+    assert_eq!(keys, ["2026-03-28", "2026-03-29", "2026-03-30"]);
+
+    let files: Vec<(&str, u64)> = shards[1]["files"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|f| (f["path"].as_str().unwrap(), f["records"].as_u64().unwrap()))
+        .collect();
+    assert_eq!(
+        files,
+        [("2026-03-29.ndjson", 2), ("2026-03-29.1.ndjson", 2)]
+    );
+
+    for (path, records) in files {
+        let content = fs::read_to_string(dir.join(path)).expect("Failed to read shard");
+        assert_eq!(content.lines().count() as u64, records);
+        for line in content.lines() {
+            let json: serde_json::Value = serde_json::from_str(line).expect("Invalid JSON line");
+            assert_eq!(json["bucket"]["key"], "2026-03-29");
+        }
+    }
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn test_bucket_warn_near_boundary() {
+    let fixture_path = fixture_dir().join("berlin_dst_start_2026.txt");
+    let output = run_cli(&[
+        "bucket",
+        "--tz",
+        "Europe/Berlin",
+        "--format",
+        "rfc3339",
+        "--output-format",
+        "json",
+        "--input",
+        fixture_path.to_str().unwrap(),
+        "--warn-near-boundary",
+        "30m",
+    ]);
+
+    assert!(
+        output.status.success(),
+        "CLI failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8(output.stdout).expect("Output is not valid UTF-8");
+    let near: Vec<serde_json::Value> = stdout
+        .lines()
+        .map(|line| {
+            let json: serde_json::Value = serde_json::from_str(line).expect("Invalid JSON line");
+            json["near_boundary"].clone()
+        })
+        .collect();
+
+    // 22:30Z is 30 minutes before Berlin midnight (end of 2026-03-28).
+    assert_eq!(near[0]["boundary"], "end");
+    assert_eq!(near[0]["seconds_to_boundary"], 1800);
+    // 00:30Z and 01:30Z are 30 minutes from the 01:00Z spring-forward.
+    assert_eq!(near[2]["dst_transition"], "2026-03-29T01:00:00Z");
+    assert!(near[2].get("boundary").is_none());
+    assert_eq!(near[3]["dst_transition"], "2026-03-29T01:00:00Z");
+
+    let output = run_cli(&[
+        "bucket",
+        "--tz",
+        "Europe/Berlin",
+        "--format",
+        "rfc3339",
+        "--output-format",
+        "json",
+        "--input",
+        fixture_path.to_str().unwrap(),
+        "--warn-near-boundary",
+        "10m",
+    ]);
+    let stdout = String::from_utf8(output.stdout).expect("Output is not valid UTF-8");
+    assert!(!stdout.contains("near_boundary"));
+}
+
+#[test]
+fn test_bucket_skew_is_applied_before_bucketing() {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_tzbucket"))
+        .args([
+            "bucket",
+            "--tz",
+            "Europe/Berlin",
+            "--format",
+            "rfc3339",
+            "--output-format",
+            "json",
+            "--skew",
+            "+00:02:30",
+            "--stdin",
+        ])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Failed to spawn tzbucket");
+
+    {
+        // 2 minutes before Berlin midnight; the skew moves it into the next day.
+        let stdin = child.stdin.as_mut().expect("Missing stdin");
+        stdin
+            .write_all(b"2026-03-28T22:58:00Z\n")
+            .expect("Failed to write stdin");
+    }
+
+    let output = child.wait_with_output().expect("Failed to wait on child");
+    assert!(
+        output.status.success(),
+        "CLI failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("Invalid JSON output");
+    assert_eq!(json["bucket"]["key"], "2026-03-29");
+    assert_eq!(json["input"]["ts"], "2026-03-28T22:58:00Z");
+    assert_eq!(json["input"]["epoch_ms"], 1_774_738_830_000_i64);
+    assert_eq!(json["skew"]["offset"], "+00:02:30");
+    assert_eq!(json["skew"]["original_epoch_ms"], 1_774_738_680_000_i64);
+}
+
+#[test]
+fn test_bucket_future_policy() {
+    // One past instant and a year-2106 epoch from a producer bug.
+    let input = std::env::temp_dir().join(format!("tzbucket_future_{}.txt", std::process::id()));
+    fs::write(&input, "1774738680\n4294967295\n").expect("Failed to write temp file");
+    let run = |policy: &str| {
+        run_cli(&[
+            "--now-override",
+            "2026-06-01T12:00:00Z",
+            "bucket",
+            "--tz",
+            "Europe/Berlin",
+            "--format",
+            "epoch_s",
+            "--output-format",
+            "json",
+            "--future-policy",
+            policy,
+            "--input",
+            input.to_str().unwrap(),
+        ])
+    };
+
+    let output = run("clamp-now");
+    assert!(
+        output.status.success(),
+        "CLI failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let records: Vec<serde_json::Value> = stdout
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("Invalid JSON line"))
+        .collect();
+    assert_eq!(records.len(), 2);
+    assert!(records[0].get("clamped_from_epoch_ms").is_none());
+    assert_eq!(records[1]["bucket"]["key"], "2026-06-01");
+    assert_eq!(records[1]["input"]["epoch_ms"], 1_780_315_200_000_i64);
+    assert_eq!(records[1]["clamped_from_epoch_ms"], 4_294_967_295_000_i64);
+
+    let output = run("reject");
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("after the current time"));
+
+    let output = run("allow");
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("\"2106-02-07\""));
+
+    fs::remove_file(&input).ok();
+}
+
+#[test]
+fn test_text_summary_footer() {
+    let input = fixture_dir().join("berlin_dst_start_2026.txt");
+    let expected_footer = "\nLines: 6\nBuckets: 3\nDST-affected buckets: 1\n\
+                           Earliest: 2026-03-28T22:30:00Z\nLatest: 2026-03-29T22:30:00Z\n";
+
+    for command in ["bucket", "count"] {
+        let output = run_cli(&[
+            command,
+            "--tz",
+            "Europe/Berlin",
+            "--format",
+            "rfc3339",
+            "--output-format",
+            "text",
+            "--summary",
+            "--input",
+            input.to_str().unwrap(),
+        ]);
+        assert!(
+            output.status.success(),
+            "CLI failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(
+            stdout.ends_with(expected_footer),
+            "{} output lacks summary: {}",
+            command,
+            stdout
+        );
+    }
+
+    let output = run_cli(&["count", "--summary", "--input", input.to_str().unwrap()]);
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn test_color_highlights_offset_change_buckets() {
+    let input = fixture_dir().join("berlin_dst_start_2026.txt");
+    let run = |color: &str| {
+        run_cli(&[
+            "--color",
+            color,
+            "bucket",
+            "--tz",
+            "Europe/Berlin",
+            "--format",
+            "rfc3339",
+            "--input",
+            input.to_str().unwrap(),
+        ])
+    };
+
+    let output = run("always");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert!(lines[0].starts_with("\x1b[1;36m2026-03-28\x1b[0m -> "));
+    // The 23-hour day.
+    assert!(lines[1].starts_with("\x1b[1;33m2026-03-29\x1b[0m -> "));
+
+    // Tests run with a piped stdout, so `auto` stays plain.
+    for color in ["never", "auto"] {
+        let output = run(color);
+        assert!(output.status.success());
+        assert!(!String::from_utf8_lossy(&output.stdout).contains('\x1b'));
+    }
+
+    let output = run_cli(&["--color", "sometimes", "now"]);
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn test_bucket_print0_terminates_records_with_nul() {
+    let input = fixture_dir().join("berlin_dst_start_2026.txt");
+    let run = |output_format: &str| {
+        let output = run_cli(&[
+            "bucket",
+            "--tz",
+            "Europe/Berlin",
+            "--format",
+            "rfc3339",
+            "--output-format",
+            output_format,
+            "-0",
+            "--input",
+            input.to_str().unwrap(),
+        ]);
+        assert!(
+            output.status.success(),
+            "CLI failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        String::from_utf8(output.stdout).expect("Output is not UTF-8")
+    };
+
+    let keys = run("text");
+    assert_eq!(
+        keys.split_terminator('\0').collect::<Vec<_>>(),
+        [
+            "2026-03-28",
+            "2026-03-29",
+            "2026-03-29",
+            "2026-03-29",
+            "2026-03-29",
+            "2026-03-30"
+        ]
+    );
+    assert!(keys.ends_with('\0'));
+
+    let records = run("json");
+    assert!(!records.contains('\n'));
+    let records: Vec<&str> = records.strip_suffix('\0').unwrap().split('\0').collect();
+    assert_eq!(records.len(), 6);
+    let first: serde_json::Value = serde_json::from_str(records[0]).expect("Invalid JSON record");
+    assert_eq!(first["bucket"]["key"], "2026-03-28");
+}
+
+#[test]
+fn test_bucket_rejects_invalid_rotate_size() {
+    let output = run_cli(&[
+        "bucket",
+        "--output-dir",
+        std::env::temp_dir().to_str().unwrap(),
+        "--rotate-size",
+        "12X",
+    ]);
+
+    assert_eq!(output.status.code(), Some(2));
+}
+
+// =============================================================================
+// Explain Tests - Nonexistent Time
+// =============================================================================
+
+#[test]
+fn test_explain_nonexistent_shift() {
+    let output = run_cli(&[
+        "explain",
+        "--tz",
+        "Europe/Berlin",
+        "--local",
+        "2026-03-29T02:30:00",
+        "--policy-nonexistent",
+        "shift_forward",
+        "--output-format",
+        "json",
+    ]);
+
+    assert!(
+        output.status.success(),
+        "CLI failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let actual = String::from_utf8(output.stdout).expect("Output is not valid UTF-8");
+    let expected = fs::read_to_string(golden_dir().join("explain_nonexistent_shift.json"))
+        .expect("Failed to read golden file");
+
+    assert_json_eq(&actual, &expected);
+}
+
+#[test]
+fn test_explain_nonexistent_error() {
+    let output = run_cli(&[
+        "explain",
+        "--tz",
+        "Europe/Berlin",
+        "--local",
+        "2026-03-29T02:30:00",
+        "--policy-nonexistent",
+        "error",
+        "--output-format",
+        "json",
+    ]);
+
+    // Should exit with code 2 for policy error
+    assert_eq!(
+        output.status.code(),
+        Some(2),
+        "Expected exit code 2 for nonexistent time error, got {:?}",
+        output.status.code()
+    );
+
+    // Verify error output
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Nonexistent time"),
+        "Expected error message about nonexistent time, got: {}",
+        stderr
+    );
+
+    let error_json: serde_json::Value = serde_json::from_str(&stderr).expect("Expected JSON error");
+    assert_eq!(error_json["exit_code"], 2);
+    assert_eq!(error_json["status"], "nonexistent");
+}
+
+// =============================================================================
+// Explain Tests - Ambiguous Time
+// =============================================================================
+
+#[test]
+fn test_explain_ambiguous_first() {
+    let output = run_cli(&[
+        "explain",
+        "--tz",
+        "Europe/Berlin",
+        "--local",
+        "2026-10-25T02:30:00",
+        "--policy-ambiguous",
+        "first",
+        "--output-format",
+        "json",
+    ]);
+
+    assert!(
+        output.status.success(),
+        "CLI failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let actual = String::from_utf8(output.stdout).expect("Output is not valid UTF-8");
+    let expected = fs::read_to_string(golden_dir().join("explain_ambiguous_first.json"))
+        .expect("Failed to read golden file");
+
+    assert_json_eq(&actual, &expected);
+}
+
+#[test]
+fn test_explain_ambiguous_second() {
+    let output = run_cli(&[
+        "explain",
+        "--tz",
+        "Europe/Berlin",
+        "--local",
+        "2026-10-25T02:30:00",
+        "--policy-ambiguous",
+        "second",
+        "--output-format",
+        "json",
+    ]);
+
+    assert!(
+        output.status.success(),
+        "CLI failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let actual = String::from_utf8(output.stdout).expect("Output is not valid UTF-8");
+
+    // Parse and verify the result
+    let json: serde_json::Value = serde_json::from_str(&actual).expect("Invalid JSON output");
+
+    assert_eq!(json["status"], "ambiguous");
+    assert_eq!(json["resolution"]["policy"], "second");
+    // The second occurrence should have +01:00 offset (after DST ends)
+    assert!(
+        json["resolution"]["result"]
+            .as_str()
+            .unwrap()
+            .contains("+01:00")
+    );
+}
+
+#[test]
+fn test_explain_ambiguous_error() {
+    let output = run_cli(&[
+        "explain",
+        "--tz",
+        "Europe/Berlin",
+        "--local",
+        "2026-10-25T02:30:00",
+        "--policy-ambiguous",
+        "error",
+        "--output-format",
+        "json",
+    ]);
+
+    // Should exit with code 2 for policy error
+    assert_eq!(
+        output.status.code(),
+        Some(2),
+        "Expected exit code 2 for ambiguous time error, got {:?}",
+        output.status.code()
+    );
+
+    // Verify error output
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Ambiguous time"),
+        "Expected error message about ambiguous time, got: {}",
+        stderr
+    );
+}
+
+// =============================================================================
+// Explain Tests - Normal Time
+// =============================================================================
+
+#[test]
+fn test_explain_normal_time() {
+    let output = run_cli(&[
+        "explain",
+        "--tz",
+        "Europe/Berlin",
+        "--local",
+        "2026-06-15T14:30:00",
+        "--output-format",
+        "json",
+    ]);
+
+    assert!(
+        output.status.success(),
+        "CLI failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let actual = String::from_utf8(output.stdout).expect("Output is not valid UTF-8");
+    let json: serde_json::Value = serde_json::from_str(&actual).expect("Invalid JSON output");
+
+    assert_eq!(json["status"], "normal");
+    assert!(json["resolution"].is_null());
+}
+
+#[test]
+fn test_explain_with_interval_reports_bucket() {
+    let output = run_cli(&[
+        "explain",
+        "--tz",
+        "Europe/Berlin",
+        "--local",
+        "2026-03-29T02:30:00",
+        "--policy-nonexistent",
+        "shift_forward",
+        "--interval",
+        "day",
+    ]);
+
+    assert!(
+        output.status.success(),
+        "CLI failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let result: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("Output is not valid JSON");
+    assert_eq!(result["resolution"]["result"], "2026-03-29T03:30:00+02:00");
+    assert_eq!(result["bucket"]["key"], "2026-03-29");
+    assert_eq!(result["bucket"]["start_utc"], "2026-03-28T23:00:00Z");
+    assert_eq!(result["bucket"]["end_utc"], "2026-03-29T22:00:00Z");
+}
+
+#[test]
+fn test_explain_ambiguous_second_week_bucket() {
+    let output = run_cli(&[
+        "explain",
+        "--tz",
+        "Europe/Berlin",
+        "--local",
+        "2026-10-25T02:30:00",
+        "--policy-ambiguous",
+        "second",
+        "-i",
+        "week",
+        "--week-start",
+        "sunday",
+    ]);
+
+    assert!(output.status.success());
+
+    let result: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("Output is not valid JSON");
+    assert_eq!(result["bucket"]["key"], "2026-10-25");
+    assert_eq!(result["bucket"]["start_local"], "2026-10-25T00:00:00+02:00");
+}
+
+// =============================================================================
+// Diff Output Tests
+// =============================================================================
+
+#[test]
+fn test_diff_output_reports_changed_assignments() {
+    let old_path = golden_dir().join("berlin_dst_start_2026.json");
+    let old = fs::read_to_string(&old_path).expect("Failed to read golden file");
+
+    // Simulate a changed assignment for the second record and drop the last one.
+    let mut lines: Vec<String> = old.lines().map(str::to_string).collect();
+    lines[1] = lines[1].replace("\"key\":\"2026-03-29\"", "\"key\":\"2026-03-28\"");
+    lines.pop();
+    // Fields beyond the assignment, added or dropped between versions, are no change.
+    lines[2] = lines[2].replace("\"key\":", "\"label_ts\":\"2026-03-29T00:00:00Z\",\"key\":");
+    lines[3] = lines[3].replace(",\"duration_seconds\":82800", "");
+
+    let new_path =
+        std::env::temp_dir().join(format!("tzbucket_diff_new_{}.ndjson", std::process::id()));
+    fs::write(&new_path, lines.join("\n")).expect("Failed to write temp file");
+
+    let output = run_cli(&[
+        "diff-output",
+        old_path.to_str().unwrap(),
+        new_path.to_str().unwrap(),
+        "--output-format",
+        "json",
+    ]);
+    fs::remove_file(&new_path).ok();
+
+    assert!(
+        output.status.success(),
+        "CLI failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let actual = String::from_utf8(output.stdout).expect("Output is not valid UTF-8");
+    let json: serde_json::Value = serde_json::from_str(&actual).expect("Invalid JSON output");
+
+    assert_eq!(json["compared"], 5);
+    assert_eq!(json["changed"], 1);
+    assert_eq!(json["only_in_old"], 1);
+    assert_eq!(json["only_in_new"], 0);
+    assert_eq!(json["changes"][0]["status"], "changed");
+    assert_eq!(json["changes"][0]["ts"], "2026-03-28T23:30:00Z");
+    assert_eq!(json["changes"][0]["old"]["key"], "2026-03-29");
+    assert_eq!(json["changes"][0]["new"]["key"], "2026-03-28");
+    assert_eq!(json["changes"][1]["status"], "only_in_old");
+}
+
+// =============================================================================
+// tzdata Impact Tests
+// =============================================================================
+
+/// Write a minimal TZif v2 file with no explicit transitions, only a POSIX
+/// TZ footer rule, so tests do not depend on the host's zoneinfo.
+fn write_posix_tzif(path: &Path, std_offset: i32, abbrev: &str, rule: &str) {
+    let mut chars = abbrev.as_bytes().to_vec();
+    chars.push(0);
+
+    let mut block = Vec::new();
+    block.extend_from_slice(b"TZif2");
+    block.extend_from_slice(&[0; 15]);
+    for count in [0, 0, 0, 0, 1, chars.len() as u32] {
+        block.extend_from_slice(&count.to_be_bytes());
+    }
+    block.extend_from_slice(&std_offset.to_be_bytes());
+    block.extend_from_slice(&[0, 0]);
+    block.extend_from_slice(&chars);
+
+    let mut bytes = block.clone();
+    bytes.extend_from_slice(&block);
+    bytes.extend_from_slice(format!("\n{}\n", rule).as_bytes());
+
+    fs::create_dir_all(path.parent().unwrap()).expect("Failed to create tzdata dir");
+    fs::write(path, bytes).expect("Failed to write TZif file");
+}
+
+#[test]
+fn test_tzimpact_reports_changed_buckets() {
+    let root = std::env::temp_dir().join(format!("tzbucket_tzimpact_{}", std::process::id()));
+    // Candidate version moves DST start one week earlier (4th instead of last Sunday of March).
+    write_posix_tzif(
+        &root.join("2025a/Europe/Berlin"),
+        3600,
+        "CET",
+        "CET-1CEST,M3.5.0,M10.5.0/3",
+    );
+    write_posix_tzif(
+        &root.join("2025b/Europe/Berlin"),
+        3600,
+        "CET",
+        "CET-1CEST,M3.4.0,M10.5.0/3",
+    );
+    write_posix_tzif(&root.join("2025a/Etc/UTC"), 0, "UTC", "UTC0");
+    write_posix_tzif(&root.join("2025b/Etc/UTC"), 0, "UTC", "UTC0");
+    let zones = root.join("zones.txt");
+    fs::write(&zones, "# zones under test\nEurope/Berlin\nEtc/UTC\n").unwrap();
+
+    let output = run_cli(&[
+        "tzimpact",
+        "--tz-list",
+        zones.to_str().unwrap(),
+        "--tzdata-dir",
+        root.to_str().unwrap(),
+        "--from-version",
+        "2025a",
+        "--to-version",
+        "2025b",
+        "--start",
+        "2026-03-01T00:00:00Z",
+        "--end",
+        "2026-04-01T00:00:00Z",
+        "--output-format",
+        "json",
+    ]);
+    fs::remove_dir_all(&root).ok();
+
+    assert!(
+        output.status.success(),
+        "CLI failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let actual = String::from_utf8(output.stdout).expect("Output is not valid UTF-8");
+    let json: serde_json::Value = serde_json::from_str(&actual).expect("Invalid JSON output");
+
+    assert_eq!(json["zones_total"], 2);
+    assert_eq!(json["zones_changed"], 1);
+
+    let berlin = &json["zones"][0];
+    assert_eq!(berlin["status"], "changed");
+    // One hour shifts to the earlier bucket on each day from 03-22 to 03-28.
+    assert_eq!(berlin["reassigned_seconds"], 7 * 3600);
+    let changed: Vec<&str> = berlin["changed_buckets"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|k| k.as_str().unwrap())
+        .collect();
+    assert_eq!(changed.first(), Some(&"2026-03-22"));
+    assert_eq!(changed.last(), Some(&"2026-03-29"));
+
+    assert_eq!(json["zones"][1]["status"], "unchanged");
+}
+
+// =============================================================================
+// Conformance Vector Tests
+// =============================================================================
+
+#[test]
+fn test_vectors_are_self_consistent() {
+    let output = run_cli(&["vectors"]);
+
+    assert!(
+        output.status.success(),
+        "CLI failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let actual = String::from_utf8(output.stdout).expect("Output is not valid UTF-8");
+    let json: serde_json::Value = serde_json::from_str(&actual).expect("Invalid JSON output");
+
+    assert_eq!(json["format"], "tzbucket-conformance-vectors");
+    assert_eq!(json["version"], 1);
+
+    let cases = json["cases"].as_array().expect("Expected cases array");
+    for category in ["gap", "overlap", "week-edge", "month-edge", "leap-year"] {
+        assert!(
+            cases.iter().any(|c| c["category"] == category),
+            "No vectors for category {}",
+            category
+        );
+    }
+
+    // Every expected bucket must contain its input instant.
+    for case in cases {
+        let epoch_ms = case["input"]["epoch_ms"].as_i64().unwrap();
+        let bound = |field: &str| {
+            chrono::DateTime::parse_from_rfc3339(case["expected"][field].as_str().unwrap())
+                .unwrap()
+                .timestamp_millis()
+        };
+        assert!(
+            bound("start_utc") <= epoch_ms && epoch_ms < bound("end_utc"),
+            "Vector {} does not contain its input",
+            case["id"]
+        );
+    }
+}
+
+#[test]
+fn test_vectors_schema_is_json() {
+    let output = run_cli(&["vectors", "--schema"]);
+
+    assert!(output.status.success());
+    let schema: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("Schema is not valid JSON");
+    assert_eq!(schema["title"], "tzbucket conformance vectors");
+}
+
+#[test]
+fn test_selftest_passes_every_vector_case() {
+    let vectors = run_cli(&["vectors"]);
+    let vectors: serde_json::Value =
+        serde_json::from_slice(&vectors.stdout).expect("Invalid vectors JSON");
+    let case_count = vectors["cases"].as_array().unwrap().len();
+
+    let output = run_cli(&["selftest", "--output-format", "json"]);
+
+    assert!(
+        output.status.success(),
+        "Selftest failed: {}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("Invalid JSON output");
+    assert_eq!(json["passed"], case_count);
+    assert_eq!(json["failed"], 0);
+    assert_eq!(json["tzdb_version"], vectors["tzdb_version"]);
+}
+
+// =============================================================================
+// Resolve Tests
+// =============================================================================
+
+#[test]
+fn test_resolve_berlin_dst_2026() {
+    let fixture_path = fixture_dir().join("berlin_local_dst_2026.txt");
+    let output = run_cli(&[
+        "resolve",
+        "--tz",
+        "Europe/Berlin",
+        "--policy-nonexistent",
+        "shift_forward",
+        "--policy-ambiguous",
+        "first",
+        "--input",
+        fixture_path.to_str().unwrap(),
+    ]);
+
+    assert!(
+        output.status.success(),
+        "CLI failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let actual = String::from_utf8(output.stdout).expect("Output is not valid UTF-8");
+    let expected = fs::read_to_string(golden_dir().join("resolve_berlin_dst_2026.json"))
+        .expect("Failed to read golden file");
+
+    assert_json_lines_eq(&actual, &expected);
+}
+
+#[test]
+fn test_resolve_csv_output() {
+    let fixture_path = fixture_dir().join("berlin_local_dst_2026.txt");
+    let output = run_cli(&[
+        "resolve",
+        "--tz",
+        "Europe/Berlin",
+        "--policy-nonexistent",
+        "shift_forward",
+        "--policy-ambiguous",
+        "second",
+        "--output-format",
+        "csv",
+        "--input",
+        fixture_path.to_str().unwrap(),
+    ]);
+
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines[0], "local,tz,status,policy,utc,offset");
+    assert_eq!(
+        lines[2],
+        "2026-03-29T02:30:00,Europe/Berlin,nonexistent,shift_forward,2026-03-29T01:30:00Z,+02:00"
+    );
+    assert_eq!(
+        lines[5],
+        "2026-10-25T02:30:00,Europe/Berlin,ambiguous,second,2026-10-25T01:30:00Z,+01:00"
+    );
+}
+
+#[test]
+fn test_resolve_verify_roundtrip_flags_gap_and_overlap() {
+    let fixture_path = fixture_dir().join("berlin_local_dst_2026.txt");
+    let output = run_cli(&[
+        "resolve",
+        "--tz",
+        "Europe/Berlin",
+        "--policy-nonexistent",
+        "shift_forward",
+        "--policy-ambiguous",
+        "first",
+        "--verify-roundtrip",
+        "--input",
+        fixture_path.to_str().unwrap(),
+    ]);
+
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let roundtrips: Vec<String> = stdout
+        .lines()
+        .map(|line| {
+            let record: serde_json::Value = serde_json::from_str(line).unwrap();
+            record["roundtrip"].as_str().unwrap().to_string()
+        })
+        .collect();
+    assert_eq!(
+        roundtrips,
+        vec![
+            "exact",
+            "shifted",
+            "exact",
+            "exact",
+            "disambiguated",
+            "exact"
+        ]
+    );
+}
+
+#[test]
+fn test_bucket_verify_roundtrip_flags_shifted_boundary() {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_tzbucket"))
+        .args([
+            "bucket",
+            "--tz",
+            "America/Sao_Paulo",
+            "--format",
+            "rfc3339",
+            "--output-format",
+            "json",
+            "--verify-roundtrip",
+        ])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to run tzbucket");
+    child
+        .stdin
+        .as_mut()
+        .expect("Missing stdin")
+        .write_all(b"2018-11-04T12:00:00Z\n2018-11-05T12:00:00Z\n")
+        .expect("Failed to write stdin");
+    let output = child
+        .wait_with_output()
+        .expect("Failed to wait for tzbucket");
+
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let records: Vec<serde_json::Value> = stdout
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(
+        records[0]["bucket"]["start_local"],
+        "2018-11-04T01:00:00-02:00"
+    );
+    assert_eq!(records[0]["roundtrip"], "shifted");
+    assert_eq!(records[1]["roundtrip"], "exact");
+}
+
+#[test]
+fn test_bucket_compare_utc_fixed() {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_tzbucket"))
+        .args([
+            "bucket",
+            "--tz",
+            "Europe/Berlin",
+            "--format",
+            "rfc3339",
+            "--output-format",
+            "json",
+            "--compare-utc-fixed",
+        ])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to run tzbucket");
+    child
+        .stdin
+        .as_mut()
+        .expect("Missing stdin")
+        .write_all(b"2026-03-29T12:00:00Z\n2026-03-29T22:30:00Z\n")
+        .expect("Failed to write stdin");
+    let output = child
+        .wait_with_output()
+        .expect("Failed to wait for tzbucket");
+
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let records: Vec<serde_json::Value> = stdout
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+
+    // Spring-forward day: 23h local day starting an hour before UTC midnight.
+    let fixed = &records[0]["utc_fixed"];
+    assert_eq!(fixed["bucket"]["key"], "2026-03-29");
+    assert_eq!(fixed["bucket"]["start_utc"], "2026-03-29T00:00:00Z");
+    assert_eq!(fixed["start_shift_seconds"], -3600);
+    assert_eq!(fixed["duration_delta_seconds"], -3600);
+    assert_eq!(fixed["same_key"], true);
+
+    // 00:30 local on Mar 30 is still Mar 29 in UTC.
+    assert_eq!(records[1]["bucket"]["key"], "2026-03-30");
+    assert_eq!(records[1]["utc_fixed"]["same_key"], false);
+}
+
+#[test]
+fn test_resolve_policy_error_exits_2() {
+    let fixture_path = fixture_dir().join("berlin_local_dst_2026.txt");
+    let output = run_cli(&[
+        "resolve",
+        "--tz",
+        "Europe/Berlin",
+        "--output-format",
+        "json",
+        "--input",
+        fixture_path.to_str().unwrap(),
+    ]);
+
+    assert_eq!(output.status.code(), Some(2));
+
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    let error: serde_json::Value = serde_json::from_str(&stderr).expect("Invalid JSON on stderr");
+    assert_eq!(error["status"], "nonexistent");
+}
+
+// =============================================================================
+// Now Tests
+// =============================================================================
+
+#[test]
+fn test_now_bucket_contains_current_time() {
+    let output = run_cli(&[
+        "now",
+        "--tz",
+        "Europe/Berlin",
+        "-i",
+        "week",
+        "--output-format",
+        "json",
+    ]);
+
+    assert!(
+        output.status.success(),
+        "CLI failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let result: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("Output is not valid JSON");
+    assert_eq!(result["tz"], "Europe/Berlin");
+    assert_eq!(result["interval"], "week");
+
+    let ts = result["input"]["ts"].as_str().unwrap();
+    let start = result["bucket"]["start_utc"].as_str().unwrap();
+    let end = result["bucket"]["end_utc"].as_str().unwrap();
+    // RFC3339 UTC strings with identical layout order lexicographically.
+    assert!(
+        start <= ts && ts < end,
+        "{} not in [{}, {})",
+        ts,
+        start,
+        end
+    );
+}
+
+#[test]
+fn test_now_override_pins_current_time() {
+    let output = run_cli(&[
+        "--now-override",
+        "2026-03-29T01:59:00+01:00",
+        "now",
+        "--tz",
+        "Europe/Berlin",
+        "--output-format",
+        "json",
+    ]);
+
+    assert!(
+        output.status.success(),
+        "CLI failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let result: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("Output is not valid JSON");
+    assert_eq!(result["input"]["ts"], "2026-03-29T00:59:00Z");
+    assert_eq!(result["bucket"]["key"], "2026-03-29");
+    assert_eq!(result["bucket"]["start_utc"], "2026-03-28T23:00:00Z");
+    assert_eq!(result["bucket"]["end_utc"], "2026-03-29T22:00:00Z");
+}
+
+#[test]
+fn test_now_override_invalid_exits_2() {
+    let output = run_cli(&["now", "--now-override", "2026-03-29 01:59"]);
+
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn test_now_invalid_timezone() {
+    let output = run_cli(&["now", "--tz", "Invalid/Zone"]);
+
+    assert_eq!(output.status.code(), Some(2));
+}
+
+// =============================================================================
+// Count Tests
+// =============================================================================
+
+#[test]
+fn test_count_per_day() {
+    let fixture_path = fixture_dir().join("count_berlin_march_2026.txt");
+    let output = run_cli(&[
+        "count",
+        "--tz",
+        "Europe/Berlin",
+        "--format",
+        "rfc3339",
+        "--input",
+        fixture_path.to_str().unwrap(),
+    ]);
+
+    assert!(
+        output.status.success(),
+        "CLI failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let records: Vec<serde_json::Value> =
+        serde_json::from_slice(&output.stdout).expect("Output is not valid JSON");
+    let counts: Vec<(&str, u64)> = records
+        .iter()
+        .map(|r| (r["key"].as_str().unwrap(), r["count"].as_u64().unwrap()))
+        .collect();
+    // 2026-03-29T22:30:00Z is already March 30 in Berlin (CEST).
+    assert_eq!(
+        counts,
+        vec![
+            ("2026-03-01", 2),
+            ("2026-03-05", 1),
+            ("2026-03-30", 1),
+            ("2026-03-31", 4),
+            ("2026-04-02", 1),
+        ]
+    );
+}
+
+#[test]
+fn test_count_calendar_heatmap() {
+    let fixture_path = fixture_dir().join("count_berlin_march_2026.txt");
+    let output = run_cli(&[
+        "count",
+        "--tz",
+        "Europe/Berlin",
+        "--format",
+        "rfc3339",
+        "--input",
+        fixture_path.to_str().unwrap(),
+        "--output-format",
+        "calendar",
+    ]);
+
+    assert!(
+        output.status.success(),
+        "CLI failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let actual = String::from_utf8(output.stdout).expect("Output is not valid UTF-8");
+    let golden_path = golden_dir().join("count_calendar_berlin_2026.txt");
+    if update_golden() {
+        fs::write(&golden_path, &actual).expect("Failed to write golden file");
+    }
+    let expected = fs::read_to_string(golden_path).expect("Failed to read golden file");
+
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn test_count_vega_lite_spec() {
+    let fixture_path = fixture_dir().join("count_berlin_march_2026.txt");
+    let output = run_cli(&[
+        "count",
+        "--tz",
+        "Europe/Berlin",
+        "--format",
+        "rfc3339",
+        "--input",
+        fixture_path.to_str().unwrap(),
+        "--output-format",
+        "vega",
+    ]);
+
+    assert!(
+        output.status.success(),
+        "CLI failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let spec: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("Output is not valid JSON");
+    assert_eq!(
+        spec["$schema"],
+        "https://vega.github.io/schema/vega-lite/v5.json"
+    );
+    assert_eq!(spec["mark"], "bar");
+    assert_eq!(spec["encoding"]["x"]["scale"]["type"], "utc");
+
+    // Wall-clock local boundaries, so the axis shows Berlin time.
+    let values = spec["data"]["values"].as_array().unwrap();
+    assert_eq!(values.len(), 5);
+    assert_eq!(values[2]["key"], "2026-03-30");
+    assert_eq!(values[2]["start"], "2026-03-30T00:00:00");
+    assert_eq!(values[2]["start_utc"], "2026-03-29T22:00:00Z");
+}
+
+#[test]
+fn test_count_calendar_requires_day_interval() {
+    let fixture_path = fixture_dir().join("count_berlin_march_2026.txt");
+    let output = run_cli(&[
+        "count",
+        "--tz",
+        "Europe/Berlin",
+        "--interval",
+        "week",
+        "--format",
+        "rfc3339",
+        "--input",
+        fixture_path.to_str().unwrap(),
+        "--output-format",
+        "calendar",
+    ]);
+
+    assert_eq!(output.status.code(), Some(2));
+}
+
+// =============================================================================
+// Plan Tests
+// =============================================================================
+
+#[test]
+fn test_plan_berlin_2026() {
+    let counts_path = fixture_dir().join("plan_counts_berlin_2026.csv");
+    let output = run_cli(&[
+        "plan",
+        "--counts",
+        counts_path.to_str().unwrap(),
+        "--target-rows-per-partition",
+        "1000",
+    ]);
+
+    assert!(
+        output.status.success(),
+        "CLI failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let actual = String::from_utf8(output.stdout).expect("Output is not valid UTF-8");
+    let expected = fs::read_to_string(golden_dir().join("plan_berlin_2026.json"))
+        .expect("Failed to read golden file");
+
+    // Small days merge, the 23-hour DST day splits in three, and the gap
+    // before 2026-04-02 starts a new partition.
+    assert_json_eq(&actual, &expected);
+}
+
+#[test]
+fn test_plan_rejects_zero_target() {
+    let counts_path = fixture_dir().join("plan_counts_berlin_2026.csv");
+    let output = run_cli(&[
+        "plan",
+        "--counts",
+        counts_path.to_str().unwrap(),
+        "--target-rows-per-partition",
+        "0",
+    ]);
+
+    assert_eq!(output.status.code(), Some(2));
+}
+
+// =============================================================================
+// Report Tests
+// =============================================================================
+
+#[test]
+fn test_report_berlin_march_2026() {
+    let fixture_path = fixture_dir().join("count_berlin_march_2026.txt");
+    let output = run_cli(&[
+        "report",
+        "--tz",
+        "Europe/Berlin",
+        "--start",
+        "2026-03-01T00:00:00+01:00",
+        "--end",
+        "2026-04-01T00:00:00+02:00",
+        "--format",
+        "rfc3339",
+        "--input",
+        fixture_path.to_str().unwrap(),
+    ]);
+
+    assert!(
+        output.status.success(),
+        "CLI failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let actual = String::from_utf8(output.stdout).expect("Output is not valid UTF-8");
+    let golden_path = golden_dir().join("report_berlin_march_2026.html");
+    if update_golden() {
+        fs::write(&golden_path, &actual).expect("Failed to write golden file");
+    }
+    let expected = fs::read_to_string(golden_path).expect("Failed to read golden file");
+
+    // The 23-hour 2026-03-29 bucket carries the spring-forward transition;
+    // the 2026-04-02 event falls outside the range.
+    assert!(actual.contains("<li>Events outside the range: 1</li>"));
+    assert!(actual.contains("<tr class=\"dst\"><td>2026-03-29</td>"));
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn test_report_rejects_empty_range() {
+    let output = run_cli(&[
+        "report",
+        "--tz",
+        "Europe/Berlin",
+        "--start",
+        "2026-04-01T00:00:00Z",
+        "--end",
+        "2026-03-01T00:00:00Z",
+    ]);
+
+    assert_eq!(output.status.code(), Some(2));
+}
+
+// =============================================================================
+// Build Index Tests
+// =============================================================================
+
+#[test]
+fn test_bucket_with_index_matches_golden() {
+    let index_path =
+        std::env::temp_dir().join(format!("tzbucket_index_{}.bin", std::process::id()));
+    let output = run_cli(&[
+        "build-index",
+        "--tz",
+        "Europe/Berlin",
+        "--start",
+        "2026-03-28T12:00:00Z",
+        "--end",
+        "2026-03-29T12:00:00Z",
+        "--output",
+        index_path.to_str().unwrap(),
+    ]);
+    assert!(
+        output.status.success(),
+        "CLI failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    // Instants outside the indexed range fall back to computation, so the
+    // output matches the plain `bucket` golden file.
+    let fixture_path = fixture_dir().join("berlin_dst_start_2026.txt");
+    let output = run_cli(&[
+        "bucket",
+        "--tz",
+        "Europe/Berlin",
+        "--format",
+        "rfc3339",
+        "--output-format",
+        "json",
+        "--input",
+        fixture_path.to_str().unwrap(),
+        "--index",
+        index_path.to_str().unwrap(),
+    ]);
+    let _ = fs::remove_file(&index_path);
+
+    assert!(
+        output.status.success(),
+        "CLI failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let actual = String::from_utf8(output.stdout).expect("Output is not valid UTF-8");
+    let expected = fs::read_to_string(golden_dir().join("berlin_dst_start_2026.json"))
+        .expect("Failed to read golden file");
+    assert_json_lines_eq(&actual, &expected);
+}
+
+#[test]
+fn test_bucket_reads_json_and_binary_indexes_alike() {
+    let fixture_path = fixture_dir().join("berlin_dst_start_2026.txt");
+    let mut outputs = Vec::new();
+    for format in ["binary", "json"] {
+        let index_path =
+            std::env::temp_dir().join(format!("tzbucket_index_{}_{}", format, std::process::id()));
+        let output = run_cli(&[
+            "build-index",
+            "--tz",
+            "Europe/Berlin",
+            "--start",
+            "2026-03-01T00:00:00Z",
+            "--end",
+            "2026-04-01T00:00:00Z",
+            "--index-format",
+            format,
+            "--output",
+            index_path.to_str().unwrap(),
+        ]);
+        assert!(output.status.success());
+        let bytes = fs::read(&index_path).unwrap();
+        assert_eq!(bytes.starts_with(b"TZBIDX"), format == "binary");
+
+        let output = run_cli(&[
+            "bucket",
+            "--tz",
+            "Europe/Berlin",
+            "--format",
+            "rfc3339",
+            "--output-format",
+            "json",
+            "--input",
+            fixture_path.to_str().unwrap(),
+            "--index",
+            index_path.to_str().unwrap(),
+        ]);
+        let _ = fs::remove_file(&index_path);
+        assert!(
+            output.status.success(),
+            "CLI failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        outputs.push(String::from_utf8(output.stdout).unwrap());
+    }
+
+    let expected = fs::read_to_string(golden_dir().join("berlin_dst_start_2026.json"))
+        .expect("Failed to read golden file");
+    assert_json_lines_eq(&outputs[0], &expected);
+    assert_eq!(outputs[0], outputs[1]);
+}
+
+#[test]
+fn test_bucket_rejects_index_for_other_timezone() {
+    let index_path =
+        std::env::temp_dir().join(format!("tzbucket_index_tz_{}.bin", std::process::id()));
+    let output = run_cli(&[
+        "build-index",
+        "--tz",
+        "UTC",
+        "--start",
+        "2026-03-28T00:00:00Z",
+        "--end",
+        "2026-03-30T00:00:00Z",
+        "--output",
+        index_path.to_str().unwrap(),
+    ]);
+    assert!(output.status.success());
+
+    let fixture_path = fixture_dir().join("berlin_dst_start_2026.txt");
+    let output = run_cli(&[
+        "bucket",
+        "--tz",
+        "Europe/Berlin",
+        "--format",
+        "rfc3339",
+        "--input",
+        fixture_path.to_str().unwrap(),
+        "--index",
+        index_path.to_str().unwrap(),
+    ]);
+    let _ = fs::remove_file(&index_path);
+
+    assert_eq!(output.status.code(), Some(2));
+}
+
+// =============================================================================
+// JSON Schema Tests
+// =============================================================================
+
+#[test]
+fn test_schema_bucket_matches_output_fields() {
+    let output = run_cli(&["schema", "bucket"]);
+
+    assert!(
+        output.status.success(),
+        "CLI failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let schema: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("Schema is not valid JSON");
+    assert_eq!(schema["title"], "BucketResult");
+
+    let required: Vec<&str> = schema["required"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|v| v.as_str().unwrap())
+        .collect();
+    // `schema_version` is only present with `--schema-version 2`.
+    assert_eq!(required, vec!["input", "tz", "interval", "bucket"]);
+    assert!(schema["properties"]["schema_version"].is_object());
+}
+
+#[test]
+fn test_schema_interval_pattern_matches_accepted_intervals() {
+    let output = run_cli(&["schema", "bucket"]);
+    assert!(
+        output.status.success(),
+        "CLI failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let schema: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("Schema is not valid JSON");
+    let pattern = schema["$defs"]["Interval"]["pattern"]
+        .as_str()
+        .expect("Interval has a pattern");
+    let pattern = regex::Regex::new(pattern).expect("pattern is a valid regex");
+
+    // The pattern admits exactly the minute and hour counts `--interval`
+    // accepts.
+    for minutes in 1..=1500 {
+        let interval = format!("{minutes}m");
+        assert_eq!(
+            pattern.is_match(&interval),
+            1440 % minutes == 0,
+            "{interval}"
+        );
+    }
+    for hours in 1..=30 {
+        let interval = format!("{hours}h");
+        assert_eq!(pattern.is_match(&interval), 24 % hours == 0, "{interval}");
+    }
+    for interval in [
+        "15m",
+        "1440m",
+        "hour",
+        "6h",
+        "day",
+        "3d:2026-01-01",
+        "fiscal_quarter:4",
+        "trailing_month:31:roll_forward",
+        "trailing_month:15:previous_valid_day",
+        "fixed:90",
+    ] {
+        assert!(pattern.is_match(interval), "{interval}");
+    }
+    for interval in [
+        "0m",
+        "7m",
+        "05m",
+        "5h",
+        "48h",
+        "fiscal_quarter:13",
+        "fixed:0",
+    ] {
+        assert!(!pattern.is_match(interval), "{interval}");
+    }
+}
+
+#[test]
+fn test_schema_rejects_unknown_type() {
+    let output = run_cli(&["schema", "nope"]);
+
+    assert_eq!(output.status.code(), Some(2));
+}
+
+// =============================================================================
+// Legacy Golden Test (for backwards compatibility)
+// Processes all DST-related fixture files
+// =============================================================================
+
+#[test]
+fn golden_json_output() {
+    let fixtures = fixture_dir();
+    let golden = golden_dir();
+
+    // Only process DST-related fixture files
+    let dst_fixtures = [
+        "berlin_dst_start_2026.txt",
+        "berlin_dst_end_2026.txt",
+        "newyork_dst_start_2026.txt",
+        "newyork_dst_end_2026.txt",
+    ];
+
+    for fixture_name in &dst_fixtures {
+        let fixture_path = fixtures.join(fixture_name);
+        let stem = fixture_path.file_stem().unwrap().to_str().unwrap();
+        let golden_path = golden.join(format!("{stem}.json"));
+
+        // Determine timezone from fixture name
+        let tz = if stem.contains("berlin") {
+            "Europe/Berlin"
+        } else if stem.contains("newyork") {
+            "America/New_York"
+        } else {
+            "UTC"
+        };
+
+        let output = Command::new(env!("CARGO_BIN_EXE_tzbucket"))
+            .args([
+                "bucket",
+                "--tz",
+                tz,
+                "--interval",
+                "day",
+                "--format",
+                "rfc3339",
+                "--output-format",
+                "json",
+                "--input",
+                fixture_path.to_str().unwrap(),
+            ])
+            .output()
+            .expect("Failed to execute tzbucket");
+
+        assert!(
+            output.status.success(),
+            "tzbucket failed for {}: {}",
+            stem,
+            String::from_utf8_lossy(&output.stderr)
+        );
+
+        let actual = String::from_utf8(output.stdout).expect("Output is not valid UTF-8");
+
+        if update_golden() {
+            fs::create_dir_all(&golden).ok();
+            fs::write(&golden_path, &actual)
+                .unwrap_or_else(|e| panic!("Failed to write golden file {golden_path:?}: {e}"));
+            eprintln!("Updated golden file: {golden_path:?}");
+            continue;
+        }
+
+        let expected = fs::read_to_string(&golden_path).unwrap_or_else(|e| {
+            panic!(
+                "Golden file {golden_path:?} not found: {e}\n\
+                 Hint: Run with UPDATE_GOLDEN=1 to generate golden files"
+            )
+        });
+
+        assert_json_lines_eq(&actual, &expected);
+    }
+}
+
+#[test]
+fn test_shift_and_span_navigate_bucket_keys() {
+    let run = |args: &[&str]| {
+        let output = run_cli(args);
+        assert!(
+            output.status.success(),
+            "CLI failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        let value: serde_json::Value =
+            serde_json::from_slice(&output.stdout).expect("Output is not valid JSON");
+        value
+    };
+
+    let bucket = run(&[
+        "shift",
+        "--tz",
+        "Europe/Berlin",
+        "-i",
+        "day",
+        "--key",
+        "2026-03-29",
+        "-n",
+        "-7",
+    ]);
+    assert_eq!(bucket["key"], "2026-03-22");
+    assert_eq!(bucket["start_utc"], "2026-03-21T23:00:00Z");
+
+    // The same month last year for an event timestamp.
+    let bucket = run(&[
+        "shift",
+        "--tz",
+        "Europe/Berlin",
+        "-i",
+        "month",
+        "--at",
+        "2026-03-31T22:30:00Z",
+        "-n",
+        "-12",
+    ]);
+    assert_eq!(bucket["key"], "2025-04");
+
+    // The skipped 02:00 hour is not a bucket, so 00 to 05 is four buckets.
+    let span = run(&[
+        "span",
+        "--tz",
+        "Europe/Berlin",
+        "-i",
+        "hour",
+        "--from-key",
+        "2026-03-29T00",
+        "--to-key",
+        "2026-03-29T05",
+    ]);
+    assert_eq!(span["intervals"], 4);
+
+    // 47 hours apart in UTC, but two local days (across the 23-hour day).
+    let span = run(&[
+        "span",
+        "--tz",
+        "Europe/Berlin",
+        "-i",
+        "day",
+        "--from-at",
+        "2026-03-28T00:30:00Z",
+        "--to-at",
+        "2026-03-29T23:30:00Z",
+    ]);
+    assert_eq!(span["from_key"], "2026-03-28");
+    assert_eq!(span["to_key"], "2026-03-30");
+    assert_eq!(span["intervals"], 2);
+
+    let output = run_cli(&[
+        "span",
+        "--tz",
+        "Europe/Berlin",
+        "-i",
+        "hour",
+        "--from-key",
+        "2026-03-29T00",
+        "--to-key",
+        "2026-03-29T02",
+    ]);
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn test_range_iso_week_keys_across_new_year() {
+    let output = run_cli(&[
+        "range",
+        "--tz",
+        "Europe/Berlin",
+        "--interval",
+        "week",
+        "--week-key",
+        "iso",
+        "--start",
+        "2026-12-27T00:00:00Z",
+        "--end",
+        "2027-01-05T00:00:00Z",
+    ]);
+    assert!(
+        output.status.success(),
+        "CLI failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let buckets: Vec<serde_json::Value> =
+        serde_json::from_slice(&output.stdout).expect("Output is not valid JSON");
+    let keys: Vec<&str> = buckets.iter().map(|b| b["key"].as_str().unwrap()).collect();
+
+    // 2026 has 53 ISO weeks; the week of 2027-01-01 still belongs to it.
+    assert_eq!(keys, ["2026-W52", "2026-W53", "2027-W01"]);
+    assert_eq!(buckets[1]["start_local"], "2026-12-28T00:00:00+01:00");
+}
+
+#[test]
+fn test_range_mmwr_week_scheme_sets_boundaries_and_keys() {
+    let output = run_cli(&[
+        "range",
+        "--tz",
+        "America/New_York",
+        "--interval",
+        "week",
+        "--week-scheme",
+        "mmwr",
+        "--start",
+        "2025-12-28T05:00:00Z",
+        "--end",
+        "2026-01-11T05:00:00Z",
+        "--output-format",
+        "text",
+    ]);
+    assert!(
+        output.status.success(),
+        "CLI failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    // MMWR weeks start on Sunday; 2026 starts on a Thursday, so its first
+    // MMWR week only starts on January 4.
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "2025-W53: 2025-12-28T00:00:00-05:00 to 2026-01-04T00:00:00-05:00\n\
+         2026-W01: 2026-01-04T00:00:00-05:00 to 2026-01-11T00:00:00-05:00\n"
+    );
+
+    // A scheme fixes the week start.
+    let output = run_cli(&[
+        "range",
+        "--tz",
+        "UTC",
+        "--interval",
+        "week",
+        "--week-scheme",
+        "mmwr",
+        "--week-start",
+        "monday",
+        "--start",
+        "2026-01-01T00:00:00Z",
+        "--end",
+        "2026-01-02T00:00:00Z",
+    ]);
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn test_keys_lists_inclusive_key_range() {
+    let output = run_cli(&[
+        "keys", "--from", "2025-11", "--to", "2026-02", "-i", "month",
+    ]);
+    assert!(
+        output.status.success(),
+        "CLI failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "2025-11\n2025-12\n2026-01\n2026-02\n"
+    );
+
+    // The skipped 02:00 hour has no key.
+    let output = run_cli(&[
+        "keys",
+        "--tz",
+        "Europe/Berlin",
+        "-i",
+        "hour",
+        "--from",
+        "2026-03-29T01",
+        "--to",
+        "2026-03-29T03",
+        "--output-format",
+        "json",
+    ]);
+    let keys: Vec<String> =
+        serde_json::from_slice(&output.stdout).expect("Output is not valid JSON");
+    assert_eq!(keys, ["2026-03-29T01", "2026-03-29T03"]);
+
+    let output = run_cli(&[
+        "keys", "--from", "2026-03", "--to", "2026-01", "-i", "month",
+    ]);
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn test_range_minutes_buckets_repeat_fall_back_windows() {
+    let output = run_cli(&[
+        "range",
+        "--tz",
+        "Europe/Berlin",
+        "--interval",
+        "30m",
+        "--start",
+        "2026-10-25T00:00:00Z",
+        "--end",
+        "2026-10-25T02:00:00Z",
+    ]);
+    assert!(
+        output.status.success(),
+        "CLI failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let buckets: Vec<serde_json::Value> =
+        serde_json::from_slice(&output.stdout).expect("Output is not valid JSON");
+    let keys: Vec<&str> = buckets.iter().map(|b| b["key"].as_str().unwrap()).collect();
+
+    // Local 02:00-03:00 happens twice; each pass gets its own buckets.
+    assert_eq!(
+        keys,
+        [
+            "2026-10-25T02:00+02:00",
+            "2026-10-25T02:30+02:00",
+            "2026-10-25T02:00+01:00",
+            "2026-10-25T02:30+01:00",
+        ]
+    );
+
+    let output = run_cli(&["now", "--interval", "7m"]);
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn test_range_hours_shifts_on_transition_day() {
+    let output = run_cli(&[
+        "range",
+        "--tz",
+        "Europe/Berlin",
+        "--interval",
+        "6h",
+        "--start",
+        "2026-10-24T22:00:00Z",
+        "--end",
+        "2026-10-25T23:00:00Z",
+    ]);
+    assert!(
+        output.status.success(),
+        "CLI failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let buckets: Vec<serde_json::Value> =
+        serde_json::from_slice(&output.stdout).expect("Output is not valid JSON");
+    let bounds: Vec<(&str, &str, &str)> = buckets
+        .iter()
+        .map(|b| {
+            (
+                b["key"].as_str().unwrap(),
+                b["start_utc"].as_str().unwrap(),
+                b["end_utc"].as_str().unwrap(),
+            )
+        })
+        .collect();
+
+    // The fall back repeats 02:00-03:00 inside the first shift: 7 hours.
+    assert_eq!(
+        bounds,
+        [
+            (
+                "2026-10-25T00",
+                "2026-10-24T22:00:00Z",
+                "2026-10-25T05:00:00Z"
+            ),
+            (
+                "2026-10-25T06",
+                "2026-10-25T05:00:00Z",
+                "2026-10-25T11:00:00Z"
+            ),
+            (
+                "2026-10-25T12",
+                "2026-10-25T11:00:00Z",
+                "2026-10-25T17:00:00Z"
+            ),
+            (
+                "2026-10-25T18",
+                "2026-10-25T17:00:00Z",
+                "2026-10-25T23:00:00Z"
+            ),
+        ]
+    );
+
+    let output = run_cli(&["now", "--interval", "5h"]);
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn test_bucket_days_interval_with_anchor() {
+    let fixture_path = fixture_dir().join("berlin_dst_start_2026.txt");
+    let output = run_cli(&[
+        "bucket",
+        "--tz",
+        "Europe/Berlin",
+        "--interval",
+        "14d",
+        "--anchor",
+        "2026-01-05",
+        "--format",
+        "rfc3339",
+        "--output-format",
+        "json",
+        "--input",
+        fixture_path.to_str().unwrap(),
+    ]);
+    assert!(
+        output.status.success(),
+        "CLI failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let records: Vec<serde_json::Value> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("Output is not valid JSON"))
+        .collect();
+
+    // Periods run 2026-01-05, 01-19, ..., 03-16, 03-30 in local time.
+    assert_eq!(records[0]["interval"], "14d:2026-01-05");
+    assert_eq!(records[0]["bucket"]["key"], "2026-03-16");
+    assert_eq!(records[0]["bucket"]["start_utc"], "2026-03-15T23:00:00Z");
+    assert_eq!(records[5]["bucket"]["key"], "2026-03-30");
+    assert_eq!(records[5]["bucket"]["start_utc"], "2026-03-29T22:00:00Z");
+
+    let output = run_cli(&["now", "--interval", "day", "--anchor", "2026-01-05"]);
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn test_bucket_input_modes_extract_timestamps() {
+    let dir = std::env::temp_dir().join(format!("tzbucket_input_modes_{}", std::process::id()));
+    fs::create_dir_all(&dir).expect("Failed to create temp dir");
+    let write = |name: &str, content: &str| {
+        let path = dir.join(name);
+        fs::write(&path, content).expect("Failed to write temp file");
+        path.to_str().unwrap().to_string()
+    };
+    let csv = write(
+        "events.csv",
+        "id,\"note, quoted\",ts\n1,\"a, b\",2026-03-29T00:30:00Z\n2,c,2026-03-29T22:30:00Z\n",
+    );
+    let headerless = write(
+        "headerless.csv",
+        "2026-03-29T00:30:00Z,a\n2026-03-29T22:30:00Z,b\n",
+    );
+    let ndjson = write(
+        "events.ndjson",
+        "{\"ts\":\"2026-03-29T00:30:00Z\",\"id\":1}\n{\"id\":2,\"ts\":\"2026-03-29T22:30:00Z\"}\n",
+    );
+    let log = write(
+        "events.log",
+        "[2026-03-29T00:30:00Z] INFO start\n  continued line\n[2026-03-29T22:30:00Z] INFO stop\n",
+    );
+
+    for (input, mode) in [
+        (&csv, "csv:ts"),
+        (&headerless, "csv:1"),
+        (&ndjson, "ndjson:ts"),
+        (&log, r"regex:^\[(?<ts>[^\]]+)\]"),
+    ] {
+        let output = run_cli(&[
+            "bucket",
+            "--tz",
+            "Europe/Berlin",
+            "--format",
+            "rfc3339",
+            "--input-mode",
+            mode,
+            "--input",
+            input,
+            "--output-format",
+            "text",
+        ]);
+        assert!(
+            output.status.success(),
+            "CLI failed for {}: {}",
+            mode,
+            String::from_utf8_lossy(&output.stderr)
+        );
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let keys: Vec<&str> = stdout
+            .lines()
+            .map(|line| line.split(' ').next().unwrap())
+            .collect();
+        // The regex skips the continuation line.
+        assert_eq!(keys, ["2026-03-29", "2026-03-30"], "{}", mode);
+    }
+
+    let output = run_cli(&["bucket", "--input-mode", "xml:ts", "--input", &csv]);
+    assert_eq!(output.status.code(), Some(2));
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn test_range_fiscal_quarters() {
+    let output = run_cli(&[
+        "range",
+        "--tz",
+        "America/New_York",
+        "--interval",
+        "fiscal_quarter:10",
+        "--start",
+        "2026-09-15T00:00:00Z",
+        "--end",
+        "2027-01-15T00:00:00Z",
+        "--output-format",
+        "text",
+    ]);
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "FY2026-Q4: 2026-07-01T00:00:00-04:00 to 2026-10-01T00:00:00-04:00\n\
+         FY2027-Q1: 2026-10-01T00:00:00-04:00 to 2027-01-01T00:00:00-05:00\n\
+         FY2027-Q2: 2027-01-01T00:00:00-05:00 to 2027-04-01T00:00:00-04:00\n"
+    );
+
+    let keys = |interval| {
+        run_cli(&[
+            "keys",
+            "--interval",
+            interval,
+            "--from",
+            "FY2026-Q4",
+            "--to",
+            "FY2027-Q2",
+        ])
+    };
+    let output = keys("fiscal_quarter:10");
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "FY2026-Q4\nFY2027-Q1\nFY2027-Q2\n"
+    );
+    assert_eq!(keys("fiscal_quarter:13").status.code(), Some(2));
+}
+
+#[test]
+fn test_output_file_is_replaced_only_on_success() {
+    let dir = std::env::temp_dir().join(format!("tzbucket_output_{}", std::process::id()));
+    fs::remove_dir_all(&dir).ok();
+    fs::create_dir_all(&dir).expect("Failed to create temp dir");
+    let path = dir.join("keys.txt");
+    let keys = |tz: &str, extra: &[&str]| {
+        let mut args = vec![
+            "keys",
+            "--tz",
+            tz,
+            "--from",
+            "2026-03-28",
+            "--to",
+            "2026-03-29",
+            "--output",
+            path.to_str().unwrap(),
+        ];
+        args.extend_from_slice(extra);
+        run_cli(&args)
+    };
+
+    let output = keys("Europe/Berlin", &[]);
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty());
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        "2026-03-28\n2026-03-29\n"
+    );
+
+    let output = keys("Europe/Berlin", &["--append"]);
+    assert!(output.status.success());
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        "2026-03-28\n2026-03-29\n2026-03-28\n2026-03-29\n"
+    );
+
+    // A run that fails after writing records keeps the previous file and
+    // leaves no temporary file.
+    let input = dir.join("input.txt");
+    fs::write(&input, "1774738680000\nnot-a-timestamp\n").expect("Failed to write temp file");
+    let output = run_cli(&[
+        "bucket",
+        "--input",
+        input.to_str().unwrap(),
+        "--output",
+        path.to_str().unwrap(),
+    ]);
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        "2026-03-28\n2026-03-29\n2026-03-28\n2026-03-29\n"
+    );
+    assert_eq!(fs::read_dir(&dir).unwrap().count(), 2);
+
+    let output = run_cli(&[
+        "keys",
+        "--from",
+        "2026-03-28",
+        "--to",
+        "2026-03-29",
+        "--append",
+    ]);
+    assert_eq!(output.status.code(), Some(2));
+
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn test_day_offset_moves_day_boundaries() {
+    let input =
+        std::env::temp_dir().join(format!("tzbucket-day-offset-{}.txt", std::process::id()));
+    fs::write(&input, "2026-03-29T03:30:00Z\n").unwrap();
+    let bucket = |extra: &[&str]| {
+        let mut args = vec![
+            "bucket",
+            "--tz",
+            "Europe/Berlin",
+            "--interval",
+            "day",
+            "--format",
+            "rfc3339",
+            "--input",
+            input.to_str().unwrap(),
+        ];
+        args.extend_from_slice(extra);
+        run_cli(&args)
+    };
+
+    // 05:30 local on the morning of the spring-forward day still belongs
+    // to the operational day that began on the 28th.
+    let output = bucket(&["--day-offset", "06:00"]);
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "2026-03-28 -> 2026-03-28T06:00:00+01:00 to 2026-03-29T06:00:00+02:00\n"
+    );
+
+    // 02:30 does not exist on the 29th: an error unless a policy resolves it.
+    let output = bucket(&["--day-offset", "02:30"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(
+        String::from_utf8(output.stderr)
+            .unwrap()
+            .contains("--policy-nonexistent=shift_forward")
+    );
+    let output = bucket(&[
+        "--day-offset",
+        "02:30",
+        "--policy-nonexistent",
+        "shift_forward",
+    ]);
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "2026-03-29 -> 2026-03-29T03:30:00+02:00 to 2026-03-30T02:30:00+02:00\n"
+    );
+
+    let output = run_cli(&[
+        "range",
+        "--tz",
+        "Europe/Berlin",
+        "--interval",
+        "day",
+        "--day-offset",
+        "06:00",
+        "--start",
+        "2026-03-28T12:00:00Z",
+        "--end",
+        "2026-03-29T12:00:00Z",
+        "--output-format",
+        "text",
+    ]);
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "2026-03-28: 2026-03-28T06:00:00+01:00 to 2026-03-29T06:00:00+02:00\n\
+         2026-03-29: 2026-03-29T06:00:00+02:00 to 2026-03-30T06:00:00+02:00\n"
+    );
+    assert_eq!(
+        run_cli(&[
+            "range",
+            "--tz",
+            "Europe/Berlin",
+            "--interval",
+            "hour",
+            "--day-offset",
+            "06:00",
+            "--start",
+            "2026-03-28T00:00:00Z",
+            "--end",
+            "2026-03-29T00:00:00Z",
+        ])
+        .status
+        .code(),
+        Some(2)
+    );
+    fs::remove_file(&input).unwrap();
+}
+
+#[test]
+fn test_errors_to_skips_bad_lines() {
+    let dir = std::env::temp_dir().join(format!("tzbucket-errors-to-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let input = dir.join("in.txt");
+    let skipped = dir.join("skipped.ndjson");
+    fs::write(
+        &input,
+        "2026-03-29T00:30:00Z\ngarbage\n\n2026-03-30T01:30:00Z\n",
+    )
+    .unwrap();
+    let bucket = |extra: &[&str]| {
+        let mut args = vec![
+            "bucket",
+            "--tz",
+            "Europe/Berlin",
+            "--format",
+            "rfc3339",
+            "--input",
+            input.to_str().unwrap(),
+        ];
+        args.extend_from_slice(extra);
+        run_cli(&args)
+    };
+    let data = "2026-03-29 -> 2026-03-29T00:00:00+01:00 to 2026-03-30T00:00:00+02:00\n";
+
+    // Data stays on stdout, the diagnostic goes to stderr.
+    let output = bucket(&[]);
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), data);
+    assert!(
+        String::from_utf8(output.stderr)
+            .unwrap()
+            .starts_with("Error: Error processing 'garbage'")
+    );
+
+    let output = bucket(&["--errors-to", "stderr"]);
+    assert!(output.status.success());
+    let both = "2026-03-29 -> 2026-03-29T00:00:00+01:00 to 2026-03-30T00:00:00+02:00\n\
+                2026-03-30 -> 2026-03-30T00:00:00+02:00 to 2026-03-31T00:00:00+02:00\n";
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), both);
+    let record: serde_json::Value =
+        serde_json::from_slice(&output.stderr).expect("one skipped-line record");
+    assert_eq!(record["line"], 2);
+    assert_eq!(record["input"], "garbage");
+
+    let output = bucket(&["--errors-to", skipped.to_str().unwrap()]);
+    assert!(output.status.success());
+    assert!(output.stderr.is_empty());
+    assert_eq!(
+        fs::read_to_string(&skipped).unwrap().lines().count(),
+        1,
+        "one record per skipped line"
+    );
+
+    let output = bucket(&["--errors-to", "null"]);
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), both);
+    assert!(output.stderr.is_empty());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_count_first_last_timestamps() {
+    let fixture_path = fixture_dir().join("count_berlin_march_2026.txt");
+    let count = |output_format| {
+        run_cli(&[
+            "count",
+            "--tz",
+            "Europe/Berlin",
+            "--format",
+            "rfc3339",
+            "--input",
+            fixture_path.to_str().unwrap(),
+            "--first-last",
+            "--output-format",
+            output_format,
+        ])
+    };
+
+    let output = count("json");
+    assert!(output.status.success());
+    let records: Vec<serde_json::Value> = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(records[0]["first_ts"], "2026-03-01T10:00:00Z");
+    assert_eq!(records[0]["first_ts_local"], "2026-03-01T11:00:00+01:00");
+    assert_eq!(records[0]["last_ts"], "2026-03-01T11:00:00Z");
+    // Shortly after local midnight, the first event of its day.
+    assert_eq!(records[2]["first_ts_local"], "2026-03-30T00:30:00+02:00");
+
+    let output = count("text");
+    assert!(output.status.success());
+    assert!(String::from_utf8(output.stdout).unwrap().contains(
+        "2026-03-31: 4 (first 2026-03-31T14:00:00+02:00, last 2026-03-31T14:00:03+02:00)\n"
+    ));
+
+    assert_eq!(count("calendar").status.code(), Some(2));
+}
+
+#[test]
+fn test_count_completeness_reports_missing_pairs() {
+    let dir = std::env::temp_dir().join(format!("tzbucket-completeness-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let events = dir.join("events.csv");
+    let roster = dir.join("roster.txt");
+    // Hourly heartbeats across the Berlin spring-forward night: the 02:00
+    // local hour does not exist, so no device owes an event for it.
+    fs::write(
+        &events,
+        "ts,device\n\
+         2026-03-29T00:10:00Z,a\n\
+         2026-03-29T00:20:00Z,b\n\
+         2026-03-29T01:10:00Z,a\n\
+         2026-03-29T02:05:00Z,a\n\
+         2026-03-29T02:06:00Z,b\n\
+         2026-03-29T02:07:00Z,unlisted\n",
+    )
+    .unwrap();
+    fs::write(&roster, "# devices\na\nb\n").unwrap();
+    let count = |roster: &Path, output_format| {
+        run_cli(&[
+            "count",
+            "--tz",
+            "Europe/Berlin",
+            "--interval",
+            "hour",
+            "--format",
+            "rfc3339",
+            "--input-mode",
+            "csv:ts",
+            "--input",
+            events.to_str().unwrap(),
+            "--completeness",
+            roster.to_str().unwrap(),
+            "--entity-field",
+            "device",
+            "--output-format",
+            output_format,
+        ])
+    };
+
+    let output = count(&roster, "text");
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "2026-03-29T03 b: 0 of 1\n\
+         1 of 6 bucket/entity pairs incomplete\n"
+    );
+
+    let output = count(&roster, "json");
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["incomplete"], 1);
+    assert_eq!(report["missing"][0]["start_utc"], "2026-03-29T01:00:00Z");
+    assert_eq!(report["missing"][0]["entity"], "b");
+
+    fs::write(&roster, "a\n").unwrap();
+    let output = count(&roster, "text");
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "0 of 3 bucket/entity pairs incomplete\n"
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_bucket_multiple_intervals() {
+    let fixture_path = fixture_dir().join("berlin_dst_start_2026.txt");
+    let bucket = |interval, extra: &[&str]| {
+        let mut args = vec![
+            "bucket",
+            "--tz",
+            "Europe/Berlin",
+            "--format",
+            "rfc3339",
+            "--input",
+            fixture_path.to_str().unwrap(),
+            "--interval",
+            interval,
+        ];
+        args.extend_from_slice(extra);
+        run_cli(&args)
+    };
+    let records = |output: Output| -> Vec<serde_json::Value> {
+        assert!(output.status.success());
+        String::from_utf8(output.stdout)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    };
+
+    // Each record carries the bucket a single-interval run assigns.
+    let multi = records(bucket("day,week,month", &["--output-format", "json"]));
+    for interval in ["day", "week", "month"] {
+        let single = records(bucket(interval, &["--output-format", "json"]));
+        assert_eq!(multi.len(), single.len());
+        for (multi, single) in multi.iter().zip(&single) {
+            assert_eq!(multi["input"], single["input"]);
+            assert_eq!(multi["buckets"][interval], single["bucket"]);
+        }
+    }
+
+    let output = bucket("day,week,month", &["--week-key", "iso"]);
+    assert!(output.status.success());
+    assert!(
+        String::from_utf8(output.stdout)
+            .unwrap()
+            .starts_with("day=2026-03-28 week=2026-W13 month=2026-03\n")
+    );
+
+    assert_eq!(bucket("day,week", &["--summary"]).status.code(), Some(2));
+    assert_eq!(bucket("day,day", &[]).status.code(), Some(2));
+}
+
+#[test]
+fn test_rollup_days_into_weeks_and_months() {
+    let dir = std::env::temp_dir().join(format!("tzbucket-rollup-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let days = dir.join("days.csv");
+    // 2026-03-23 to 2026-04-05: two full ISO weeks, the second crossing into
+    // April.
+    let mut csv = String::from("key,count\n");
+    for day in 23..=31 {
+        csv.push_str(&format!("2026-03-{},1\n", day));
+    }
+    for day in 1..=5 {
+        csv.push_str(&format!("2026-04-{:02},10\n", day));
+    }
+    fs::write(&days, csv).unwrap();
+    let rollup = |path: &Path, args: &[&str]| {
+        let mut argv = vec![
+            "rollup",
+            "--tz",
+            "Europe/Berlin",
+            "--input",
+            path.to_str().unwrap(),
+            "--output-format",
+            "text",
+        ];
+        argv.extend_from_slice(args);
+        run_cli(&argv)
+    };
+
+    let output = rollup(&days, &["--interval", "day", "--to", "week,month"]);
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "week 2026-03-23: 7 from 7 day buckets\n\
+         week 2026-03-30: 52 from 7 day buckets\n\
+         month 2026-03: 9 from 9 day buckets (incomplete)\n\
+         month 2026-04: 50 from 5 day buckets (incomplete)\n"
+    );
+
+    // Weeks do not nest in months: the week of 2026-03-30 needs a policy.
+    let weeks = dir.join("weeks.csv");
+    fs::write(&weeks, "key,count\n2026-03-23,7\n2026-03-30,52\n").unwrap();
+    let output = rollup(&weeks, &["--interval", "week", "--to", "month"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(
+        String::from_utf8(output.stderr)
+            .unwrap()
+            .contains("crosses the month boundary")
+    );
+    let output = rollup(
+        &weeks,
+        &[
+            "--interval",
+            "week",
+            "--to",
+            "month",
+            "--attribution",
+            "start",
+        ],
+    );
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "month 2026-03: 59 from 2 week buckets (incomplete)\n"
+    );
+    // Two of the week's seven days are in March.
+    let output = rollup(
+        &weeks,
+        &[
+            "--interval",
+            "week",
+            "--to",
+            "month",
+            "--attribution",
+            "majority",
+        ],
+    );
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "month 2026-03: 7 from 1 week buckets (incomplete)\n\
+         month 2026-04: 52 from 1 week buckets (incomplete)\n"
+    );
+    let output = rollup(
+        &weeks,
+        &[
+            "--interval",
+            "week",
+            "--to",
+            "month",
+            "--attribution",
+            "split",
+        ],
+    );
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "month 2026-03: 22 from 2 week buckets (incomplete)\n\
+         month 2026-04: 37 from 1 week buckets (incomplete)\n"
+    );
+
+    let output = rollup(&days, &["--interval", "day", "--to", "hour"]);
+    assert_eq!(output.status.code(), Some(2));
+
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn test_annotate_dst_marks_transitions() {
+    let input = std::env::temp_dir().join(format!("tzbucket-annotate-{}.txt", std::process::id()));
+    // Out of order: the spring transition is only inside the range once the
+    // last line is read; the autumn one lies outside it.
+    fs::write(
+        &input,
+        "2026-03-30T12:00:00Z\n2026-03-30T13:00:00Z\n2026-03-28T12:00:00Z\n",
+    )
+    .unwrap();
+    let run = |command: &str, output_format: &str| {
+        run_cli(&[
+            command,
+            "--tz",
+            "Europe/Berlin",
+            "--format",
+            "rfc3339",
+            "--input",
+            input.to_str().unwrap(),
+            "--output-format",
+            output_format,
+            "--annotate-dst",
+        ])
+    };
+
+    let output = run("bucket", "json");
+    assert!(output.status.success());
+    let records: Vec<serde_json::Value> = String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(records.len(), 4);
+    assert_eq!(records[2]["type"], "transition");
+    assert_eq!(records[2]["at_utc"], "2026-03-29T01:00:00Z");
+    assert_eq!(records[2]["at_local"], "2026-03-29T03:00:00+02:00");
+    assert_eq!(records[2]["offset_before"], "+01:00");
+    assert_eq!(records[2]["offset_after"], "+02:00");
+    assert_eq!(records[3]["bucket"]["key"], "2026-03-28");
+
+    let output = run("count", "text");
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "2026-03-28: 1\n\
+         transition 2026-03-29T01:00:00Z (2026-03-29T03:00:00+02:00): +01:00 -> +02:00\n\
+         2026-03-30: 2\n"
+    );
+
+    let output = run("count", "vega");
+    assert_eq!(output.status.code(), Some(2));
+
+    fs::remove_file(&input).ok();
+}
+
+#[test]
+fn test_matrix_shows_date_in_every_zone() {
+    let zones = std::env::temp_dir().join(format!("tzbucket-matrix-{}.txt", std::process::id()));
+    fs::write(
+        &zones,
+        "# follow the sun\nAmerica/Los_Angeles\nEurope/Berlin\n\nPacific/Kiritimati\n",
+    )
+    .unwrap();
+    let matrix = |args: &[&str]| {
+        let mut argv = vec!["matrix", "--tz-list", zones.to_str().unwrap()];
+        argv.extend_from_slice(args);
+        run_cli(&argv)
+    };
+
+    let output = matrix(&["--at", "2026-03-29T10:30:00Z"]);
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "America/Los_Angeles  2026-03-29T03:30:00-07:00  -07:00  2026-03-29\n\
+         Europe/Berlin        2026-03-29T12:30:00+02:00  +02:00  2026-03-29\n\
+         Pacific/Kiritimati   2026-03-30T00:30:00+14:00  +14:00  2026-03-30\n"
+    );
+
+    // Without --at, the clock (here --now-override) supplies the instant.
+    let output = run_cli(&[
+        "--now-override",
+        "2026-01-01T00:00:00Z",
+        "matrix",
+        "--tz-list",
+        zones.to_str().unwrap(),
+        "--output-format",
+        "json",
+    ]);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["at_utc"], "2026-01-01T00:00:00Z");
+    assert_eq!(json["zones"][0]["key"], "2025-12-31");
+    assert_eq!(json["zones"][1]["offset"], "+01:00");
+    assert_eq!(json["zones"][2]["local_time"], "2026-01-01T14:00:00+14:00");
+
+    fs::write(&zones, "Europe/Berlin\nMars/Olympus_Mons\n").unwrap();
+    let output = matrix(&[]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(
+        String::from_utf8(output.stderr)
+            .unwrap()
+            .contains(":2: Invalid timezone")
+    );
+
+    fs::remove_file(&zones).ok();
+}
+
+#[cfg(feature = "zone-tab")]
+#[test]
+fn test_bucket_by_country() {
+    let input = std::env::temp_dir().join(format!("tzbucket-country-{}.txt", std::process::id()));
+    fs::write(&input, "2026-03-29T21:30:00Z\n").unwrap();
+    let run = |args: &[&str]| {
+        let mut all = vec![
+            "bucket",
+            "--format",
+            "rfc3339",
+            "--input",
+            input.to_str().unwrap(),
+            "--output-format",
+            "text",
+        ];
+        all.extend_from_slice(args);
+        run_cli(&all)
+    };
+
+    // One zone: the same as --tz.
+    let output = run(&["--country", "is"]);
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&run(&["--tz", "Atlantic/Reykjavik"]).stdout)
+    );
+
+    let output = run(&["--country", "DE"]);
+    assert_eq!(output.status.code(), Some(2));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Europe/Berlin, Europe/Busingen"),
+        "{}",
+        stderr
+    );
+
+    let output = run(&["--country", "DE", "--all-zones", "-i", "day,month"]);
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "Europe/Berlin day=2026-03-29 month=2026-03\n\
+         Europe/Busingen day=2026-03-29 month=2026-03\n"
+    );
+
+    let output = run(&["--country", "XX"]);
+    assert_eq!(output.status.code(), Some(2));
+    fs::remove_file(&input).ok();
+}
+
+#[cfg(feature = "tzf-rs")]
+#[test]
+fn test_bucket_by_location() {
+    let input = std::env::temp_dir().join(format!("tzbucket-location-{}.txt", std::process::id()));
+    fs::write(&input, "2026-03-29T21:30:00Z\n2026-03-08T06:30:00Z\n").unwrap();
+    let run = |args: &[&str]| {
+        let mut all = vec![
+            "bucket",
+            "--format",
+            "rfc3339",
+            "--input",
+            input.to_str().unwrap(),
+            "--output-format",
+            "text",
+        ];
+        all.extend_from_slice(args);
+        run_cli(&all)
+    };
+
+    // Berlin and New York: the same buckets as naming the zone.
+    for (lat, lon, tz) in [
+        ("52.52", "13.405", "Europe/Berlin"),
+        ("40.7128", "-74.006", "America/New_York"),
+    ] {
+        let output = run(&["--lat", lat, "--lon", lon]);
+        assert!(output.status.success(), "{}", tz);
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&run(&["--tz", tz]).stdout)
+        );
+    }
+
+    let output = run(&["--lat", "91", "--lon", "0"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Invalid location '91, 0'"));
+
+    // --lat needs --lon and excludes --tz.
+    assert_eq!(run(&["--lat", "52.52"]).status.code(), Some(2));
+    assert_eq!(
+        run(&["--lat", "52.52", "--lon", "13.405", "--tz", "UTC"])
+            .status
+            .code(),
+        Some(2)
+    );
+
+    fs::remove_file(&input).ok();
+}
+
+#[test]
+fn test_offsets_exports_history_as_csv() {
+    let output = run_cli(&[
+        "offsets",
+        "--tz",
+        "Asia/Tehran",
+        "--from",
+        "2021",
+        "--to",
+        "2030",
+        "--output-format",
+        "csv",
+    ]);
+    assert!(
+        output.status.success(),
+        "CLI failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    // Iran abolished DST after 2022.
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "tz,effective_from_utc,offset,abbrev,is_dst\n\
+         Asia/Tehran,2021-01-01T00:00:00Z,+03:30,+0330,false\n\
+         Asia/Tehran,2021-03-21T20:30:00Z,+04:30,+0430,true\n\
+         Asia/Tehran,2021-09-21T19:30:00Z,+03:30,+0330,false\n\
+         Asia/Tehran,2022-03-21T20:30:00Z,+04:30,+0430,true\n\
+         Asia/Tehran,2022-09-21T19:30:00Z,+03:30,+0330,false\n"
+    );
+
+    let output = run_cli(&[
+        "offsets",
+        "--tz",
+        "Europe/Berlin",
+        "--from",
+        "2026-03-29T01:00:00Z",
+        "--to",
+        "2026",
+    ]);
+    assert!(output.status.success());
+    let records: Vec<serde_json::Value> = String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0]["abbrev"], "CEST");
+    assert_eq!(records[0]["is_dst"], true);
+    assert_eq!(records[1]["effective_from_utc"], "2026-10-25T01:00:00Z");
+
+    let output = run_cli(&[
+        "offsets",
+        "--tz",
+        "Europe/Berlin",
+        "--from",
+        "2027",
+        "--to",
+        "2026",
+    ]);
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn test_dst_info_flags_transition_days() {
+    let output = run_cli(&[
+        "range",
+        "--tz",
+        "Europe/Berlin",
+        "--start",
+        "2026-10-24T00:00:00Z",
+        "--end",
+        "2026-10-26T00:00:00Z",
+        "--dst-info",
+    ]);
+    assert!(
+        output.status.success(),
+        "CLI failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let buckets: Vec<serde_json::Value> = serde_json::from_slice(&output.stdout).unwrap();
+    let info: Vec<(&str, bool, i64, i64)> = buckets
+        .iter()
+        .map(|b| {
+            (
+                b["key"].as_str().unwrap(),
+                b["is_dst_transition"].as_bool().unwrap(),
+                b["start_offset_seconds"].as_i64().unwrap(),
+                b["end_offset_seconds"].as_i64().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        info,
+        [
+            ("2026-10-24", false, 7200, 7200),
+            ("2026-10-25", true, 7200, 3600),
+            ("2026-10-26", false, 3600, 3600),
+        ]
+    );
+
+    // Without the flag the fields are absent.
+    let output = run_cli(&[
+        "range",
+        "--tz",
+        "Europe/Berlin",
+        "--start",
+        "2026-10-25T00:00:00Z",
+        "--end",
+        "2026-10-25T01:00:00Z",
+    ]);
+    let buckets: Vec<serde_json::Value> = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(buckets[0].get("is_dst_transition").is_none());
+}
+
+#[test]
+fn test_same_rules_compares_zone_offsets() {
+    let output = run_cli(&[
+        "same-rules",
+        "Europe/Berlin",
+        "Europe/Paris",
+        "--from",
+        "2020",
+        "--to",
+        "2030",
+    ]);
+    assert!(
+        output.status.success(),
+        "CLI failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "same: Europe/Berlin and Europe/Paris have identical UTC offsets \
+         from 2020-01-01T00:00:00Z to 2031-01-01T00:00:00Z\n"
+    );
+
+    // Havana switches at local midnight, New York at 02:00: two hours apart.
+    let output = run_cli(&[
+        "same-rules",
+        "America/New_York",
+        "America/Havana",
+        "--from",
+        "2026",
+        "--to",
+        "2026",
         "--output-format",
         "json",
     ]);
+    assert_eq!(output.status.code(), Some(3));
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["same"], false);
+    assert_eq!(report["first_difference"]["at_utc"], "2026-03-08T05:00:00Z");
+    assert_eq!(report["first_difference"]["first_offset"], "-05:00");
+    assert_eq!(report["first_difference"]["second_offset"], "-04:00");
+}
 
-    // Should exit with code 2 for policy error
-    assert_eq!(
-        output.status.code(),
-        Some(2),
-        "Expected exit code 2 for ambiguous time error, got {:?}",
-        output.status.code()
+#[test]
+fn test_legacy_tz_warns_or_fails_with_strict_tz() {
+    let input = std::env::temp_dir().join(format!("tzbucket-legacy-{}.txt", std::process::id()));
+    fs::write(&input, "1774742400000\n").unwrap();
+    let run = |args: &[&str]| {
+        let mut all = vec![
+            "bucket",
+            "--input",
+            input.to_str().unwrap(),
+            "--tz",
+            "US/Eastern",
+        ];
+        all.extend_from_slice(args);
+        run_cli(&all)
+    };
+
+    let output = run(&["--output-format", "json"]);
+    assert!(output.status.success());
+    let record: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(record["tz"], "US/Eastern");
+    assert_eq!(record["canonical_tz"], "America/New_York");
+    let warning: serde_json::Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert_eq!(warning["code"], "legacy_tz");
+    assert_eq!(warning["canonical_tz"], "America/New_York");
+
+    let output = run(&["--strict-tz"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(output.stdout.is_empty());
+    assert!(
+        String::from_utf8_lossy(&output.stderr)
+            .contains("Timezone 'US/Eastern' is a legacy name for 'America/New_York'")
     );
 
-    // Verify error output
-    let stderr = String::from_utf8_lossy(&output.stderr);
+    // Current names pass --strict-tz untouched.
+    let output = run_cli(&[
+        "bucket",
+        "--input",
+        input.to_str().unwrap(),
+        "--tz",
+        "America/New_York",
+        "--strict-tz",
+        "--output-format",
+        "json",
+    ]);
+    fs::remove_file(&input).unwrap();
+    assert!(output.status.success());
+    assert!(output.stderr.is_empty());
+    assert!(!String::from_utf8_lossy(&output.stdout).contains("canonical_tz"));
+}
+
+#[test]
+fn test_key_rehydrates_stored_keys() {
+    let output = run_cli(&[
+        "key",
+        "--tz",
+        "America/New_York",
+        "-i",
+        "month",
+        "2026-03",
+        "2026-11",
+    ]);
     assert!(
-        stderr.contains("Ambiguous time"),
-        "Expected error message about ambiguous time, got: {}",
-        stderr
+        output.status.success(),
+        "CLI failed: {}",
+        String::from_utf8_lossy(&output.stderr)
     );
+    let buckets: Vec<serde_json::Value> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("Output is not valid JSON"))
+        .collect();
+    assert_eq!(buckets.len(), 2);
+    assert_eq!(buckets[0]["start_utc"], "2026-03-01T05:00:00Z");
+    assert_eq!(buckets[0]["end_utc"], "2026-04-01T04:00:00Z");
+    assert_eq!(buckets[1]["start_utc"], "2026-11-01T04:00:00Z");
+
+    // A week key on the wrong weekday is not a bucket key.
+    let output = run_cli(&["key", "--tz", "Europe/Berlin", "-i", "week", "2026-03-24"]);
+    assert_eq!(output.status.code(), Some(2));
 }
 
-// =============================================================================
-// Explain Tests - Normal Time
-// =============================================================================
+#[test]
+fn test_bucket_repeated_tz_emits_one_record_per_zone() {
+    let run = |args: &[&str]| {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_tzbucket"))
+            .args(["bucket", "--format", "rfc3339", "--output-format", "text"])
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .expect("Failed to run tzbucket");
+        child
+            .stdin
+            .as_mut()
+            .expect("Missing stdin")
+            .write_all(b"2026-03-29T23:30:00Z\n")
+            .expect("Failed to write stdin");
+        child
+            .wait_with_output()
+            .expect("Failed to wait for tzbucket")
+    };
+
+    let output = run(&[
+        "--tz",
+        "Europe/Berlin",
+        "--tz",
+        "America/New_York",
+        "--tz",
+        "UTC",
+    ]);
+    assert!(
+        output.status.success(),
+        "CLI failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    // 01:30 on Monday in Berlin, Sunday evening in New York.
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "Europe/Berlin day=2026-03-30\n\
+         America/New_York day=2026-03-29\n\
+         UTC day=2026-03-29\n"
+    );
+
+    let output = run(&["--tz", "UTC", "--tz", "Asia/Tokyo", "--summary"]);
+    assert_eq!(output.status.code(), Some(2));
+}
 
 #[test]
-fn test_explain_normal_time() {
+fn test_difftest_agrees_with_gnu_date_across_dst() {
+    // Needs GNU date and the system's compiled tzdata.
+    let gnu_date = std::process::Command::new("date")
+        .arg("--version")
+        .output()
+        .is_ok_and(|o| String::from_utf8_lossy(&o.stdout).contains("GNU"));
+    if !gnu_date || !std::path::Path::new("/usr/share/zoneinfo/Europe/Berlin").is_file() {
+        eprintln!("skipping: GNU date or /usr/share/zoneinfo not available");
+        return;
+    }
+
     let output = run_cli(&[
-        "explain",
+        "difftest",
         "--tz",
         "Europe/Berlin",
-        "--local",
-        "2026-06-15T14:30:00",
+        "--tz",
+        "America/New_York",
+        "--from",
+        "2020",
+        "--to",
+        "2030",
+        "--count",
+        "50",
+        "--seed",
+        "7",
         "--output-format",
         "json",
     ]);
 
     assert!(
         output.status.success(),
-        "CLI failed: {}",
-        String::from_utf8_lossy(&output.stderr)
+        "difftest diverged: {}",
+        String::from_utf8_lossy(&output.stdout)
     );
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("Invalid JSON output");
+    assert_eq!(json["seed"], 7);
+    assert_eq!(json["zones"], 2);
+    assert_eq!(json["samples"], 50);
+    assert_eq!(json["divergences"], serde_json::json!([]));
 
-    let actual = String::from_utf8(output.stdout).expect("Output is not valid UTF-8");
-    let json: serde_json::Value = serde_json::from_str(&actual).expect("Invalid JSON output");
-
-    assert_eq!(json["status"], "normal");
-    assert!(json["resolution"].is_null());
+    let output = run_cli(&["difftest", "-i", "hour"]);
+    assert_eq!(output.status.code(), Some(2));
 }
 
-// =============================================================================
-// Legacy Golden Test (for backwards compatibility)
-// Processes all DST-related fixture files
-// =============================================================================
+#[test]
+fn test_library_args_match_cli_defaults() {
+    use clap::Parser;
+    use tzbucket_cli::cli::{BucketArgs, Cli, Commands, ExplainArgs, RangeArgs};
+
+    let parse = |args: &[&str]| Cli::try_parse_from(args).unwrap().command;
+
+    let Commands::Bucket(parsed) = parse(&["tzbucket", "bucket"]) else {
+        panic!("expected bucket");
+    };
+    assert_eq!(
+        format!("{:?}", parsed),
+        format!("{:?}", BucketArgs::default())
+    );
+
+    let Commands::Range(parsed) = parse(&[
+        "tzbucket",
+        "range",
+        "--tz",
+        "UTC",
+        "--start",
+        "1970-01-01T00:00:00Z",
+        "--end",
+        "1970-01-01T00:00:00Z",
+    ]) else {
+        panic!("expected range");
+    };
+    assert_eq!(
+        format!("{:?}", parsed),
+        format!("{:?}", RangeArgs::default())
+    );
+
+    let Commands::Explain(parsed) = parse(&[
+        "tzbucket",
+        "explain",
+        "--tz",
+        "UTC",
+        "--local",
+        "1970-01-01T00:00:00",
+    ]) else {
+        panic!("expected explain");
+    };
+    assert_eq!(
+        format!("{:?}", parsed),
+        format!("{:?}", ExplainArgs::default())
+    );
+}
 
 #[test]
-fn golden_json_output() {
-    let fixtures = fixture_dir();
-    let golden = golden_dir();
+fn test_library_args_hold_parsed_values() {
+    use chrono::{Duration, NaiveDate, TimeZone, Utc};
+    use clap::Parser;
+    use tzbucket_cli::cli::{Cli, Commands, IntervalArg, RangeBound};
+    use tzbucket_core::Interval;
 
-    // Only process DST-related fixture files
-    let dst_fixtures = [
-        "berlin_dst_start_2026.txt",
-        "berlin_dst_end_2026.txt",
-        "newyork_dst_start_2026.txt",
-        "newyork_dst_end_2026.txt",
-    ];
+    let Commands::Bucket(parsed) = Cli::try_parse_from([
+        "tzbucket",
+        "bucket",
+        "-i",
+        "14d,week",
+        "--anchor",
+        "2026-01-05",
+        "--skew",
+        "-00:00:30",
+    ])
+    .unwrap()
+    .command
+    else {
+        panic!("expected bucket");
+    };
+    assert_eq!(
+        parsed.interval,
+        vec![IntervalArg::Days(14), IntervalArg::Interval(Interval::Week)]
+    );
+    assert_eq!(parsed.anchor, NaiveDate::from_ymd_opt(2026, 1, 5));
+    assert_eq!(parsed.skew, Some(Duration::seconds(-30)));
 
-    for fixture_name in &dst_fixtures {
-        let fixture_path = fixtures.join(fixture_name);
-        let stem = fixture_path.file_stem().unwrap().to_str().unwrap();
-        let golden_path = golden.join(format!("{stem}.json"));
+    let Commands::Range(parsed) = Cli::try_parse_from([
+        "tzbucket",
+        "range",
+        "--tz",
+        "Europe/Berlin",
+        "--start",
+        "2026-03-29T00:00:00+01:00",
+        "--end",
+        "2026-03-30T00:00:00+02:00",
+    ])
+    .unwrap()
+    .command
+    else {
+        panic!("expected range");
+    };
+    assert_eq!(
+        parsed.start,
+        RangeBound::Instant(Utc.with_ymd_and_hms(2026, 3, 28, 23, 0, 0).unwrap())
+    );
+    assert_eq!(parsed.end.to_string(), "2026-03-29T22:00:00Z");
 
-        // Determine timezone from fixture name
-        let tz = if stem.contains("berlin") {
-            "Europe/Berlin"
-        } else if stem.contains("newyork") {
-            "America/New_York"
-        } else {
-            "UTC"
-        };
+    let output = run_cli(&["bucket", "--interval", "hourly"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(
+        String::from_utf8(output.stderr)
+            .unwrap()
+            .contains("Invalid interval 'hourly'")
+    );
 
-        let output = Command::new(env!("CARGO_BIN_EXE_tzbucket"))
-            .args([
-                "bucket",
-                "--tz",
-                tz,
-                "--interval",
-                "day",
-                "--format",
-                "rfc3339",
-                "--output-format",
-                "json",
-                "--input",
-                fixture_path.to_str().unwrap(),
-            ])
-            .output()
-            .expect("Failed to execute tzbucket");
+    let output = run_cli(&[
+        "range",
+        "--tz",
+        "UTC",
+        "--start",
+        "someday",
+        "--end",
+        "2026-01-01T00:00:00Z",
+    ]);
+    assert_eq!(output.status.code(), Some(2));
+}
 
-        assert!(
-            output.status.success(),
-            "tzbucket failed for {}: {}",
-            stem,
-            String::from_utf8_lossy(&output.stderr)
-        );
+#[test]
+fn test_library_runners_write_cli_output() {
+    use chrono::{NaiveDate, TimeZone, Utc};
+    use tzbucket_cli::cli::{BucketArgs, ExplainArgs, RangeArgs};
+    use tzbucket_cli::error::OutputFormat;
+    use tzbucket_core::{FixedClock, Interval, Policy, TimestampFormat};
 
-        let actual = String::from_utf8(output.stdout).expect("Output is not valid UTF-8");
+    let berlin = chrono_tz::Europe::Berlin;
+    let clock = FixedClock(Utc.with_ymd_and_hms(2026, 4, 1, 0, 0, 0).unwrap());
+    let stdout = |args: &[&str]| String::from_utf8(run_cli(args).stdout).unwrap();
+    let mut out = Vec::new();
 
-        if update_golden() {
-            fs::create_dir_all(&golden).ok();
-            fs::write(&golden_path, &actual)
-                .unwrap_or_else(|e| panic!("Failed to write golden file {golden_path:?}: {e}"));
-            eprintln!("Updated golden file: {golden_path:?}");
-            continue;
-        }
+    let args = RangeArgs::default()
+        .with_tz(berlin)
+        .with_interval(Interval::Hour)
+        .with_start(Utc.with_ymd_and_hms(2026, 3, 29, 0, 0, 0).unwrap())
+        .with_end(Utc.with_ymd_and_hms(2026, 3, 29, 3, 0, 0).unwrap())
+        .with_output_format(OutputFormat::Json);
+    tzbucket_cli::run_range(args, &clock, &mut out).unwrap();
+    assert_json_eq(
+        &String::from_utf8(std::mem::take(&mut out)).unwrap(),
+        &stdout(&[
+            "range",
+            "--tz",
+            "Europe/Berlin",
+            "--interval",
+            "hour",
+            "--start",
+            "2026-03-29T00:00:00Z",
+            "--end",
+            "2026-03-29T03:00:00Z",
+        ]),
+    );
 
-        let expected = fs::read_to_string(&golden_path).unwrap_or_else(|e| {
-            panic!(
-                "Golden file {golden_path:?} not found: {e}\n\
-                 Hint: Run with UPDATE_GOLDEN=1 to generate golden files"
-            )
+    let input = fixture_dir().join("berlin_dst_start_2026.txt");
+    let args = BucketArgs::default()
+        .with_tz(berlin)
+        .with_format(TimestampFormat::Rfc3339)
+        .with_input(input.to_str().unwrap())
+        .with_output_format(OutputFormat::Text);
+    tzbucket_cli::run_bucket(args, &clock, &mut out).unwrap();
+    assert_eq!(
+        String::from_utf8(std::mem::take(&mut out)).unwrap(),
+        stdout(&[
+            "bucket",
+            "--tz",
+            "Europe/Berlin",
+            "--format",
+            "rfc3339",
+            "--input",
+            input.to_str().unwrap(),
+        ]),
+    );
+
+    let local = NaiveDate::from_ymd_opt(2026, 10, 25)
+        .unwrap()
+        .and_hms_opt(2, 30, 0)
+        .unwrap();
+    let args = ExplainArgs::default()
+        .with_tz(berlin)
+        .with_local(local)
+        .with_policy(Policy {
+            ambiguous: tzbucket_core::AmbiguousPolicy::First,
+            ..Policy::default()
         });
+    tzbucket_cli::run_explain(args, &mut out).unwrap();
+    assert_json_eq(
+        &String::from_utf8(out).unwrap(),
+        &stdout(&[
+            "explain",
+            "--tz",
+            "Europe/Berlin",
+            "--local",
+            "2026-10-25T02:30:00",
+            "--policy-ambiguous",
+            "first",
+        ]),
+    );
+}
 
-        assert_json_lines_eq(&actual, &expected);
-    }
+#[test]
+fn test_library_run_writes_to_given_writer() {
+    use clap::Parser;
+    use std::process::ExitCode;
+    use tzbucket_cli::cli::Cli;
+
+    let run = |args: &[&str]| {
+        let cli = Cli::try_parse_from(args).unwrap();
+        let mut out = Vec::new();
+        let code = tzbucket_cli::run(cli, &mut out);
+        (code, String::from_utf8(out).unwrap())
+    };
+
+    let (code, out) = run(&[
+        "tzbucket",
+        "range",
+        "--tz",
+        "Europe/Berlin",
+        "--interval",
+        "day",
+        "--start",
+        "2026-03-27T00:00:00Z",
+        "--end",
+        "2026-03-31T00:00:00Z",
+        "--output-format",
+        "json",
+    ]);
+    assert_eq!(code, ExitCode::SUCCESS);
+    let expected = fs::read_to_string(golden_dir().join("range_berlin_march_2026.json"))
+        .expect("Failed to read golden file");
+    assert_json_eq(&out, &expected);
+
+    // `--now-override` reaches the commands that read the clock.
+    let (code, out) = run(&[
+        "tzbucket",
+        "--now-override",
+        "2026-03-29T00:30:00Z",
+        "now",
+        "--tz",
+        "Europe/Berlin",
+        "--output-format",
+        "json",
+    ]);
+    assert_eq!(code, ExitCode::SUCCESS);
+    let now: serde_json::Value = serde_json::from_str(&out).unwrap();
+    assert_eq!(now["bucket"]["key"], "2026-03-29");
+
+    // A failed command writes nothing; the error goes to stderr.
+    let (code, out) = run(&[
+        "tzbucket",
+        "key",
+        "--tz",
+        "UTC",
+        "--output-format",
+        "yaml",
+        "2026-03",
+    ]);
+    assert_eq!(code, ExitCode::from(2));
+    assert!(out.is_empty());
+}
+
+#[cfg(feature = "humantime")]
+#[test]
+fn test_range_accepts_human_bounds() {
+    // Wednesday 2026-04-01, 10:00 in Berlin.
+    let output = run_cli(&[
+        "--now-override",
+        "2026-04-01T08:00:00Z",
+        "range",
+        "--tz",
+        "Europe/Berlin",
+        "--start",
+        "last monday",
+        "--end",
+        "tomorrow",
+        "--output-format",
+        "text",
+    ]);
+    assert!(
+        output.status.success(),
+        "CLI failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let keys: Vec<&str> = stdout
+        .lines()
+        .map(|line| line.split(':').next().unwrap())
+        .collect();
+    assert_eq!(keys, ["2026-03-30", "2026-03-31", "2026-04-01"]);
+
+    // The plan reports how each bound was read.
+    let output = run_cli(&[
+        "--now-override",
+        "2026-04-01T08:00:00Z",
+        "range",
+        "--tz",
+        "Europe/Berlin",
+        "--start",
+        "last monday",
+        "--end",
+        "2026-04-02T00:00:00+02:00",
+        "--dry-run",
+    ]);
+    let plan: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(plan["start_format"], "humantime");
+    assert_eq!(plan["end_format"], "rfc3339");
+    assert_eq!(plan["bucket_count"], 3);
+
+    // Strict input is still required to be well-formed.
+    let output = run_cli(&[
+        "range",
+        "--tz",
+        "Europe/Berlin",
+        "--start",
+        "someday",
+        "--end",
+        "tomorrow",
+    ]);
+    assert_eq!(output.status.code(), Some(2));
 }
@@ -0,0 +1,57 @@
+//! Row vs. batch vs. columnar bucketing throughput.
+//!
+//! Run with `cargo bench -p tzbucket-core --bench columnar`. Uses only `std`
+//! timing, so numbers are indicative; compare the columns of one run.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use chrono::DateTime;
+use tzbucket_core::{
+    BucketSpec, Interval, compute_bucket, compute_buckets, compute_buckets_columnar,
+};
+
+const ROWS: i64 = 1_000_000;
+
+fn main() {
+    let tz = chrono_tz::Europe::Berlin;
+    // One event every 30 seconds from 2026-03-01T00:00:00Z, about 12 days.
+    let epoch_ms: Vec<i64> = (0..ROWS).map(|i| 1_772_323_200_000 + i * 30_000).collect();
+    let instants: Vec<_> = epoch_ms
+        .iter()
+        .map(|&ms| DateTime::from_timestamp_millis(ms).unwrap())
+        .collect();
+
+    for interval in [Interval::Day, Interval::FixedSeconds(3600)] {
+        let row = time(|| {
+            for &ms in &epoch_ms {
+                let instant = DateTime::from_timestamp_millis(ms).unwrap();
+                black_box(compute_bucket(instant, tz, interval, None));
+            }
+        });
+        let batch = time(|| {
+            black_box(compute_buckets(&instants, &BucketSpec::new(tz, interval)));
+        });
+        let columnar = time(|| {
+            black_box(compute_buckets_columnar(&epoch_ms, tz, interval, None).unwrap());
+        });
+
+        println!(
+            "{:<10} row {:>8.1} ns/row   batch {:>8.1} ns/row   columnar {:>8.1} ns/row",
+            interval.to_string(),
+            per_row(row),
+            per_row(batch),
+            per_row(columnar)
+        );
+    }
+}
+
+fn time(f: impl FnOnce()) -> Duration {
+    let start = Instant::now();
+    f();
+    start.elapsed()
+}
+
+fn per_row(elapsed: Duration) -> f64 {
+    elapsed.as_nanos() as f64 / ROWS as f64
+}
@@ -0,0 +1,57 @@
+//! Timestamp parsing throughput.
+//!
+//! Run with `cargo bench -p tzbucket-core --bench parse`, once with and once
+//! with `--features fast-parse`. Uses only `std` timing, so numbers are
+//! indicative.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, SecondsFormat};
+use tzbucket_core::parse::{TimestampFormat, parse_timestamp};
+
+const ROWS: i64 = 10_000;
+const ROUNDS: i64 = 100;
+
+fn main() {
+    // One event every 30 seconds from 2026-03-01T00:00:00Z, parsed repeatedly
+    // so that the lines stay in cache and parsing itself is measured.
+    let epoch_ms: Vec<String> = (0..ROWS)
+        .map(|i| (1_772_323_200_000 + i * 30_000).to_string())
+        .collect();
+    let rfc3339: Vec<String> = (0..ROWS)
+        .map(|i| {
+            DateTime::from_timestamp_millis(1_772_323_200_000 + i * 30_000)
+                .unwrap()
+                .to_rfc3339_opts(SecondsFormat::Millis, true)
+        })
+        .collect();
+
+    for (format, lines) in [
+        (TimestampFormat::EpochMs, &epoch_ms),
+        (TimestampFormat::Rfc3339, &rfc3339),
+    ] {
+        let elapsed = time(|| {
+            for _ in 0..ROUNDS {
+                for line in lines {
+                    black_box(parse_timestamp(black_box(line), format).unwrap());
+                }
+            }
+        });
+        println!(
+            "{:<10} {:>8.1} ns/line",
+            format.to_string(),
+            per_row(elapsed)
+        );
+    }
+}
+
+fn time(f: impl FnOnce()) -> Duration {
+    let start = Instant::now();
+    f();
+    start.elapsed()
+}
+
+fn per_row(elapsed: Duration) -> f64 {
+    elapsed.as_nanos() as f64 / (ROWS * ROUNDS) as f64
+}
@@ -0,0 +1,71 @@
+//! Per-row bucketing cost in UTC, a fixed-offset zone and a DST zone.
+//!
+//! `UTC` and `Etc/GMT-5` take the fixed-offset fast path of
+//! `compute_bucket_typed`; `compute_bucket` adds the RFC3339 rendering of
+//! the boundaries, which is the same for every zone.
+//!
+//! Run with `cargo bench -p tzbucket-core --bench zones`. Uses only `std`
+//! timing, so numbers are indicative; compare the columns of one run.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use chrono::DateTime;
+use tzbucket_core::{Interval, compute_bucket, compute_bucket_typed};
+
+const ROWS: i64 = 1_000_000;
+
+fn main() {
+    // One event every 30 seconds from 2026-03-01T00:00:00Z, about 12 days.
+    let instants: Vec<_> = (0..ROWS)
+        .map(|i| DateTime::from_timestamp(1_772_323_200 + i * 30, 0).unwrap())
+        .collect();
+    let zones = [
+        chrono_tz::UTC,
+        chrono_tz::Etc::GMTMinus5,
+        chrono_tz::Europe::Berlin,
+    ];
+
+    for interval in [
+        Interval::Hour,
+        Interval::Day,
+        Interval::Week,
+        Interval::Month,
+    ] {
+        for (label, typed) in [("typed", true), ("bucket", false)] {
+            let per_zone: Vec<f64> = zones
+                .iter()
+                .map(|&tz| {
+                    per_row(time(|| {
+                        for &instant in &instants {
+                            if typed {
+                                black_box(compute_bucket_typed(instant, tz, interval, None));
+                            } else {
+                                black_box(compute_bucket(instant, tz, interval, None));
+                            }
+                        }
+                    }))
+                })
+                .collect();
+
+            println!(
+                "{:<6} {:<6} UTC {:>7.1} ns/row   Etc/GMT-5 {:>7.1} ns/row   Europe/Berlin {:>7.1} ns/row",
+                interval.to_string(),
+                label,
+                per_zone[0],
+                per_zone[1],
+                per_zone[2]
+            );
+        }
+    }
+}
+
+fn time(f: impl FnOnce()) -> Duration {
+    let start = Instant::now();
+    f();
+    start.elapsed()
+}
+
+fn per_row(elapsed: Duration) -> f64 {
+    elapsed.as_nanos() as f64 / ROWS as f64
+}
@@ -0,0 +1,299 @@
+//! Bucket memoization for servers (feature `cache`).
+//!
+//! A service bucketing a stream of events in a handful of zones computes the
+//! same day, week and month buckets over and over. [`BucketCache`] memoizes
+//! [`compute_bucket`](crate::compute_bucket) by zone, interval, week start
+//! and local date, so a repeated lookup costs one UTC-to-local conversion and
+//! a clone. Sub-day intervals (minutes, hours, fixed windows) are computed
+//! without the cache.
+//!
+//! [`BucketCache::global`] is the process-wide instance; set its capacity
+//! with [`BucketCache::init_global`] before first use.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, MutexGuard, OnceLock, PoisonError};
+
+use chrono::{DateTime, NaiveDate, Utc};
+use chrono_tz::Tz;
+
+use crate::compute::compute_typed_keyed_bucket;
+use crate::metrics::{self, MetricsSink, NoopMetrics};
+use crate::models::{Bucket, Interval, WeekKeyFormat, WeekStart};
+use crate::tz::utc_to_local;
+
+/// Capacity of [`BucketCache::global`] unless set by
+/// [`BucketCache::init_global`]: a few years of days for a few zones.
+pub const DEFAULT_CAPACITY: usize = 10_000;
+
+static GLOBAL: OnceLock<BucketCache> = OnceLock::new();
+
+/// A thread-safe, bounded cache of computed buckets.
+///
+/// Once full, the oldest entry is evicted first. A capacity of 0 disables
+/// caching; every lookup then counts as a miss.
+///
+/// # Examples
+///
+/// ```
+/// use chrono::{TimeZone, Utc};
+/// use tzbucket_core::cache::BucketCache;
+/// use tzbucket_core::{Interval, compute_bucket};
+///
+/// let cache = BucketCache::new(100);
+/// let tz = chrono_tz::Europe::Berlin;
+/// let morning = Utc.with_ymd_and_hms(2026, 3, 29, 6, 0, 0).unwrap();
+/// let evening = Utc.with_ymd_and_hms(2026, 3, 29, 18, 0, 0).unwrap();
+///
+/// let bucket = cache.compute_bucket(morning, tz, Interval::Day, None);
+/// assert_eq!(bucket.key, compute_bucket(morning, tz, Interval::Day, None).key);
+/// assert_eq!(cache.compute_bucket(evening, tz, Interval::Day, None).key, "2026-03-29");
+///
+/// let stats = cache.stats();
+/// assert_eq!((stats.hits, stats.misses), (1, 1));
+/// assert_eq!(stats.hit_rate(), 0.5);
+/// ```
+#[derive(Debug)]
+pub struct BucketCache {
+    capacity: usize,
+    entries: Mutex<Entries>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+type CacheKey = (Tz, Interval, WeekStart, NaiveDate);
+
+#[derive(Debug, Default)]
+struct Entries {
+    buckets: HashMap<CacheKey, CachedBucket>,
+    /// Keys in insertion order, oldest first.
+    order: VecDeque<CacheKey>,
+}
+
+#[derive(Debug)]
+struct CachedBucket {
+    start_utc: DateTime<Utc>,
+    end_utc: DateTime<Utc>,
+    bucket: Bucket,
+}
+
+/// Counters of a [`BucketCache`], see [`BucketCache::stats`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CacheStats {
+    /// Lookups answered from the cache.
+    pub hits: u64,
+    /// Lookups that computed the bucket, including uncached intervals.
+    pub misses: u64,
+    /// Buckets currently held.
+    pub entries: usize,
+    /// Maximum number of buckets held.
+    pub capacity: usize,
+}
+
+impl CacheStats {
+    /// Share of lookups answered from the cache, 0.0 before the first lookup.
+    pub fn hit_rate(&self) -> f64 {
+        match self.hits + self.misses {
+            0 => 0.0,
+            total => self.hits as f64 / total as f64,
+        }
+    }
+}
+
+impl BucketCache {
+    /// An empty cache holding at most `capacity` buckets.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: Mutex::new(Entries::default()),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// The process-wide cache, created with [`DEFAULT_CAPACITY`] on first
+    /// use unless [`BucketCache::init_global`] came first.
+    pub fn global() -> &'static BucketCache {
+        GLOBAL.get_or_init(|| BucketCache::new(DEFAULT_CAPACITY))
+    }
+
+    /// Create the process-wide cache with `capacity`. Returns `false`, leaving
+    /// the capacity unchanged, if it already exists.
+    pub fn init_global(capacity: usize) -> bool {
+        GLOBAL.set(BucketCache::new(capacity)).is_ok()
+    }
+
+    /// [`compute_bucket`](crate::compute_bucket), answered from the cache
+    /// when the bucket of `instant`'s local date is held.
+    pub fn compute_bucket(
+        &self,
+        instant: DateTime<Utc>,
+        tz: Tz,
+        interval: Interval,
+        week_start: Option<WeekStart>,
+    ) -> Bucket {
+        self.compute_bucket_with_metrics(instant, tz, interval, week_start, &NoopMetrics)
+    }
+
+    /// Like [`BucketCache::compute_bucket`], counting
+    /// [`metrics::CACHE_HITS`] and [`metrics::CACHE_MISSES`] into `metrics`.
+    /// Misses also report the metrics of
+    /// [`compute_bucket_with_metrics`](crate::compute_bucket_with_metrics).
+    pub fn compute_bucket_with_metrics(
+        &self,
+        instant: DateTime<Utc>,
+        tz: Tz,
+        interval: Interval,
+        week_start: Option<WeekStart>,
+        metrics: &dyn MetricsSink,
+    ) -> Bucket {
+        let week_start = week_start.unwrap_or_default();
+        let key = (
+            tz,
+            interval,
+            week_start,
+            utc_to_local(instant, tz).date_naive(),
+        );
+        let cacheable = self.capacity > 0 && !is_sub_day(interval);
+
+        if cacheable {
+            // A bucket whose boundary fell into a DST gap or overlap may not
+            // contain every instant of its local date; compute those anew.
+            if let Some(cached) = self.lock().buckets.get(&key)
+                && (cached.start_utc..cached.end_utc).contains(&instant)
+            {
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                metrics.counter(metrics::CACHE_HITS, 1);
+                return cached.bucket.clone();
+            }
+        }
+
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        metrics.counter(metrics::CACHE_MISSES, 1);
+        let typed = compute_typed_keyed_bucket(
+            instant,
+            tz,
+            interval,
+            Some(week_start),
+            WeekKeyFormat::StartDate,
+            metrics,
+        );
+        let (start_utc, end_utc) = (typed.start_utc, typed.end_utc);
+        let bucket = Bucket::from(typed);
+        if cacheable && (start_utc..end_utc).contains(&instant) {
+            self.insert(
+                key,
+                CachedBucket {
+                    start_utc,
+                    end_utc,
+                    bucket: bucket.clone(),
+                },
+            );
+        }
+        bucket
+    }
+
+    /// Hit and miss counts since creation, and the current size.
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            entries: self.lock().buckets.len(),
+            capacity: self.capacity,
+        }
+    }
+
+    /// Drop all cached buckets. The hit and miss counters keep counting.
+    pub fn clear(&self) {
+        let mut entries = self.lock();
+        entries.buckets.clear();
+        entries.order.clear();
+    }
+
+    fn insert(&self, key: CacheKey, bucket: CachedBucket) {
+        let mut entries = self.lock();
+        if entries.buckets.insert(key, bucket).is_some() {
+            return;
+        }
+        entries.order.push_back(key);
+        while entries.order.len() > self.capacity {
+            if let Some(oldest) = entries.order.pop_front() {
+                entries.buckets.remove(&oldest);
+            }
+        }
+    }
+
+    /// The entries, even if another thread panicked while holding them: the
+    /// map stays consistent between statements.
+    fn lock(&self) -> MutexGuard<'_, Entries> {
+        self.entries.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Intervals whose bucket depends on more than the local date.
+fn is_sub_day(interval: Interval) -> bool {
+    matches!(
+        interval,
+        Interval::Minutes(_) | Interval::Hour | Interval::Hours(_) | Interval::FixedSeconds(_)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    use crate::compute::compute_bucket;
+
+    #[test]
+    fn evicts_oldest_beyond_capacity() {
+        let cache = BucketCache::new(2);
+        let tz = chrono_tz::Europe::Berlin;
+        let day = |d| Utc.with_ymd_and_hms(2026, 3, d, 12, 0, 0).unwrap();
+
+        for d in [27, 28, 29] {
+            let bucket = cache.compute_bucket(day(d), tz, Interval::Day, None);
+            assert_eq!(
+                bucket.key,
+                compute_bucket(day(d), tz, Interval::Day, None).key
+            );
+        }
+        assert_eq!(cache.stats().entries, 2);
+
+        // The 27th was evicted, the 29th is still held.
+        cache.compute_bucket(day(27), tz, Interval::Day, None);
+        cache.compute_bucket(day(29), tz, Interval::Day, None);
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses), (1, 4));
+    }
+
+    #[test]
+    fn sub_day_intervals_bypass_the_cache() {
+        let cache = BucketCache::new(10);
+        let instant = Utc.with_ymd_and_hms(2026, 3, 29, 0, 30, 0).unwrap();
+        let tz = chrono_tz::Europe::Berlin;
+
+        let expected = compute_bucket(instant, tz, Interval::Hour, None);
+        for _ in 0..2 {
+            let bucket = cache.compute_bucket(instant, tz, Interval::Hour, None);
+            assert_eq!(
+                (bucket.key, bucket.end_utc),
+                (expected.key.clone(), expected.end_utc.clone())
+            );
+        }
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses, stats.entries), (0, 2, 0));
+    }
+
+    #[test]
+    fn week_start_is_part_of_the_key() {
+        let cache = BucketCache::new(10);
+        let instant = Utc.with_ymd_and_hms(2026, 3, 29, 12, 0, 0).unwrap();
+        let tz = chrono_tz::UTC;
+
+        let monday = cache.compute_bucket(instant, tz, Interval::Week, Some(WeekStart::Monday));
+        let sunday = cache.compute_bucket(instant, tz, Interval::Week, Some(WeekStart::Sunday));
+        assert_eq!(monday.key, "2026-03-23");
+        assert_eq!(sunday.key, "2026-03-29");
+    }
+}
@@ -0,0 +1,117 @@
+//! Calendar month arithmetic.
+//!
+//! Days 29-31 do not exist in every month. This module resolves such days
+//! under an explicit [`MonthClamp`] policy, shared by month addition and the
+//! anchors of [`Interval::TrailingMonth`](crate::models::Interval::TrailingMonth)
+//! buckets.
+
+use chrono::{Datelike, NaiveDate};
+
+use crate::models::MonthClamp;
+
+/// The given day in a month, resolved under `clamp` if the month is shorter.
+///
+/// Returns the date and whether the day had to be clamped.
+pub(crate) fn day_in_month(
+    year: i32,
+    month: u32,
+    day: u32,
+    clamp: MonthClamp,
+) -> (NaiveDate, bool) {
+    if let Some(date) = NaiveDate::from_ymd_opt(year, month, day) {
+        return (date, false);
+    }
+
+    let last = (28..=31)
+        .rev()
+        .find_map(|d| NaiveDate::from_ymd_opt(year, month, d))
+        .expect("every month has at least 28 days");
+    let date = match clamp {
+        MonthClamp::PreviousValidDay => last,
+        MonthClamp::RollForward => last.succ_opt().expect("date within chrono's range"),
+    };
+    (date, true)
+}
+
+/// Add `months` calendar months to `date`, keeping the day of month.
+///
+/// If the day does not exist in the target month, `clamp` decides the result.
+/// Returns the date and the policy applied, or `None` if the day existed.
+///
+/// # Examples
+///
+/// ```
+/// use chrono::NaiveDate;
+/// use tzbucket_core::calendar::add_months;
+/// use tzbucket_core::models::MonthClamp;
+///
+/// let jan31 = NaiveDate::from_ymd_opt(2026, 1, 31).unwrap();
+///
+/// let (date, applied) = add_months(jan31, 1, MonthClamp::PreviousValidDay);
+/// assert_eq!(date, NaiveDate::from_ymd_opt(2026, 2, 28).unwrap());
+/// assert_eq!(applied, Some(MonthClamp::PreviousValidDay));
+///
+/// let (date, _) = add_months(jan31, 1, MonthClamp::RollForward);
+/// assert_eq!(date, NaiveDate::from_ymd_opt(2026, 3, 1).unwrap());
+/// ```
+pub fn add_months(
+    date: NaiveDate,
+    months: i32,
+    clamp: MonthClamp,
+) -> (NaiveDate, Option<MonthClamp>) {
+    let total = date.year() * 12 + date.month0() as i32 + months;
+    let year = total.div_euclid(12);
+    let month = total.rem_euclid(12) as u32 + 1;
+
+    let (result, clamped) = day_in_month(year, month, date.day(), clamp);
+    (result, clamped.then_some(clamp))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn add_months_keeps_existing_days() {
+        assert_eq!(
+            add_months(date(2026, 1, 15), 1, MonthClamp::RollForward),
+            (date(2026, 2, 15), None)
+        );
+        assert_eq!(
+            add_months(date(2026, 1, 31), -2, MonthClamp::PreviousValidDay),
+            (date(2025, 11, 30), Some(MonthClamp::PreviousValidDay))
+        );
+        assert_eq!(
+            add_months(date(2026, 11, 30), 14, MonthClamp::PreviousValidDay),
+            (date(2028, 1, 30), None)
+        );
+    }
+
+    #[test]
+    fn add_months_clamps_in_leap_years() {
+        assert_eq!(
+            add_months(date(2028, 1, 30), 1, MonthClamp::PreviousValidDay),
+            (date(2028, 2, 29), Some(MonthClamp::PreviousValidDay))
+        );
+        assert_eq!(
+            add_months(date(2028, 1, 30), 1, MonthClamp::RollForward),
+            (date(2028, 3, 1), Some(MonthClamp::RollForward))
+        );
+    }
+
+    #[test]
+    fn roll_forward_lands_on_first_of_next_month() {
+        assert_eq!(
+            day_in_month(2026, 11, 31, MonthClamp::RollForward),
+            (date(2026, 12, 1), true)
+        );
+        assert_eq!(
+            day_in_month(2026, 12, 31, MonthClamp::RollForward),
+            (date(2026, 12, 31), false)
+        );
+    }
+}
@@ -0,0 +1,113 @@
+//! Clock abstraction.
+//!
+//! Everything in tzbucket that needs "now" takes a [`Clock`] instead of
+//! calling [`Utc::now`] directly, so applications and tests can pin or step
+//! the current time.
+
+use std::sync::Mutex;
+
+use chrono::{DateTime, Duration, Utc};
+
+/// Source of the current instant.
+pub trait Clock {
+    /// The current instant in UTC.
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// The system wall clock.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// A clock that always returns the same instant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FixedClock(pub DateTime<Utc>);
+
+impl Clock for FixedClock {
+    fn now(&self) -> DateTime<Utc> {
+        self.0
+    }
+}
+
+/// A clock that only moves when told to.
+///
+/// ```
+/// use chrono::{Duration, TimeZone, Utc};
+/// use tzbucket_core::clock::{Clock, MockClock};
+///
+/// let clock = MockClock::new(Utc.with_ymd_and_hms(2026, 3, 29, 0, 0, 0).unwrap());
+/// clock.advance(Duration::hours(1));
+/// assert_eq!(clock.now(), Utc.with_ymd_and_hms(2026, 3, 29, 1, 0, 0).unwrap());
+/// ```
+#[derive(Debug)]
+pub struct MockClock {
+    now: Mutex<DateTime<Utc>>,
+}
+
+impl MockClock {
+    /// Create a clock starting at `now`.
+    pub fn new(now: DateTime<Utc>) -> Self {
+        Self {
+            now: Mutex::new(now),
+        }
+    }
+
+    /// Jump to `now`.
+    pub fn set(&self, now: DateTime<Utc>) {
+        *self.lock() = now;
+    }
+
+    /// Move the clock forward (or backward, for negative durations).
+    pub fn advance(&self, by: Duration) {
+        *self.lock() += by;
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, DateTime<Utc>> {
+        // The guarded value is a plain timestamp; a poisoned lock cannot
+        // leave it half-written.
+        self.now.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> DateTime<Utc> {
+        *self.lock()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn fixed_clock_is_constant() {
+        let instant = Utc.with_ymd_and_hms(2026, 10, 25, 0, 30, 0).unwrap();
+        let clock = FixedClock(instant);
+
+        assert_eq!(clock.now(), instant);
+        assert_eq!(clock.now(), instant);
+    }
+
+    #[test]
+    fn mock_clock_set_and_advance() {
+        let clock = MockClock::new(Utc.with_ymd_and_hms(2026, 3, 29, 0, 0, 0).unwrap());
+
+        clock.advance(Duration::minutes(90));
+        assert_eq!(
+            clock.now(),
+            Utc.with_ymd_and_hms(2026, 3, 29, 1, 30, 0).unwrap()
+        );
+
+        clock.set(Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap());
+        assert_eq!(
+            clock.now(),
+            Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap()
+        );
+    }
+}
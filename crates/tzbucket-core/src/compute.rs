@@ -4,13 +4,30 @@
 //! correctly handles DST transitions by computing boundaries in local
 //! time and converting each boundary independently to UTC.
 
-use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use std::collections::BTreeMap;
+use std::convert::Infallible;
+use std::ops::Range;
+
+use chrono::{
+    DateTime, Datelike, Duration, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, Offset,
+    TimeZone, Timelike, Utc,
+};
 use chrono_tz::Tz;
 
-use crate::models::{Bucket, BucketResult, InputTimestamp, Interval, WeekStart};
+use crate::calendar::{add_months, day_in_month};
+use crate::clock::Clock;
+use crate::error::TzBucketError;
+use crate::metrics::{self, MetricsSink, NoopMetrics};
+use crate::models::{
+    AmbiguousPolicy, Bucket, BucketColumns, BucketKey, BucketResult, BucketResultRef, BucketSpec,
+    InputTimestamp, InputTimestampRef, Interval, MonthClamp, NonexistentPolicy, Policy,
+    TypedBucket, WeekKeyFormat, WeekScheme, WeekStart, format_key,
+};
 use crate::parse::{TimestampFormat, parse_timestamp};
+use crate::resolve::resolve_local;
 use crate::tz::{
-    format_rfc3339, format_rfc3339_utc, local_midnight_to_utc, parse_tz, utc_to_local,
+    LocalKind, fixed_offset, format_rfc3339, format_rfc3339_utc, local_to_utc_classified,
+    offset_transitions, parse_tz, utc_to_local,
 };
 
 /// Compute a time bucket for a given UTC instant.
@@ -55,51 +72,1176 @@ pub fn compute_bucket(
     interval: Interval,
     week_start: Option<WeekStart>,
 ) -> Bucket {
-    // Convert to local time
-    let local = utc_to_local(instant, tz);
-
-    // Compute bucket boundaries based on interval
-    let (start_local_date, end_local_date, key) = match interval {
-        Interval::Day => compute_day_bucket(&local),
-        Interval::Week => compute_week_bucket(&local, week_start.unwrap_or_default()),
-        Interval::Month => compute_month_bucket(&local),
-    };
+    compute_bucket_with_metrics(instant, tz, interval, week_start, &NoopMetrics)
+}
+
+/// Compute a time bucket, reporting metrics into `metrics`.
+///
+/// Behaves exactly like [`compute_bucket`]. Additionally records one
+/// [`metrics::BUCKETS_COMPUTED`] increment, the bucket's UTC duration under
+/// [`metrics::BUCKET_DURATION_SECONDS`], and an increment of
+/// [`metrics::AMBIGUOUS_BOUNDARIES`] or [`metrics::NONEXISTENT_BOUNDARIES`]
+/// for every boundary that fell on a DST edge case.
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "trace", skip(tz, metrics), fields(tz = %tz))
+)]
+pub fn compute_bucket_with_metrics(
+    instant: DateTime<Utc>,
+    tz: Tz,
+    interval: Interval,
+    week_start: Option<WeekStart>,
+    metrics: &dyn MetricsSink,
+) -> Bucket {
+    compute_keyed_bucket(
+        instant,
+        tz,
+        interval,
+        week_start,
+        WeekKeyFormat::StartDate,
+        metrics,
+    )
+}
+
+/// Compute a time bucket, writing week keys in `week_key` format.
+///
+/// Behaves exactly like [`compute_bucket`] except for the key of
+/// [`Interval::Week`] buckets; the boundaries do not depend on the key format.
+///
+/// # Examples
+///
+/// ```
+/// use chrono::{TimeZone, Utc};
+/// use tzbucket_core::compute::compute_bucket_with_week_key;
+/// use tzbucket_core::models::{Interval, WeekKeyFormat};
+///
+/// // Tuesday 2024-12-31 lies in the first ISO week of 2025.
+/// let instant = Utc.with_ymd_and_hms(2024, 12, 31, 12, 0, 0).single().unwrap();
+/// let bucket = compute_bucket_with_week_key(
+///     instant,
+///     chrono_tz::Europe::Berlin,
+///     Interval::Week,
+///     None,
+///     WeekKeyFormat::Iso,
+/// );
+///
+/// assert_eq!(bucket.key, "2025-W01");
+/// assert_eq!(bucket.start_local, "2024-12-30T00:00:00+01:00");
+/// ```
+pub fn compute_bucket_with_week_key(
+    instant: DateTime<Utc>,
+    tz: Tz,
+    interval: Interval,
+    week_start: Option<WeekStart>,
+    week_key: WeekKeyFormat,
+) -> Bucket {
+    compute_keyed_bucket(instant, tz, interval, week_start, week_key, &NoopMetrics)
+}
+
+/// Compute a time bucket with week boundaries and keys from `scheme`.
+///
+/// [`Interval::Week`] buckets start on the scheme's week start day and are
+/// keyed `YYYY-Www` in the scheme's numbering; other intervals are computed
+/// as by [`compute_bucket`].
+///
+/// # Examples
+///
+/// ```
+/// use chrono::{TimeZone, Utc};
+/// use tzbucket_core::{Interval, WeekScheme, compute_bucket_with_week_scheme};
+///
+/// // Saturday 2026-01-03 closes MMWR week 53 of 2025 but lies in ISO week 1.
+/// let instant = Utc.with_ymd_and_hms(2026, 1, 3, 12, 0, 0).unwrap();
+/// let tz = chrono_tz::America::New_York;
+///
+/// let mmwr = compute_bucket_with_week_scheme(instant, tz, Interval::Week, WeekScheme::Mmwr);
+/// assert_eq!(mmwr.key, "2025-W53");
+/// assert_eq!(mmwr.start_local, "2025-12-28T00:00:00-05:00");
+///
+/// let iso = compute_bucket_with_week_scheme(instant, tz, Interval::Week, WeekScheme::Iso);
+/// assert_eq!(iso.key, "2026-W01");
+/// assert_eq!(iso.start_local, "2025-12-29T00:00:00-05:00");
+/// ```
+pub fn compute_bucket_with_week_scheme(
+    instant: DateTime<Utc>,
+    tz: Tz,
+    interval: Interval,
+    scheme: WeekScheme,
+) -> Bucket {
+    compute_bucket_with_week_key(
+        instant,
+        tz,
+        interval,
+        Some(scheme.week_start()),
+        scheme.week_key(),
+    )
+}
+
+fn compute_keyed_bucket(
+    instant: DateTime<Utc>,
+    tz: Tz,
+    interval: Interval,
+    week_start: Option<WeekStart>,
+    week_key: WeekKeyFormat,
+    metrics: &dyn MetricsSink,
+) -> Bucket {
+    compute_typed_keyed_bucket(instant, tz, interval, week_start, week_key, metrics).into()
+}
+
+/// Compute a time bucket with chrono boundaries.
+///
+/// Behaves exactly like [`compute_bucket`], returning the boundaries as
+/// [`DateTime`]s so they need not be parsed back from RFC3339.
+/// `Bucket::from` gives the [`compute_bucket`] result.
+///
+/// # Examples
+///
+/// ```
+/// use chrono::{Duration, TimeZone, Utc};
+/// use tzbucket_core::{Bucket, Interval, compute_bucket, compute_bucket_typed};
+///
+/// let instant = Utc.with_ymd_and_hms(2026, 3, 29, 12, 0, 0).unwrap();
+/// let tz = chrono_tz::Europe::Berlin;
+/// let bucket = compute_bucket_typed(instant, tz, Interval::Day, None);
+///
+/// assert_eq!(bucket.start_utc, Utc.with_ymd_and_hms(2026, 3, 28, 23, 0, 0).unwrap());
+/// assert_eq!(bucket.duration(), Duration::hours(23));
+/// assert_eq!(
+///     Bucket::from(bucket).end_local,
+///     compute_bucket(instant, tz, Interval::Day, None).end_local
+/// );
+/// ```
+pub fn compute_bucket_typed(
+    instant: DateTime<Utc>,
+    tz: Tz,
+    interval: Interval,
+    week_start: Option<WeekStart>,
+) -> TypedBucket {
+    compute_typed_keyed_bucket(
+        instant,
+        tz,
+        interval,
+        week_start,
+        WeekKeyFormat::StartDate,
+        &NoopMetrics,
+    )
+}
+
+/// The start of the bucket containing `instant`, like SQL
+/// `date_trunc(interval, instant AT TIME ZONE tz)` converted back to UTC.
+///
+/// # Examples
+///
+/// ```
+/// use chrono::{TimeZone, Utc};
+/// use tzbucket_core::{Interval, floor_to_bucket};
+///
+/// let instant = Utc.with_ymd_and_hms(2026, 3, 29, 12, 0, 0).unwrap();
+/// let floor = floor_to_bucket(instant, chrono_tz::Europe::Berlin, Interval::Day, None);
+/// assert_eq!(floor, Utc.with_ymd_and_hms(2026, 3, 28, 23, 0, 0).unwrap());
+/// ```
+pub fn floor_to_bucket(
+    instant: DateTime<Utc>,
+    tz: Tz,
+    interval: Interval,
+    week_start: Option<WeekStart>,
+) -> DateTime<Utc> {
+    compute_bucket_typed(instant, tz, interval, week_start).start_utc
+}
+
+/// The first bucket boundary at or after `instant`: `instant` itself if it
+/// starts a bucket, otherwise the end of its bucket.
+///
+/// # Examples
+///
+/// ```
+/// use chrono::{TimeZone, Utc};
+/// use tzbucket_core::{Interval, ceil_to_bucket};
+///
+/// let tz = chrono_tz::Europe::Berlin;
+/// let instant = Utc.with_ymd_and_hms(2026, 3, 29, 12, 0, 0).unwrap();
+/// // The 23-hour day ends at local midnight, 22:00 UTC.
+/// let ceil = ceil_to_bucket(instant, tz, Interval::Day, None);
+/// assert_eq!(ceil, Utc.with_ymd_and_hms(2026, 3, 29, 22, 0, 0).unwrap());
+/// assert_eq!(ceil_to_bucket(ceil, tz, Interval::Day, None), ceil);
+/// ```
+pub fn ceil_to_bucket(
+    instant: DateTime<Utc>,
+    tz: Tz,
+    interval: Interval,
+    week_start: Option<WeekStart>,
+) -> DateTime<Utc> {
+    let bucket = compute_bucket_typed(instant, tz, interval, week_start);
+    if bucket.start_utc == instant {
+        instant
+    } else {
+        bucket.end_utc
+    }
+}
+
+/// The bucket boundary nearest to `instant`, measured in elapsed time; an
+/// instant exactly halfway rounds up to the end of its bucket.
+///
+/// Halfway through a 25-hour fall-back day is 11:30 local time, not noon;
+/// on a 23-hour spring-forward day it is 12:30.
+///
+/// # Examples
+///
+/// ```
+/// use chrono::{TimeZone, Utc};
+/// use tzbucket_core::{Interval, round_to_bucket};
+///
+/// let tz = chrono_tz::Europe::Berlin;
+/// let morning = Utc.with_ymd_and_hms(2026, 3, 29, 9, 0, 0).unwrap();
+/// let evening = Utc.with_ymd_and_hms(2026, 3, 29, 11, 0, 0).unwrap();
+/// assert_eq!(
+///     round_to_bucket(morning, tz, Interval::Day, None),
+///     Utc.with_ymd_and_hms(2026, 3, 28, 23, 0, 0).unwrap()
+/// );
+/// assert_eq!(
+///     round_to_bucket(evening, tz, Interval::Day, None),
+///     Utc.with_ymd_and_hms(2026, 3, 29, 22, 0, 0).unwrap()
+/// );
+/// ```
+pub fn round_to_bucket(
+    instant: DateTime<Utc>,
+    tz: Tz,
+    interval: Interval,
+    week_start: Option<WeekStart>,
+) -> DateTime<Utc> {
+    let bucket = compute_bucket_typed(instant, tz, interval, week_start);
+    if instant - bucket.start_utc < bucket.end_utc - instant {
+        bucket.start_utc
+    } else {
+        bucket.end_utc
+    }
+}
+
+pub(crate) fn compute_typed_keyed_bucket(
+    instant: DateTime<Utc>,
+    tz: Tz,
+    interval: Interval,
+    week_start: Option<WeekStart>,
+    week_key: WeekKeyFormat,
+    metrics: &dyn MetricsSink,
+) -> TypedBucket {
+    if let Interval::FixedSeconds(seconds) = interval {
+        return compute_fixed_bucket(instant, tz, seconds, metrics);
+    }
+    if let Interval::Minutes(minutes) = interval {
+        return compute_minutes_bucket(instant, tz, minutes, metrics);
+    }
+    if let Some(offset) = fixed_offset(tz) {
+        return compute_fixed_offset_bucket(
+            instant, tz, offset, interval, week_start, week_key, metrics,
+        );
+    }
+
+    let Ok(ResolvedBounds {
+        start_local,
+        end_local,
+        key,
+        start_utc,
+        end_utc,
+        start_kind,
+        end_kind,
+    }) = containing_bounds(
+        instant,
+        tz,
+        interval,
+        week_start.unwrap_or_default(),
+        week_key,
+        |local| Ok::<_, Infallible>(boundary_to_utc(local, tz, interval)),
+    );
 
-    // Convert boundaries to UTC (independently, to handle DST correctly)
-    let start_utc = local_midnight_to_utc(start_local_date, tz);
-    let end_utc = local_midnight_to_utc(end_local_date, tz);
+    record_boundary(metrics, start_kind);
+    record_boundary(metrics, end_kind);
+    metrics.counter(metrics::BUCKETS_COMPUTED, 1);
+    metrics.histogram(
+        metrics::BUCKET_DURATION_SECONDS,
+        (end_utc - start_utc).num_seconds() as f64,
+    );
 
-    // Format local boundaries from the resolved UTC instants.
+    // Local boundaries come from the resolved UTC instants.
     // This avoids panicking in zones where local midnight can be nonexistent.
-    let start_local_dt = start_utc.with_timezone(&tz);
-    let end_local_dt = end_utc.with_timezone(&tz);
+    TypedBucket {
+        key,
+        start_local: start_utc.with_timezone(&tz),
+        end_local: end_utc.with_timezone(&tz),
+        start_utc,
+        end_utc,
+        month_clamp: applied_month_clamp(interval, start_local.date(), end_local.date()),
+    }
+}
+
+/// Compute a calendar bucket whose days start at `day_offset` local time
+/// instead of midnight, such as an operational day running 06:00 to 06:00.
+///
+/// An instant belongs to the business date of its local time minus
+/// `day_offset` (05:59 still counts to the previous day), and the bucket of
+/// `interval` is chosen from that date as usual; day, week, month and longer
+/// buckets are keyed by their dates. Both boundaries are `day_offset` on the
+/// bucket's first date and on the first date of the next bucket, resolved
+/// with `policy` when a DST transition skips or repeats that wall-clock
+/// time.
+///
+/// # Errors
+///
+/// Returns [`TzBucketError::ParseError`] for intervals that are not made of
+/// whole days ([`Interval::Minutes`], [`Interval::Hour`], [`Interval::Hours`]
+/// and [`Interval::FixedSeconds`]), and [`TzBucketError::NonexistentTime`]
+/// or [`TzBucketError::AmbiguousTime`] when a boundary needs a policy
+/// decision and the corresponding policy is `Error`.
+///
+/// # Examples
+///
+/// ```
+/// use chrono::{NaiveTime, TimeZone, Utc};
+/// use tzbucket_core::compute::compute_bucket_with_day_offset;
+/// use tzbucket_core::models::{Interval, Policy};
+///
+/// // 05:30 in Berlin on March 29 still belongs to the operational day of
+/// // March 28, which loses an hour to the spring forward at 02:00.
+/// let instant = Utc.with_ymd_and_hms(2026, 3, 29, 3, 30, 0).unwrap();
+/// let six = NaiveTime::from_hms_opt(6, 0, 0).unwrap();
+/// let bucket = compute_bucket_with_day_offset(
+///     instant,
+///     chrono_tz::Europe::Berlin,
+///     Interval::Day,
+///     None,
+///     six,
+///     Policy::default(),
+/// )
+/// .unwrap();
+///
+/// assert_eq!(bucket.key, "2026-03-28");
+/// assert_eq!(bucket.start_local, "2026-03-28T06:00:00+01:00");
+/// assert_eq!(bucket.end_local, "2026-03-29T06:00:00+02:00");
+/// ```
+pub fn compute_bucket_with_day_offset(
+    instant: DateTime<Utc>,
+    tz: Tz,
+    interval: Interval,
+    week_start: Option<WeekStart>,
+    day_offset: NaiveTime,
+    policy: Policy,
+) -> crate::error::Result<Bucket> {
+    let week_start = week_start.unwrap_or_default();
+    let offset = day_offset - NaiveTime::MIN;
+    let business_date = (utc_to_local(instant, tz).naive_local() - offset).date();
+
+    let bounds = |date: NaiveDate| -> crate::error::Result<_> {
+        let (start, end, key) = bucket_dates(date, interval, week_start).ok_or_else(|| {
+            TzBucketError::ParseError(format!(
+                "a day offset needs an interval of whole days, not '{}'",
+                interval
+            ))
+        })?;
+        let start_utc = resolve_local(start.and_time(day_offset), tz, policy)?.utc();
+        let end_utc = resolve_local(end.and_time(day_offset), tz, policy)?.utc();
+        Ok((start, end, key, start_utc, end_utc))
+    };
 
-    Bucket {
+    // A boundary resolved to the other pass of a repeated time can leave the
+    // instant just outside the bucket of its business date.
+    let mut bucket = bounds(business_date)?;
+    if instant < bucket.3 {
+        bucket = bounds(bucket.0 - Duration::days(1))?;
+    } else if instant >= bucket.4 {
+        bucket = bounds(bucket.1)?;
+    }
+    let (start, end, key, start_utc, end_utc) = bucket;
+
+    Ok(Bucket {
         key,
-        start_local: format_rfc3339(&start_local_dt),
-        end_local: format_rfc3339(&end_local_dt),
+        start_local: format_rfc3339(&start_utc.with_timezone(&tz)),
+        end_local: format_rfc3339(&end_utc.with_timezone(&tz)),
         start_utc: format_rfc3339_utc(&start_utc),
         end_utc: format_rfc3339_utc(&end_utc),
+        duration_seconds: (end_utc - start_utc).num_seconds(),
+        week_numbers: None,
+        month_clamp: applied_month_clamp(interval, start, end),
+        label_ts: None,
+        dst_info: None,
+    })
+}
+
+/// Compute a time bucket, resolving boundaries that fall into a DST gap or
+/// overlap with `policy` instead of the built-in fallbacks.
+///
+/// [`compute_bucket`] always resolves such a boundary: an ambiguous local
+/// midnight to its first occurrence, a nonexistent one to the next valid
+/// time. Here the [`Policy`] decides, and its `Error` variants turn the edge
+/// case into an error, so callers can detect or reject these buckets; with
+/// `ShiftForward` and `First` the result is [`compute_bucket`]'s. The
+/// returned bucket always contains `instant`: if the chosen occurrence
+/// leaves it outside the bucket of its local time, the neighbouring bucket
+/// that contains it is returned, as many buckets over as needed. Minute and
+/// fixed windows never need a decision and match [`compute_bucket`].
+///
+/// # Errors
+///
+/// Returns [`TzBucketError::NonexistentTime`] or
+/// [`TzBucketError::AmbiguousTime`] when a boundary needs a policy decision
+/// and the corresponding policy is `Error`.
+///
+/// # Examples
+///
+/// ```
+/// use chrono::{TimeZone, Utc};
+/// use tzbucket_core::compute::compute_bucket_with_policy;
+/// use tzbucket_core::models::{AmbiguousPolicy, Interval, Policy};
+///
+/// // Havana repeats local midnight when DST ends on 2026-11-01.
+/// let instant = Utc.with_ymd_and_hms(2026, 11, 1, 12, 0, 0).unwrap();
+/// let tz = chrono_tz::America::Havana;
+///
+/// assert!(compute_bucket_with_policy(instant, tz, Interval::Day, None, Policy::default()).is_err());
+///
+/// let policy = Policy {
+///     ambiguous: AmbiguousPolicy::Second,
+///     ..Policy::default()
+/// };
+/// let bucket = compute_bucket_with_policy(instant, tz, Interval::Day, None, policy).unwrap();
+/// assert_eq!(bucket.start_local, "2026-11-01T00:00:00-05:00");
+/// ```
+pub fn compute_bucket_with_policy(
+    instant: DateTime<Utc>,
+    tz: Tz,
+    interval: Interval,
+    week_start: Option<WeekStart>,
+    policy: Policy,
+) -> crate::error::Result<Bucket> {
+    if matches!(interval, Interval::Minutes(_) | Interval::FixedSeconds(_)) {
+        return Ok(compute_bucket(instant, tz, interval, week_start));
+    }
+    // The policy only picks among the resolutions compute_bucket would make,
+    // so its fallbacks are reproduced exactly.
+    let resolve = |local: NaiveDateTime| {
+        let (utc, kind) = boundary_to_utc(local, tz, interval);
+        let utc = match kind {
+            LocalKind::Normal => utc,
+            LocalKind::Nonexistent => match policy.nonexistent {
+                NonexistentPolicy::Error => {
+                    return Err(TzBucketError::NonexistentTime {
+                        local,
+                        tz: tz.to_string(),
+                    });
+                }
+                NonexistentPolicy::ShiftForward => utc,
+            },
+            LocalKind::Ambiguous => match policy.ambiguous {
+                AmbiguousPolicy::Error => {
+                    return Err(TzBucketError::AmbiguousTime {
+                        local,
+                        tz: tz.to_string(),
+                    });
+                }
+                AmbiguousPolicy::First => utc,
+                AmbiguousPolicy::Second => tz
+                    .from_local_datetime(&local)
+                    .latest()
+                    .map_or(utc, |dt| dt.with_timezone(&Utc)),
+            },
+        };
+        Ok((utc, kind))
+    };
+
+    let ResolvedBounds {
+        start_local,
+        end_local,
+        key,
+        start_utc,
+        end_utc,
+        ..
+    } = containing_bounds(
+        instant,
+        tz,
+        interval,
+        week_start.unwrap_or_default(),
+        WeekKeyFormat::StartDate,
+        resolve,
+    )?;
+
+    Ok(Bucket {
+        key,
+        start_local: format_rfc3339(&start_utc.with_timezone(&tz)),
+        end_local: format_rfc3339(&end_utc.with_timezone(&tz)),
+        start_utc: format_rfc3339_utc(&start_utc),
+        end_utc: format_rfc3339_utc(&end_utc),
+        duration_seconds: (end_utc - start_utc).num_seconds(),
+        week_numbers: None,
+        month_clamp: applied_month_clamp(interval, start_local.date(), end_local.date()),
+        label_ts: None,
+        dst_info: None,
+    })
+}
+
+/// Compute an epoch-aligned fixed-duration bucket.
+///
+/// Windows start at multiples of `seconds` since the Unix epoch, independent
+/// of `tz`; the zone only affects the local rendering of the boundaries. The
+/// key is the UTC start.
+fn compute_fixed_bucket(
+    instant: DateTime<Utc>,
+    tz: Tz,
+    seconds: u64,
+    metrics: &dyn MetricsSink,
+) -> TypedBucket {
+    let (start_utc, end_utc) = fixed_bounds(instant, seconds);
+
+    metrics.counter(metrics::BUCKETS_COMPUTED, 1);
+    metrics.histogram(
+        metrics::BUCKET_DURATION_SECONDS,
+        (end_utc - start_utc).num_seconds() as f64,
+    );
+
+    TypedBucket {
+        key: fixed_key(start_utc),
+        start_local: start_utc.with_timezone(&tz),
+        end_local: end_utc.with_timezone(&tz),
+        start_utc,
+        end_utc,
+        month_clamp: None,
+    }
+}
+
+/// Compute a calendar or hour bucket in a zone with a single UTC offset,
+/// such as `UTC` or `Etc/GMT-5`.
+///
+/// Every local time exists exactly once there, so the local boundaries are
+/// shifted by the offset instead of being resolved against the zone's
+/// transitions. The result is the one the general path computes.
+fn compute_fixed_offset_bucket(
+    instant: DateTime<Utc>,
+    tz: Tz,
+    offset: FixedOffset,
+    interval: Interval,
+    week_start: Option<WeekStart>,
+    week_key: WeekKeyFormat,
+    metrics: &dyn MetricsSink,
+) -> TypedBucket {
+    let offset = Duration::seconds(i64::from(offset.local_minus_utc()));
+    let (start_local, end_local, key) = local_bounds(
+        instant.naive_utc() + offset,
+        interval,
+        week_start.unwrap_or_default(),
+        week_key,
+    );
+    let start_utc = (start_local - offset).and_utc();
+    let end_utc = (end_local - offset).and_utc();
+
+    metrics.counter(metrics::BUCKETS_COMPUTED, 1);
+    metrics.histogram(
+        metrics::BUCKET_DURATION_SECONDS,
+        (end_utc - start_utc).num_seconds() as f64,
+    );
+
+    TypedBucket {
+        key,
+        start_local: start_utc.with_timezone(&tz),
+        end_local: end_utc.with_timezone(&tz),
+        start_utc,
+        end_utc,
+        month_clamp: applied_month_clamp(interval, start_local.date(), end_local.date()),
+    }
+}
+
+/// UTC boundaries of the epoch-aligned window of `seconds` containing
+/// `instant`.
+fn fixed_bounds(instant: DateTime<Utc>, seconds: u64) -> (DateTime<Utc>, DateTime<Utc>) {
+    let step = i64::try_from(seconds.max(1)).unwrap_or(i64::MAX);
+    let start = instant.timestamp().div_euclid(step).saturating_mul(step);
+    let start_utc = DateTime::from_timestamp(start, 0).unwrap_or(DateTime::<Utc>::MIN_UTC);
+    let end_utc =
+        DateTime::from_timestamp(start.saturating_add(step), 0).unwrap_or(DateTime::<Utc>::MAX_UTC);
+    (start_utc, end_utc)
+}
+
+/// Compute a bucket of local minutes.
+///
+/// Unlike the calendar intervals, the boundaries are not converted
+/// independently: a repeated window would then merge both passes, and the
+/// key (the local start with its offset) tells the passes apart instead.
+fn compute_minutes_bucket(
+    instant: DateTime<Utc>,
+    tz: Tz,
+    minutes: u32,
+    metrics: &dyn MetricsSink,
+) -> TypedBucket {
+    let (start_utc, end_utc) = minutes_bounds(instant, tz, minutes);
+
+    metrics.counter(metrics::BUCKETS_COMPUTED, 1);
+    metrics.histogram(
+        metrics::BUCKET_DURATION_SECONDS,
+        (end_utc - start_utc).num_seconds() as f64,
+    );
+
+    TypedBucket {
+        key: minutes_key(start_utc, tz),
+        start_local: start_utc.with_timezone(&tz),
+        end_local: end_utc.with_timezone(&tz),
+        start_utc,
+        end_utc,
+        month_clamp: None,
+    }
+}
+
+/// Key of a fixed-length bucket: its UTC start, as [`format_rfc3339_utc`]
+/// renders it.
+fn fixed_key(start_utc: DateTime<Utc>) -> BucketKey {
+    format_key(format_args!("{}", start_utc.format("%Y-%m-%dT%H:%M:%SZ")))
+}
+
+/// Key of a minutes bucket: its first local minute with the UTC offset,
+/// `YYYY-MM-DDTHH:MM+HH:MM`. Offsets with seconds, such as Monrovia's
+/// -00:44:30 until 1972, keep them (`-00:44:30`): rounded to the minute the
+/// key would name a different instant.
+fn minutes_key(start_utc: DateTime<Utc>, tz: Tz) -> BucketKey {
+    let start = start_utc.with_timezone(&tz);
+    let format = if start.offset().fix().local_minus_utc() % 60 == 0 {
+        "%Y-%m-%dT%H:%M%:z"
+    } else {
+        "%Y-%m-%dT%H:%M%::z"
+    };
+    format_key(format_args!("{}", start.format(format)))
+}
+
+/// UTC boundaries of the local minutes window containing `instant`.
+///
+/// The bucket is the stretch of time around `instant` during which the local
+/// clock reads inside the window. Between offset changes that is where the
+/// clock reads the window start and end; at an offset change the bucket
+/// continues if the clock stays inside the window, and ends (or starts) if it
+/// jumps out, reaches the window end, or jumps back to the window start.
+fn minutes_bounds(instant: DateTime<Utc>, tz: Tz, minutes: u32) -> (DateTime<Utc>, DateTime<Utc>) {
+    let offset_at = |t: DateTime<Utc>| utc_to_local(t, tz).naive_local() - t.naive_utc();
+    let utc_of = |local: NaiveDateTime, offset: Duration| (local - offset).and_utc();
+
+    let step = i64::from(minutes.clamp(1, 1440));
+    let local = utc_to_local(instant, tz).naive_local();
+    let midnight = local.date().and_hms_opt(0, 0, 0).unwrap();
+    let minute = i64::from(local.num_seconds_from_midnight() / 60);
+    let start_local = midnight + Duration::minutes(minute - minute % step);
+    let end_local = (start_local + Duration::minutes(step)).min(midnight + Duration::days(1));
+    let in_window = |t: DateTime<Utc>| {
+        let local = t.naive_utc() + offset_at(t);
+        start_local <= local && local < end_local
+    };
+
+    // Windows are at most a day long, so two days either side cover every
+    // offset change that can matter.
+    let transitions =
+        offset_transitions(tz, instant - Duration::days(2), instant + Duration::days(2));
+
+    let mut start = utc_of(start_local, offset_at(instant));
+    for &transition in transitions.iter().rev().filter(|&&t| t <= instant) {
+        if start >= transition {
+            break;
+        }
+        let before = transition - Duration::seconds(1);
+        if !in_window(before) || utc_of(end_local, offset_at(before)) == transition {
+            start = transition;
+            break;
+        }
+        start = utc_of(start_local, offset_at(before));
+    }
+
+    let mut end = utc_of(end_local, offset_at(instant));
+    for &transition in transitions.iter().filter(|&&t| t > instant) {
+        if end <= transition {
+            break;
+        }
+        if !in_window(transition) || utc_of(start_local, offset_at(transition)) == transition {
+            end = transition;
+            break;
+        }
+        end = utc_of(end_local, offset_at(transition));
+    }
+
+    (start, end)
+}
+
+/// Compute the bucket of one instant for several intervals at once, e.g. the
+/// day, week and month roll-up keys of an event.
+///
+/// Each bucket is exactly what [`compute_bucket`] returns for its interval.
+/// Repeated intervals appear once in the map, which iterates in [`Interval`]
+/// order.
+///
+/// # Examples
+///
+/// ```
+/// use chrono::{TimeZone, Utc};
+/// use tzbucket_core::{Interval, compute_buckets_for_intervals};
+///
+/// let instant = Utc.with_ymd_and_hms(2026, 3, 29, 12, 0, 0).unwrap();
+/// let buckets = compute_buckets_for_intervals(
+///     instant,
+///     chrono_tz::Europe::Berlin,
+///     &[Interval::Day, Interval::Week, Interval::Month],
+///     None,
+/// );
+///
+/// assert_eq!(buckets[&Interval::Day].key, "2026-03-29");
+/// assert_eq!(buckets[&Interval::Week].key, "2026-03-23");
+/// assert_eq!(buckets[&Interval::Month].key, "2026-03");
+/// ```
+pub fn compute_buckets_for_intervals(
+    instant: DateTime<Utc>,
+    tz: Tz,
+    intervals: &[Interval],
+    week_start: Option<WeekStart>,
+) -> BTreeMap<Interval, Bucket> {
+    intervals
+        .iter()
+        .map(|&interval| (interval, compute_bucket(instant, tz, interval, week_start)))
+        .collect()
+}
+
+/// Compute the bucket of one instant in several zones at once, e.g. the
+/// user's zone, the office zone and UTC of an event.
+///
+/// Each bucket is exactly what [`compute_bucket`] returns for its zone. The
+/// result follows the order of `zones`, repeats included.
+///
+/// # Examples
+///
+/// ```
+/// use chrono::{TimeZone, Utc};
+/// use tzbucket_core::{Interval, compute_bucket_multi_tz};
+///
+/// // 01:30 in Berlin, still the previous evening in New York.
+/// let instant = Utc.with_ymd_and_hms(2026, 3, 29, 23, 30, 0).unwrap();
+/// let zones = [chrono_tz::Europe::Berlin, chrono_tz::America::New_York, chrono_tz::UTC];
+/// let buckets = compute_bucket_multi_tz(instant, &zones, Interval::Day, None);
+///
+/// let keys: Vec<&str> = buckets.iter().map(|(_, bucket)| bucket.key.as_str()).collect();
+/// assert_eq!(keys, ["2026-03-30", "2026-03-29", "2026-03-29"]);
+/// assert_eq!(buckets[1].0, chrono_tz::America::New_York);
+/// ```
+pub fn compute_bucket_multi_tz(
+    instant: DateTime<Utc>,
+    zones: &[Tz],
+    interval: Interval,
+    week_start: Option<WeekStart>,
+) -> Vec<(Tz, Bucket)> {
+    zones
+        .iter()
+        .map(|&tz| (tz, compute_bucket(instant, tz, interval, week_start)))
+        .collect()
+}
+
+/// Compute buckets for many instants at once, as columns.
+///
+/// Equivalent to calling [`compute_bucket`] for each of `epoch_ms` and
+/// keeping `key`, `start_utc` and `end_utc` (as epoch milliseconds), but
+/// without building a [`Bucket`] per row: boundaries are only computed when
+/// an instant leaves the previous row's bucket, so sorted or clustered input
+/// costs little more than one key clone per row.
+///
+/// # Errors
+///
+/// Returns [`TzBucketError::ParseError`] if an instant is outside the range
+/// chrono can represent.
+///
+/// # Examples
+///
+/// ```
+/// use tzbucket_core::{Interval, compute_buckets_columnar};
+///
+/// // 2026-03-29T00:30:00Z and 2026-03-29T21:30:00Z: both in Berlin's 23-hour day.
+/// let columns = compute_buckets_columnar(
+///     &[1_774_744_200_000, 1_774_819_800_000],
+///     chrono_tz::Europe::Berlin,
+///     Interval::Day,
+///     None,
+/// )
+/// .unwrap();
+///
+/// assert_eq!(columns.keys, ["2026-03-29", "2026-03-29"]);
+/// assert_eq!(columns.end_ms[0] - columns.start_ms[0], 23 * 3_600_000);
+/// ```
+pub fn compute_buckets_columnar(
+    epoch_ms: &[i64],
+    tz: Tz,
+    interval: Interval,
+    week_start: Option<WeekStart>,
+) -> crate::error::Result<BucketColumns> {
+    let week_start = week_start.unwrap_or_default();
+    let mut columns = BucketColumns::with_capacity(epoch_ms.len());
+    let mut current: Option<(i64, i64, BucketKey)> = None;
+
+    for &ms in epoch_ms {
+        let in_current = matches!(&current, Some((start, end, _)) if *start <= ms && ms < *end);
+        if !in_current {
+            let instant = DateTime::from_timestamp_millis(ms).ok_or_else(|| {
+                TzBucketError::ParseError(format!("epoch_ms {} is out of range", ms))
+            })?;
+            let (start_utc, end_utc, key) = bucket_bounds(instant, tz, interval, week_start);
+            current = Some((
+                start_utc.timestamp_millis(),
+                end_utc.timestamp_millis(),
+                key,
+            ));
+        }
+
+        let (start, end, key) = current.as_ref().expect("set above");
+        columns.keys.push(key.clone());
+        columns.start_ms.push(*start);
+        columns.end_ms.push(*end);
+    }
+
+    Ok(columns)
+}
+
+/// Compute the bucket of every instant in `instants`.
+///
+/// Returns exactly what [`compute_bucket`] returns for each instant, in input
+/// order. The last bucket is reused while instants stay inside its UTC range,
+/// so sorted or clustered input skips most timezone lookups.
+///
+/// # Examples
+///
+/// ```
+/// use chrono::{TimeZone, Utc};
+/// use tzbucket_core::{BucketSpec, Interval, compute_buckets};
+///
+/// let instants = [
+///     Utc.with_ymd_and_hms(2026, 3, 28, 23, 30, 0).unwrap(),
+///     Utc.with_ymd_and_hms(2026, 3, 29, 21, 30, 0).unwrap(),
+///     Utc.with_ymd_and_hms(2026, 3, 29, 22, 30, 0).unwrap(),
+/// ];
+/// let spec = BucketSpec::new(chrono_tz::Europe::Berlin, Interval::Day);
+/// let buckets = compute_buckets(&instants, &spec);
+///
+/// let keys: Vec<_> = buckets.iter().map(|bucket| bucket.key.as_str()).collect();
+/// assert_eq!(keys, ["2026-03-29", "2026-03-29", "2026-03-30"]);
+/// ```
+pub fn compute_buckets(instants: &[DateTime<Utc>], spec: &BucketSpec) -> Vec<Bucket> {
+    let mut cache = LastBucket::default();
+    instants
+        .iter()
+        .map(|&instant| cache.bucket(instant, spec))
+        .collect()
+}
+
+/// Parse and bucket every timestamp in `inputs`.
+///
+/// The batch form of [`compute_bucket_from_string`]: one result per input, in
+/// input order, so a malformed timestamp fails only its own item. Buckets are
+/// reused as in [`compute_buckets`].
+///
+/// # Examples
+///
+/// ```
+/// use tzbucket_core::{BucketSpec, Interval, TimestampFormat, compute_buckets_from_strings};
+///
+/// let spec = BucketSpec::new(chrono_tz::Europe::Berlin, Interval::Day);
+/// let results = compute_buckets_from_strings(
+///     &["2026-03-29T00:30:00Z", "not a timestamp"],
+///     TimestampFormat::Rfc3339,
+///     &spec,
+/// );
+///
+/// assert_eq!(results[0].as_ref().unwrap().bucket.key, "2026-03-29");
+/// assert!(results[1].is_err());
+/// ```
+pub fn compute_buckets_from_strings<S: AsRef<str>>(
+    inputs: &[S],
+    format: TimestampFormat,
+    spec: &BucketSpec,
+) -> Vec<crate::error::Result<BucketResult>> {
+    let mut cache = LastBucket::default();
+    inputs
+        .iter()
+        .map(|input| {
+            let input = input.as_ref();
+            let instant = parse_timestamp(input, format)?;
+            Ok(BucketResult {
+                input: InputTimestamp {
+                    ts: input.trim().to_string(),
+                    epoch_ms: instant.timestamp_millis(),
+                },
+                tz: spec.tz.name().to_string(),
+                interval: spec.interval,
+                bucket: cache.bucket(instant, spec),
+            })
+        })
+        .collect()
+}
+
+/// The most recently computed bucket of a batch and its UTC range.
+#[derive(Default)]
+struct LastBucket {
+    last: Option<(Range<DateTime<Utc>>, Bucket)>,
+}
+
+impl LastBucket {
+    fn bucket(&mut self, instant: DateTime<Utc>, spec: &BucketSpec) -> Bucket {
+        if let Some((range, bucket)) = &self.last
+            && range.contains(&instant)
+        {
+            return bucket.clone();
+        }
+
+        let bucket = compute_bucket(instant, spec.tz, spec.interval, spec.week_start);
+        self.last = bucket.utc_range().ok().map(|range| (range, bucket.clone()));
+        bucket
+    }
+}
+
+/// UTC boundaries and key of the bucket containing `instant`, without
+/// formatting the boundaries.
+fn bucket_bounds(
+    instant: DateTime<Utc>,
+    tz: Tz,
+    interval: Interval,
+    week_start: WeekStart,
+) -> (DateTime<Utc>, DateTime<Utc>, BucketKey) {
+    if let Interval::FixedSeconds(seconds) = interval {
+        let (start_utc, end_utc) = fixed_bounds(instant, seconds);
+        return (start_utc, end_utc, fixed_key(start_utc));
+    }
+    if let Interval::Minutes(minutes) = interval {
+        let (start_utc, end_utc) = minutes_bounds(instant, tz, minutes);
+        return (start_utc, end_utc, minutes_key(start_utc, tz));
+    }
+
+    let Ok(bounds) = containing_bounds(
+        instant,
+        tz,
+        interval,
+        week_start,
+        WeekKeyFormat::StartDate,
+        |local| Ok::<_, Infallible>(boundary_to_utc(local, tz, interval)),
+    );
+    (bounds.start_utc, bounds.end_utc, bounds.key)
+}
+
+/// Local wall-clock boundaries and key of the bucket containing `local`,
+/// for every interval except [`Interval::Minutes`] and
+/// [`Interval::FixedSeconds`].
+///
+/// Hour buckets span one local hour. A repeated hour (DST fall back) is a
+/// single bucket covering both occurrences, two UTC hours long; a skipped
+/// hour (DST spring forward) contains no instants and so never forms a
+/// bucket. Multi-hour windows are keyed like hours, by their first hour.
+fn local_bounds(
+    local: NaiveDateTime,
+    interval: Interval,
+    week_start: WeekStart,
+    week_key: WeekKeyFormat,
+) -> (NaiveDateTime, NaiveDateTime, BucketKey) {
+    if let Some(hours) = hour_count(interval) {
+        let midnight = local.date().and_hms_opt(0, 0, 0).unwrap();
+        let start = midnight + Duration::hours(i64::from(local.hour() - local.hour() % hours));
+        let end = (start + Duration::hours(i64::from(hours))).min(midnight + Duration::days(1));
+        let key = format_key(format_args!("{}", start.format("%Y-%m-%dT%H")));
+        return (start, end, key);
+    }
+
+    let (start_date, end_date, key) = match interval {
+        Interval::Week => compute_week_bucket(local.date(), week_start, week_key),
+        _ => bucket_dates(local.date(), interval, week_start)
+            .expect("calendar intervals have bucket dates"),
+    };
+    (
+        start_date.and_hms_opt(0, 0, 0).unwrap(),
+        end_date.and_hms_opt(0, 0, 0).unwrap(),
+        key,
+    )
+}
+
+/// Convert a local bucket boundary to UTC.
+///
+/// A nonexistent hour boundary resolves to the transition that skipped it,
+/// the first instant with a later local time. Shifting forward by the gap
+/// length (as for midnights) would overshoot gaps that do not end on the
+/// hour, such as Chatham's 02:45 -> 03:45.
+pub(crate) fn boundary_to_utc(
+    local: NaiveDateTime,
+    tz: Tz,
+    interval: Interval,
+) -> (DateTime<Utc>, LocalKind) {
+    let (utc, kind) = local_to_utc_classified(local, tz);
+    if hour_count(interval).is_some() && kind == LocalKind::Nonexistent {
+        let transition =
+            offset_transitions(tz, utc - Duration::days(1), utc + Duration::seconds(1))
+                .pop()
+                .unwrap_or(utc);
+        return (transition, kind);
+    }
+    (utc, kind)
+}
+
+/// Window length of hour-aligned intervals, `None` for the others.
+fn hour_count(interval: Interval) -> Option<u32> {
+    match interval {
+        Interval::Hour => Some(1),
+        Interval::Hours(hours) => Some(hours.clamp(1, 24)),
+        _ => None,
+    }
+}
+
+/// A bucket of local time with its boundaries resolved to UTC.
+struct ResolvedBounds {
+    start_local: NaiveDateTime,
+    end_local: NaiveDateTime,
+    key: BucketKey,
+    start_utc: DateTime<Utc>,
+    end_utc: DateTime<Utc>,
+    start_kind: LocalKind,
+    end_kind: LocalKind,
+}
+
+/// The bucket containing `instant`, with its local boundaries resolved to
+/// UTC by `resolve`.
+///
+/// A local boundary resolves to the same instant whether it ends one bucket
+/// or starts the next, so buckets tile the timeline. The bucket of the
+/// instant's local time can still miss it: St. John's fell back from 00:01
+/// to 23:01 in 2010, and with midnight resolved to its first occurrence the
+/// second pass through 23:01-00:00 lies after the end of its local day. Such
+/// an instant belongs to the bucket on the other side of the boundary, and
+/// so on until one contains it; the steps all go the same way.
+fn containing_bounds<E>(
+    instant: DateTime<Utc>,
+    tz: Tz,
+    interval: Interval,
+    week_start: WeekStart,
+    week_key: WeekKeyFormat,
+    resolve: impl Fn(NaiveDateTime) -> Result<(DateTime<Utc>, LocalKind), E>,
+) -> Result<ResolvedBounds, E> {
+    let mut local = utc_to_local(instant, tz).naive_local();
+    loop {
+        let (start_local, end_local, key) = local_bounds(local, interval, week_start, week_key);
+        let (start_utc, start_kind) = resolve(start_local)?;
+        let (end_utc, end_kind) = resolve(end_local)?;
+        if instant < start_utc {
+            local = start_local - Duration::seconds(1);
+        } else if instant >= end_utc {
+            local = end_local;
+        } else {
+            return Ok(ResolvedBounds {
+                start_local,
+                end_local,
+                key,
+                start_utc,
+                end_utc,
+                start_kind,
+                end_kind,
+            });
+        }
+    }
+}
+
+/// The clamp policy of a trailing month bucket whose start or end is not on
+/// the anchor day.
+fn applied_month_clamp(interval: Interval, start: NaiveDate, end: NaiveDate) -> Option<MonthClamp> {
+    match interval {
+        Interval::TrailingMonth { anchor_day, clamp } => {
+            let anchor = u32::from(anchor_day);
+            (start.day() != anchor || end.day() != anchor).then_some(clamp)
+        }
+        _ => None,
+    }
+}
+
+fn record_boundary(metrics: &dyn MetricsSink, kind: LocalKind) {
+    match kind {
+        LocalKind::Normal => {}
+        LocalKind::Ambiguous => metrics.counter(metrics::AMBIGUOUS_BOUNDARIES, 1),
+        LocalKind::Nonexistent => metrics.counter(metrics::NONEXISTENT_BOUNDARIES, 1),
+    }
+}
+
+/// Local calendar dates bounding the bucket that contains `date`.
+///
+/// Returns `(start, end, key)`: the bucket covers local midnight of `start`
+/// (inclusive) to local midnight of `end` (exclusive). This is the calendar
+/// part of [`compute_bucket`], without any timezone conversion. Returns
+/// `None` for [`Interval::Minutes`], [`Interval::Hour`],
+/// [`Interval::Hours`] and [`Interval::FixedSeconds`], whose
+/// boundaries are not local midnights.
+///
+/// # Examples
+///
+/// ```
+/// use chrono::NaiveDate;
+/// use tzbucket_core::compute::bucket_dates;
+/// use tzbucket_core::models::{Interval, MonthClamp, WeekStart};
+///
+/// let date = NaiveDate::from_ymd_opt(2026, 3, 10).unwrap();
+/// let interval = Interval::TrailingMonth {
+///     anchor_day: 15,
+///     clamp: MonthClamp::PreviousValidDay,
+/// };
+/// let (start, end, key) = bucket_dates(date, interval, WeekStart::Monday).unwrap();
+///
+/// assert_eq!(start, NaiveDate::from_ymd_opt(2026, 2, 15).unwrap());
+/// assert_eq!(end, NaiveDate::from_ymd_opt(2026, 3, 15).unwrap());
+/// assert_eq!(key, "2026-02-15");
+/// ```
+pub fn bucket_dates(
+    date: NaiveDate,
+    interval: Interval,
+    week_start: WeekStart,
+) -> Option<(NaiveDate, NaiveDate, BucketKey)> {
+    match interval {
+        Interval::Day => Some(compute_day_bucket(date)),
+        Interval::Days { n, anchor } => Some(compute_days_bucket(date, n, anchor)),
+        Interval::Week => Some(compute_week_bucket(
+            date,
+            week_start,
+            WeekKeyFormat::StartDate,
+        )),
+        Interval::Month => Some(compute_month_bucket(date)),
+        Interval::Quarter => Some(compute_quarter_bucket(date)),
+        Interval::FiscalQuarter { start_month } => {
+            Some(compute_fiscal_quarter_bucket(date, start_month))
+        }
+        Interval::Year => Some(compute_year_bucket(date)),
+        Interval::TrailingMonth { anchor_day, clamp } => {
+            Some(compute_trailing_month_bucket(date, anchor_day, clamp))
+        }
+        Interval::Minutes(_) | Interval::Hour | Interval::Hours(_) | Interval::FixedSeconds(_) => {
+            None
+        }
     }
 }
 
 /// Compute day bucket boundaries.
-fn compute_day_bucket(local: &DateTime<Tz>) -> (NaiveDate, NaiveDate, String) {
-    let date = local.date_naive();
+fn compute_day_bucket(date: NaiveDate) -> (NaiveDate, NaiveDate, BucketKey) {
     let next_date = date + chrono::Duration::days(1);
-    let key = format!("{}", date.format("%Y-%m-%d"));
+    let key = format_key(format_args!("{}", date.format("%Y-%m-%d")));
     (date, next_date, key)
 }
 
+/// Compute boundaries of the `n`-day period containing `date`, counted in
+/// whole periods before or after the one starting on `anchor`.
+fn compute_days_bucket(
+    date: NaiveDate,
+    n: u32,
+    anchor: NaiveDate,
+) -> (NaiveDate, NaiveDate, BucketKey) {
+    let n = i64::from(n.max(1));
+    let start = anchor + Duration::days((date - anchor).num_days().div_euclid(n) * n);
+    let end = start + Duration::days(n);
+    let key = format_key(format_args!("{}", start.format("%Y-%m-%d")));
+    (start, end, key)
+}
+
 /// Compute week bucket boundaries.
 ///
-/// The bucket key uses the week starting date in `YYYY-MM-DD` format.
-/// This works for both Monday and Sunday week starts.
+/// The bucket key is written in `key_format`: the week starting date in
+/// `YYYY-MM-DD` format, or the ISO week. Both work for Monday and Sunday week
+/// starts.
 fn compute_week_bucket(
-    local: &DateTime<Tz>,
+    date: NaiveDate,
     week_start: WeekStart,
-) -> (NaiveDate, NaiveDate, String) {
-    let date = local.date_naive();
+    key_format: WeekKeyFormat,
+) -> (NaiveDate, NaiveDate, BucketKey) {
     let weekday = date.weekday();
 
     // Calculate days since week start
@@ -115,14 +1257,12 @@ fn compute_week_bucket(
     let week_start_date = date - chrono::Duration::days(days_from_week_start);
     let week_end_date = week_start_date + chrono::Duration::weeks(1);
 
-    // Use week starting date as the key (YYYY-MM-DD format)
-    let key = format!("{}", week_start_date.format("%Y-%m-%d"));
+    let key = key_format.key(week_start_date, week_start);
     (week_start_date, week_end_date, key)
 }
 
 /// Compute month bucket boundaries.
-fn compute_month_bucket(local: &DateTime<Tz>) -> (NaiveDate, NaiveDate, String) {
-    let date = local.date_naive();
+fn compute_month_bucket(date: NaiveDate) -> (NaiveDate, NaiveDate, BucketKey) {
     let year = date.year();
     let month = date.month();
 
@@ -136,14 +1276,129 @@ fn compute_month_bucket(local: &DateTime<Tz>) -> (NaiveDate, NaiveDate, String)
         NaiveDate::from_ymd_opt(year, month + 1, 1).unwrap()
     };
 
-    let key = format!("{}", date.format("%Y-%m"));
+    let key = format_key(format_args!("{}", date.format("%Y-%m")));
     (month_start, month_end, key)
 }
 
+/// Compute quarter bucket boundaries.
+///
+/// Quarters start on Jan 1, Apr 1, Jul 1 and Oct 1. The key is `YYYY-QN`.
+fn compute_quarter_bucket(date: NaiveDate) -> (NaiveDate, NaiveDate, BucketKey) {
+    let year = date.year();
+    let quarter = (date.month() - 1) / 3 + 1;
+
+    let quarter_start = NaiveDate::from_ymd_opt(year, quarter * 3 - 2, 1).unwrap();
+    let quarter_end = if quarter == 4 {
+        NaiveDate::from_ymd_opt(year + 1, 1, 1).unwrap()
+    } else {
+        NaiveDate::from_ymd_opt(year, quarter * 3 + 1, 1).unwrap()
+    };
+
+    let key = format_key(format_args!("{}-Q{}", year, quarter));
+    (quarter_start, quarter_end, key)
+}
+
+/// Compute fiscal quarter boundaries for a fiscal year starting in
+/// `start_month`. The key is `FY<year>-Q<n>`, named after the calendar year
+/// the fiscal year ends in.
+fn compute_fiscal_quarter_bucket(
+    date: NaiveDate,
+    start_month: u8,
+) -> (NaiveDate, NaiveDate, BucketKey) {
+    let start_month = u32::from(start_month.clamp(1, 12));
+    let months_into_year = (date.month() + 12 - start_month) % 12;
+    let quarter = months_into_year / 3 + 1;
+    // Named after the calendar year of its last month.
+    let fiscal_year = if start_month == 1 || date.month() < start_month {
+        date.year()
+    } else {
+        date.year() + 1
+    };
+
+    let month_start = NaiveDate::from_ymd_opt(date.year(), date.month(), 1).unwrap();
+    let months_into_quarter = (months_into_year % 3) as i32;
+    let (quarter_start, _) = add_months(
+        month_start,
+        -months_into_quarter,
+        MonthClamp::PreviousValidDay,
+    );
+    let (quarter_end, _) = add_months(quarter_start, 3, MonthClamp::PreviousValidDay);
+
+    let key = format_key(format_args!("FY{}-Q{}", fiscal_year, quarter));
+    (quarter_start, quarter_end, key)
+}
+
+/// First day of quarter `quarter` (1-4) of fiscal year `fiscal_year`, for a
+/// fiscal year starting in `start_month`. `None` if out of range.
+pub(crate) fn fiscal_quarter_start(
+    fiscal_year: i32,
+    quarter: u32,
+    start_month: u8,
+) -> Option<NaiveDate> {
+    let start_month = u32::from(start_month.clamp(1, 12));
+    let first_year = if start_month == 1 {
+        fiscal_year
+    } else {
+        fiscal_year.checked_sub(1)?
+    };
+    // Month index (year * 12 + month0) of the quarter start.
+    let month = i64::from(first_year) * 12 + i64::from(start_month - 1 + (quarter - 1) * 3);
+    let year = i32::try_from(month.div_euclid(12)).ok()?;
+    NaiveDate::from_ymd_opt(year, month.rem_euclid(12) as u32 + 1, 1)
+}
+
+/// Compute year bucket boundaries (Jan 1 to Jan 1, key `YYYY`).
+fn compute_year_bucket(date: NaiveDate) -> (NaiveDate, NaiveDate, BucketKey) {
+    let year = date.year();
+    let year_start = NaiveDate::from_ymd_opt(year, 1, 1).unwrap();
+    let year_end = NaiveDate::from_ymd_opt(year + 1, 1, 1).unwrap();
+    (year_start, year_end, format_key(format_args!("{}", year)))
+}
+
+/// Compute trailing month bucket boundaries.
+///
+/// The period starts on the anchor day of the month containing `date`, or of
+/// the previous month if `date` is before that anchor. A rolled-forward
+/// anchor lands on the 1st of the following month at the latest, so the
+/// previous month's anchor never lies after `date`. The key is the start date
+/// in `YYYY-MM-DD` format.
+fn compute_trailing_month_bucket(
+    date: NaiveDate,
+    anchor_day: u8,
+    clamp: MonthClamp,
+) -> (NaiveDate, NaiveDate, BucketKey) {
+    let this_anchor = anchor_in_month(date.year(), date.month(), anchor_day, clamp);
+
+    let (start_year, start_month) = if date >= this_anchor {
+        (date.year(), date.month())
+    } else if date.month() == 1 {
+        (date.year() - 1, 12)
+    } else {
+        (date.year(), date.month() - 1)
+    };
+    let (end_year, end_month) = if start_month == 12 {
+        (start_year + 1, 1)
+    } else {
+        (start_year, start_month + 1)
+    };
+
+    let start = anchor_in_month(start_year, start_month, anchor_day, clamp);
+    let end = anchor_in_month(end_year, end_month, anchor_day, clamp);
+    let key = format_key(format_args!("{}", start.format("%Y-%m-%d")));
+    (start, end, key)
+}
+
+/// The anchor day in the given month, resolved under `clamp` if the month is
+/// shorter.
+fn anchor_in_month(year: i32, month: u32, anchor_day: u8, clamp: MonthClamp) -> NaiveDate {
+    day_in_month(year, month, u32::from(anchor_day.clamp(1, 31)), clamp).0
+}
+
 /// Compute a bucket result from a timestamp string.
 ///
 /// This is a convenience function that parses the timestamp, computes the bucket,
-/// and returns a complete [`BucketResult`].
+/// and returns a complete [`BucketResult`]. It copies `input` and `tz_name`
+/// into the result; [`compute_bucket_ref_from_string`] borrows them instead.
 ///
 /// # Arguments
 ///
@@ -163,36 +1418,318 @@ pub fn compute_bucket_from_string(
     interval: Interval,
     week_start: Option<WeekStart>,
 ) -> crate::error::Result<BucketResult> {
-    // Parse timezone
-    let tz = parse_tz(tz_name)?;
+    compute_bucket_ref_from_string(input, format, tz_name, interval, week_start)
+        .map(BucketResult::from)
+}
 
-    // Parse timestamp
+/// Like [`compute_bucket_from_string`], returning a [`BucketResultRef`] that
+/// borrows the (trimmed) input and the zone name instead of copying them.
+///
+/// For callers serializing one result per input line, this saves two string
+/// copies per line; the JSON output is the same.
+///
+/// # Examples
+///
+/// ```
+/// use tzbucket_core::compute::compute_bucket_ref_from_string;
+/// use tzbucket_core::{Interval, TimestampFormat};
+///
+/// let line = String::from("2026-03-29T12:00:00Z\n");
+/// let result = compute_bucket_ref_from_string(
+///     &line,
+///     TimestampFormat::Rfc3339,
+///     "Europe/Berlin",
+///     Interval::Day,
+///     None,
+/// )
+/// .unwrap();
+///
+/// assert_eq!(result.input.ts, "2026-03-29T12:00:00Z");
+/// assert_eq!(result.bucket.key, "2026-03-29");
+/// ```
+pub fn compute_bucket_ref_from_string<'a>(
+    input: &'a str,
+    format: TimestampFormat,
+    tz_name: &'a str,
+    interval: Interval,
+    week_start: Option<WeekStart>,
+) -> crate::error::Result<BucketResultRef<'a>> {
+    let tz = parse_tz(tz_name)?;
     let instant = parse_timestamp(input, format)?;
-
-    // Compute bucket
     let bucket = compute_bucket(instant, tz, interval, week_start);
 
-    // Create input timestamp
-    let input_ts = InputTimestamp {
-        ts: input.trim().to_string(),
-        epoch_ms: instant.timestamp_millis(),
-    };
-
-    Ok(BucketResult {
-        input: input_ts,
-        tz: tz_name.to_string(),
+    Ok(BucketResultRef {
+        input: InputTimestampRef {
+            ts: input.trim(),
+            epoch_ms: instant.timestamp_millis(),
+        },
+        tz: tz_name,
         interval,
         bucket,
     })
 }
 
+/// Compute the bucket containing the current instant of `clock`.
+///
+/// The returned [`BucketResult`] records the clock reading as its input
+/// (RFC3339, UTC).
+///
+/// # Examples
+///
+/// ```
+/// use chrono::{TimeZone, Utc};
+/// use tzbucket_core::clock::FixedClock;
+/// use tzbucket_core::compute::compute_current_bucket;
+/// use tzbucket_core::models::Interval;
+///
+/// let clock = FixedClock(Utc.with_ymd_and_hms(2026, 3, 29, 0, 15, 0).unwrap());
+/// let tz = chrono_tz::Europe::Berlin;
+/// let result = compute_current_bucket(&clock, tz, Interval::Day, None);
+///
+/// assert_eq!(result.input.ts, "2026-03-29T00:15:00Z");
+/// assert_eq!(result.bucket.key, "2026-03-29");
+/// ```
+pub fn compute_current_bucket(
+    clock: &dyn Clock,
+    tz: Tz,
+    interval: Interval,
+    week_start: Option<WeekStart>,
+) -> BucketResult {
+    let instant = clock.now();
+
+    BucketResult {
+        input: InputTimestamp {
+            ts: format_rfc3339_utc(&instant),
+            epoch_ms: instant.timestamp_millis(),
+        },
+        tz: tz.to_string(),
+        interval,
+        bucket: compute_bucket(instant, tz, interval, week_start),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use chrono::TimeZone;
+    use std::cell::RefCell;
+
+    #[derive(Default)]
+    struct RecordingSink {
+        counters: RefCell<Vec<(&'static str, u64)>>,
+        histograms: RefCell<Vec<(&'static str, f64)>>,
+    }
+
+    impl MetricsSink for RecordingSink {
+        fn counter(&self, name: &'static str, value: u64) {
+            self.counters.borrow_mut().push((name, value));
+        }
+
+        fn histogram(&self, name: &'static str, value: f64) {
+            self.histograms.borrow_mut().push((name, value));
+        }
+    }
+
+    fn get_berlin_tz() -> Tz {
+        parse_tz("Europe/Berlin").unwrap()
+    }
+
+    #[test]
+    fn batch_buckets_match_scalar_buckets() {
+        let tz = get_berlin_tz();
+        // Every 20 minutes across the 2026 spring-forward and fall-back weeks,
+        // then back in time to leave and re-enter cached buckets.
+        let mut instants: Vec<DateTime<Utc>> = [(3, 27), (10, 23)]
+            .into_iter()
+            .flat_map(|(month, day)| {
+                let start = Utc.with_ymd_and_hms(2026, month, day, 0, 0, 0).unwrap();
+                (0..4 * 72).map(move |i| start + Duration::minutes(20 * i))
+            })
+            .collect();
+        instants.push(instants[10]);
+
+        for interval in [Interval::Hour, Interval::Day, Interval::Week] {
+            let spec = BucketSpec::new(tz, interval).with_week_start(WeekStart::Sunday);
+            let batch = compute_buckets(&instants, &spec);
+            assert_eq!(batch.len(), instants.len());
+            for (instant, bucket) in instants.iter().zip(&batch) {
+                let scalar = compute_bucket(*instant, tz, interval, Some(WeekStart::Sunday));
+                assert_eq!(
+                    (&bucket.key, &bucket.start_local, &bucket.end_utc),
+                    (&scalar.key, &scalar.start_local, &scalar.end_utc),
+                    "{} {}",
+                    interval,
+                    instant
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn typed_bucket_converts_to_string_bucket() {
+        let tz = get_berlin_tz();
+        let instant = Utc.with_ymd_and_hms(2026, 10, 25, 0, 30, 0).unwrap();
+        for interval in [
+            Interval::Minutes(15),
+            Interval::Hour,
+            Interval::Day,
+            Interval::Month,
+            Interval::TrailingMonth {
+                anchor_day: 31,
+                clamp: MonthClamp::PreviousValidDay,
+            },
+            Interval::FixedSeconds(3600),
+        ] {
+            let typed = compute_bucket_typed(instant, tz, interval, None);
+            assert!(typed.contains(instant), "{}", interval);
+            let expected = serde_json::to_value(compute_bucket(instant, tz, interval, None));
+            let converted = serde_json::to_value(Bucket::from(typed));
+            assert_eq!(converted.unwrap(), expected.unwrap(), "{}", interval);
+        }
+
+        let day = compute_bucket_typed(instant, tz, Interval::Day, None);
+        assert_eq!(day.duration(), Duration::hours(25));
+        assert_eq!(
+            day.end_local.naive_local().to_string(),
+            "2026-10-26 00:00:00"
+        );
+    }
+
+    #[test]
+    fn policy_decides_dst_hour_boundaries() {
+        let tz = get_berlin_tz();
+        let policy = |nonexistent, ambiguous| Policy {
+            nonexistent,
+            ambiguous,
+        };
+
+        // Ordinary boundaries need no decision.
+        let instant = Utc.with_ymd_and_hms(2026, 3, 29, 12, 0, 0).unwrap();
+        let bucket =
+            compute_bucket_with_policy(instant, tz, Interval::Day, None, Policy::default())
+                .unwrap();
+        let expected = compute_bucket(instant, tz, Interval::Day, None);
+        assert_eq!(
+            (bucket.start_utc, bucket.end_utc),
+            (expected.start_utc, expected.end_utc)
+        );
+
+        // 01:30 CET: the hour ends at the skipped 02:00.
+        let instant = Utc.with_ymd_and_hms(2026, 3, 29, 0, 30, 0).unwrap();
+        let err = compute_bucket_with_policy(instant, tz, Interval::Hour, None, Policy::default());
+        assert!(matches!(err, Err(TzBucketError::NonexistentTime { .. })));
+        let shifted = policy(NonexistentPolicy::ShiftForward, AmbiguousPolicy::Error);
+        let bucket =
+            compute_bucket_with_policy(instant, tz, Interval::Hour, None, shifted).unwrap();
+        assert_eq!(bucket.key, "2026-03-29T01");
+        assert_eq!(bucket.end_local, "2026-03-29T03:00:00+02:00");
+
+        // 02:30 CEST, the first pass through the repeated hour.
+        let instant = Utc.with_ymd_and_hms(2026, 10, 25, 0, 30, 0).unwrap();
+        let err = compute_bucket_with_policy(instant, tz, Interval::Hour, None, Policy::default());
+        assert!(matches!(err, Err(TzBucketError::AmbiguousTime { .. })));
+        let first = policy(NonexistentPolicy::Error, AmbiguousPolicy::First);
+        let bucket = compute_bucket_with_policy(instant, tz, Interval::Hour, None, first).unwrap();
+        assert_eq!(bucket.key, "2026-10-25T02");
+        assert_eq!(bucket.start_utc, "2026-10-25T00:00:00Z");
+        assert_eq!(bucket.end_utc, "2026-10-25T02:00:00Z");
+        // With the second pass as the start, the instant stays in hour 01.
+        let second = policy(NonexistentPolicy::Error, AmbiguousPolicy::Second);
+        let bucket = compute_bucket_with_policy(instant, tz, Interval::Hour, None, second).unwrap();
+        assert_eq!(bucket.key, "2026-10-25T01");
+        assert_eq!(bucket.start_utc, "2026-10-24T23:00:00Z");
+        assert_eq!(bucket.end_utc, "2026-10-25T01:00:00Z");
+    }
+
+    #[test]
+    fn policy_bucket_contains_instant() {
+        // Goose Bay's 1988 double daylight time moved the clock by two
+        // hours at 00:01; Lord Howe moves by 30 minutes and Chatham at :45.
+        let zones = [
+            "America/Goose_Bay",
+            "America/St_Johns",
+            "Australia/Lord_Howe",
+            "Pacific/Chatham",
+            "Europe/Berlin",
+        ];
+        // The first one reproduces compute_bucket.
+        let policies = [
+            (NonexistentPolicy::ShiftForward, AmbiguousPolicy::First),
+            (NonexistentPolicy::ShiftForward, AmbiguousPolicy::Second),
+            (NonexistentPolicy::Error, AmbiguousPolicy::Second),
+            (NonexistentPolicy::ShiftForward, AmbiguousPolicy::Error),
+        ]
+        .map(|(nonexistent, ambiguous)| Policy {
+            nonexistent,
+            ambiguous,
+        });
+        let start = Utc.with_ymd_and_hms(1985, 1, 1, 0, 0, 0).unwrap();
+        let end = Utc.with_ymd_and_hms(1992, 1, 1, 0, 0, 0).unwrap();
+
+        for name in zones {
+            let tz = parse_tz(name).unwrap();
+            for transition in offset_transitions(tz, start, end) {
+                let mut at = transition - Duration::hours(3);
+                while at < transition + Duration::hours(3) {
+                    for interval in [Interval::Hour, Interval::Hours(3), Interval::Day] {
+                        for policy in policies {
+                            let Ok(bucket) =
+                                compute_bucket_with_policy(at, tz, interval, None, policy)
+                            else {
+                                continue;
+                            };
+                            assert!(
+                                bucket.utc_range().unwrap().contains(&at),
+                                "{} {} {:?} {}: {:?}",
+                                name,
+                                interval,
+                                policy,
+                                at,
+                                bucket
+                            );
+                            if policy == policies[0] {
+                                let expected = compute_bucket(at, tz, interval, None);
+                                assert_eq!(
+                                    (bucket.key, bucket.start_utc, bucket.end_utc),
+                                    (expected.key, expected.start_utc, expected.end_utc),
+                                    "{} {} {}",
+                                    name,
+                                    interval,
+                                    at
+                                );
+                            }
+                        }
+                    }
+                    at += Duration::minutes(10);
+                }
+            }
+        }
+
+        // Goose Bay sprang forward two hours, 00:01 -> 02:01, at 04:01Z; the
+        // instant is in hour 02, which starts at the transition.
+        let tz = parse_tz("America/Goose_Bay").unwrap();
+        let instant = Utc.with_ymd_and_hms(1988, 4, 3, 4, 1, 0).unwrap();
+        let bucket =
+            compute_bucket_with_policy(instant, tz, Interval::Hour, None, policies[0]).unwrap();
+        assert_eq!(bucket.key, "1988-04-03T02");
+        assert_eq!(bucket.start_utc, "1988-04-03T04:01:00Z");
+    }
 
-    fn get_berlin_tz() -> Tz {
-        parse_tz("Europe/Berlin").unwrap()
+    #[test]
+    fn batch_from_strings_fails_per_item() {
+        let spec = BucketSpec::new(get_berlin_tz(), Interval::Day);
+        let results = compute_buckets_from_strings(
+            &["1774744200000", "bogus", " 1774819800000 "],
+            TimestampFormat::EpochMs,
+            &spec,
+        );
+
+        assert_eq!(results.len(), 3);
+        assert!(matches!(results[1], Err(TzBucketError::ParseError(_))));
+        let last = results[2].as_ref().unwrap();
+        assert_eq!(last.input.ts, "1774819800000");
+        assert_eq!(last.tz, "Europe/Berlin");
+        assert_eq!(last.bucket.key, "2026-03-29");
     }
 
     #[test]
@@ -232,6 +1769,7 @@ mod tests {
         assert_eq!(bucket.start_utc, "2026-03-28T23:00:00Z");
         // End UTC: 2026-03-29 22:00Z (23-hour day!)
         assert_eq!(bucket.end_utc, "2026-03-29T22:00:00Z");
+        assert_eq!(bucket.duration_seconds, 23 * 3600);
     }
 
     #[test]
@@ -254,6 +1792,276 @@ mod tests {
         assert_eq!(bucket.start_utc, "2026-10-24T22:00:00Z");
         // End UTC: 2026-10-25 23:00Z (25-hour day!)
         assert_eq!(bucket.end_utc, "2026-10-25T23:00:00Z");
+        assert_eq!(bucket.duration_seconds, 25 * 3600);
+    }
+
+    #[test]
+    fn hour_bucket_normal_hour() {
+        // 2026-03-28 12:34 UTC = 2026-03-28 13:34 Berlin
+        let instant = Utc
+            .with_ymd_and_hms(2026, 3, 28, 12, 34, 0)
+            .single()
+            .unwrap();
+        let bucket = compute_bucket(instant, get_berlin_tz(), Interval::Hour, None);
+
+        assert_eq!(bucket.key, "2026-03-28T13");
+        assert_eq!(bucket.start_local, "2026-03-28T13:00:00+01:00");
+        assert_eq!(bucket.end_local, "2026-03-28T14:00:00+01:00");
+        assert_eq!(bucket.start_utc, "2026-03-28T12:00:00Z");
+        assert_eq!(bucket.end_utc, "2026-03-28T13:00:00Z");
+    }
+
+    #[test]
+    fn hour_bucket_dst_spring_forward_skips_hour() {
+        // 01:59:59Z is the last instant before the 02:00 -> 03:00 jump; the
+        // next second is 03:00 local, so no instant has a T02 bucket.
+        let tz = get_berlin_tz();
+        let before = Utc.with_ymd_and_hms(2026, 3, 29, 0, 59, 59).unwrap();
+        let after = Utc.with_ymd_and_hms(2026, 3, 29, 1, 0, 0).unwrap();
+
+        let before = compute_bucket(before, tz, Interval::Hour, None);
+        assert_eq!(before.key, "2026-03-29T01");
+        assert_eq!(before.end_utc, "2026-03-29T01:00:00Z");
+
+        let after = compute_bucket(after, tz, Interval::Hour, None);
+        assert_eq!(after.key, "2026-03-29T03");
+        assert_eq!(after.start_local, "2026-03-29T03:00:00+02:00");
+        assert_eq!(after.start_utc, "2026-03-29T01:00:00Z");
+        assert_eq!(after.end_utc, "2026-03-29T02:00:00Z");
+    }
+
+    #[test]
+    fn hour_bucket_dst_fall_back_spans_both_occurrences() {
+        // Berlin repeats 02:00-03:00 on 2026-10-25: 00:00Z-01:00Z (+02:00)
+        // and 01:00Z-02:00Z (+01:00) are both local 02:xx.
+        let tz = get_berlin_tz();
+        for (hour, minute) in [(0, 30), (1, 30)] {
+            let instant = Utc.with_ymd_and_hms(2026, 10, 25, hour, minute, 0).unwrap();
+            let bucket = compute_bucket(instant, tz, Interval::Hour, None);
+
+            assert_eq!(bucket.key, "2026-10-25T02");
+            assert_eq!(bucket.start_local, "2026-10-25T02:00:00+02:00");
+            assert_eq!(bucket.end_local, "2026-10-25T03:00:00+01:00");
+            assert_eq!(bucket.start_utc, "2026-10-25T00:00:00Z");
+            assert_eq!(bucket.end_utc, "2026-10-25T02:00:00Z");
+        }
+    }
+
+    #[test]
+    fn hour_bucket_starts_at_transition_inside_hour() {
+        // Chatham skips 02:45 -> 03:45 (+12:45 -> +13:45) at 1990-10-06T14:00Z,
+        // so local hour 03 starts at the transition, not at a shifted 04:00.
+        let tz = parse_tz("Pacific/Chatham").unwrap();
+        let instant = Utc.with_ymd_and_hms(1990, 10, 6, 14, 0, 0).unwrap();
+        let bucket = compute_bucket(instant, tz, Interval::Hour, None);
+
+        assert_eq!(bucket.key, "1990-10-07T03");
+        assert_eq!(bucket.start_local, "1990-10-07T03:45:00+13:45");
+        assert_eq!(bucket.start_utc, "1990-10-06T14:00:00Z");
+        assert_eq!(bucket.end_utc, "1990-10-06T14:15:00Z");
+    }
+
+    #[test]
+    fn hour_bucket_second_pass_of_partial_repeat() {
+        // Chatham falls back 03:45 -> 02:45 (+13:45 -> +12:45) at
+        // 1990-03-17T14:00Z. Local 03:00 is repeated and resolves to its
+        // first occurrence, so the second pass through 02:45-03:00 belongs
+        // to hour 03, which runs on to 04:00 +12:45.
+        let tz = parse_tz("Pacific/Chatham").unwrap();
+        let instant = Utc.with_ymd_and_hms(1990, 3, 17, 14, 5, 0).unwrap();
+        let bucket = compute_bucket(instant, tz, Interval::Hour, None);
+
+        assert_eq!(bucket.key, "1990-03-18T03");
+        assert_eq!(bucket.start_utc, "1990-03-17T13:15:00Z");
+        assert_eq!(bucket.end_utc, "1990-03-17T15:15:00Z");
+        assert_eq!(bucket.start_local, "1990-03-18T03:00:00+13:45");
+    }
+
+    #[test]
+    fn day_bucket_second_pass_across_midnight() {
+        // St. John's fell back from 00:01 to 23:01 on 2010-11-07, repeating
+        // local midnight. 02:45Z is 23:14 on the 6th again, after the 6th
+        // ended at the first midnight: it belongs to the 7th.
+        let tz = parse_tz("America/St_Johns").unwrap();
+        let instant = Utc.with_ymd_and_hms(2010, 11, 7, 2, 45, 0).unwrap();
+        let bucket = compute_bucket(instant, tz, Interval::Day, None);
+
+        assert_eq!(bucket.key, "2010-11-07");
+        assert_eq!(bucket.start_utc, "2010-11-07T02:30:00Z");
+        assert_eq!(bucket.end_utc, "2010-11-08T03:30:00Z");
+    }
+
+    #[test]
+    fn buckets_tile_around_transitions_off_midnight() {
+        // Zones whose repeated stretches cross a bucket boundary: 00:01
+        // fall backs (St. John's, Goose Bay, Moncton), Goose Bay's 1988
+        // double fall back, Hong Kong's midnight DST and Chatham's :45
+        // transitions.
+        let zones = [
+            "America/St_Johns",
+            "America/Goose_Bay",
+            "America/Moncton",
+            "Asia/Hong_Kong",
+            "Pacific/Chatham",
+        ];
+        let intervals = [
+            Interval::Hour,
+            Interval::Hours(6),
+            Interval::Day,
+            Interval::Month,
+            Interval::TrailingMonth {
+                anchor_day: 7,
+                clamp: MonthClamp::PreviousValidDay,
+            },
+        ];
+        let span = |bucket: &Bucket| {
+            (
+                bucket.key.clone(),
+                bucket.start_utc.clone(),
+                bucket.end_utc.clone(),
+            )
+        };
+        let start = Utc.with_ymd_and_hms(1940, 1, 1, 0, 0, 0).unwrap();
+        let end = Utc.with_ymd_and_hms(2012, 1, 1, 0, 0, 0).unwrap();
+
+        for name in zones {
+            let tz = parse_tz(name).unwrap();
+            for transition in offset_transitions(tz, start, end) {
+                for interval in intervals {
+                    let from_key = |bucket: &Bucket| {
+                        crate::navigate::bucket_for_key(&bucket.key, tz, interval, None)
+                            .map(|b| span(&b))
+                            .ok()
+                    };
+                    let mut at = transition - Duration::hours(3);
+                    let mut bucket = compute_bucket(at, tz, interval, None);
+                    assert_eq!(from_key(&bucket), Some(span(&bucket)), "{}", name);
+                    while at < transition + Duration::hours(3) {
+                        let range = bucket.utc_range().unwrap();
+                        if at >= range.end {
+                            // The next bucket starts where this one ends.
+                            let next = compute_bucket(range.end, tz, interval, None);
+                            assert_eq!(next.start_utc, bucket.end_utc, "{} {}", name, interval);
+                            assert_eq!(from_key(&next), Some(span(&next)), "{}", name);
+                            bucket = next;
+                            continue;
+                        }
+                        assert!(range.start <= at, "{} {} {}", name, interval, at);
+                        assert_eq!(
+                            span(&compute_bucket(at, tz, interval, None)),
+                            span(&bucket),
+                            "{} {} {}",
+                            name,
+                            interval,
+                            at
+                        );
+                        at += Duration::minutes(5);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn minutes_buckets_skip_spring_forward_gap() {
+        let bucket = |h, m| {
+            let instant = Utc.with_ymd_and_hms(2026, 3, 29, h, m, 0).unwrap();
+            compute_bucket(instant, get_berlin_tz(), Interval::Minutes(15), None)
+        };
+
+        let before = bucket(0, 50);
+        assert_eq!(before.key, "2026-03-29T01:45+01:00");
+        assert_eq!(before.end_utc, "2026-03-29T01:00:00Z");
+        // Local 02:00-03:00 does not exist: the next window starts at 03:00.
+        let after = bucket(1, 0);
+        assert_eq!(after.key, "2026-03-29T03:00+02:00");
+        assert_eq!(after.start_utc, "2026-03-29T01:00:00Z");
+    }
+
+    #[test]
+    fn minutes_buckets_split_repeated_windows_by_offset() {
+        let bucket = |h, m, minutes| {
+            let instant = Utc.with_ymd_and_hms(2026, 10, 25, h, m, 0).unwrap();
+            compute_bucket(instant, get_berlin_tz(), Interval::Minutes(minutes), None)
+        };
+
+        // Local 02:30 happens at 00:30Z (+02:00) and 01:30Z (+01:00).
+        let first = bucket(0, 35, 15);
+        let second = bucket(1, 35, 15);
+        assert_eq!(first.key, "2026-10-25T02:30+02:00");
+        assert_eq!(first.start_utc, "2026-10-25T00:30:00Z");
+        assert_eq!(first.end_utc, "2026-10-25T00:45:00Z");
+        assert_eq!(second.key, "2026-10-25T02:30+01:00");
+        assert_eq!(second.start_utc, "2026-10-25T01:30:00Z");
+
+        // The clock jumps back to the start of the 02:00-04:00 window: two passes.
+        let first = bucket(0, 30, 120);
+        assert_eq!(first.start_utc, "2026-10-25T00:00:00Z");
+        assert_eq!(first.end_utc, "2026-10-25T01:00:00Z");
+        let second = bucket(1, 30, 120);
+        assert_eq!(second.key, "2026-10-25T02:00+01:00");
+        assert_eq!(second.end_utc, "2026-10-25T03:00:00Z");
+    }
+
+    #[test]
+    fn minutes_windows_spanning_a_transition_stretch_or_shrink() {
+        let tz = get_berlin_tz();
+        let six_hours = |instant| compute_bucket(instant, tz, Interval::Minutes(360), None);
+
+        // 00:00-06:00 local is 7 hours long on the fall-back day...
+        let bucket = six_hours(Utc.with_ymd_and_hms(2026, 10, 25, 2, 0, 0).unwrap());
+        assert_eq!(bucket.key, "2026-10-25T00:00+02:00");
+        assert_eq!(bucket.start_utc, "2026-10-24T22:00:00Z");
+        assert_eq!(bucket.end_utc, "2026-10-25T05:00:00Z");
+
+        // ...and 5 hours long on the spring-forward day.
+        let bucket = six_hours(Utc.with_ymd_and_hms(2026, 3, 29, 2, 0, 0).unwrap());
+        assert_eq!(bucket.start_utc, "2026-03-28T23:00:00Z");
+        assert_eq!(bucket.end_utc, "2026-03-29T04:00:00Z");
+    }
+
+    #[test]
+    fn minutes_bucket_cut_by_partial_gap() {
+        // Chatham springs forward 02:45 -> 03:45 (+12:45 -> +13:45) at
+        // 1989-10-07T14:00Z; the hour window 03:00-04:00 starts at 03:45.
+        let tz = parse_tz("Pacific/Chatham").unwrap();
+        let instant = Utc.with_ymd_and_hms(1989, 10, 7, 14, 5, 0).unwrap();
+        let bucket = compute_bucket(instant, tz, Interval::Minutes(60), None);
+
+        assert_eq!(bucket.key, "1989-10-08T03:45+13:45");
+        assert_eq!(bucket.start_utc, "1989-10-07T14:00:00Z");
+        assert_eq!(bucket.end_utc, "1989-10-07T14:15:00Z");
+    }
+
+    #[test]
+    fn hours_windows_on_transition_days() {
+        let tz = get_berlin_tz();
+        let shifts = |start: DateTime<Utc>| {
+            let mut cursor = start;
+            let mut shifts = Vec::new();
+            for _ in 0..4 {
+                let bucket = compute_bucket(cursor, tz, Interval::Hours(6), None);
+                let end = DateTime::parse_from_rfc3339(&bucket.end_utc).unwrap();
+                let hours = (end.with_timezone(&Utc) - cursor).num_hours();
+                shifts.push((bucket.key.to_string(), hours));
+                cursor = end.with_timezone(&Utc);
+            }
+            shifts
+        };
+
+        let spring = shifts(Utc.with_ymd_and_hms(2026, 3, 28, 23, 0, 0).unwrap());
+        assert_eq!(
+            spring,
+            [
+                ("2026-03-29T00".to_string(), 5),
+                ("2026-03-29T06".to_string(), 6),
+                ("2026-03-29T12".to_string(), 6),
+                ("2026-03-29T18".to_string(), 6),
+            ]
+        );
+        let fall = shifts(Utc.with_ymd_and_hms(2026, 10, 24, 22, 0, 0).unwrap());
+        assert_eq!(fall[0], ("2026-10-25T00".to_string(), 7));
+        assert_eq!(fall[3], ("2026-10-25T18".to_string(), 6));
     }
 
     #[test]
@@ -290,6 +2098,106 @@ mod tests {
         assert!(bucket.end_local.starts_with("2026-04-05"));
     }
 
+    #[test]
+    fn iso_week_keys_use_the_week_numbering_year() {
+        let key = |y, m, d, week_start| {
+            let instant = Utc.with_ymd_and_hms(y, m, d, 12, 0, 0).single().unwrap();
+            compute_bucket_with_week_key(
+                instant,
+                get_berlin_tz(),
+                Interval::Week,
+                Some(week_start),
+                WeekKeyFormat::Iso,
+            )
+            .key
+        };
+
+        assert_eq!(key(2026, 3, 29, WeekStart::Monday), "2026-W13");
+        // The week of 2024-12-30 belongs to 2025; 2026 has 53 ISO weeks.
+        assert_eq!(key(2024, 12, 31, WeekStart::Monday), "2025-W01");
+        assert_eq!(key(2027, 1, 1, WeekStart::Monday), "2026-W53");
+        // Sunday 2026-12-27 starts the week of Monday 2026-12-28.
+        assert_eq!(key(2027, 1, 2, WeekStart::Sunday), "2026-W53");
+        assert_eq!(key(2027, 1, 3, WeekStart::Sunday), "2027-W01");
+    }
+
+    #[test]
+    fn week_schemes_number_weeks_around_new_year() {
+        let week = |y, m, d, scheme| {
+            let instant = Utc.with_ymd_and_hms(y, m, d, 12, 0, 0).single().unwrap();
+            let bucket =
+                compute_bucket_with_week_scheme(instant, chrono_tz::UTC, Interval::Week, scheme);
+            (bucket.key.to_string(), bucket.start_local[..10].to_string())
+        };
+        let expect = |key: &str, start: &str| (key.to_string(), start.to_string());
+
+        // 2025 starts on a Wednesday: every scheme's week 1 holds January 1.
+        assert_eq!(
+            week(2025, 1, 1, WeekScheme::Mmwr),
+            expect("2025-W01", "2024-12-29")
+        );
+        assert_eq!(
+            week(2025, 1, 1, WeekScheme::Iso),
+            expect("2025-W01", "2024-12-30")
+        );
+        // 2022 starts on a Saturday: MMWR and ISO put January 1 in the last
+        // week of 2021, the broadcast calendar in week 1 of 2022.
+        assert_eq!(
+            week(2022, 1, 1, WeekScheme::Mmwr),
+            expect("2021-W52", "2021-12-26")
+        );
+        assert_eq!(
+            week(2022, 1, 1, WeekScheme::Iso),
+            expect("2021-W52", "2021-12-27")
+        );
+        assert_eq!(
+            week(2022, 1, 1, WeekScheme::Broadcast),
+            expect("2022-W01", "2021-12-27")
+        );
+        // 2020 has 53 MMWR weeks; broadcast 2017 ran from 2016-12-26 to
+        // 2017-12-31, 53 weeks.
+        assert_eq!(
+            week(2021, 1, 2, WeekScheme::Mmwr),
+            expect("2020-W53", "2020-12-27")
+        );
+        assert_eq!(
+            week(2017, 12, 31, WeekScheme::Broadcast),
+            expect("2017-W53", "2017-12-25")
+        );
+
+        // Only week buckets depend on the scheme.
+        let instant = Utc.with_ymd_and_hms(2022, 1, 1, 12, 0, 0).unwrap();
+        let day = compute_bucket_with_week_scheme(
+            instant,
+            chrono_tz::UTC,
+            Interval::Day,
+            WeekScheme::Mmwr,
+        );
+        assert_eq!(day.key, "2022-01-01");
+    }
+
+    #[test]
+    fn days_buckets_align_to_anchor() {
+        let tz = get_berlin_tz();
+        let interval = Interval::Days {
+            n: 14,
+            anchor: NaiveDate::from_ymd_opt(2026, 1, 5).unwrap(),
+        };
+
+        // The period 2026-03-16 to 2026-03-30 contains the spring forward.
+        let instant = Utc.with_ymd_and_hms(2026, 3, 29, 12, 0, 0).unwrap();
+        let bucket = compute_bucket(instant, tz, interval, None);
+        assert_eq!(bucket.key, "2026-03-16");
+        assert_eq!(bucket.start_utc, "2026-03-15T23:00:00Z");
+        assert_eq!(bucket.end_utc, "2026-03-29T22:00:00Z");
+
+        // Periods extend before the anchor too.
+        let instant = Utc.with_ymd_and_hms(2026, 1, 1, 12, 0, 0).unwrap();
+        let bucket = compute_bucket(instant, tz, interval, None);
+        assert_eq!(bucket.key, "2025-12-22");
+        assert!(bucket.end_local.starts_with("2026-01-05"));
+    }
+
     #[test]
     fn month_bucket() {
         let instant = Utc
@@ -318,6 +2226,469 @@ mod tests {
         assert!(bucket.end_local.starts_with("2027-01-01"));
     }
 
+    #[test]
+    fn quarter_bucket_spans_dst_transition() {
+        // Q1 2026 in Berlin starts in CET and ends in CEST.
+        let instant = Utc.with_ymd_and_hms(2026, 3, 29, 12, 0, 0).unwrap();
+        let bucket = compute_bucket(instant, get_berlin_tz(), Interval::Quarter, None);
+
+        assert_eq!(bucket.key, "2026-Q1");
+        assert_eq!(bucket.start_local, "2026-01-01T00:00:00+01:00");
+        assert_eq!(bucket.end_local, "2026-04-01T00:00:00+02:00");
+        assert_eq!(bucket.start_utc, "2025-12-31T23:00:00Z");
+        assert_eq!(bucket.end_utc, "2026-03-31T22:00:00Z");
+    }
+
+    #[test]
+    fn quarter_bucket_q4_ends_next_year() {
+        let date = NaiveDate::from_ymd_opt(2026, 11, 15).unwrap();
+        let (start, end, key) = bucket_dates(date, Interval::Quarter, WeekStart::Monday).unwrap();
+
+        assert_eq!(key, "2026-Q4");
+        assert_eq!(start, NaiveDate::from_ymd_opt(2026, 10, 1).unwrap());
+        assert_eq!(end, NaiveDate::from_ymd_opt(2027, 1, 1).unwrap());
+    }
+
+    #[test]
+    fn fiscal_quarters_are_named_after_the_closing_year() {
+        let interval = Interval::FiscalQuarter { start_month: 10 };
+        let cases = [
+            ((2026, 10, 1), "FY2027-Q1", (2026, 10, 1), (2027, 1, 1)),
+            ((2026, 12, 31), "FY2027-Q1", (2026, 10, 1), (2027, 1, 1)),
+            ((2027, 2, 14), "FY2027-Q2", (2027, 1, 1), (2027, 4, 1)),
+            ((2026, 9, 30), "FY2026-Q4", (2026, 7, 1), (2026, 10, 1)),
+        ];
+        for ((y, m, d), key, (sy, sm, sd), (ey, em, ed)) in cases {
+            let date = NaiveDate::from_ymd_opt(y, m, d).unwrap();
+            let (start, end, actual) = bucket_dates(date, interval, WeekStart::Monday).unwrap();
+            assert_eq!(actual, key, "{}", date);
+            assert_eq!(start, NaiveDate::from_ymd_opt(sy, sm, sd).unwrap());
+            assert_eq!(end, NaiveDate::from_ymd_opt(ey, em, ed).unwrap());
+        }
+
+        // A January start gives the calendar quarters.
+        let date = NaiveDate::from_ymd_opt(2026, 11, 15).unwrap();
+        let calendar = Interval::FiscalQuarter { start_month: 1 };
+        let (start, end, key) = bucket_dates(date, calendar, WeekStart::Monday).unwrap();
+        assert_eq!(key, "FY2026-Q4");
+        assert_eq!(
+            (start, end),
+            bucket_dates(date, Interval::Quarter, WeekStart::Monday)
+                .map(|(start, end, _)| (start, end))
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn day_offset_moves_day_and_week_boundaries() {
+        let six = NaiveTime::from_hms_opt(6, 0, 0).unwrap();
+        let bucket = |instant, interval| {
+            compute_bucket_with_day_offset(
+                instant,
+                get_berlin_tz(),
+                interval,
+                None,
+                six,
+                Policy::default(),
+            )
+            .unwrap()
+        };
+
+        // 05:59 and 06:00 local on Tuesday 2026-01-06.
+        let before = Utc.with_ymd_and_hms(2026, 1, 6, 4, 59, 0).unwrap();
+        let after = Utc.with_ymd_and_hms(2026, 1, 6, 5, 0, 0).unwrap();
+        assert_eq!(bucket(before, Interval::Day).key, "2026-01-05");
+        assert_eq!(bucket(after, Interval::Day).key, "2026-01-06");
+        assert_eq!(
+            bucket(after, Interval::Day).end_local,
+            "2026-01-07T06:00:00+01:00"
+        );
+
+        // Monday 05:59 still belongs to the previous week.
+        let monday = Utc.with_ymd_and_hms(2026, 1, 5, 4, 59, 0).unwrap();
+        let week = bucket(monday, Interval::Week);
+        assert_eq!(week.key, "2025-12-29");
+        assert_eq!(week.start_local, "2025-12-29T06:00:00+01:00");
+        assert_eq!(week.end_local, "2026-01-05T06:00:00+01:00");
+
+        let month = bucket(after, Interval::Month);
+        assert_eq!(month.start_local, "2026-01-01T06:00:00+01:00");
+        assert_eq!(month.end_local, "2026-02-01T06:00:00+01:00");
+    }
+
+    #[test]
+    fn day_offset_boundaries_follow_policy() {
+        let half_past_two = NaiveTime::from_hms_opt(2, 30, 0).unwrap();
+        let bucket = |instant, policy| {
+            compute_bucket_with_day_offset(
+                instant,
+                get_berlin_tz(),
+                Interval::Day,
+                None,
+                half_past_two,
+                policy,
+            )
+        };
+        let lenient = |ambiguous| Policy {
+            nonexistent: NonexistentPolicy::ShiftForward,
+            ambiguous,
+        };
+
+        // 02:30 does not exist on 2026-03-29.
+        let spring = Utc.with_ymd_and_hms(2026, 3, 28, 12, 0, 0).unwrap();
+        assert!(matches!(
+            bucket(spring, Policy::default()),
+            Err(TzBucketError::NonexistentTime { .. })
+        ));
+        let day = bucket(spring, lenient(AmbiguousPolicy::First)).unwrap();
+        assert_eq!(day.start_local, "2026-03-28T02:30:00+01:00");
+        assert_eq!(day.end_local, "2026-03-29T03:30:00+02:00");
+
+        // 02:30 occurs twice on 2026-10-25. With `second` the day starts at
+        // the repeat, so the first pass of 02:45 is still the day before.
+        let first_pass = Utc.with_ymd_and_hms(2026, 10, 25, 0, 45, 0).unwrap();
+        let day = bucket(first_pass, lenient(AmbiguousPolicy::First)).unwrap();
+        assert_eq!(day.key, "2026-10-25");
+        assert_eq!(day.start_local, "2026-10-25T02:30:00+02:00");
+        let day = bucket(first_pass, lenient(AmbiguousPolicy::Second)).unwrap();
+        assert_eq!(day.key, "2026-10-24");
+        assert_eq!(day.end_local, "2026-10-25T02:30:00+01:00");
+    }
+
+    #[test]
+    fn buckets_for_intervals_match_single_interval_buckets() {
+        let tz = get_berlin_tz();
+        let intervals = [
+            Interval::Month,
+            Interval::Day,
+            Interval::Week,
+            Interval::Hour,
+            Interval::Day,
+        ];
+        // The Sunday of the spring-forward week, in the shortened day.
+        let instant = Utc.with_ymd_and_hms(2026, 3, 29, 21, 30, 0).unwrap();
+        let buckets = compute_buckets_for_intervals(instant, tz, &intervals, None);
+
+        assert_eq!(
+            buckets.keys().copied().collect::<Vec<_>>(),
+            [
+                Interval::Hour,
+                Interval::Day,
+                Interval::Week,
+                Interval::Month
+            ]
+        );
+        for (&interval, bucket) in &buckets {
+            let single = compute_bucket(instant, tz, interval, None);
+            assert_eq!(
+                (&bucket.key, &bucket.start_utc, &bucket.end_utc),
+                (&single.key, &single.start_utc, &single.end_utc)
+            );
+        }
+        assert_eq!(buckets[&Interval::Week].key, "2026-03-23");
+    }
+
+    #[test]
+    fn day_offset_rejects_sub_day_intervals() {
+        let instant = Utc.with_ymd_and_hms(2026, 1, 6, 12, 0, 0).unwrap();
+        let six = NaiveTime::from_hms_opt(6, 0, 0).unwrap();
+        for interval in [
+            Interval::Hour,
+            Interval::Minutes(15),
+            Interval::FixedSeconds(60),
+        ] {
+            let result = compute_bucket_with_day_offset(
+                instant,
+                get_berlin_tz(),
+                interval,
+                None,
+                six,
+                Policy::default(),
+            );
+            assert!(matches!(result, Err(TzBucketError::ParseError(_))));
+        }
+    }
+
+    #[test]
+    fn fiscal_quarter_spans_dst_transition() {
+        // April-start fiscal year: Q3 of FY2027 is Oct-Dec 2026 and contains
+        // Berlin's fall back.
+        let instant = Utc.with_ymd_and_hms(2026, 11, 2, 12, 0, 0).unwrap();
+        let interval = Interval::FiscalQuarter { start_month: 4 };
+        let bucket = compute_bucket(instant, get_berlin_tz(), interval, None);
+
+        assert_eq!(bucket.key, "FY2027-Q3");
+        assert_eq!(bucket.start_local, "2026-10-01T00:00:00+02:00");
+        assert_eq!(bucket.end_local, "2027-01-01T00:00:00+01:00");
+        assert_eq!(bucket.start_utc, "2026-09-30T22:00:00Z");
+        assert_eq!(bucket.end_utc, "2026-12-31T23:00:00Z");
+    }
+
+    #[test]
+    fn year_bucket_boundaries_convert_independently() {
+        // New York: both Jan 1 midnights are EST, but the year still spans
+        // both DST transitions.
+        let instant = Utc.with_ymd_and_hms(2026, 7, 4, 12, 0, 0).unwrap();
+        let tz = parse_tz("America/New_York").unwrap();
+        let bucket = compute_bucket(instant, tz, Interval::Year, None);
+
+        assert_eq!(bucket.key, "2026");
+        assert_eq!(bucket.start_local, "2026-01-01T00:00:00-05:00");
+        assert_eq!(bucket.end_local, "2027-01-01T00:00:00-05:00");
+        assert_eq!(bucket.start_utc, "2026-01-01T05:00:00Z");
+        assert_eq!(bucket.end_utc, "2027-01-01T05:00:00Z");
+    }
+
+    #[test]
+    fn trailing_month_bucket_spans_anchor_to_anchor() {
+        // 2026-03-20 in Berlin is after the 15th: period Mar 15 - Apr 15,
+        // crossing the spring-forward transition on Mar 29.
+        let instant = Utc
+            .with_ymd_and_hms(2026, 3, 20, 12, 0, 0)
+            .single()
+            .unwrap();
+        let bucket = compute_bucket(
+            instant,
+            get_berlin_tz(),
+            Interval::TrailingMonth {
+                anchor_day: 15,
+                clamp: MonthClamp::PreviousValidDay,
+            },
+            None,
+        );
+
+        assert_eq!(bucket.key, "2026-03-15");
+        assert_eq!(bucket.start_local, "2026-03-15T00:00:00+01:00");
+        assert_eq!(bucket.end_local, "2026-04-15T00:00:00+02:00");
+        assert_eq!(bucket.month_clamp, None);
+    }
+
+    #[test]
+    fn trailing_month_anchor_clamps_to_month_end() {
+        let date = |m, d| NaiveDate::from_ymd_opt(2026, m, d).unwrap();
+        let interval = Interval::TrailingMonth {
+            anchor_day: 31,
+            clamp: MonthClamp::PreviousValidDay,
+        };
+
+        // Before the clamped Feb anchor: period Jan 31 - Feb 28.
+        let (start, end, _) = bucket_dates(date(2, 27), interval, WeekStart::Monday).unwrap();
+        assert_eq!((start, end), (date(1, 31), date(2, 28)));
+
+        // On/after it: period Feb 28 - Mar 31.
+        let (start, end, key) = bucket_dates(date(3, 15), interval, WeekStart::Monday).unwrap();
+        assert_eq!((start, end), (date(2, 28), date(3, 31)));
+        assert_eq!(key, "2026-02-28");
+    }
+
+    #[test]
+    fn trailing_month_anchor_rolls_forward() {
+        let date = |m, d| NaiveDate::from_ymd_opt(2026, m, d).unwrap();
+        let interval = Interval::TrailingMonth {
+            anchor_day: 31,
+            clamp: MonthClamp::RollForward,
+        };
+
+        // The Feb anchor rolls to Mar 1: period Jan 31 - Mar 1.
+        let (start, end, _) = bucket_dates(date(2, 28), interval, WeekStart::Monday).unwrap();
+        assert_eq!((start, end), (date(1, 31), date(3, 1)));
+
+        // Then Mar 1 - Mar 31, and the Apr anchor rolls to May 1.
+        let (start, end, _) = bucket_dates(date(3, 1), interval, WeekStart::Monday).unwrap();
+        assert_eq!((start, end), (date(3, 1), date(3, 31)));
+        let (start, end, _) = bucket_dates(date(4, 30), interval, WeekStart::Monday).unwrap();
+        assert_eq!((start, end), (date(3, 31), date(5, 1)));
+    }
+
+    #[test]
+    fn trailing_month_bucket_reports_applied_clamp() {
+        let tz = get_berlin_tz();
+        let instant = Utc
+            .with_ymd_and_hms(2026, 2, 10, 12, 0, 0)
+            .single()
+            .unwrap();
+
+        for clamp in [MonthClamp::PreviousValidDay, MonthClamp::RollForward] {
+            let interval = Interval::TrailingMonth {
+                anchor_day: 30,
+                clamp,
+            };
+            assert_eq!(
+                compute_bucket(instant, tz, interval, None).month_clamp,
+                Some(clamp)
+            );
+        }
+
+        let unclamped = Interval::TrailingMonth {
+            anchor_day: 28,
+            clamp: MonthClamp::RollForward,
+        };
+        assert_eq!(
+            compute_bucket(instant, tz, unclamped, None).month_clamp,
+            None
+        );
+    }
+
+    #[test]
+    fn fixed_seconds_bucket_is_epoch_aligned() {
+        let instant = Utc
+            .with_ymd_and_hms(2026, 3, 29, 1, 7, 30)
+            .single()
+            .unwrap();
+        let bucket = compute_bucket(instant, get_berlin_tz(), Interval::FixedSeconds(300), None);
+
+        assert_eq!(bucket.key, "2026-03-29T01:05:00Z");
+        assert_eq!(bucket.start_utc, "2026-03-29T01:05:00Z");
+        assert_eq!(bucket.end_utc, "2026-03-29T01:10:00Z");
+        // Local rendering crosses the spring-forward gap without stretching.
+        assert_eq!(bucket.start_local, "2026-03-29T03:05:00+02:00");
+        assert_eq!(
+            bucket_dates(
+                instant.date_naive(),
+                Interval::FixedSeconds(300),
+                WeekStart::Monday
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn fixed_seconds_bucket_before_epoch() {
+        let instant = Utc
+            .with_ymd_and_hms(1969, 12, 31, 23, 59, 59)
+            .single()
+            .unwrap();
+        let bucket = compute_bucket(instant, Tz::UTC, Interval::FixedSeconds(3600), None);
+
+        assert_eq!(bucket.start_utc, "1969-12-31T23:00:00Z");
+        assert_eq!(bucket.end_utc, "1970-01-01T00:00:00Z");
+    }
+
+    #[test]
+    fn fixed_offset_zones_match_the_general_path() {
+        // Abidjan and Panama have kept one offset since before 1920, so the
+        // general path computes the same boundaries as the fast path for
+        // UTC and Etc/GMT+5.
+        let instant = Utc
+            .with_ymd_and_hms(2026, 12, 31, 22, 30, 0)
+            .single()
+            .unwrap();
+        for (fixed, general) in [
+            (Tz::UTC, Tz::Africa__Abidjan),
+            (Tz::Etc__GMTPlus5, Tz::America__Panama),
+        ] {
+            for interval in [
+                Interval::Hour,
+                Interval::Hours(6),
+                Interval::Day,
+                Interval::Week,
+                Interval::Month,
+                Interval::Quarter,
+                Interval::Year,
+                Interval::TrailingMonth {
+                    anchor_day: 31,
+                    clamp: MonthClamp::PreviousValidDay,
+                },
+            ] {
+                let fast = compute_bucket(instant, fixed, interval, None);
+                let slow = compute_bucket(instant, general, interval, None);
+                assert_eq!(
+                    (&fast.key, &fast.start_utc, &fast.end_utc, fast.month_clamp),
+                    (&slow.key, &slow.start_utc, &slow.end_utc, slow.month_clamp),
+                    "{} {}",
+                    fixed,
+                    interval
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn rounding_uses_elapsed_time_and_rounds_ties_up() {
+        let tz = get_berlin_tz();
+        let at = |h, m| {
+            Utc.with_ymd_and_hms(2026, 10, 25, h, m, 0)
+                .single()
+                .unwrap()
+        };
+        // The 25-hour fall-back day runs from 22:00Z to 23:00Z the next day;
+        // its midpoint is 10:30Z (11:30 local).
+        let start = Utc
+            .with_ymd_and_hms(2026, 10, 24, 22, 0, 0)
+            .single()
+            .unwrap();
+        let end = at(23, 0);
+
+        assert_eq!(floor_to_bucket(at(10, 30), tz, Interval::Day, None), start);
+        assert_eq!(round_to_bucket(at(10, 29), tz, Interval::Day, None), start);
+        assert_eq!(round_to_bucket(at(10, 30), tz, Interval::Day, None), end);
+        assert_eq!(ceil_to_bucket(start, tz, Interval::Day, None), start);
+        assert_eq!(ceil_to_bucket(at(0, 0), tz, Interval::Day, None), end);
+    }
+
+    #[test]
+    fn columnar_matches_row_api() {
+        let tz = get_berlin_tz();
+        // Unsorted, across the spring-forward day, including a repeat.
+        let instants = [
+            "2026-03-29T21:30:00Z",
+            "2026-03-28T22:30:00Z",
+            "2026-03-29T00:30:00Z",
+            "2026-03-29T00:45:00Z",
+            "2026-03-29T22:00:00Z",
+        ];
+        let epoch_ms: Vec<i64> = instants
+            .iter()
+            .map(|ts| {
+                parse_timestamp(ts, TimestampFormat::Rfc3339)
+                    .unwrap()
+                    .timestamp_millis()
+            })
+            .collect();
+
+        for interval in [Interval::Day, Interval::Week, Interval::FixedSeconds(3600)] {
+            let columns = compute_buckets_columnar(&epoch_ms, tz, interval, None).unwrap();
+            assert_eq!(columns.len(), instants.len());
+
+            for (i, &ms) in epoch_ms.iter().enumerate() {
+                let instant = DateTime::from_timestamp_millis(ms).unwrap();
+                let bucket = compute_bucket(instant, tz, interval, None);
+                let parse = |ts: &str| {
+                    parse_timestamp(ts, TimestampFormat::Rfc3339)
+                        .unwrap()
+                        .timestamp_millis()
+                };
+                assert_eq!(columns.keys[i], bucket.key);
+                assert_eq!(columns.start_ms[i], parse(&bucket.start_utc));
+                assert_eq!(columns.end_ms[i], parse(&bucket.end_utc));
+            }
+        }
+    }
+
+    #[test]
+    fn columnar_rejects_out_of_range_instant() {
+        let result = compute_buckets_columnar(&[0, i64::MAX], Tz::UTC, Interval::Day, None);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn trailing_month_anchor_first_matches_calendar_month() {
+        let date = NaiveDate::from_ymd_opt(2026, 12, 31).unwrap();
+        let (start, end, _) = bucket_dates(
+            date,
+            Interval::TrailingMonth {
+                anchor_day: 1,
+                clamp: MonthClamp::PreviousValidDay,
+            },
+            WeekStart::Monday,
+        )
+        .unwrap();
+        let (month_start, month_end, _) =
+            bucket_dates(date, Interval::Month, WeekStart::Monday).unwrap();
+
+        assert_eq!((start, end), (month_start, month_end));
+    }
+
     #[test]
     fn compute_bucket_from_string_epoch_ms() {
         // 2026-03-29 00:15:00 UTC in epoch milliseconds
@@ -355,4 +2726,191 @@ mod tests {
         assert_eq!(result.bucket.key, "2026-03-29");
         assert_eq!(result.input.ts, "2026-03-29T00:15:00Z");
     }
+
+    #[test]
+    fn borrowed_result_serializes_like_owned() {
+        let line = " 1774743300000\n";
+        let borrowed = compute_bucket_ref_from_string(
+            line,
+            TimestampFormat::EpochMs,
+            "Europe/Berlin",
+            Interval::Week,
+            None,
+        )
+        .unwrap();
+        let owned = compute_bucket_from_string(
+            line,
+            TimestampFormat::EpochMs,
+            "Europe/Berlin",
+            Interval::Week,
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(borrowed.input.ts, "1774743300000");
+        assert_eq!(
+            serde_json::to_string(&borrowed).unwrap(),
+            serde_json::to_string(&owned).unwrap()
+        );
+        assert_eq!(
+            serde_json::to_string(&BucketResult::from(borrowed)).unwrap(),
+            serde_json::to_string(&owned).unwrap()
+        );
+    }
+
+    #[test]
+    fn metrics_record_bucket_duration() {
+        let instant = Utc
+            .with_ymd_and_hms(2026, 3, 29, 0, 15, 0)
+            .single()
+            .unwrap();
+        let sink = RecordingSink::default();
+        let bucket =
+            compute_bucket_with_metrics(instant, get_berlin_tz(), Interval::Day, None, &sink);
+
+        assert_eq!(bucket.key, "2026-03-29");
+        assert_eq!(
+            *sink.counters.borrow(),
+            vec![(metrics::BUCKETS_COMPUTED, 1)]
+        );
+        // 23-hour day
+        assert_eq!(
+            *sink.histograms.borrow(),
+            vec![(metrics::BUCKET_DURATION_SECONDS, 82_800.0)]
+        );
+    }
+
+    #[test]
+    fn metrics_count_nonexistent_boundary() {
+        // Sao Paulo skipped local midnight on 2018-11-04.
+        let instant = Utc
+            .with_ymd_and_hms(2018, 11, 4, 3, 30, 0)
+            .single()
+            .unwrap();
+        let tz = parse_tz("America/Sao_Paulo").unwrap();
+        let sink = RecordingSink::default();
+        compute_bucket_with_metrics(instant, tz, Interval::Day, None, &sink);
+
+        assert!(
+            sink.counters
+                .borrow()
+                .contains(&(metrics::NONEXISTENT_BOUNDARIES, 1))
+        );
+    }
+
+    /// Recorded `(field, value)` pairs, values in `Debug` form.
+    #[cfg(feature = "tracing")]
+    type Fields = Vec<(String, String)>;
+
+    /// Span names with their fields, and event fields, in order.
+    #[cfg(feature = "tracing")]
+    #[derive(Default)]
+    struct RecordingSubscriber {
+        spans: std::sync::Mutex<Vec<(&'static str, Fields)>>,
+        events: std::sync::Mutex<Vec<Fields>>,
+    }
+
+    #[cfg(feature = "tracing")]
+    struct FieldRecorder<'a>(&'a mut Fields);
+
+    #[cfg(feature = "tracing")]
+    impl tracing::field::Visit for FieldRecorder<'_> {
+        fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+            self.0
+                .push((field.name().to_string(), format!("{value:?}")));
+        }
+    }
+
+    #[cfg(feature = "tracing")]
+    impl tracing::Subscriber for RecordingSubscriber {
+        fn enabled(&self, _: &tracing::Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, span: &tracing::span::Attributes<'_>) -> tracing::span::Id {
+            let mut fields = Vec::new();
+            span.record(&mut FieldRecorder(&mut fields));
+            let mut spans = self.spans.lock().unwrap();
+            spans.push((span.metadata().name(), fields));
+            tracing::span::Id::from_u64(spans.len() as u64)
+        }
+
+        fn record(&self, _: &tracing::span::Id, _: &tracing::span::Record<'_>) {}
+
+        fn record_follows_from(&self, _: &tracing::span::Id, _: &tracing::span::Id) {}
+
+        fn event(&self, event: &tracing::Event<'_>) {
+            let mut fields = Vec::new();
+            event.record(&mut FieldRecorder(&mut fields));
+            self.events.lock().unwrap().push(fields);
+        }
+
+        fn enter(&self, _: &tracing::span::Id) {}
+
+        fn exit(&self, _: &tracing::span::Id) {}
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn tracing_spans_and_dst_events() {
+        use crate::parse::{TimestampFormat, parse_timestamp};
+
+        let subscriber = std::sync::Arc::new(RecordingSubscriber::default());
+        tracing::subscriber::with_default(subscriber.clone(), || {
+            // Sao Paulo skipped local midnight on 2018-11-04.
+            let instant =
+                parse_timestamp("2018-11-04T03:30:00Z", TimestampFormat::Rfc3339).unwrap();
+            let tz = parse_tz("America/Sao_Paulo").unwrap();
+            compute_bucket(instant, tz, Interval::Day, None);
+            assert!(parse_timestamp("nope", TimestampFormat::Rfc3339).is_err());
+        });
+
+        let field = |fields: &[(String, String)], name: &str| {
+            fields
+                .iter()
+                .find(|(field, _)| field == name)
+                .map(|(_, value)| value.clone())
+        };
+        let spans = subscriber.spans.lock().unwrap();
+        let names: Vec<_> = spans.iter().map(|(name, _)| *name).collect();
+        assert_eq!(
+            names,
+            [
+                "parse_timestamp",
+                "parse_tz",
+                "compute_bucket_with_metrics",
+                "parse_timestamp"
+            ]
+        );
+        assert_eq!(
+            field(&spans[0].1, "input").as_deref(),
+            Some("\"2018-11-04T03:30:00Z\"")
+        );
+        assert_eq!(field(&spans[0].1, "format").as_deref(), Some("Rfc3339"));
+        assert_eq!(
+            field(&spans[1].1, "name").as_deref(),
+            Some("\"America/Sao_Paulo\"")
+        );
+        let compute = &spans[2].1;
+        assert_eq!(field(compute, "tz").as_deref(), Some("America/Sao_Paulo"));
+        assert_eq!(field(compute, "interval").as_deref(), Some("Day"));
+        assert_eq!(field(compute, "week_start").as_deref(), Some("None"));
+        assert!(field(compute, "instant").is_some());
+
+        let events = subscriber.events.lock().unwrap();
+        let gap = events
+            .iter()
+            .find(|fields| {
+                field(fields, "monotonic_counter.tzbucket_nonexistent_resolutions").is_some()
+            })
+            .expect("nonexistent resolution event");
+        assert_eq!(
+            field(gap, "monotonic_counter.tzbucket_nonexistent_resolutions").as_deref(),
+            Some("1")
+        );
+        assert_eq!(field(gap, "tz").as_deref(), Some("America/Sao_Paulo"));
+        assert_eq!(field(gap, "local").as_deref(), Some("2018-11-04 00:00:00"));
+        // `err` on parse_timestamp records the failure as an event.
+        assert!(events.iter().any(|fields| field(fields, "error").is_some()));
+    }
 }
@@ -4,6 +4,7 @@
 //! with specific error categories for parsing, timezone handling,
 //! policy violations, and runtime issues.
 
+use chrono::NaiveDateTime;
 use thiserror::Error;
 
 /// The main error type for tzbucket operations.
@@ -21,6 +22,24 @@ pub enum TzBucketError {
     #[error("Policy error: {0}")]
     PolicyError(String),
 
+    /// Local time skipped by a DST transition, with the `Error` policy.
+    #[error("Nonexistent local time {local} in timezone {tz}")]
+    NonexistentTime {
+        /// The local time that does not exist.
+        local: NaiveDateTime,
+        /// IANA timezone name.
+        tz: String,
+    },
+
+    /// Local time repeated by a DST transition, with the `Error` policy.
+    #[error("Ambiguous local time {local} in timezone {tz}")]
+    AmbiguousTime {
+        /// The local time that occurs twice.
+        local: NaiveDateTime,
+        /// IANA timezone name.
+        tz: String,
+    },
+
     /// Runtime or I/O error.
     #[error("Runtime error: {0}")]
     RuntimeError(String),
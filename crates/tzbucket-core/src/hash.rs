@@ -0,0 +1,136 @@
+//! Stable hashing of bucket keys.
+//!
+//! [`bucket_key_hash64`] is XXH64 with seed `0` over the UTF-8 bytes of the
+//! key. The algorithm is fixed, so partitioning derived from it agrees with
+//! any other XXH64 implementation, e.g. Python's `xxhash.xxh64_intdigest(key)`
+//! or `XXH64(key, len, 0)` in C.
+
+const PRIME_1: u64 = 0x9E37_79B1_85EB_CA87;
+const PRIME_2: u64 = 0xC2B2_AE3D_27D4_EB4F;
+const PRIME_3: u64 = 0x1656_67B1_9E37_79F9;
+const PRIME_4: u64 = 0x85EB_CA77_C2B2_AE63;
+const PRIME_5: u64 = 0x27D4_EB2F_1656_67C5;
+
+/// Seed used for bucket keys. Changing it changes every hash.
+const SEED: u64 = 0;
+
+/// 64-bit hash of a bucket key, stable across platforms, releases and
+/// languages (XXH64, seed `0`).
+///
+/// # Examples
+///
+/// ```
+/// use tzbucket_core::bucket_key_hash64;
+///
+/// let partition = bucket_key_hash64("2026-03-29") % 16;
+/// assert_eq!(partition, bucket_key_hash64("2026-03-29") % 16);
+/// ```
+pub fn bucket_key_hash64(key: &str) -> u64 {
+    xxh64(key.as_bytes(), SEED)
+}
+
+fn xxh64(input: &[u8], seed: u64) -> u64 {
+    let mut stripes = input.chunks_exact(32);
+    let mut hash = if input.len() >= 32 {
+        let mut acc = [
+            seed.wrapping_add(PRIME_1).wrapping_add(PRIME_2),
+            seed.wrapping_add(PRIME_2),
+            seed,
+            seed.wrapping_sub(PRIME_1),
+        ];
+        for stripe in &mut stripes {
+            for (lane, word) in acc.iter_mut().zip(stripe.chunks_exact(8)) {
+                *lane = round(*lane, read_u64(word));
+            }
+        }
+        let mut hash = acc[0]
+            .rotate_left(1)
+            .wrapping_add(acc[1].rotate_left(7))
+            .wrapping_add(acc[2].rotate_left(12))
+            .wrapping_add(acc[3].rotate_left(18));
+        for lane in acc {
+            hash = merge_round(hash, lane);
+        }
+        hash
+    } else {
+        seed.wrapping_add(PRIME_5)
+    };
+    hash = hash.wrapping_add(input.len() as u64);
+
+    let mut tail = stripes.remainder();
+    while tail.len() >= 8 {
+        hash ^= round(0, read_u64(&tail[..8]));
+        hash = hash
+            .rotate_left(27)
+            .wrapping_mul(PRIME_1)
+            .wrapping_add(PRIME_4);
+        tail = &tail[8..];
+    }
+    if tail.len() >= 4 {
+        let word = u32::from_le_bytes(tail[..4].try_into().expect("4 bytes"));
+        hash ^= u64::from(word).wrapping_mul(PRIME_1);
+        hash = hash
+            .rotate_left(23)
+            .wrapping_mul(PRIME_2)
+            .wrapping_add(PRIME_3);
+        tail = &tail[4..];
+    }
+    for &byte in tail {
+        hash ^= u64::from(byte).wrapping_mul(PRIME_5);
+        hash = hash.rotate_left(11).wrapping_mul(PRIME_1);
+    }
+
+    avalanche(hash)
+}
+
+fn round(acc: u64, input: u64) -> u64 {
+    acc.wrapping_add(input.wrapping_mul(PRIME_2))
+        .rotate_left(31)
+        .wrapping_mul(PRIME_1)
+}
+
+fn merge_round(acc: u64, lane: u64) -> u64 {
+    (acc ^ round(0, lane))
+        .wrapping_mul(PRIME_1)
+        .wrapping_add(PRIME_4)
+}
+
+fn avalanche(mut hash: u64) -> u64 {
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(PRIME_2);
+    hash ^= hash >> 29;
+    hash = hash.wrapping_mul(PRIME_3);
+    hash ^= hash >> 32;
+    hash
+}
+
+fn read_u64(bytes: &[u8]) -> u64 {
+    u64::from_le_bytes(bytes.try_into().expect("8 bytes"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_reference_xxh64_vectors() {
+        assert_eq!(xxh64(b"", 0), 0xEF46_DB37_51D8_E999);
+        assert_eq!(xxh64(b"a", 0), 0xD24E_C4F1_A98C_6E5B);
+        assert_eq!(xxh64(b"abc", 0), 0x44BC_2CF5_AD77_0999);
+        // Longer than one 32-byte stripe.
+        assert_eq!(
+            xxh64(b"Nobody inspects the spammish repetition", 0),
+            0xFBCE_A83C_8A37_8BF1
+        );
+    }
+
+    #[test]
+    fn bucket_keys_hash_to_fixed_values() {
+        // Pinned so that a change to the algorithm or seed fails loudly.
+        assert_eq!(bucket_key_hash64("2026-03-29"), 0xE33D_4035_3810_D202);
+        assert_ne!(
+            bucket_key_hash64("2026-03-29"),
+            bucket_key_hash64("2026-03-30")
+        );
+    }
+}
@@ -0,0 +1,270 @@
+//! `date -d`-style human input (feature `humantime`).
+//!
+//! [`parse_human`] accepts the relative and calendar expressions people type
+//! at a shell, evaluated in a target zone against a given current time:
+//!
+//! - `now`, `today`, `yesterday`, `tomorrow`
+//! - `<N> <unit> ago`, `in <N> <unit>`, `+<N> <unit>`, `-<N> <unit>`
+//! - `last <unit>`, `next <unit>`
+//! - `[last|this|next] <weekday>`
+//! - `<month> <day> [<year>]`, `<day> <month> [<year>]`
+//!
+//! Units are `second`, `minute`, `hour`, `day`, `week`, `month` and `year`
+//! (plural, `sec`, `min` and `hr` also work). Seconds, minutes and hours move
+//! by elapsed time; days and longer move the local calendar date and keep
+//! the wall-clock time, like GNU `date`. Day expressions resolve to local
+//! midnight. Local times that fall into a DST gap shift forward; ambiguous
+//! ones resolve to the earlier instant.
+//!
+//! This is for interactive use only: pipelines should keep to the strict
+//! formats in [`crate::parse`], whose meaning does not depend on the clock.
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, Utc, Weekday};
+use chrono_tz::Tz;
+
+use crate::calendar::add_months;
+use crate::error::{Result, TzBucketError};
+use crate::models::MonthClamp;
+use crate::tz::{local_midnight_to_utc, local_to_utc, utc_to_local};
+
+/// Parse a human date expression in `tz`, relative to `now`.
+///
+/// # Errors
+///
+/// Returns [`TzBucketError::ParseError`] if the expression is not
+/// recognized or names a date that does not exist.
+///
+/// # Examples
+///
+/// ```
+/// use chrono::{TimeZone, Utc};
+/// use tzbucket_core::human::parse_human;
+///
+/// let tz = chrono_tz::Europe::Berlin;
+/// // Wednesday 2026-04-01, 10:00 in Berlin.
+/// let now = Utc.with_ymd_and_hms(2026, 4, 1, 8, 0, 0).unwrap();
+///
+/// let last_monday = parse_human("last monday", tz, now).unwrap();
+/// assert_eq!(last_monday, Utc.with_ymd_and_hms(2026, 3, 29, 22, 0, 0).unwrap());
+///
+/// // Four calendar days back crosses the spring forward: same wall-clock
+/// // time, 95 elapsed hours.
+/// let earlier = parse_human("4 days ago", tz, now).unwrap();
+/// assert_eq!(earlier, Utc.with_ymd_and_hms(2026, 3, 28, 9, 0, 0).unwrap());
+/// ```
+pub fn parse_human(input: &str, tz: Tz, now: DateTime<Utc>) -> Result<DateTime<Utc>> {
+    let lower = input.trim().to_lowercase();
+    let words: Vec<&str> = lower.split_whitespace().collect();
+    let local_now = utc_to_local(now, tz).naive_local();
+    let today = local_now.date();
+    let midnight = |date: NaiveDate| local_midnight_to_utc(date, tz);
+    let invalid = || TzBucketError::ParseError(format!("unrecognized date expression '{}'", input));
+
+    let instant = match words.as_slice() {
+        ["now"] => Some(now),
+        ["today"] => Some(midnight(today)),
+        ["yesterday"] => today.pred_opt().map(midnight),
+        ["tomorrow"] => today.succ_opt().map(midnight),
+        [n, unit, "ago"] => {
+            let n = n.parse::<i64>().map_err(|_| invalid())?;
+            shift(now, local_now, tz, -n, unit_of(unit).ok_or_else(invalid)?)
+        }
+        ["in", n, unit] => {
+            let n = n.parse::<i64>().map_err(|_| invalid())?;
+            shift(now, local_now, tz, n, unit_of(unit).ok_or_else(invalid)?)
+        }
+        [n, unit] if n.starts_with(['+', '-']) => {
+            let n = n.parse::<i64>().map_err(|_| invalid())?;
+            shift(now, local_now, tz, n, unit_of(unit).ok_or_else(invalid)?)
+        }
+        [direction @ ("last" | "this" | "next"), word] => {
+            if let Some(weekday) = weekday_of(word) {
+                weekday_date(today, weekday, direction).map(midnight)
+            } else {
+                let n = if *direction == "last" { -1 } else { 1 };
+                match (*direction, unit_of(word)) {
+                    ("this", _) | (_, None) => return Err(invalid()),
+                    (_, Some(unit)) => shift(now, local_now, tz, n, unit),
+                }
+            }
+        }
+        [word] => {
+            let weekday = weekday_of(word).ok_or_else(invalid)?;
+            weekday_date(today, weekday, "this").map(midnight)
+        }
+        [first, second, rest @ ..] if rest.len() <= 1 => {
+            let (month, day) = match (month_of(first), month_of(second)) {
+                (Some(month), None) => (month, second),
+                (None, Some(month)) => (month, first),
+                _ => return Err(invalid()),
+            };
+            let day = day.parse::<u32>().map_err(|_| invalid())?;
+            let year = match rest {
+                [year] => year.parse::<i32>().map_err(|_| invalid())?,
+                _ => today.year(),
+            };
+            let date = NaiveDate::from_ymd_opt(year, month, day).ok_or_else(|| {
+                TzBucketError::ParseError(format!("'{}' is not a valid date", input))
+            })?;
+            Some(midnight(date))
+        }
+        _ => return Err(invalid()),
+    };
+
+    instant.ok_or_else(|| TzBucketError::ParseError(format!("'{}' is out of range", input)))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Unit {
+    Second,
+    Minute,
+    Hour,
+    Day,
+    Week,
+    Month,
+    Year,
+}
+
+fn unit_of(word: &str) -> Option<Unit> {
+    let unit = match word.strip_suffix('s').unwrap_or(word) {
+        "second" | "sec" => Unit::Second,
+        "minute" | "min" => Unit::Minute,
+        "hour" | "hr" => Unit::Hour,
+        "day" => Unit::Day,
+        "week" => Unit::Week,
+        "month" => Unit::Month,
+        "year" => Unit::Year,
+        _ => return None,
+    };
+    Some(unit)
+}
+
+fn weekday_of(word: &str) -> Option<Weekday> {
+    let weekday = match word {
+        "monday" | "mon" => Weekday::Mon,
+        "tuesday" | "tue" => Weekday::Tue,
+        "wednesday" | "wed" => Weekday::Wed,
+        "thursday" | "thu" => Weekday::Thu,
+        "friday" | "fri" => Weekday::Fri,
+        "saturday" | "sat" => Weekday::Sat,
+        "sunday" | "sun" => Weekday::Sun,
+        _ => return None,
+    };
+    Some(weekday)
+}
+
+fn month_of(word: &str) -> Option<u32> {
+    const MONTHS: [&str; 12] = [
+        "january",
+        "february",
+        "march",
+        "april",
+        "may",
+        "june",
+        "july",
+        "august",
+        "september",
+        "october",
+        "november",
+        "december",
+    ];
+    MONTHS
+        .iter()
+        .position(|month| word == *month || (word.len() == 3 && month.starts_with(word)))
+        .map(|i| i as u32 + 1)
+}
+
+/// The date of `weekday` relative to `today`: strictly before it for
+/// `last`, strictly after it for `next`, and today or later for `this`.
+fn weekday_date(today: NaiveDate, weekday: Weekday, direction: &str) -> Option<NaiveDate> {
+    let ahead = i64::from(
+        (weekday.num_days_from_monday() + 7 - today.weekday().num_days_from_monday()) % 7,
+    );
+    let days = match direction {
+        "last" => ahead - 7,
+        "next" if ahead == 0 => 7,
+        _ => ahead,
+    };
+    today.checked_add_signed(Duration::days(days))
+}
+
+/// Move `now` by `n` units: elapsed time for seconds to hours, the local
+/// calendar (keeping the wall-clock time) for days and longer.
+fn shift(
+    now: DateTime<Utc>,
+    local_now: NaiveDateTime,
+    tz: Tz,
+    n: i64,
+    unit: Unit,
+) -> Option<DateTime<Utc>> {
+    let elapsed = |seconds: i64| now.checked_add_signed(Duration::seconds(n.checked_mul(seconds)?));
+    let calendar = |local: Option<NaiveDateTime>| local.map(|local| local_to_utc(local, tz));
+    let months = |n: i64| {
+        let months = i32::try_from(n).ok()?;
+        let (date, _) = add_months(local_now.date(), months, MonthClamp::PreviousValidDay);
+        Some(date.and_time(local_now.time()))
+    };
+
+    match unit {
+        Unit::Second => elapsed(1),
+        Unit::Minute => elapsed(60),
+        Unit::Hour => elapsed(3600),
+        Unit::Day => calendar(local_now.checked_add_signed(Duration::days(n))),
+        Unit::Week => calendar(local_now.checked_add_signed(Duration::weeks(n))),
+        Unit::Month => calendar(months(n)),
+        Unit::Year => calendar(months(n.checked_mul(12)?)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    const BERLIN: Tz = chrono_tz::Europe::Berlin;
+
+    /// Wednesday 2026-04-01, 10:00 in Berlin.
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 4, 1, 8, 0, 0).unwrap()
+    }
+
+    fn local(input: &str) -> String {
+        let instant = parse_human(input, BERLIN, now()).unwrap();
+        instant
+            .with_timezone(&BERLIN)
+            .format("%Y-%m-%dT%H:%M:%S%:z")
+            .to_string()
+    }
+
+    #[test]
+    fn day_words_resolve_to_local_midnight() {
+        assert_eq!(local("today"), "2026-04-01T00:00:00+02:00");
+        assert_eq!(local("Yesterday"), "2026-03-31T00:00:00+02:00");
+        assert_eq!(local("march 1"), "2026-03-01T00:00:00+01:00");
+        assert_eq!(local("1 Mar 2025"), "2025-03-01T00:00:00+01:00");
+    }
+
+    #[test]
+    fn weekdays_are_relative_to_today() {
+        assert_eq!(local("last wednesday"), "2026-03-25T00:00:00+01:00");
+        assert_eq!(local("wednesday"), "2026-04-01T00:00:00+02:00");
+        assert_eq!(local("next wed"), "2026-04-08T00:00:00+02:00");
+        assert_eq!(local("last monday"), "2026-03-30T00:00:00+02:00");
+    }
+
+    #[test]
+    fn calendar_units_keep_wall_clock_time() {
+        assert_eq!(local("2 weeks ago"), "2026-03-18T10:00:00+01:00");
+        assert_eq!(local("last month"), "2026-03-01T10:00:00+01:00");
+        assert_eq!(local("in 1 year"), "2027-04-01T10:00:00+02:00");
+        // Elapsed units cross the DST change without adjusting.
+        assert_eq!(local("-96 hours"), "2026-03-28T09:00:00+01:00");
+    }
+
+    #[test]
+    fn unknown_expressions_are_rejected() {
+        for input in ["", "soon", "3 fortnights ago", "this day", "february 30"] {
+            assert!(parse_human(input, BERLIN, now()).is_err(), "{}", input);
+        }
+    }
+}
@@ -0,0 +1,528 @@
+//! Precomputed instant-to-bucket lookup.
+//!
+//! [`BucketIndex`] computes every bucket of a range once and then assigns
+//! instants by binary search over the bucket starts, without any timezone
+//! conversion or string formatting per lookup. Use it when bucketing many
+//! instants in a known range with one timezone and interval.
+
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::compute::compute_bucket;
+use crate::error::{Result, TzBucketError};
+#[cfg(feature = "binary-index")]
+use crate::models::MonthClamp;
+use crate::models::{Bucket, Interval, WeekStart};
+use crate::tz::parse_tz;
+
+/// Buckets of one timezone and interval covering a UTC range, searchable by
+/// instant.
+///
+/// The index covers `[start_utc of the first bucket, end_utc of the last
+/// bucket)`, which includes the range it was built for. Buckets are
+/// contiguous, so every covered instant belongs to exactly one of them.
+///
+/// Serializes as `{tz, interval, week_start, buckets}`; deserializing
+/// validates that the buckets are contiguous.
+///
+/// # Examples
+///
+/// ```
+/// use chrono::{TimeZone, Utc};
+/// use tzbucket_core::{BucketIndex, Interval};
+///
+/// let index = BucketIndex::builder(chrono_tz::Europe::Berlin, Interval::Day)
+///     .range(
+///         Utc.with_ymd_and_hms(2026, 3, 1, 0, 0, 0).unwrap(),
+///         Utc.with_ymd_and_hms(2026, 4, 1, 0, 0, 0).unwrap(),
+///     )
+///     .build()
+///     .unwrap();
+///
+/// let instant = Utc.with_ymd_and_hms(2026, 3, 29, 0, 15, 0).unwrap();
+/// assert_eq!(index.lookup(instant).unwrap().key, "2026-03-29");
+/// ```
+#[derive(Debug, Clone)]
+pub struct BucketIndex {
+    tz: Tz,
+    interval: Interval,
+    week_start: WeekStart,
+    /// `start_utc` of each bucket in epoch milliseconds, ascending.
+    starts_ms: Vec<i64>,
+    /// `end_utc` of the last bucket in epoch milliseconds.
+    end_ms: i64,
+    buckets: Vec<Bucket>,
+}
+
+impl BucketIndex {
+    /// Start building an index for `tz` and `interval`.
+    pub fn builder(tz: Tz, interval: Interval) -> BucketIndexBuilder {
+        BucketIndexBuilder {
+            tz,
+            interval,
+            week_start: WeekStart::default(),
+            range: None,
+        }
+    }
+
+    /// The bucket containing `instant`, or `None` outside the index.
+    pub fn lookup(&self, instant: DateTime<Utc>) -> Option<&Bucket> {
+        self.lookup_ms(instant.timestamp_millis())
+    }
+
+    /// The bucket containing the instant `epoch_ms`, or `None` outside the
+    /// index.
+    pub fn lookup_ms(&self, epoch_ms: i64) -> Option<&Bucket> {
+        if epoch_ms >= self.end_ms {
+            return None;
+        }
+        let after = self.starts_ms.partition_point(|&start| start <= epoch_ms);
+        after.checked_sub(1).map(|i| &self.buckets[i])
+    }
+
+    /// All buckets, ordered by start.
+    pub fn buckets(&self) -> &[Bucket] {
+        &self.buckets
+    }
+
+    /// Number of buckets.
+    pub fn len(&self) -> usize {
+        self.buckets.len()
+    }
+
+    /// Whether the index has no buckets (never true for a built index).
+    pub fn is_empty(&self) -> bool {
+        self.buckets.is_empty()
+    }
+
+    /// Timezone of the buckets.
+    pub fn tz(&self) -> Tz {
+        self.tz
+    }
+
+    /// Interval of the buckets.
+    pub fn interval(&self) -> Interval {
+        self.interval
+    }
+
+    /// Week start used for week buckets.
+    pub fn week_start(&self) -> WeekStart {
+        self.week_start
+    }
+
+    /// Approximate memory held by the index in bytes: the struct itself plus
+    /// its allocations (vector capacity and bucket strings).
+    pub fn memory_size(&self) -> usize {
+        let strings: usize = self
+            .buckets
+            .iter()
+            .map(|b| {
+                b.key.capacity()
+                    + b.start_local.capacity()
+                    + b.end_local.capacity()
+                    + b.start_utc.capacity()
+                    + b.end_utc.capacity()
+                    + b.label_ts.as_ref().map_or(0, String::capacity)
+            })
+            .sum();
+
+        std::mem::size_of::<Self>()
+            + self.starts_ms.capacity() * std::mem::size_of::<i64>()
+            + self.buckets.capacity() * std::mem::size_of::<Bucket>()
+            + strings
+    }
+
+    /// Build from buckets ordered by start, checking they are contiguous.
+    /// `duration_seconds` is recomputed, as index files written before the
+    /// field existed lack it.
+    fn from_buckets(
+        tz: Tz,
+        interval: Interval,
+        week_start: WeekStart,
+        mut buckets: Vec<Bucket>,
+    ) -> Result<Self> {
+        let mut starts_ms = Vec::with_capacity(buckets.len());
+        let mut end_ms = None;
+
+        for bucket in &mut buckets {
+            let start = parse_utc_ms(&bucket.start_utc)?;
+            let end = parse_utc_ms(&bucket.end_utc)?;
+            if end <= start || end_ms.is_some_and(|previous| previous != start) {
+                return Err(TzBucketError::RuntimeError(format!(
+                    "bucket '{}' does not continue the previous bucket",
+                    bucket.key
+                )));
+            }
+            bucket.duration_seconds = (end - start) / 1000;
+            starts_ms.push(start);
+            end_ms = Some(end);
+        }
+
+        Ok(Self {
+            tz,
+            interval,
+            week_start,
+            starts_ms,
+            end_ms: end_ms.unwrap_or(i64::MIN),
+            buckets,
+        })
+    }
+}
+
+/// Builder for [`BucketIndex`]; [`range`](Self::range) is required.
+#[derive(Debug, Clone)]
+pub struct BucketIndexBuilder {
+    tz: Tz,
+    interval: Interval,
+    week_start: WeekStart,
+    range: Option<(DateTime<Utc>, DateTime<Utc>)>,
+}
+
+impl BucketIndexBuilder {
+    /// Week start for week buckets (default Monday).
+    pub fn week_start(mut self, week_start: WeekStart) -> Self {
+        self.week_start = week_start;
+        self
+    }
+
+    /// Cover the half-open UTC range `[start, end)`.
+    pub fn range(mut self, start: DateTime<Utc>, end: DateTime<Utc>) -> Self {
+        self.range = Some((start, end));
+        self
+    }
+
+    /// Compute all buckets overlapping the range.
+    ///
+    /// Fails when no range was set or the range is empty.
+    pub fn build(self) -> Result<BucketIndex> {
+        let Some((start, end)) = self.range else {
+            return Err(TzBucketError::RuntimeError(
+                "bucket index needs a range".to_string(),
+            ));
+        };
+        if start >= end {
+            return Err(TzBucketError::RuntimeError(format!(
+                "empty bucket index range: {} is not before {}",
+                start, end
+            )));
+        }
+
+        let mut buckets = Vec::new();
+        let mut cursor = start;
+        while cursor < end {
+            let bucket = compute_bucket(cursor, self.tz, self.interval, Some(self.week_start));
+            let next = DateTime::from_timestamp_millis(parse_utc_ms(&bucket.end_utc)?)
+                .expect("bucket end within chrono's range");
+            buckets.push(bucket);
+            cursor = next;
+        }
+
+        BucketIndex::from_buckets(self.tz, self.interval, self.week_start, buckets)
+    }
+}
+
+fn parse_utc_ms(ts: &str) -> Result<i64> {
+    DateTime::parse_from_rfc3339(ts)
+        .map(|dt| dt.timestamp_millis())
+        .map_err(|e| TzBucketError::ParseError(format!("invalid bucket boundary '{}': {}", ts, e)))
+}
+
+/// Serialized form of [`BucketIndex`]; the search table is rebuilt on load.
+#[derive(Serialize, Deserialize)]
+struct IndexData<B> {
+    tz: String,
+    interval: Interval,
+    week_start: WeekStart,
+    buckets: B,
+}
+
+impl Serialize for BucketIndex {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        IndexData {
+            tz: self.tz.name().to_string(),
+            interval: self.interval,
+            week_start: self.week_start,
+            buckets: &self.buckets,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for BucketIndex {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let data = IndexData::<Vec<Bucket>>::deserialize(deserializer)?;
+        let tz = parse_tz(&data.tz).map_err(serde::de::Error::custom)?;
+        BucketIndex::from_buckets(tz, data.interval, data.week_start, data.buckets)
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+/// First bytes of a binary index file: a name and the format version.
+#[cfg(feature = "binary-index")]
+pub const BINARY_INDEX_MAGIC: &[u8; 8] = b"TZBIDX\x00\x01";
+
+#[cfg(feature = "binary-index")]
+impl BucketIndex {
+    /// Write the index in the binary format read by
+    /// [`from_binary`](Self::from_binary): [`BINARY_INDEX_MAGIC`], then the
+    /// search table and the bucket strings as bincode columns.
+    ///
+    /// Stores the fields [`BucketIndexBuilder::build`] computes; week
+    /// numbers, labels and DST details are not part of an index.
+    pub fn write_binary(&self, mut writer: impl std::io::Write) -> Result<()> {
+        let data = BinaryIndex {
+            tz: self.tz.name(),
+            interval: self.interval,
+            week_start: self.week_start,
+            starts_ms: self.starts_ms.clone(),
+            end_ms: self.end_ms,
+            keys: self.buckets.iter().map(|b| &*b.key).collect(),
+            start_local: self
+                .buckets
+                .iter()
+                .map(|b| b.start_local.as_str())
+                .collect(),
+            end_local: self.buckets.iter().map(|b| b.end_local.as_str()).collect(),
+            boundaries_utc: self
+                .buckets
+                .iter()
+                .map(|b| b.start_utc.as_str())
+                .chain(self.buckets.last().map(|b| b.end_utc.as_str()))
+                .collect(),
+            month_clamp: self.buckets.iter().map(|b| b.month_clamp).collect(),
+        };
+        writer
+            .write_all(BINARY_INDEX_MAGIC)
+            .map_err(|e| TzBucketError::RuntimeError(format!("failed to write index: {}", e)))?;
+        bincode::serialize_into(writer, &data)
+            .map_err(|e| TzBucketError::RuntimeError(format!("failed to write index: {}", e)))
+    }
+
+    /// Read an index written by [`write_binary`](Self::write_binary).
+    ///
+    /// The keys and boundaries are copied out of `bytes` as stored and the
+    /// search table is read as is, so loading parses and formats no
+    /// timestamps; the boundaries are still checked to be contiguous.
+    pub fn from_binary(bytes: &[u8]) -> Result<Self> {
+        let invalid = |reason: String| {
+            TzBucketError::RuntimeError(format!("invalid binary index: {}", reason))
+        };
+        let data = bytes
+            .strip_prefix(BINARY_INDEX_MAGIC.as_slice())
+            .ok_or_else(|| invalid("missing header".to_string()))?;
+        let data: BinaryIndex<'_> =
+            bincode::deserialize(data).map_err(|e| invalid(e.to_string()))?;
+
+        let tz = parse_tz(data.tz)?;
+        let rows = data.starts_ms.len();
+        if [
+            data.keys.len(),
+            data.start_local.len(),
+            data.end_local.len(),
+            data.month_clamp.len(),
+            data.boundaries_utc.len().saturating_sub(1),
+        ]
+        .iter()
+        .any(|&len| len != rows)
+        {
+            return Err(invalid("columns differ in length".to_string()));
+        }
+
+        let ends_ms = data.starts_ms.iter().skip(1).chain([&data.end_ms]);
+        let mut buckets = Vec::with_capacity(rows);
+        for (i, (&start, &end)) in data.starts_ms.iter().zip(ends_ms).enumerate() {
+            if end <= start {
+                return Err(TzBucketError::RuntimeError(format!(
+                    "bucket '{}' does not continue the previous bucket",
+                    data.keys[i]
+                )));
+            }
+            buckets.push(Bucket {
+                key: data.keys[i].into(),
+                start_local: data.start_local[i].to_string(),
+                end_local: data.end_local[i].to_string(),
+                start_utc: data.boundaries_utc[i].to_string(),
+                end_utc: data.boundaries_utc[i + 1].to_string(),
+                duration_seconds: (end - start) / 1000,
+                week_numbers: None,
+                month_clamp: data.month_clamp[i],
+                label_ts: None,
+                dst_info: None,
+            });
+        }
+
+        Ok(Self {
+            tz,
+            interval: data.interval,
+            week_start: data.week_start,
+            starts_ms: data.starts_ms,
+            end_ms: if rows == 0 { i64::MIN } else { data.end_ms },
+            buckets,
+        })
+    }
+}
+
+/// Binary form of [`BucketIndex`]: the search table and the bucket fields as
+/// columns, with the strings borrowed from the input when decoding.
+#[cfg(feature = "binary-index")]
+#[derive(Serialize, Deserialize)]
+struct BinaryIndex<'a> {
+    tz: &'a str,
+    interval: Interval,
+    week_start: WeekStart,
+    starts_ms: Vec<i64>,
+    end_ms: i64,
+    #[serde(borrow)]
+    keys: Vec<&'a str>,
+    #[serde(borrow)]
+    start_local: Vec<&'a str>,
+    #[serde(borrow)]
+    end_local: Vec<&'a str>,
+    /// `start_utc` of each bucket, then `end_utc` of the last one: stored
+    /// rather than formatted from `starts_ms`, which would dominate loading.
+    #[serde(borrow)]
+    boundaries_utc: Vec<&'a str>,
+    month_clamp: Vec<Option<MonthClamp>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    fn berlin_march_2026() -> BucketIndex {
+        BucketIndex::builder(chrono_tz::Europe::Berlin, Interval::Day)
+            .range(
+                Utc.with_ymd_and_hms(2026, 3, 1, 0, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2026, 4, 1, 0, 0, 0).unwrap(),
+            )
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn lookup_matches_compute_bucket() {
+        let index = berlin_march_2026();
+        let tz = chrono_tz::Europe::Berlin;
+
+        // Every 17 minutes across the spring-forward day and its neighbours.
+        let mut instant = Utc.with_ymd_and_hms(2026, 3, 27, 0, 0, 0).unwrap();
+        while instant < Utc.with_ymd_and_hms(2026, 3, 31, 0, 0, 0).unwrap() {
+            let expected = compute_bucket(instant, tz, Interval::Day, None);
+            assert_eq!(index.lookup(instant).unwrap().key, expected.key);
+            instant += Duration::minutes(17);
+        }
+    }
+
+    #[test]
+    fn lookup_outside_index_is_none() {
+        let index = berlin_march_2026();
+
+        // The first bucket starts at Berlin midnight, 2026-02-28T23:00Z.
+        let first = Utc.with_ymd_and_hms(2026, 2, 28, 23, 0, 0).unwrap();
+        assert_eq!(index.lookup(first).unwrap().key, "2026-03-01");
+        assert!(index.lookup(first - Duration::milliseconds(1)).is_none());
+
+        // The last bucket ends at Berlin midnight, 2026-04-01T22:00Z.
+        let end = Utc.with_ymd_and_hms(2026, 4, 1, 22, 0, 0).unwrap();
+        assert_eq!(
+            index.lookup(end - Duration::milliseconds(1)).unwrap().key,
+            "2026-04-01"
+        );
+        assert!(index.lookup(end).is_none());
+    }
+
+    #[test]
+    fn build_requires_non_empty_range() {
+        let builder = BucketIndex::builder(chrono_tz::UTC, Interval::Day);
+        assert!(builder.clone().build().is_err());
+
+        let instant = Utc.with_ymd_and_hms(2026, 3, 1, 0, 0, 0).unwrap();
+        assert!(builder.range(instant, instant).build().is_err());
+    }
+
+    #[test]
+    fn serde_round_trip_rebuilds_lookup() {
+        let index = berlin_march_2026();
+        let json = serde_json::to_string(&index).unwrap();
+        let restored: BucketIndex = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(restored.len(), 32);
+        assert_eq!(restored.tz(), chrono_tz::Europe::Berlin);
+        let instant = Utc.with_ymd_and_hms(2026, 3, 29, 21, 59, 59).unwrap();
+        assert_eq!(restored.lookup(instant).unwrap().key, "2026-03-29");
+    }
+
+    #[test]
+    fn deserialize_fills_in_missing_durations() {
+        let mut value = serde_json::to_value(berlin_march_2026()).unwrap();
+        for bucket in value["buckets"].as_array_mut().unwrap() {
+            bucket.as_object_mut().unwrap().remove("duration_seconds");
+        }
+        let restored: BucketIndex = serde_json::from_value(value).unwrap();
+
+        let instant = Utc.with_ymd_and_hms(2026, 3, 29, 12, 0, 0).unwrap();
+        assert_eq!(
+            restored.lookup(instant).unwrap().duration_seconds,
+            23 * 3600
+        );
+    }
+
+    #[test]
+    fn deserialize_rejects_gaps() {
+        let mut value = serde_json::to_value(berlin_march_2026()).unwrap();
+        value["buckets"].as_array_mut().unwrap().remove(5);
+
+        assert!(serde_json::from_value::<BucketIndex>(value).is_err());
+    }
+
+    #[cfg(feature = "binary-index")]
+    #[test]
+    fn binary_round_trip_matches_json() {
+        let index = BucketIndex::builder(
+            chrono_tz::Europe::London,
+            Interval::TrailingMonth {
+                anchor_day: 31,
+                clamp: MonthClamp::PreviousValidDay,
+            },
+        )
+        .range(
+            Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2027, 1, 1, 0, 0, 0).unwrap(),
+        )
+        .build()
+        .unwrap();
+        let mut bytes = Vec::new();
+        index.write_binary(&mut bytes).unwrap();
+        let restored = BucketIndex::from_binary(&bytes).unwrap();
+
+        assert_eq!(
+            serde_json::to_value(&restored).unwrap(),
+            serde_json::to_value(&index).unwrap()
+        );
+        let instant = Utc.with_ymd_and_hms(2026, 3, 29, 12, 0, 0).unwrap();
+        assert_eq!(
+            restored.lookup(instant).unwrap().key,
+            index.lookup(instant).unwrap().key
+        );
+    }
+
+    #[cfg(feature = "binary-index")]
+    #[test]
+    fn from_binary_rejects_other_data() {
+        let mut bytes = Vec::new();
+        berlin_march_2026().write_binary(&mut bytes).unwrap();
+
+        assert!(BucketIndex::from_binary(&bytes[1..]).is_err());
+        assert!(BucketIndex::from_binary(&bytes[..bytes.len() - 1]).is_err());
+        let json = serde_json::to_vec(&berlin_march_2026()).unwrap();
+        assert!(BucketIndex::from_binary(&json).is_err());
+    }
+
+    #[test]
+    fn memory_size_counts_buckets() {
+        let index = berlin_march_2026();
+        assert!(index.memory_size() > index.len() * std::mem::size_of::<Bucket>());
+    }
+}
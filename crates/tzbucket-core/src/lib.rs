@@ -3,17 +3,60 @@
 //! A DST-safe time bucketing library for Rust.
 //!
 //! This library provides functionality for assigning timestamps to calendar-based
-//! buckets (day/week/month) in an IANA timezone, with explicit DST handling.
+//! buckets (minutes to years) in an IANA timezone, with explicit DST handling.
 //!
 //! ## Features
 //!
 //! - **DST Safety**: Bucket boundaries are computed in local time and converted
 //!   independently to UTC, correctly handling 23-hour and 25-hour days.
-//! - **Multiple Intervals**: Support for day, week, and month buckets.
+//! - **Multiple Intervals**: Support for minute, hour, `<N>`-hour, day,
+//!   anchored `<N>`-day, week, month, quarter, fiscal quarter, trailing month,
+//!   year and fixed-length buckets.
 //! - **Flexible Week Start**: Configurable week start (Monday or Sunday).
 //! - **Multiple Input Formats**: Parse epoch milliseconds, epoch seconds, or RFC3339.
 //! - **IANA Timezones**: Full support for IANA timezone database via chrono-tz.
 //!
+//! ## Cargo Features
+//!
+//! - `tracing`: emit [`tracing`](https://docs.rs/tracing) spans around timestamp
+//!   parsing, timezone resolution and bucket computation, plus
+//!   `monotonic_counter.tzbucket_ambiguous_resolutions` and
+//!   `monotonic_counter.tzbucket_nonexistent_resolutions` events whenever a local
+//!   boundary falls into a DST overlap or gap.
+//! - `schemars`: derive [`schemars::JsonSchema`](https://docs.rs/schemars) for
+//!   the serializable output types.
+//! - `proto`: [`prost`](https://docs.rs/prost) messages in [`proto`] mirroring
+//!   `proto/tzbucket/v1/tzbucket.proto`, with conversions from the output types.
+//! - `testing`: [`proptest`](https://docs.rs/proptest) strategies in [`testing`]
+//!   for intervals, week starts, IANA zones and instants near DST transitions.
+//! - `filter-tzdata`: embed only the zones whose names match the regex in
+//!   the `CHRONO_TZ_TIMEZONE_FILTER` environment variable at build time, e.g.
+//!   `CHRONO_TZ_TIMEZONE_FILTER="^(Europe/Berlin|America/New_York)$"`. The
+//!   [`regex`](https://docs.rs/regex) syntax applies and the match is
+//!   unanchored, so `Europe` keeps every `Europe/*` zone. `UTC`, `GMT` and
+//!   links to a kept zone are always kept; an unset or empty variable keeps
+//!   all zones. Other zones fail to parse with the same
+//!   [`InvalidTimezone`](TzBucketError::InvalidTimezone) error as unknown
+//!   names.
+//! - `fast-parse`: byte-level fast paths in [`parse`] for epoch integers and
+//!   canonical RFC3339 timestamps, with chrono as the fallback.
+//! - `humantime`: `date -d`-style expressions (`2 weeks ago`, `last monday`,
+//!   `march 1`) in [`human`], evaluated in a zone for interactive input.
+//! - `zone-tab`: the tzdb `zone.tab` table, embedded for
+//!   [`tz::zones_for_country`] lookups by ISO 3166 country code.
+//! - `compact-str`: store bucket keys inline as
+//!   [`compact_str::CompactString`](https://docs.rs/compact_str) instead of
+//!   a `String` ([`BucketKey`]); keys up to 24 bytes need no heap allocation
+//!   and serialize the same.
+//! - `binary-index`: [`BucketIndex::write_binary`] and
+//!   [`BucketIndex::from_binary`], a bincode index file format that loads
+//!   without parsing or formatting timestamps.
+//! - `tzf-rs`: timezone boundaries from [`tzf-rs`](https://docs.rs/tzf-rs),
+//!   embedded for [`tz::tz_for_location`] lookups by latitude and longitude.
+//! - `cache`: [`cache::BucketCache`], a bounded memo of day-and-longer buckets
+//!   keyed by zone, interval and local date, with a process-wide instance
+//!   and hit/miss counters for servers repeating lookups.
+//!
 //! ## Example
 //!
 //! ```rust
@@ -33,20 +76,57 @@
 //! println!("End (local): {}", bucket.end_local);
 //! ```
 
+#[cfg(feature = "cache")]
+pub mod cache;
+pub mod calendar;
+pub mod clock;
 pub mod compute;
 pub mod error;
+pub mod hash;
+#[cfg(feature = "humantime")]
+pub mod human;
+pub mod index;
+pub mod metrics;
 pub mod models;
+pub mod navigate;
 pub mod parse;
+#[cfg(feature = "proto")]
+pub mod proto;
+pub mod resolve;
+pub mod rollup;
+pub mod series;
+#[cfg(feature = "testing")]
+pub mod testing;
 pub mod tz;
 
 // Re-export commonly used types at the crate root
-pub use compute::{compute_bucket, compute_bucket_from_string};
+pub use calendar::add_months;
+pub use clock::{Clock, FixedClock, MockClock, SystemClock};
+pub use compute::{
+    bucket_dates, ceil_to_bucket, compute_bucket, compute_bucket_from_string,
+    compute_bucket_multi_tz, compute_bucket_ref_from_string, compute_bucket_typed,
+    compute_bucket_with_day_offset, compute_bucket_with_metrics, compute_bucket_with_policy,
+    compute_bucket_with_week_key, compute_bucket_with_week_scheme, compute_buckets,
+    compute_buckets_columnar, compute_buckets_for_intervals, compute_buckets_from_strings,
+    compute_current_bucket, floor_to_bucket, round_to_bucket,
+};
 pub use error::{Result, TzBucketError};
+pub use hash::bucket_key_hash64;
+pub use index::{BucketIndex, BucketIndexBuilder};
+pub use metrics::{MetricsSink, NoopMetrics};
 pub use models::{
-    AmbiguousPolicy, Bucket, BucketResult, InputTimestamp, Interval, NonexistentPolicy, Policy,
-    WeekStart,
+    AmbiguousPolicy, Bucket, BucketColumns, BucketKey, BucketResult, BucketResultRef, BucketSpec,
+    DstInfo, InputTimestamp, InputTimestampRef, Interval, LabelPoint, MonthClamp,
+    NonexistentPolicy, OutputSchemaVersion, Policy, TypedBucket, WeekKeyFormat, WeekNumbers,
+    WeekScheme, WeekStart,
+};
+pub use navigate::{
+    bucket_for_key, buckets_between, buckets_between_at, shift_bucket, shift_bucket_at,
 };
 pub use parse::{TimestampFormat, parse_timestamp, parse_timestamp_auto};
+pub use resolve::{AppliedPolicy, LocalResolution, LocalStatus, RoundTrip, resolve_local};
+pub use rollup::{Attribution, attribute};
+pub use series::BucketSeries;
 
 /// Prelude module for convenient imports.
 ///
@@ -0,0 +1,46 @@
+//! Metrics hooks.
+//!
+//! This module defines [`MetricsSink`], a minimal callback interface that
+//! bucket computation reports into. Host applications implement it to forward
+//! counters and histograms to Prometheus, StatsD, or any other backend without
+//! tzbucket depending on a metrics crate.
+
+/// Counter: number of buckets computed.
+pub const BUCKETS_COMPUTED: &str = "tzbucket_buckets_computed_total";
+
+/// Counter: bucket boundaries that fell on an ambiguous local time (DST fall back).
+pub const AMBIGUOUS_BOUNDARIES: &str = "tzbucket_ambiguous_boundaries_total";
+
+/// Counter: bucket boundaries that fell on a nonexistent local time (DST spring forward).
+pub const NONEXISTENT_BOUNDARIES: &str = "tzbucket_nonexistent_boundaries_total";
+
+/// Histogram: real UTC duration of computed buckets, in seconds.
+pub const BUCKET_DURATION_SECONDS: &str = "tzbucket_bucket_duration_seconds";
+
+/// Counter: lookups answered by a `BucketCache` (feature `cache`).
+pub const CACHE_HITS: &str = "tzbucket_cache_hits_total";
+
+/// Counter: `BucketCache` lookups that computed the bucket (feature `cache`).
+pub const CACHE_MISSES: &str = "tzbucket_cache_misses_total";
+
+/// Receiver for metrics emitted during bucket computation.
+///
+/// Metric names are the `&'static str` constants defined in this module.
+/// Implementations must be cheap: they are called on the hot path.
+pub trait MetricsSink {
+    /// Increment the counter `name` by `value`.
+    fn counter(&self, name: &'static str, value: u64);
+
+    /// Record a single observation `value` for the histogram `name`.
+    fn histogram(&self, name: &'static str, value: f64);
+}
+
+/// A [`MetricsSink`] that discards everything.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoopMetrics;
+
+impl MetricsSink for NoopMetrics {
+    fn counter(&self, _name: &'static str, _value: u64) {}
+
+    fn histogram(&self, _name: &'static str, _value: f64) {}
+}
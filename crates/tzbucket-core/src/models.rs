@@ -1,42 +1,245 @@
 //! Core data types for tzbucket.
 //!
 //! This module defines the primary types used throughout the library:
-//! - [`Interval`] - Bucket granularity (day/week/month)
+//! - [`Interval`] - Bucket granularity (hour/day/week/month/quarter/fiscal quarter/year/trailing month/fixed)
 //! - [`WeekStart`] - Week boundary configuration
+//! - [`WeekKeyFormat`] - How week bucket keys are written
+//! - [`WeekScheme`] - Week-numbering system (ISO 8601, MMWR, broadcast)
+//! - [`MonthClamp`] - How to resolve days 29-31 in shorter months
+//! - [`LabelPoint`] - Which instant of a bucket labels it
+//! - [`OutputSchemaVersion`] - Version of the JSON output shape
 //! - [`NonexistentPolicy`] - How to handle nonexistent local times
 //! - [`AmbiguousPolicy`] - How to handle ambiguous local times
 //! - [`Policy`] - Combined DST handling policy
 //! - [`Bucket`] - A computed time bucket
+//! - [`TypedBucket`] - A computed time bucket with chrono boundaries
+//! - [`WeekNumbers`] - ISO/US week numbers for a bucket start
+//! - [`DstInfo`] - UTC offsets at a bucket's boundaries
 //! - [`InputTimestamp`] - Parsed input timestamp
 //! - [`BucketResult`] - Complete result for a bucket operation
+//! - [`BucketColumns`] - Bucket assignments of many instants as columns
 
-use serde::Serialize;
+use std::ops::Range;
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Offset, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+
+use crate::tz::{format_rfc3339, format_rfc3339_utc, offset_transitions};
 
 /// Bucket granularity interval.
-#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
-#[serde(rename_all = "lowercase")]
+///
+/// Serialized as its [`Display`](std::fmt::Display) form: `<minutes>m`,
+/// `hour`, `<hours>h`, `day`, `<n>d:<anchor>`, `week`, `month`, `quarter`,
+/// `fiscal_quarter:<start_month>`, `year`,
+/// `trailing_month:<anchor_day>[:roll_forward]`, or `fixed:<seconds>`.
+/// Intervals order by variant (as declared here), then by their parameters.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum Interval {
+    /// Window of the given number of local minutes, aligned to local
+    /// midnight (`15` gives 00:00, 00:15, ...). Minute counts that do not
+    /// divide a day cut the last window of each day at midnight.
+    ///
+    /// Windows skipped by a DST spring forward have no bucket. A window
+    /// repeated by a fall back forms one bucket per pass, told apart by the
+    /// UTC offset in the key; a window that only partly repeats runs through
+    /// the change in one bucket.
+    Minutes(u32),
+    /// Hourly bucket (HH:00:00 to the next HH:00:00 in local time). The hour
+    /// repeated at a DST fall back is one bucket two UTC hours long; the
+    /// hour skipped at a spring forward has no bucket.
+    Hour,
+    /// Window of the given number of local hours, aligned to local midnight
+    /// (`6` gives 00:00, 06:00, 12:00 and 18:00). Like the calendar
+    /// intervals, the local boundaries are converted to UTC independently, so
+    /// a window containing a DST change is an hour shorter or longer (5 or 7
+    /// hours for `6`). Hour counts that do not divide a day cut the last
+    /// window of each day at midnight.
+    Hours(u32),
     /// Daily bucket (00:00:00 to next day 00:00:00 in local time)
     #[default]
     Day,
+    /// Period of `n` local days (00:00:00 to 00:00:00), aligned so that one
+    /// period starts on `anchor`, e.g. 14-day sprints from a campaign start.
+    Days {
+        /// Number of days per period.
+        n: u32,
+        /// Local date that starts a period.
+        anchor: NaiveDate,
+    },
     /// Weekly bucket (week start 00:00:00 to next week start 00:00:00)
     Week,
     /// Monthly bucket (1st day 00:00:00 to 1st of next month 00:00:00)
     Month,
+    /// Calendar quarter bucket (Jan 1, Apr 1, Jul 1 or Oct 1 00:00:00 to the
+    /// start of the next quarter)
+    Quarter,
+    /// Fiscal quarter of a fiscal year starting on the 1st of `start_month`
+    /// (1-12), keyed `FY<year>-Q<n>`. A fiscal year is named after the
+    /// calendar year it ends in: with `start_month` 10, `FY2027-Q1` is
+    /// October to December 2026. With `start_month` 1 the quarters are the
+    /// calendar quarters.
+    FiscalQuarter {
+        /// Month the fiscal year starts in.
+        start_month: u8,
+    },
+    /// Yearly bucket (Jan 1 00:00:00 to Jan 1 of the next year 00:00:00)
+    Year,
+    /// Monthly period starting on `anchor_day` (1-31) at 00:00:00 and ending
+    /// on the anchor day of the next month, e.g. a billing cycle anchored to
+    /// the signup day. In months shorter than `anchor_day` the anchor is
+    /// resolved by `clamp`.
+    TrailingMonth {
+        /// Day of month the period starts on.
+        anchor_day: u8,
+        /// How to resolve the anchor in months shorter than `anchor_day`.
+        clamp: MonthClamp,
+    },
+    /// Fixed-length window of the given number of seconds, aligned to the
+    /// Unix epoch (like Prometheus step windows) and independent of the
+    /// timezone.
+    FixedSeconds(u64),
 }
 
 impl std::fmt::Display for Interval {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
+            Interval::Minutes(minutes) => write!(f, "{}m", minutes),
+            Interval::Hour => write!(f, "hour"),
+            Interval::Hours(hours) => write!(f, "{}h", hours),
             Interval::Day => write!(f, "day"),
+            Interval::Days { n, anchor } => write!(f, "{}d:{}", n, anchor.format("%Y-%m-%d")),
             Interval::Week => write!(f, "week"),
             Interval::Month => write!(f, "month"),
+            Interval::Quarter => write!(f, "quarter"),
+            Interval::FiscalQuarter { start_month } => {
+                write!(f, "fiscal_quarter:{}", start_month)
+            }
+            Interval::Year => write!(f, "year"),
+            Interval::TrailingMonth {
+                anchor_day,
+                clamp: MonthClamp::PreviousValidDay,
+            } => write!(f, "trailing_month:{}", anchor_day),
+            Interval::TrailingMonth { anchor_day, clamp } => {
+                write!(f, "trailing_month:{}:{}", anchor_day, clamp)
+            }
+            Interval::FixedSeconds(seconds) => write!(f, "fixed:{}", seconds),
         }
     }
 }
 
+impl std::str::FromStr for Interval {
+    type Err = crate::error::TzBucketError;
+
+    /// Parse the [`Display`](std::fmt::Display) form of an interval.
+    fn from_str(s: &str) -> crate::error::Result<Self> {
+        let invalid =
+            || crate::error::TzBucketError::ParseError(format!("invalid interval '{}'", s));
+        match s {
+            "hour" => return Ok(Interval::Hour),
+            "day" => return Ok(Interval::Day),
+            "week" => return Ok(Interval::Week),
+            "month" => return Ok(Interval::Month),
+            "quarter" => return Ok(Interval::Quarter),
+            "year" => return Ok(Interval::Year),
+            _ => {}
+        }
+
+        if let Some(spec) = s.strip_prefix("trailing_month:") {
+            let (anchor, clamp) = match spec.split_once(':') {
+                Some((anchor, "previous_valid_day")) => (anchor, MonthClamp::PreviousValidDay),
+                Some((anchor, "roll_forward")) => (anchor, MonthClamp::RollForward),
+                Some(_) => return Err(invalid()),
+                None => (spec, MonthClamp::PreviousValidDay),
+            };
+            return match anchor.parse::<u8>() {
+                Ok(anchor_day @ 1..=31) => Ok(Interval::TrailingMonth { anchor_day, clamp }),
+                _ => Err(invalid()),
+            };
+        }
+
+        if let Some(month) = s.strip_prefix("fiscal_quarter:") {
+            return match month.parse::<u8>() {
+                Ok(start_month @ 1..=12) => Ok(Interval::FiscalQuarter { start_month }),
+                _ => Err(invalid()),
+            };
+        }
+
+        if let Some((n, anchor)) = s.split_once("d:")
+            && let Ok(n) = n.parse::<u32>()
+        {
+            return match NaiveDate::parse_from_str(anchor, "%Y-%m-%d") {
+                Ok(anchor) if n > 0 => Ok(Interval::Days { n, anchor }),
+                _ => Err(invalid()),
+            };
+        }
+
+        if let Some(hours) = s.strip_suffix('h') {
+            return match hours.parse::<u32>() {
+                Ok(hours @ 1..=24) if 24 % hours == 0 => Ok(Interval::Hours(hours)),
+                _ => Err(invalid()),
+            };
+        }
+
+        if let Some(minutes) = s.strip_suffix('m') {
+            return match minutes.parse::<u32>() {
+                Ok(minutes @ 1..=1440) if 1440 % minutes == 0 => Ok(Interval::Minutes(minutes)),
+                _ => Err(invalid()),
+            };
+        }
+
+        match s.strip_prefix("fixed:").map(str::parse::<u64>) {
+            Some(Ok(seconds)) if seconds > 0 => Ok(Interval::FixedSeconds(seconds)),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl Serialize for Interval {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Interval {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for Interval {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        "Interval".into()
+    }
+
+    fn json_schema(_generator: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        // Spell out the minute and hour counts `from_str` accepts rather
+        // than any number.
+        let divisors = |n: u32| {
+            (1..=n)
+                .filter(|d| n.is_multiple_of(*d))
+                .map(|d| d.to_string())
+                .collect::<Vec<_>>()
+                .join("|")
+        };
+        let pattern = format!(
+            "^(({})m|hour|({})h|day|[1-9][0-9]*d:[0-9]{{4}}-[0-9]{{2}}-[0-9]{{2}}|week|month|quarter|fiscal_quarter:([1-9]|1[0-2])|year|trailing_month:([1-9]|[12][0-9]|3[01])(:previous_valid_day|:roll_forward)?|fixed:[1-9][0-9]*)$",
+            divisors(1440),
+            divisors(24)
+        );
+        schemars::json_schema!({
+            "description": "Bucket interval: `<minutes>m` (a divisor of 1440), `hour`, `<hours>h` (a divisor of 24), `day`, `<n>d:<anchor YYYY-MM-DD>`, `week`, `month`, `quarter`, `fiscal_quarter:<start_month 1-12>`, `year`, or `trailing_month:<anchor_day>[:roll_forward]`, or `fixed:<seconds>`.",
+            "type": "string",
+            "pattern": pattern
+        })
+    }
+}
+
 /// Week start day configuration.
-#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(rename_all = "lowercase")]
 pub enum WeekStart {
     /// Week starts on Monday (ISO 8601)
@@ -55,6 +258,252 @@ impl std::fmt::Display for WeekStart {
     }
 }
 
+/// How week bucket keys are written.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum WeekKeyFormat {
+    /// The week's first day, `YYYY-MM-DD`.
+    #[default]
+    StartDate,
+    /// The ISO 8601 week, `YYYY-Www`.
+    ///
+    /// The year is the ISO week-numbering year, so 2024-12-30 starts
+    /// `2025-W01` and 2027-01-01 falls in `2026-W53`. Sunday-start weeks are
+    /// keyed by the ISO week of their Monday, which shares six of their days.
+    Iso,
+    /// The MMWR (CDC epidemiological) week, `YYYY-Www`, see
+    /// [`WeekScheme::Mmwr`]. Monday-start weeks are keyed by the MMWR week of
+    /// their Monday.
+    Mmwr,
+    /// The broadcast calendar week, `YYYY-Www`, see
+    /// [`WeekScheme::Broadcast`].
+    Broadcast,
+}
+
+impl WeekKeyFormat {
+    /// The key of the week bucket starting on `start`.
+    pub fn key(self, start: NaiveDate, week_start: WeekStart) -> BucketKey {
+        let scheme = match self {
+            WeekKeyFormat::StartDate => {
+                return format_key(format_args!("{}", start.format("%Y-%m-%d")));
+            }
+            WeekKeyFormat::Iso => WeekScheme::Iso,
+            WeekKeyFormat::Mmwr => WeekScheme::Mmwr,
+            WeekKeyFormat::Broadcast => WeekScheme::Broadcast,
+        };
+        let monday = match week_start {
+            WeekStart::Monday => start,
+            WeekStart::Sunday => start + Duration::days(1),
+        };
+        let (year, week) = scheme.week_of(monday);
+        format_key(format_args!("{:04}-W{:02}", year, week))
+    }
+}
+
+impl std::fmt::Display for WeekKeyFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WeekKeyFormat::StartDate => write!(f, "start_date"),
+            WeekKeyFormat::Iso => write!(f, "iso"),
+            WeekKeyFormat::Mmwr => write!(f, "mmwr"),
+            WeekKeyFormat::Broadcast => write!(f, "broadcast"),
+        }
+    }
+}
+
+/// A week-numbering system: the day weeks start on and how they are
+/// numbered within a year.
+///
+/// A scheme fixes both the week bucket boundaries ([`WeekScheme::week_start`])
+/// and the `YYYY-Www` key ([`WeekScheme::week_key`]). Week 1 is the first
+/// week containing the scheme's anchor day of the year, and the week-year can
+/// differ from the calendar year around January 1.
+///
+/// # Examples
+///
+/// ```
+/// use chrono::NaiveDate;
+/// use tzbucket_core::WeekScheme;
+///
+/// // 2026 starts on a Thursday: the ISO and broadcast years start on
+/// // Monday 2025-12-29, the MMWR year only on Sunday 2026-01-04.
+/// let saturday = NaiveDate::from_ymd_opt(2026, 1, 3).unwrap();
+/// assert_eq!(WeekScheme::Iso.week_of(saturday), (2026, 1));
+/// assert_eq!(WeekScheme::Broadcast.week_of(saturday), (2026, 1));
+/// assert_eq!(WeekScheme::Mmwr.week_of(saturday), (2025, 53));
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum WeekScheme {
+    /// ISO 8601: weeks start on Monday, week 1 contains January 4 (the
+    /// year's first Thursday).
+    #[default]
+    Iso,
+    /// CDC MMWR epidemiological weeks: weeks start on Sunday, week 1 is the
+    /// first week with at least four days in the year (it contains
+    /// January 4).
+    Mmwr,
+    /// Broadcast calendar: weeks start on Monday, week 1 contains
+    /// January 1, so a broadcast year can start in late December.
+    Broadcast,
+}
+
+impl WeekScheme {
+    /// The day the scheme's weeks start on.
+    pub fn week_start(self) -> WeekStart {
+        match self {
+            WeekScheme::Iso | WeekScheme::Broadcast => WeekStart::Monday,
+            WeekScheme::Mmwr => WeekStart::Sunday,
+        }
+    }
+
+    /// The key format writing the scheme's `YYYY-Www` keys.
+    pub fn week_key(self) -> WeekKeyFormat {
+        match self {
+            WeekScheme::Iso => WeekKeyFormat::Iso,
+            WeekScheme::Mmwr => WeekKeyFormat::Mmwr,
+            WeekScheme::Broadcast => WeekKeyFormat::Broadcast,
+        }
+    }
+
+    /// The week-year and week number (1-53) of the scheme's week containing
+    /// `date`.
+    pub fn week_of(self, date: NaiveDate) -> (i32, u32) {
+        let days_from_start = match self.week_start() {
+            WeekStart::Monday => date.weekday().num_days_from_monday(),
+            WeekStart::Sunday => date.weekday().num_days_from_sunday(),
+        };
+        // The week belongs to the year of this day of it, and is numbered
+        // by the weeks of that year before it.
+        let anchor_day = match self {
+            WeekScheme::Iso | WeekScheme::Mmwr => 3,
+            WeekScheme::Broadcast => 6,
+        };
+        let anchor = date + Duration::days(i64::from(anchor_day) - i64::from(days_from_start));
+        (anchor.year(), anchor.ordinal0() / 7 + 1)
+    }
+}
+
+impl std::fmt::Display for WeekScheme {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WeekScheme::Iso => write!(f, "iso"),
+            WeekScheme::Mmwr => write!(f, "mmwr"),
+            WeekScheme::Broadcast => write!(f, "broadcast"),
+        }
+    }
+}
+
+/// How to resolve a day of month that does not exist in a shorter month,
+/// e.g. January 31 plus one month.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize,
+)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum MonthClamp {
+    /// Use the last day of the shorter month (January 31 -> February 28).
+    #[default]
+    PreviousValidDay,
+    /// Use the first day of the following month (January 31 -> March 1).
+    RollForward,
+}
+
+impl std::fmt::Display for MonthClamp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MonthClamp::PreviousValidDay => write!(f, "previous_valid_day"),
+            MonthClamp::RollForward => write!(f, "roll_forward"),
+        }
+    }
+}
+
+/// Which instant of a bucket its `label_ts` represents.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum LabelPoint {
+    /// The bucket start (same instant as the key).
+    #[default]
+    Start,
+    /// The bucket end (exclusive boundary).
+    End,
+    /// Halfway between start and end in elapsed UTC time.
+    Midpoint,
+}
+
+impl std::fmt::Display for LabelPoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LabelPoint::Start => write!(f, "start"),
+            LabelPoint::End => write!(f, "end"),
+            LabelPoint::Midpoint => write!(f, "midpoint"),
+        }
+    }
+}
+
+/// Version of the JSON output shape, embedded as `schema_version`.
+///
+/// Serialized as an integer. [`OutputSchemaVersion::V1`] is the original,
+/// unversioned shape: it has no `schema_version` field, so it can only be
+/// requested, never detected.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum OutputSchemaVersion {
+    /// Records without a `schema_version` field, the default.
+    #[default]
+    V1,
+    /// Records carrying `"schema_version": 2`.
+    V2,
+}
+
+impl OutputSchemaVersion {
+    /// The newest version, emitted only when requested; the default stays
+    /// [`OutputSchemaVersion::V1`] so existing output does not change.
+    pub const CURRENT: Self = OutputSchemaVersion::V2;
+
+    /// The integer written to `schema_version`.
+    pub fn number(self) -> u32 {
+        match self {
+            OutputSchemaVersion::V1 => 1,
+            OutputSchemaVersion::V2 => 2,
+        }
+    }
+
+    /// Whether records of this version carry a `schema_version` field.
+    pub fn is_embedded(self) -> bool {
+        self >= OutputSchemaVersion::V2
+    }
+}
+
+impl std::fmt::Display for OutputSchemaVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.number())
+    }
+}
+
+impl Serialize for OutputSchemaVersion {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u32(self.number())
+    }
+}
+
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for OutputSchemaVersion {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        "OutputSchemaVersion".into()
+    }
+
+    fn json_schema(_generator: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        schemars::json_schema!({
+            "description": "Version of the output shape. Absent in version 1 output.",
+            "type": "integer",
+            "enum": [2]
+        })
+    }
+}
+
 /// Policy for handling nonexistent local times.
 ///
 /// Nonexistent times occur during DST spring forward when a range
@@ -92,14 +541,44 @@ pub struct Policy {
     pub ambiguous: AmbiguousPolicy,
 }
 
+/// Storage of bucket keys: a `String`, or with the `compact-str` feature a
+/// `CompactString`, which keeps keys of up to 24 bytes (every key but the
+/// `<N>m` keys of offsets with seconds) inline instead of on the heap. Both
+/// serialize as the same JSON string and deref to `&str`.
+#[cfg(not(feature = "compact-str"))]
+pub type BucketKey = String;
+/// Storage of bucket keys: a `String`, or with the `compact-str` feature a
+/// `CompactString`, which keeps keys of up to 24 bytes (every key but the
+/// `<N>m` keys of offsets with seconds) inline instead of on the heap. Both
+/// serialize as the same JSON string and deref to `&str`.
+#[cfg(feature = "compact-str")]
+pub type BucketKey = compact_str::CompactString;
+
+/// Format a bucket key straight into [`BucketKey`] storage.
+pub(crate) fn format_key(args: std::fmt::Arguments<'_>) -> BucketKey {
+    let mut key = BucketKey::default();
+    std::fmt::Write::write_fmt(&mut key, args).expect("a key formats without error");
+    key
+}
+
 /// A computed time bucket with boundaries in both local and UTC time.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Bucket {
-    /// Bucket key (format depends on interval):
-    /// - Day: `YYYY-MM-DD`
-    /// - Week: `YYYY-WXX` where XX is week number
+    /// Bucket key (format depends on interval, see the key table in
+    /// `docs/output_format.md`):
+    /// - `<N>m`: `YYYY-MM-DDTHH:MM±HH:MM` (local start and offset, with seconds
+    ///   such as `-00:44:30` where the offset has them)
+    /// - Hour, `<N>h`: `YYYY-MM-DDTHH` (local start hour)
+    /// - Day, `<N>d:<anchor>`, trailing month: `YYYY-MM-DD` (local start date)
+    /// - Week: `YYYY-MM-DD` (start date), or `YYYY-Www` in a
+    ///   [`WeekKeyFormat`] numbering
     /// - Month: `YYYY-MM`
-    pub key: String,
+    /// - Quarter: `YYYY-QN`; fiscal quarter: `FYYYYY-QN`
+    /// - Year: `YYYY`
+    /// - Fixed: the UTC start, RFC3339 with `Z` suffix
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
+    pub key: BucketKey,
     /// Bucket start in local time with offset (RFC3339 format).
     pub start_local: String,
     /// Bucket end in local time with offset (RFC3339 format).
@@ -108,10 +587,306 @@ pub struct Bucket {
     pub start_utc: String,
     /// Bucket end in UTC (RFC3339 format with Z suffix).
     pub end_utc: String,
+    /// Elapsed seconds from `start_utc` to `end_utc`, e.g. 82800 for a
+    /// 23-hour spring-forward day. Unaffected by
+    /// [`Bucket::with_inclusive_end`].
+    #[serde(default)]
+    pub duration_seconds: i64,
+    /// Week numbers of the bucket start date, when requested (see
+    /// [`Bucket::with_week_numbers`]).
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    pub week_numbers: Option<WeekNumbers>,
+    /// Clamp policy applied when a trailing month anchor did not exist in
+    /// the start or end month.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub month_clamp: Option<MonthClamp>,
+    /// Label instant in UTC (RFC3339 format with Z suffix), when requested
+    /// (see [`Bucket::with_label`]).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label_ts: Option<String>,
+    /// UTC offsets at the boundaries and whether they differ, when requested
+    /// (see [`Bucket::with_dst_info`]).
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    pub dst_info: Option<DstInfo>,
+}
+
+impl Bucket {
+    /// The bucket of `interval` in `tz` with the given key, such as a key
+    /// stored in a database. Same as
+    /// [`navigate::bucket_for_key`](crate::navigate::bucket_for_key).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TzBucketError::ParseError`](crate::TzBucketError::ParseError)
+    /// if `key` is not a key of `interval` in `tz`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tzbucket_core::{Bucket, Interval, WeekStart};
+    ///
+    /// let tz = chrono_tz::America::New_York;
+    /// let bucket = Bucket::from_key("2026-03", tz, Interval::Month, None).unwrap();
+    /// assert_eq!(bucket.start_utc, "2026-03-01T05:00:00Z");
+    /// assert_eq!(bucket.end_utc, "2026-04-01T04:00:00Z");
+    ///
+    /// let week = Bucket::from_key("2026-03-08", tz, Interval::Week, Some(WeekStart::Sunday));
+    /// assert_eq!(week.unwrap().duration_seconds, 7 * 86_400 - 3600);
+    /// ```
+    pub fn from_key(
+        key: &str,
+        tz: Tz,
+        interval: Interval,
+        week_start: Option<WeekStart>,
+    ) -> crate::error::Result<Bucket> {
+        crate::navigate::bucket_for_key(key, tz, interval, week_start)
+    }
+
+    /// Attach [`WeekNumbers`] for the bucket start date.
+    ///
+    /// Only buckets keyed by their start date (day, week, trailing month) are
+    /// annotated; month buckets are returned unchanged.
+    pub fn with_week_numbers(mut self) -> Self {
+        if let Ok(start) = NaiveDate::parse_from_str(&self.key, "%Y-%m-%d") {
+            self.week_numbers = Some(WeekNumbers::for_date(start));
+        }
+        self
+    }
+
+    /// Rewrite the key of a week bucket keyed by its start date in `format`.
+    ///
+    /// Like [`Bucket::with_week_numbers`] this reads the start date from the
+    /// key, so callers apply it to week buckets only (a day key would be
+    /// rewritten too). Keys that are not dates are left unchanged.
+    pub fn with_week_key(mut self, week_start: WeekStart, format: WeekKeyFormat) -> Self {
+        if let Ok(start) = NaiveDate::parse_from_str(&self.key, "%Y-%m-%d") {
+            self.key = format.key(start, week_start);
+        }
+        self
+    }
+
+    /// Set `label_ts` to the bucket instant selected by `point`.
+    ///
+    /// The midpoint is taken in elapsed time, so it falls at 12:30 local time
+    /// on a 23-hour day and at 11:30 on a 25-hour day. Sub-second midpoints
+    /// are truncated to the second.
+    pub fn with_label(mut self, point: LabelPoint) -> Self {
+        let parse = |ts: &str| DateTime::parse_from_rfc3339(ts).map(|dt| dt.with_timezone(&Utc));
+        if let (Ok(start), Ok(end)) = (parse(&self.start_utc), parse(&self.end_utc)) {
+            let label = match point {
+                LabelPoint::Start => start,
+                LabelPoint::End => end,
+                LabelPoint::Midpoint => start + (end - start) / 2,
+            };
+            self.label_ts = Some(label.format("%Y-%m-%dT%H:%M:%SZ").to_string());
+        }
+        self
+    }
+
+    /// Attach [`DstInfo`]: the UTC offsets at the boundaries in `tz` and
+    /// whether the offset changes inside the bucket.
+    ///
+    /// Lets consumers spot 23- and 25-hour days without subtracting
+    /// boundaries. Apply it before [`Bucket::with_inclusive_end`], which moves
+    /// the end; buckets whose boundaries do not parse are returned unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chrono::{TimeZone, Utc};
+    /// use tzbucket_core::{Interval, compute_bucket};
+    ///
+    /// let tz = chrono_tz::Europe::Berlin;
+    /// let instant = Utc.with_ymd_and_hms(2026, 3, 29, 12, 0, 0).unwrap();
+    /// let bucket = compute_bucket(instant, tz, Interval::Day, None).with_dst_info(tz);
+    /// let info = bucket.dst_info.unwrap();
+    ///
+    /// assert!(info.is_dst_transition);
+    /// assert_eq!((info.start_offset_seconds, info.end_offset_seconds), (3600, 7200));
+    /// ```
+    pub fn with_dst_info(mut self, tz: Tz) -> Self {
+        if let Ok(range) = self.utc_range() {
+            let offset_at =
+                |t: DateTime<Utc>| t.with_timezone(&tz).offset().fix().local_minus_utc();
+            let is_dst_transition = offset_transitions(tz, range.start, range.end)
+                .iter()
+                .any(|&at| at < range.end);
+            self.dst_info = Some(DstInfo {
+                is_dst_transition,
+                start_offset_seconds: offset_at(range.start),
+                end_offset_seconds: offset_at(range.end),
+            });
+        }
+        self
+    }
+
+    /// Rewrite `end_local` and `end_utc` as the last millisecond inside the
+    /// bucket, for consumers that need closed intervals.
+    ///
+    /// Buckets are half-open everywhere else; this only changes the rendered
+    /// end. `tz` supplies the offset of that last millisecond, which can
+    /// differ from the offset at the exclusive end.
+    pub fn with_inclusive_end(mut self, tz: Tz) -> Self {
+        if let Ok(end) = DateTime::parse_from_rfc3339(&self.end_utc) {
+            let last = end.with_timezone(&Utc) - Duration::milliseconds(1);
+            self.end_local = last
+                .with_timezone(&tz)
+                .format("%Y-%m-%dT%H:%M:%S%.3f%:z")
+                .to_string();
+            self.end_utc = last.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string();
+        }
+        self
+    }
+
+    /// The bucket's UTC range, `start_utc..end_utc`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TzBucketError::ParseError`](crate::error::TzBucketError::ParseError)
+    /// if a boundary is not RFC3339, which only happens to buckets built or
+    /// edited by hand.
+    pub fn utc_range(&self) -> crate::error::Result<Range<DateTime<Utc>>> {
+        let parse = |ts: &str| {
+            DateTime::parse_from_rfc3339(ts)
+                .map(|dt| dt.with_timezone(&Utc))
+                .map_err(|e| {
+                    crate::error::TzBucketError::ParseError(format!(
+                        "invalid bucket boundary '{}': {}",
+                        ts, e
+                    ))
+                })
+        };
+        Ok(parse(&self.start_utc)?..parse(&self.end_utc)?)
+    }
+
+    /// Whether `instant` lies in the half-open range `[start_utc, end_utc)`.
+    ///
+    /// Compares instants rather than RFC3339 strings, so boundaries with
+    /// fractional seconds or another precision compare correctly. A bucket
+    /// whose boundaries do not parse contains nothing. After
+    /// [`Bucket::with_inclusive_end`] the end is the last millisecond, which
+    /// then no longer counts as inside.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chrono::{TimeZone, Utc};
+    /// use tzbucket_core::{Interval, compute_bucket};
+    ///
+    /// let instant = Utc.with_ymd_and_hms(2026, 3, 29, 12, 0, 0).unwrap();
+    /// let bucket = compute_bucket(instant, chrono_tz::Europe::Berlin, Interval::Day, None);
+    ///
+    /// assert!(bucket.contains(instant));
+    /// // The 23-hour day ends at 22:00 UTC.
+    /// assert!(!bucket.contains(Utc.with_ymd_and_hms(2026, 3, 29, 22, 0, 0).unwrap()));
+    /// ```
+    pub fn contains(&self, instant: DateTime<Utc>) -> bool {
+        self.utc_range().is_ok_and(|range| range.contains(&instant))
+    }
+}
+
+/// A computed bucket with chrono boundaries instead of RFC3339 strings.
+///
+/// Returned by [`compute_bucket_typed`](crate::compute::compute_bucket_typed)
+/// for callers that go on to do arithmetic with the boundaries. The local
+/// boundaries are the UTC ones viewed in the bucket's zone. Convert with
+/// `Bucket::from` to serialize.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypedBucket {
+    /// Bucket key, as in [`Bucket::key`].
+    pub key: BucketKey,
+    /// Bucket start (inclusive).
+    pub start_utc: DateTime<Utc>,
+    /// Bucket end (exclusive).
+    pub end_utc: DateTime<Utc>,
+    /// Bucket start in the bucket's zone.
+    pub start_local: DateTime<Tz>,
+    /// Bucket end in the bucket's zone.
+    pub end_local: DateTime<Tz>,
+    /// Clamp policy applied when a trailing month anchor did not exist in
+    /// the start or end month.
+    pub month_clamp: Option<MonthClamp>,
+}
+
+impl TypedBucket {
+    /// Whether `instant` lies in the half-open range `[start_utc, end_utc)`.
+    pub fn contains(&self, instant: DateTime<Utc>) -> bool {
+        (self.start_utc..self.end_utc).contains(&instant)
+    }
+
+    /// Elapsed time from start to end, e.g. 23 hours for a spring-forward
+    /// day.
+    pub fn duration(&self) -> Duration {
+        self.end_utc - self.start_utc
+    }
+}
+
+impl From<TypedBucket> for Bucket {
+    fn from(bucket: TypedBucket) -> Self {
+        let duration_seconds = bucket.duration().num_seconds();
+        Bucket {
+            key: bucket.key,
+            start_local: format_rfc3339(&bucket.start_local),
+            end_local: format_rfc3339(&bucket.end_local),
+            start_utc: format_rfc3339_utc(&bucket.start_utc),
+            end_utc: format_rfc3339_utc(&bucket.end_utc),
+            duration_seconds,
+            week_numbers: None,
+            month_clamp: bucket.month_clamp,
+            label_ts: None,
+            dst_info: None,
+        }
+    }
+}
+
+/// UTC offsets of a bucket's boundaries, see [`Bucket::with_dst_info`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct DstInfo {
+    /// Whether the UTC offset changes inside the bucket, as on the days DST
+    /// starts and ends.
+    pub is_dst_transition: bool,
+    /// UTC offset at `start_utc`, in seconds east of UTC.
+    pub start_offset_seconds: i32,
+    /// UTC offset at `end_utc`, in seconds east of UTC.
+    pub end_offset_seconds: i32,
+}
+
+/// Week numbering metadata for a date.
+///
+/// With Monday week starts the ISO fields identify the bucket exactly; with
+/// Sunday week starts `us_week_number` does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct WeekNumbers {
+    /// ISO 8601 week number (1-53).
+    pub iso_week: u32,
+    /// ISO 8601 week-numbering year (may differ from the calendar year).
+    pub iso_week_year: i32,
+    /// US week number (1-54): weeks start on Sunday, week 1 contains January 1.
+    pub us_week_number: u32,
+}
+
+impl WeekNumbers {
+    /// Week numbers of `date`.
+    pub fn for_date(date: NaiveDate) -> Self {
+        let iso = date.iso_week();
+        let jan1_offset = NaiveDate::from_ymd_opt(date.year(), 1, 1)
+            .expect("January 1 exists for every representable year")
+            .weekday()
+            .num_days_from_sunday();
+
+        Self {
+            iso_week: iso.week(),
+            iso_week_year: iso.year(),
+            us_week_number: (date.ordinal0() + jan1_offset) / 7 + 1,
+        }
+    }
 }
 
 /// Parsed input timestamp.
 #[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct InputTimestamp {
     /// Original input string.
     pub ts: String,
@@ -121,6 +896,7 @@ pub struct InputTimestamp {
 
 /// Complete result of a bucket computation.
 #[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct BucketResult {
     /// The input timestamp that was processed.
     pub input: InputTimestamp,
@@ -132,6 +908,111 @@ pub struct BucketResult {
     pub bucket: Bucket,
 }
 
+/// Borrowed form of [`InputTimestamp`], see [`BucketResultRef`].
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct InputTimestampRef<'a> {
+    /// Original input string.
+    pub ts: &'a str,
+    /// Epoch milliseconds (UTC).
+    pub epoch_ms: i64,
+}
+
+/// A [`BucketResult`] borrowing the input string and zone name from the
+/// caller, as returned by
+/// [`compute_bucket_ref_from_string`](crate::compute::compute_bucket_ref_from_string).
+///
+/// Serializes exactly like [`BucketResult`], without copying every input
+/// line into the result first.
+#[derive(Debug, Clone, Serialize)]
+pub struct BucketResultRef<'a> {
+    /// The input timestamp that was processed.
+    pub input: InputTimestampRef<'a>,
+    /// The timezone used for bucket computation.
+    pub tz: &'a str,
+    /// The interval (granularity) used.
+    pub interval: Interval,
+    /// The computed bucket.
+    pub bucket: Bucket,
+}
+
+impl From<BucketResultRef<'_>> for BucketResult {
+    fn from(result: BucketResultRef<'_>) -> Self {
+        BucketResult {
+            input: InputTimestamp {
+                ts: result.input.ts.to_string(),
+                epoch_ms: result.input.epoch_ms,
+            },
+            tz: result.tz.to_string(),
+            interval: result.interval,
+            bucket: result.bucket,
+        }
+    }
+}
+
+/// Bucket assignments of many instants in struct-of-arrays form, as
+/// returned by [`compute_buckets_columnar`](crate::compute::compute_buckets_columnar).
+///
+/// Row `i` of every column belongs to input instant `i`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct BucketColumns {
+    /// Bucket keys.
+    pub keys: Vec<BucketKey>,
+    /// Bucket starts in epoch milliseconds (UTC).
+    pub start_ms: Vec<i64>,
+    /// Bucket ends (exclusive) in epoch milliseconds (UTC).
+    pub end_ms: Vec<i64>,
+}
+
+impl BucketColumns {
+    /// Empty columns with room for `rows` rows each.
+    pub fn with_capacity(rows: usize) -> Self {
+        Self {
+            keys: Vec::with_capacity(rows),
+            start_ms: Vec::with_capacity(rows),
+            end_ms: Vec::with_capacity(rows),
+        }
+    }
+
+    /// Number of rows.
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Whether there are no rows.
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+}
+
+/// What to bucket by: timezone, interval and week start, as taken by the
+/// batch API ([`compute_buckets`](crate::compute::compute_buckets)).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BucketSpec {
+    /// Timezone the bucket boundaries are computed in.
+    pub tz: Tz,
+    /// Bucket interval.
+    pub interval: Interval,
+    /// Week start day (only used for [`Interval::Week`]); `None` is Monday.
+    pub week_start: Option<WeekStart>,
+}
+
+impl BucketSpec {
+    /// A spec with the default week start.
+    pub fn new(tz: Tz, interval: Interval) -> Self {
+        Self {
+            tz,
+            interval,
+            week_start: None,
+        }
+    }
+
+    /// Use `week_start` for week buckets.
+    pub fn with_week_start(mut self, week_start: WeekStart) -> Self {
+        self.week_start = Some(week_start);
+        self
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -141,11 +1022,135 @@ mod tests {
         assert_eq!(Interval::default(), Interval::Day);
     }
 
+    #[test]
+    fn output_schema_version_serializes_as_integer() {
+        assert_eq!(OutputSchemaVersion::default(), OutputSchemaVersion::V1);
+        assert_eq!(
+            serde_json::to_string(&OutputSchemaVersion::CURRENT).unwrap(),
+            "2"
+        );
+        assert!(!OutputSchemaVersion::V1.is_embedded());
+    }
+
+    // Runs with and without `compact-str`: keys must serialize as the same
+    // JSON strings whether `BucketKey` is a `String` or a `CompactString`.
+    #[test]
+    fn bucket_keys_serialize_as_strings() {
+        use chrono::TimeZone;
+
+        let at = Utc.with_ymd_and_hms(1970, 6, 1, 12, 0, 0).unwrap();
+        let cases = [
+            (chrono_tz::Europe::Berlin, Interval::Day, "1970-06-01"),
+            (chrono_tz::Europe::Berlin, Interval::Hour, "1970-06-01T13"),
+            (chrono_tz::Europe::Berlin, Interval::Month, "1970-06"),
+            // Longer than a `CompactString` holds inline: Monrovia's offset
+            // had seconds until 1972.
+            (
+                chrono_tz::Africa::Monrovia,
+                Interval::Minutes(15),
+                "1970-06-01T11:15-00:44:30",
+            ),
+        ];
+        for (tz, interval, key) in cases {
+            let bucket = crate::compute::compute_bucket(at, tz, interval, None);
+            assert_eq!(bucket.key, key);
+            #[cfg(feature = "compact-str")]
+            assert_eq!(bucket.key.is_heap_allocated(), key.len() > 24);
+            assert_eq!(
+                serde_json::to_string(&bucket.key).unwrap(),
+                format!("\"{}\"", key)
+            );
+            let json = serde_json::to_value(&bucket).unwrap();
+            assert_eq!(json["key"], key);
+            let back: Bucket = serde_json::from_value(json).unwrap();
+            assert_eq!(back.key, key);
+        }
+    }
+
     #[test]
     fn interval_display() {
+        assert_eq!(format!("{}", Interval::Minutes(15)), "15m");
+        assert_eq!(format!("{}", Interval::Hour), "hour");
+        assert_eq!(format!("{}", Interval::Hours(6)), "6h");
         assert_eq!(format!("{}", Interval::Day), "day");
+        assert_eq!(
+            format!(
+                "{}",
+                Interval::Days {
+                    n: 14,
+                    anchor: NaiveDate::from_ymd_opt(2026, 1, 5).unwrap()
+                }
+            ),
+            "14d:2026-01-05"
+        );
         assert_eq!(format!("{}", Interval::Week), "week");
         assert_eq!(format!("{}", Interval::Month), "month");
+        assert_eq!(format!("{}", Interval::Quarter), "quarter");
+        assert_eq!(
+            format!("{}", Interval::FiscalQuarter { start_month: 10 }),
+            "fiscal_quarter:10"
+        );
+        assert_eq!(format!("{}", Interval::Year), "year");
+        assert_eq!(
+            format!(
+                "{}",
+                Interval::TrailingMonth {
+                    anchor_day: 15,
+                    clamp: MonthClamp::PreviousValidDay
+                }
+            ),
+            "trailing_month:15"
+        );
+        assert_eq!(
+            format!(
+                "{}",
+                Interval::TrailingMonth {
+                    anchor_day: 31,
+                    clamp: MonthClamp::RollForward
+                }
+            ),
+            "trailing_month:31:roll_forward"
+        );
+        assert_eq!(format!("{}", Interval::FixedSeconds(300)), "fixed:300");
+    }
+
+    #[test]
+    fn interval_parses_display_form() {
+        for interval in [
+            Interval::Minutes(5),
+            Interval::Hour,
+            Interval::Hours(6),
+            Interval::Day,
+            Interval::Days {
+                n: 7,
+                anchor: NaiveDate::from_ymd_opt(2026, 1, 1).unwrap(),
+            },
+            Interval::Week,
+            Interval::Month,
+            Interval::Quarter,
+            Interval::FiscalQuarter { start_month: 4 },
+            Interval::Year,
+            Interval::TrailingMonth {
+                anchor_day: 15,
+                clamp: MonthClamp::PreviousValidDay,
+            },
+            Interval::TrailingMonth {
+                anchor_day: 31,
+                clamp: MonthClamp::RollForward,
+            },
+            Interval::FixedSeconds(300),
+        ] {
+            assert_eq!(interval.to_string().parse::<Interval>().unwrap(), interval);
+        }
+        assert!("trailing_month:0".parse::<Interval>().is_err());
+        assert!("fixed:0".parse::<Interval>().is_err());
+        assert!("fiscal_quarter:13".parse::<Interval>().is_err());
+        assert!("fiscal_quarter".parse::<Interval>().is_err());
+        assert!("0m".parse::<Interval>().is_err());
+        assert!("7m".parse::<Interval>().is_err());
+        assert!("minute".parse::<Interval>().is_err());
+        assert!("0d:2026-01-05".parse::<Interval>().is_err());
+        assert!("14d".parse::<Interval>().is_err());
     }
 
     #[test]
@@ -174,6 +1179,168 @@ mod tests {
             serde_json::to_string(&Interval::Month).unwrap(),
             "\"month\""
         );
+        assert_eq!(
+            serde_json::to_string(&Interval::TrailingMonth {
+                anchor_day: 31,
+                clamp: MonthClamp::default()
+            })
+            .unwrap(),
+            "\"trailing_month:31\""
+        );
+    }
+
+    #[test]
+    fn week_numbers_across_year_boundary() {
+        // Monday 2025-12-29 is in ISO week 1 of 2026, US week 53 of 2025.
+        let monday = WeekNumbers::for_date(NaiveDate::from_ymd_opt(2025, 12, 29).unwrap());
+        assert_eq!(monday.iso_week, 1);
+        assert_eq!(monday.iso_week_year, 2026);
+        assert_eq!(monday.us_week_number, 53);
+
+        // Sunday 2026-01-04: second US week of 2026 (Jan 1 was a Thursday).
+        let sunday = WeekNumbers::for_date(NaiveDate::from_ymd_opt(2026, 1, 4).unwrap());
+        assert_eq!(sunday.iso_week, 1);
+        assert_eq!(sunday.us_week_number, 2);
+    }
+
+    #[test]
+    fn bucket_week_numbers_skip_month_keys() {
+        let bucket = Bucket {
+            key: "2026-03".into(),
+            start_local: String::new(),
+            end_local: String::new(),
+            start_utc: String::new(),
+            end_utc: String::new(),
+            duration_seconds: 0,
+            week_numbers: None,
+            month_clamp: None,
+            label_ts: None,
+            dst_info: None,
+        };
+
+        assert_eq!(bucket.with_week_numbers().week_numbers, None);
+    }
+
+    #[test]
+    fn bucket_label_points() {
+        // Europe/Berlin 2026-03-29: a 23-hour day.
+        let bucket = Bucket {
+            key: "2026-03-29".into(),
+            start_local: "2026-03-29T00:00:00+01:00".to_string(),
+            end_local: "2026-03-30T00:00:00+02:00".to_string(),
+            start_utc: "2026-03-28T23:00:00Z".to_string(),
+            end_utc: "2026-03-29T22:00:00Z".to_string(),
+            duration_seconds: 23 * 3600,
+            week_numbers: None,
+            month_clamp: None,
+            label_ts: None,
+            dst_info: None,
+        };
+        let label = |point| bucket.clone().with_label(point).label_ts.unwrap();
+
+        assert_eq!(label(LabelPoint::Start), "2026-03-28T23:00:00Z");
+        assert_eq!(label(LabelPoint::End), "2026-03-29T22:00:00Z");
+        assert_eq!(label(LabelPoint::Midpoint), "2026-03-29T10:30:00Z");
+    }
+
+    #[test]
+    fn bucket_contains_is_half_open() {
+        let bucket = Bucket {
+            key: "2026-03-29".into(),
+            start_local: "2026-03-29T00:00:00+01:00".to_string(),
+            end_local: "2026-03-30T00:00:00+02:00".to_string(),
+            start_utc: "2026-03-28T23:00:00Z".to_string(),
+            end_utc: "2026-03-29T22:00:00Z".to_string(),
+            duration_seconds: 23 * 3600,
+            week_numbers: None,
+            month_clamp: None,
+            label_ts: None,
+            dst_info: None,
+        };
+        let at = |rfc3339| {
+            DateTime::parse_from_rfc3339(rfc3339)
+                .unwrap()
+                .with_timezone(&Utc)
+        };
+
+        assert!(bucket.contains(at("2026-03-28T23:00:00Z")));
+        assert!(bucket.contains(at("2026-03-29T21:59:59.999Z")));
+        assert!(!bucket.contains(at("2026-03-28T22:59:59.999Z")));
+        assert!(!bucket.contains(at("2026-03-29T22:00:00Z")));
+
+        let broken = Bucket {
+            end_utc: "tomorrow".to_string(),
+            ..bucket
+        };
+        assert!(broken.utc_range().is_err());
+        assert!(!broken.contains(at("2026-03-29T12:00:00Z")));
+    }
+
+    #[test]
+    fn bucket_inclusive_end_uses_offset_before_end() {
+        // America/Sao_Paulo 2018-11-03: the next local midnight did not exist,
+        // so the bucket ends at 01:00-02:00 but its last instant is still -03:00.
+        let bucket = Bucket {
+            key: "2018-11-03".into(),
+            start_local: "2018-11-03T00:00:00-03:00".to_string(),
+            end_local: "2018-11-04T01:00:00-02:00".to_string(),
+            start_utc: "2018-11-03T03:00:00Z".to_string(),
+            end_utc: "2018-11-04T03:00:00Z".to_string(),
+            duration_seconds: 24 * 3600,
+            week_numbers: None,
+            month_clamp: None,
+            label_ts: None,
+            dst_info: None,
+        }
+        .with_inclusive_end(chrono_tz::America::Sao_Paulo);
+
+        assert_eq!(bucket.end_local, "2018-11-03T23:59:59.999-03:00");
+        assert_eq!(bucket.end_utc, "2018-11-04T02:59:59.999Z");
+        assert_eq!(bucket.start_utc, "2018-11-03T03:00:00Z");
+    }
+
+    #[test]
+    fn bucket_dst_info_sees_transitions_inside() {
+        use crate::compute::compute_bucket;
+        use chrono::TimeZone;
+
+        let tz = chrono_tz::Europe::Berlin;
+        let info = |interval, instant| {
+            compute_bucket(instant, tz, interval, None)
+                .with_dst_info(tz)
+                .dst_info
+                .unwrap()
+        };
+        let summer = Utc.with_ymd_and_hms(2026, 7, 1, 12, 0, 0).unwrap();
+
+        // Both transitions of 2026 lie inside the year; the ends agree.
+        assert_eq!(
+            info(Interval::Year, summer),
+            DstInfo {
+                is_dst_transition: true,
+                start_offset_seconds: 3600,
+                end_offset_seconds: 3600,
+            }
+        );
+        assert_eq!(
+            info(Interval::Day, summer),
+            DstInfo {
+                is_dst_transition: false,
+                start_offset_seconds: 7200,
+                end_offset_seconds: 7200,
+            }
+        );
+        // The hour before the spring forward ends at it, but does not contain it.
+        let before = Utc.with_ymd_and_hms(2026, 3, 29, 0, 30, 0).unwrap();
+        let hour = info(Interval::Hour, before);
+        assert!(!hour.is_dst_transition);
+        assert_eq!(hour.end_offset_seconds, 7200);
+
+        let json =
+            serde_json::to_value(compute_bucket(summer, tz, Interval::Day, None).with_dst_info(tz))
+                .unwrap();
+        assert_eq!(json["is_dst_transition"], false);
+        assert_eq!(json["start_offset_seconds"], 7200);
     }
 
     #[test]
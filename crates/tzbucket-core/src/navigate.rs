@@ -0,0 +1,673 @@
+//! Bucket navigation by key.
+//!
+//! [`bucket_for_key`] turns a bucket key back into its bucket,
+//! [`shift_bucket`] moves a key by whole intervals ([`shift_bucket_at`]
+//! starting from an instant instead) and [`buckets_between`] counts the
+//! intervals separating two keys ([`buckets_between_at`] two instants).
+//!
+//! Calendar intervals and fixed windows are numbered consecutively, so
+//! navigation is key arithmetic. Hour, hours and minutes buckets are walked
+//! one by one instead: skipped and repeated local times make the number of
+//! local hours (or windows) between two keys differ from the number of
+//! buckets.
+
+use chrono::{DateTime, Datelike, Duration, FixedOffset, NaiveDate, NaiveDateTime, Utc};
+use chrono_tz::Tz;
+
+use crate::calendar::day_in_month;
+use crate::compute::{boundary_to_utc, compute_bucket, fiscal_quarter_start};
+use crate::error::{Result, TzBucketError};
+use crate::models::{Bucket, Interval, MonthClamp, WeekStart};
+use crate::tz::local_midnight_to_utc;
+
+/// Upper bound on buckets walked by [`shift_bucket`] and [`buckets_between`]
+/// (about 114 years of hours).
+pub const MAX_WALK_STEPS: u64 = 1_000_000;
+
+/// The bucket with the given key.
+///
+/// # Errors
+///
+/// Returns [`TzBucketError::ParseError`] if `key` is not a key of `interval`
+/// in `tz`: malformed, not a bucket start (a week key on the wrong weekday, an
+/// unaligned fixed window), or an hour skipped by a DST transition.
+///
+/// # Examples
+///
+/// ```
+/// use tzbucket_core::Interval;
+/// use tzbucket_core::navigate::bucket_for_key;
+///
+/// let bucket = bucket_for_key("2026-03-29", chrono_tz::Europe::Berlin, Interval::Day, None).unwrap();
+///
+/// assert_eq!(bucket.start_utc, "2026-03-28T23:00:00Z");
+/// assert_eq!(bucket.end_utc, "2026-03-29T22:00:00Z");
+/// ```
+pub fn bucket_for_key(
+    key: &str,
+    tz: Tz,
+    interval: Interval,
+    week_start: Option<WeekStart>,
+) -> Result<Bucket> {
+    let start = match interval {
+        Interval::Hour | Interval::Hours(_) => {
+            let local = NaiveDateTime::parse_from_str(&format!("{}:00", key), "%Y-%m-%dT%H:%M")
+                .map_err(|_| invalid_key(key, interval, tz))?;
+            boundary_to_utc(local, tz, interval).0
+        }
+        Interval::Minutes(_) => {
+            parse_minutes_key(key).ok_or_else(|| invalid_key(key, interval, tz))?
+        }
+        _ => {
+            let position = position(key, interval, week_start.unwrap_or_default())
+                .ok_or_else(|| invalid_key(key, interval, tz))?;
+            position_start(position, tz, interval, week_start.unwrap_or_default())
+                .ok_or_else(|| out_of_range(key))?
+        }
+    };
+
+    let bucket = compute_bucket(start, tz, interval, week_start);
+    if bucket.key != key {
+        return Err(invalid_key(key, interval, tz));
+    }
+    Ok(bucket)
+}
+
+/// The bucket `n` intervals after the one keyed `key` (before it if `n` is
+/// negative).
+///
+/// # Errors
+///
+/// Returns [`TzBucketError::ParseError`] if `key` is invalid (see
+/// [`bucket_for_key`]), the target is outside chrono's range, or a walked
+/// shift (minutes, hours or days) exceeds [`MAX_WALK_STEPS`].
+///
+/// # Examples
+///
+/// ```
+/// use tzbucket_core::Interval;
+/// use tzbucket_core::navigate::shift_bucket;
+///
+/// let tz = chrono_tz::Europe::Berlin;
+/// let bucket = shift_bucket("2026-03-29", tz, Interval::Day, None, -7).unwrap();
+/// assert_eq!(bucket.key, "2026-03-22");
+///
+/// // Local 02:00 does not exist on 2026-03-29: the next hour bucket is 03.
+/// let bucket = shift_bucket("2026-03-29T01", tz, Interval::Hour, None, 1).unwrap();
+/// assert_eq!(bucket.key, "2026-03-29T03");
+/// ```
+pub fn shift_bucket(
+    key: &str,
+    tz: Tz,
+    interval: Interval,
+    week_start: Option<WeekStart>,
+    n: i64,
+) -> Result<Bucket> {
+    let bucket = bucket_for_key(key, tz, interval, week_start)?;
+    if n == 0 {
+        return Ok(bucket);
+    }
+
+    if is_walked(interval) {
+        if n.unsigned_abs() > MAX_WALK_STEPS {
+            return Err(TzBucketError::ParseError(format!(
+                "{} shift {} exceeds {} steps",
+                interval, n, MAX_WALK_STEPS
+            )));
+        }
+        let mut bucket = bucket;
+        for _ in 0..n.unsigned_abs() {
+            bucket = step(&bucket, tz, interval, n > 0)?;
+        }
+        return Ok(bucket);
+    }
+
+    let week_start = week_start.unwrap_or_default();
+    let target = position(key, interval, week_start)
+        .and_then(|position| position.checked_add(n))
+        .and_then(|position| position_start(position, tz, interval, week_start))
+        .ok_or_else(|| out_of_range(key))?;
+    Ok(compute_bucket(target, tz, interval, Some(week_start)))
+}
+
+/// The bucket `n` intervals after the one containing `instant` (before it if
+/// `n` is negative): "the same bucket last month" for an event timestamp.
+///
+/// # Errors
+///
+/// As [`shift_bucket`], except that the starting bucket is always valid.
+///
+/// # Examples
+///
+/// ```
+/// use chrono::{TimeZone, Utc};
+/// use tzbucket_core::Interval;
+/// use tzbucket_core::navigate::shift_bucket_at;
+///
+/// let tz = chrono_tz::Europe::Berlin;
+/// let instant = Utc.with_ymd_and_hms(2026, 3, 31, 12, 0, 0).unwrap();
+///
+/// // One month back is all of February, not 31 days back.
+/// let bucket = shift_bucket_at(instant, tz, Interval::Month, None, -1).unwrap();
+/// assert_eq!(bucket.key, "2026-02");
+/// assert_eq!(bucket.start_utc, "2026-01-31T23:00:00Z");
+/// ```
+pub fn shift_bucket_at(
+    instant: DateTime<Utc>,
+    tz: Tz,
+    interval: Interval,
+    week_start: Option<WeekStart>,
+    n: i64,
+) -> Result<Bucket> {
+    let bucket = compute_bucket(instant, tz, interval, week_start);
+    shift_bucket(&bucket.key, tz, interval, week_start, n)
+}
+
+/// How many intervals the bucket keyed `to` lies after the one keyed `from`
+/// (negative if it lies before).
+///
+/// # Errors
+///
+/// Returns [`TzBucketError::ParseError`] if either key is invalid (see
+/// [`bucket_for_key`]) or minutes, hour or day keys are more than
+/// [`MAX_WALK_STEPS`] apart.
+///
+/// # Examples
+///
+/// ```
+/// use tzbucket_core::Interval;
+/// use tzbucket_core::navigate::buckets_between;
+///
+/// let tz = chrono_tz::Europe::Berlin;
+/// assert_eq!(buckets_between("2025-11", "2026-03", tz, Interval::Month, None).unwrap(), 4);
+/// assert_eq!(
+///     buckets_between("2026-03-29T04", "2026-03-29T00", tz, Interval::Hour, None).unwrap(),
+///     -3
+/// );
+/// ```
+pub fn buckets_between(
+    from: &str,
+    to: &str,
+    tz: Tz,
+    interval: Interval,
+    week_start: Option<WeekStart>,
+) -> Result<i64> {
+    let from_bucket = bucket_for_key(from, tz, interval, week_start)?;
+    let to_bucket = bucket_for_key(to, tz, interval, week_start)?;
+
+    if !is_walked(interval) {
+        let week_start = week_start.unwrap_or_default();
+        let from = position(from, interval, week_start).ok_or_else(|| out_of_range(from))?;
+        let to = position(to, interval, week_start).ok_or_else(|| out_of_range(to))?;
+        return Ok(to - from);
+    }
+
+    let forward = to_bucket.start_utc >= from_bucket.start_utc;
+    let mut bucket = from_bucket;
+    let mut steps = 0;
+    while bucket.start_utc != to_bucket.start_utc {
+        if steps == MAX_WALK_STEPS {
+            return Err(TzBucketError::ParseError(format!(
+                "{} keys '{}' and '{}' are more than {} steps apart",
+                interval, from, to, MAX_WALK_STEPS
+            )));
+        }
+        bucket = step(&bucket, tz, interval, forward)?;
+        steps += 1;
+    }
+    let steps = steps as i64;
+    Ok(if forward { steps } else { -steps })
+}
+
+/// How many intervals the bucket containing `to` lies after the one
+/// containing `from` (negative if it lies before): the bucket boundaries
+/// crossed between two instants, such as how many local days apart two
+/// events are.
+///
+/// This is not the UTC difference divided by the interval length, which
+/// miscounts across DST changes and uneven months.
+///
+/// # Errors
+///
+/// As [`buckets_between`], except that both buckets are always valid.
+///
+/// # Examples
+///
+/// ```
+/// use chrono::{TimeZone, Utc};
+/// use tzbucket_core::Interval;
+/// use tzbucket_core::navigate::buckets_between_at;
+///
+/// let tz = chrono_tz::Europe::Berlin;
+/// // Local 2026-03-28 13:00 and 2026-03-30 00:30: 35.5 hours, two days apart.
+/// let from = Utc.with_ymd_and_hms(2026, 3, 28, 12, 0, 0).unwrap();
+/// let to = Utc.with_ymd_and_hms(2026, 3, 29, 22, 30, 0).unwrap();
+/// assert_eq!(buckets_between_at(from, to, tz, Interval::Day, None).unwrap(), 2);
+/// assert_eq!(buckets_between_at(to, from, tz, Interval::Day, None).unwrap(), -2);
+/// ```
+pub fn buckets_between_at(
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    tz: Tz,
+    interval: Interval,
+    week_start: Option<WeekStart>,
+) -> Result<i64> {
+    let from = compute_bucket(from, tz, interval, week_start);
+    let to = compute_bucket(to, tz, interval, week_start);
+    buckets_between(&from.key, &to.key, tz, interval, week_start)
+}
+
+/// The start of a minutes bucket from its key: the local start minute and
+/// its UTC offset, `±HH:MM` or, in a few historical zones, `±HH:MM:SS`
+/// (which chrono does not parse).
+fn parse_minutes_key(key: &str) -> Option<DateTime<Utc>> {
+    let (local, offset) = key.split_at(key.rfind(['+', '-'])?);
+    let local = NaiveDateTime::parse_from_str(local, "%Y-%m-%dT%H:%M").ok()?;
+
+    let sign = if offset.starts_with('-') { -1 } else { 1 };
+    let fields: Vec<i32> = offset[1..]
+        .split(':')
+        .map(|field| {
+            let digits = field.len() == 2 && field.bytes().all(|b| b.is_ascii_digit());
+            digits.then(|| field.parse().ok()).flatten()
+        })
+        .collect::<Option<_>>()?;
+    let seconds = match fields[..] {
+        [hours, minutes] if minutes < 60 => hours * 3600 + minutes * 60,
+        [hours, minutes, seconds] if minutes < 60 && seconds < 60 => {
+            hours * 3600 + minutes * 60 + seconds
+        }
+        _ => return None,
+    };
+    let offset = FixedOffset::east_opt(sign * seconds)?;
+    Some((local - Duration::seconds(i64::from(offset.local_minus_utc()))).and_utc())
+}
+
+/// Whether buckets of `interval` are navigated by walking: the ones a
+/// transition can skip entirely. Besides hours, that is a whole day when a
+/// zone crosses the date line, such as Apia's 2011-12-30.
+fn is_walked(interval: Interval) -> bool {
+    matches!(
+        interval,
+        Interval::Minutes(_)
+            | Interval::Hour
+            | Interval::Hours(_)
+            | Interval::Day
+            | Interval::Days { n: 0 | 1, .. }
+    )
+}
+
+/// The next (or previous) bucket.
+fn step(bucket: &Bucket, tz: Tz, interval: Interval, forward: bool) -> Result<Bucket> {
+    let instant = if forward {
+        parse_utc(&bucket.end_utc)?
+    } else {
+        parse_utc(&bucket.start_utc)? - Duration::seconds(1)
+    };
+    Ok(compute_bucket(instant, tz, interval, None))
+}
+
+fn parse_utc(s: &str) -> Result<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(s)
+        .map(|dt| dt.with_timezone(&Utc))
+        .map_err(|e| TzBucketError::ParseError(e.to_string()))
+}
+
+/// Consecutive number of the bucket keyed `key`, for every interval that is
+/// not walked. `None` if the key is malformed.
+///
+/// Some keys that parse but do not start a bucket (such as a trailing-month
+/// key off the anchor day) still get a number; [`bucket_for_key`] rejects
+/// them by comparing keys.
+fn position(key: &str, interval: Interval, week_start: WeekStart) -> Option<i64> {
+    let date = || NaiveDate::parse_from_str(key, "%Y-%m-%d").ok();
+    match interval {
+        Interval::Minutes(_) | Interval::Hour | Interval::Hours(_) => None,
+        Interval::Day => date().map(|d| i64::from(d.num_days_from_ce())),
+        Interval::Days { n, anchor } => {
+            let n = i64::from(n.max(1));
+            let days = (date()? - anchor).num_days();
+            (days.rem_euclid(n) == 0).then(|| days.div_euclid(n))
+        }
+        Interval::Week => {
+            let days = i64::from(date()?.num_days_from_ce()) - week_residue(week_start);
+            (days.rem_euclid(7) == 0).then(|| days.div_euclid(7))
+        }
+        Interval::Month => {
+            let d = NaiveDate::parse_from_str(&format!("{}-01", key), "%Y-%m-%d").ok()?;
+            Some(i64::from(d.year()) * 12 + i64::from(d.month0()))
+        }
+        Interval::Quarter => {
+            let (year, quarter) = key.split_once("-Q")?;
+            let year = year.parse::<i32>().ok()?;
+            let quarter = quarter
+                .parse::<i64>()
+                .ok()
+                .filter(|q| (1..=4).contains(q))?;
+            Some(i64::from(year) * 4 + quarter - 1)
+        }
+        Interval::FiscalQuarter { .. } => {
+            let (year, quarter) = key.strip_prefix("FY")?.split_once("-Q")?;
+            let year = year.parse::<i32>().ok()?;
+            let quarter = quarter
+                .parse::<i64>()
+                .ok()
+                .filter(|q| (1..=4).contains(q))?;
+            Some(i64::from(year) * 4 + quarter - 1)
+        }
+        Interval::Year => key.parse::<i32>().ok().map(i64::from),
+        Interval::TrailingMonth { anchor_day, clamp } => {
+            let d = date()?;
+            // A rolled-forward anchor starts its period on the 1st of the
+            // following month.
+            let rolled = clamp == MonthClamp::RollForward && d.day() != u32::from(anchor_day);
+            let month = i64::from(d.year()) * 12 + i64::from(d.month0());
+            Some(if rolled { month - 1 } else { month })
+        }
+        Interval::FixedSeconds(seconds) => {
+            let seconds = i64::try_from(seconds).ok()?;
+            let start = DateTime::parse_from_rfc3339(key).ok()?.timestamp();
+            (start.rem_euclid(seconds) == 0).then(|| start.div_euclid(seconds))
+        }
+    }
+}
+
+/// An instant inside the bucket numbered `position`; `None` if out of range.
+fn position_start(
+    position: i64,
+    tz: Tz,
+    interval: Interval,
+    week_start: WeekStart,
+) -> Option<DateTime<Utc>> {
+    let month = |position: i64| {
+        let year = i32::try_from(position.div_euclid(12)).ok()?;
+        Some((year, position.rem_euclid(12) as u32 + 1))
+    };
+    let date = match interval {
+        Interval::Minutes(_) | Interval::Hour | Interval::Hours(_) => return None,
+        Interval::FixedSeconds(seconds) => {
+            let start = position.checked_mul(i64::try_from(seconds).ok()?)?;
+            return DateTime::from_timestamp(start, 0);
+        }
+        Interval::Day => NaiveDate::from_num_days_from_ce_opt(i32::try_from(position).ok()?)?,
+        Interval::Days { n, anchor } => {
+            let days = position.checked_mul(i64::from(n.max(1)))?;
+            anchor.checked_add_signed(Duration::try_days(days)?)?
+        }
+        Interval::Week => {
+            let days = position.checked_mul(7)? + week_residue(week_start);
+            NaiveDate::from_num_days_from_ce_opt(i32::try_from(days).ok()?)?
+        }
+        Interval::Month => {
+            let (year, month) = month(position)?;
+            NaiveDate::from_ymd_opt(year, month, 1)?
+        }
+        Interval::Quarter => {
+            let year = i32::try_from(position.div_euclid(4)).ok()?;
+            NaiveDate::from_ymd_opt(year, position.rem_euclid(4) as u32 * 3 + 1, 1)?
+        }
+        Interval::FiscalQuarter { start_month } => {
+            let year = i32::try_from(position.div_euclid(4)).ok()?;
+            fiscal_quarter_start(year, position.rem_euclid(4) as u32 + 1, start_month)?
+        }
+        Interval::Year => NaiveDate::from_ymd_opt(i32::try_from(position).ok()?, 1, 1)?,
+        Interval::TrailingMonth { anchor_day, clamp } => {
+            let (year, month) = month(position)?;
+            // Range check: day_in_month expects a representable month.
+            NaiveDate::from_ymd_opt(year, month, 1)?;
+            day_in_month(year, month, u32::from(anchor_day), clamp).0
+        }
+    };
+    // Midnight may be nonexistent; local_midnight_to_utc shifts it forward,
+    // which stays inside the bucket.
+    Some(local_midnight_to_utc(date, tz))
+}
+
+/// `num_days_from_ce() % 7` of every week start (0001-01-01 is a Monday
+/// with day number 1).
+fn week_residue(week_start: WeekStart) -> i64 {
+    match week_start {
+        WeekStart::Monday => 1,
+        WeekStart::Sunday => 0,
+    }
+}
+
+fn invalid_key(key: &str, interval: Interval, tz: Tz) -> TzBucketError {
+    TzBucketError::ParseError(format!(
+        "'{}' is not a bucket key for interval '{}' in {}",
+        key, interval, tz
+    ))
+}
+
+fn out_of_range(key: &str) -> TzBucketError {
+    TzBucketError::ParseError(format!("bucket for key '{}' is out of range", key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BERLIN: Tz = chrono_tz::Europe::Berlin;
+
+    #[test]
+    fn bucket_for_key_round_trips_every_calendar_interval() {
+        for (interval, key) in [
+            (Interval::Hour, "2026-10-25T02"),
+            (Interval::Day, "2026-03-29"),
+            (
+                Interval::Days {
+                    n: 14,
+                    anchor: NaiveDate::from_ymd_opt(2026, 1, 5).unwrap(),
+                },
+                "2026-03-30",
+            ),
+            (Interval::Week, "2026-03-23"),
+            (Interval::Month, "2026-03"),
+            (Interval::Quarter, "2026-Q1"),
+            (Interval::FiscalQuarter { start_month: 10 }, "FY2027-Q1"),
+            (Interval::Year, "2026"),
+            (
+                Interval::TrailingMonth {
+                    anchor_day: 31,
+                    clamp: MonthClamp::RollForward,
+                },
+                "2026-03-01",
+            ),
+            (Interval::FixedSeconds(3600), "2026-03-29T01:00:00Z"),
+        ] {
+            let bucket = bucket_for_key(key, BERLIN, interval, None).unwrap();
+            assert_eq!(bucket.key, key, "{}", interval);
+        }
+    }
+
+    #[test]
+    fn bucket_for_key_rejects_non_bucket_keys() {
+        for (interval, key) in [
+            (Interval::Hour, "2026-03-29T02"),
+            (Interval::Day, "2026-02-30"),
+            (Interval::Week, "2026-03-24"),
+            (Interval::Quarter, "2026-Q5"),
+            (Interval::FiscalQuarter { start_month: 10 }, "2026-Q1"),
+            (Interval::Year, "+2026"),
+            (Interval::FixedSeconds(3600), "2026-03-29T01:30:00Z"),
+        ] {
+            assert!(
+                bucket_for_key(key, BERLIN, interval, None).is_err(),
+                "{} {}",
+                interval,
+                key
+            );
+        }
+    }
+
+    #[test]
+    fn shift_bucket_crosses_year_boundaries() {
+        let shifted = |interval, key, n| {
+            shift_bucket(key, BERLIN, interval, Some(WeekStart::Sunday), n)
+                .unwrap()
+                .key
+        };
+
+        assert_eq!(shifted(Interval::Week, "2025-12-28", 1), "2026-01-04");
+        assert_eq!(shifted(Interval::Month, "2026-01", -1), "2025-12");
+        assert_eq!(shifted(Interval::Quarter, "2026-Q1", -5), "2024-Q4");
+        let fiscal = Interval::FiscalQuarter { start_month: 7 };
+        assert_eq!(shifted(fiscal, "FY2027-Q4", 1), "FY2028-Q1");
+        assert_eq!(shifted(Interval::Year, "2026", 3), "2029");
+        assert_eq!(
+            shifted(Interval::FixedSeconds(900), "2026-03-29T00:00:00Z", -1),
+            "2026-03-28T23:45:00Z"
+        );
+    }
+
+    #[test]
+    fn shift_trailing_month_through_short_months() {
+        let interval = Interval::TrailingMonth {
+            anchor_day: 31,
+            clamp: MonthClamp::PreviousValidDay,
+        };
+        let bucket = shift_bucket("2026-01-31", BERLIN, interval, None, 1).unwrap();
+        assert_eq!(bucket.key, "2026-02-28");
+        let bucket = shift_bucket("2026-02-28", BERLIN, interval, None, 1).unwrap();
+        assert_eq!(bucket.key, "2026-03-31");
+    }
+
+    #[test]
+    fn shift_bucket_at_compares_with_last_year() {
+        // 52 weeks back is again the 167-hour week of the spring-forward
+        // Sunday (2025-03-30).
+        let instant = DateTime::parse_from_rfc3339("2026-03-29T20:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let bucket = shift_bucket_at(instant, BERLIN, Interval::Week, None, -52).unwrap();
+        assert_eq!(bucket.key, "2025-03-24");
+        assert_eq!(bucket.duration_seconds, 7 * 86_400 - 3600);
+        let bucket = shift_bucket_at(instant, BERLIN, Interval::Year, None, -1).unwrap();
+        assert_eq!(bucket.key, "2025");
+    }
+
+    #[test]
+    fn buckets_between_at_counts_local_months() {
+        let at = |s| DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc);
+        // An hour apart across the UTC month boundary, both in local February.
+        let (from, to) = (at("2026-01-31T23:30:00Z"), at("2026-02-01T00:30:00Z"));
+        assert_eq!(
+            buckets_between_at(from, to, BERLIN, Interval::Month, None).unwrap(),
+            0
+        );
+        assert_eq!(
+            buckets_between_at(from, to, chrono_tz::UTC, Interval::Month, None).unwrap(),
+            1
+        );
+        // 27 days 23 hours in UTC (across the spring-forward change), but
+        // 13:00 local on both dates: 28 days apart.
+        let (from, to) = (at("2026-03-01T12:00:00Z"), at("2026-03-29T11:00:00Z"));
+        assert_eq!(
+            buckets_between_at(from, to, BERLIN, Interval::Day, None).unwrap(),
+            28
+        );
+    }
+
+    #[test]
+    fn hour_navigation_counts_buckets_not_local_hours() {
+        // The repeated 02:00 hour on 2026-10-25 is one bucket.
+        let bucket = shift_bucket("2026-10-25T01", BERLIN, Interval::Hour, None, 2).unwrap();
+        assert_eq!(bucket.key, "2026-10-25T03");
+        assert_eq!(
+            buckets_between(
+                "2026-03-29T01",
+                "2026-03-29T03",
+                BERLIN,
+                Interval::Hour,
+                None
+            )
+            .unwrap(),
+            1
+        );
+    }
+
+    #[test]
+    fn day_navigation_steps_over_skipped_days() {
+        // Apia and Fakaofo skipped 2011-12-30 crossing the date line;
+        // Kwajalein skipped 1993-08-21.
+        for (tz, before, skipped, after) in [
+            (
+                chrono_tz::Pacific::Apia,
+                "2011-12-29",
+                "2011-12-30",
+                "2011-12-31",
+            ),
+            (
+                chrono_tz::Pacific::Fakaofo,
+                "2011-12-29",
+                "2011-12-30",
+                "2011-12-31",
+            ),
+            (
+                chrono_tz::Pacific::Kwajalein,
+                "1993-08-20",
+                "1993-08-21",
+                "1993-08-22",
+            ),
+        ] {
+            assert!(bucket_for_key(skipped, tz, Interval::Day, None).is_err());
+            assert_eq!(
+                shift_bucket(after, tz, Interval::Day, None, -1)
+                    .unwrap()
+                    .key,
+                before
+            );
+            assert_eq!(
+                shift_bucket(before, tz, Interval::Day, None, 1)
+                    .unwrap()
+                    .key,
+                after
+            );
+            assert_eq!(
+                buckets_between(before, after, tz, Interval::Day, None).unwrap(),
+                1
+            );
+        }
+
+        let tz = chrono_tz::Pacific::Apia;
+        assert_eq!(
+            shift_bucket("2012-01-01", tz, Interval::Day, None, -3)
+                .unwrap()
+                .key,
+            "2011-12-28"
+        );
+        assert_eq!(
+            buckets_between("2011-12-01", "2012-01-01", tz, Interval::Day, None).unwrap(),
+            30
+        );
+    }
+
+    #[test]
+    fn minutes_keys_keep_offset_seconds() {
+        // Monrovia kept -00:44:30 until 1972.
+        let tz = chrono_tz::Africa::Monrovia;
+        let instant = DateTime::parse_from_rfc3339("1960-01-01T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let bucket = compute_bucket(instant, tz, Interval::Minutes(15), None);
+        assert_eq!(bucket.key, "1960-01-01T11:15-00:44:30");
+        assert_eq!(bucket.start_utc, "1960-01-01T11:59:30Z");
+
+        let round_trip = bucket_for_key(&bucket.key, tz, Interval::Minutes(15), None).unwrap();
+        assert_eq!(round_trip.start_utc, bucket.start_utc);
+        let next = shift_bucket(&bucket.key, tz, Interval::Minutes(15), None, 1).unwrap();
+        assert_eq!(next.key, "1960-01-01T11:30-00:44:30");
+        assert!(bucket_for_key("1960-01-01T11:15-00:45", tz, Interval::Minutes(15), None).is_err());
+    }
+
+    #[test]
+    fn buckets_between_is_shift_inverse() {
+        for n in [-400, -1, 0, 1, 53] {
+            let to = shift_bucket("2026-03-23", BERLIN, Interval::Week, None, n).unwrap();
+            assert_eq!(
+                buckets_between("2026-03-23", &to.key, BERLIN, Interval::Week, None).unwrap(),
+                n
+            );
+        }
+    }
+}
@@ -4,6 +4,13 @@
 //! - `epoch_ms`: Unix epoch milliseconds (default)
 //! - `epoch_s`: Unix epoch seconds
 //! - `rfc3339`: RFC3339 formatted strings (e.g., `2026-03-29T00:15:00Z`)
+//!
+//! With the `fast-parse` feature, epoch integers are parsed eight digits at a
+//! time within a 64-bit word (SWAR) and RFC3339 strings in the canonical layout
+//! `YYYY-MM-DDTHH:MM:SS[.f]{Z|±HH:MM}` are decoded from fixed byte positions
+//! without going through chrono's format parser. Anything outside that
+//! layout (lowercase separators, leap seconds, ...) falls back to chrono, so
+//! the results are identical either way.
 
 use chrono::{DateTime, TimeZone, Utc};
 use std::str::FromStr;
@@ -72,6 +79,7 @@ impl FromStr for TimestampFormat {
 /// // Parse RFC3339
 /// let dt = parse_timestamp("2026-03-29T00:15:00Z", TimestampFormat::Rfc3339).unwrap();
 /// ```
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", err))]
 pub fn parse_timestamp(input: &str, format: TimestampFormat) -> Result<DateTime<Utc>> {
     let trimmed = input.trim();
 
@@ -84,7 +92,7 @@ pub fn parse_timestamp(input: &str, format: TimestampFormat) -> Result<DateTime<
 
 /// Parse epoch milliseconds.
 fn parse_epoch_ms(input: &str) -> Result<DateTime<Utc>> {
-    let ms: i64 = input.parse().map_err(|_| {
+    let ms: i64 = parse_i64(input).ok_or_else(|| {
         TzBucketError::ParseError(format!(
             "Invalid epoch milliseconds: '{}'. Expected integer value.",
             input
@@ -98,7 +106,7 @@ fn parse_epoch_ms(input: &str) -> Result<DateTime<Utc>> {
 
 /// Parse epoch seconds.
 fn parse_epoch_s(input: &str) -> Result<DateTime<Utc>> {
-    let s: i64 = input.parse().map_err(|_| {
+    let s: i64 = parse_i64(input).ok_or_else(|| {
         TzBucketError::ParseError(format!(
             "Invalid epoch seconds: '{}'. Expected integer value.",
             input
@@ -117,6 +125,11 @@ fn parse_epoch_s(input: &str) -> Result<DateTime<Utc>> {
 /// - `2026-03-29T00:15:00+01:00`
 /// - `2026-03-29T00:15:00-05:00`
 fn parse_rfc3339(input: &str) -> Result<DateTime<Utc>> {
+    #[cfg(feature = "fast-parse")]
+    if let Some(dt) = fast::rfc3339(input.as_bytes()) {
+        return Ok(dt);
+    }
+
     // Try parsing with various RFC3339 formats
     DateTime::parse_from_rfc3339(input)
         .map(|dt| dt.with_timezone(&Utc))
@@ -128,6 +141,147 @@ fn parse_rfc3339(input: &str) -> Result<DateTime<Utc>> {
         })
 }
 
+/// Parse a decimal `i64`, with the byte-loop fast path when enabled.
+fn parse_i64(input: &str) -> Option<i64> {
+    #[cfg(feature = "fast-parse")]
+    return fast::i64(input.as_bytes());
+
+    #[cfg(not(feature = "fast-parse"))]
+    input.parse().ok()
+}
+
+#[cfg(feature = "fast-parse")]
+mod fast {
+    use chrono::{DateTime, NaiveDate, TimeDelta, Utc};
+
+    /// Decimal `i64` with an optional sign, `None` on any other byte or on
+    /// overflow (same accepted language as `str::parse::<i64>`).
+    pub(super) fn i64(bytes: &[u8]) -> Option<i64> {
+        let (negative, digits) = match bytes {
+            [b'-', rest @ ..] => (true, rest),
+            [b'+', rest @ ..] => (false, rest),
+            _ => (false, bytes),
+        };
+        // 18 digits always fit; longer inputs take the checked path.
+        if digits.is_empty() || digits.len() > 18 {
+            return std::str::from_utf8(bytes).ok()?.parse().ok();
+        }
+
+        let mut chunks = digits.chunks_exact(8);
+        let mut value: i64 = 0;
+        for chunk in &mut chunks {
+            let chunk = chunk.try_into().expect("8 bytes");
+            value = value * 100_000_000 + i64::from(eight_digits(chunk)?);
+        }
+        for &b in chunks.remainder() {
+            let digit = b.wrapping_sub(b'0');
+            if digit > 9 {
+                return None;
+            }
+            value = value * 10 + i64::from(digit);
+        }
+        Some(if negative { -value } else { value })
+    }
+
+    /// `YYYY-MM-DDTHH:MM:SS[.f{1,9}]` followed by `Z` or `±HH:MM`, or `None`
+    /// to let chrono handle the input.
+    pub(super) fn rfc3339(b: &[u8]) -> Option<DateTime<Utc>> {
+        let head: &[u8; 19] = b.get(..19)?.try_into().ok()?;
+        if head[4] != b'-'
+            || head[7] != b'-'
+            || head[10] != b'T'
+            || head[13] != b':'
+            || head[16] != b':'
+        {
+            return None;
+        }
+        let h = head;
+        let date = eight_digits([h[0], h[1], h[2], h[3], h[5], h[6], h[8], h[9]])?;
+        let time = eight_digits([b'0', b'0', h[11], h[12], h[14], h[15], h[17], h[18]])?;
+        let (year, month, day) = (date / 10_000, date / 100 % 100, date % 100);
+        let (hour, minute, second) = (time / 10_000, time / 100 % 100, time % 100);
+
+        let mut rest = &b[19..];
+        let mut nanos = 0;
+        if let [b'.', tail @ ..] = rest {
+            let mut len = 0;
+            while let Some(digit) = tail.get(len).map(|c| c.wrapping_sub(b'0'))
+                && digit <= 9
+            {
+                if len == 9 {
+                    return None;
+                }
+                nanos = nanos * 10 + u32::from(digit);
+                len += 1;
+            }
+            if len == 0 {
+                return None;
+            }
+            nanos *= NANOS_SCALE[len];
+            rest = &tail[len..];
+        }
+
+        let offset_seconds = match *rest {
+            [b'Z'] => 0,
+            [sign @ (b'+' | b'-'), h1, h2, b':', m1, m2] => {
+                let offset = eight_digits([b'0', b'0', b'0', b'0', h1, h2, m1, m2])?;
+                let (hours, minutes) = (offset / 100, offset % 100);
+                if hours > 23 || minutes > 59 {
+                    return None;
+                }
+                let seconds = i64::from(hours * 3600 + minutes * 60);
+                if sign == b'-' { -seconds } else { seconds }
+            }
+            _ => return None,
+        };
+
+        // Both constructors validate their fields; a leap second (`:60`) is
+        // rejected here and left to chrono.
+        let local = NaiveDate::from_ymd_opt(year as i32, month, day)?
+            .and_hms_nano_opt(hour, minute, second, nanos)?;
+        let utc = if offset_seconds == 0 {
+            local
+        } else {
+            local.checked_sub_signed(TimeDelta::seconds(offset_seconds))?
+        };
+        Some(utc.and_utc())
+    }
+
+    /// Factor turning an `n`-digit fraction into nanoseconds.
+    const NANOS_SCALE: [u32; 10] = [
+        0,
+        100_000_000,
+        10_000_000,
+        1_000_000,
+        100_000,
+        10_000,
+        1_000,
+        100,
+        10,
+        1,
+    ];
+
+    /// Value of eight ASCII digits, converted in one 64-bit word (SWAR).
+    fn eight_digits(chunk: [u8; 8]) -> Option<u32> {
+        let word = u64::from_le_bytes(chunk);
+        // Every byte must be in 0x30..=0x39: high nibble 3, and adding 6
+        // must not carry into the high nibble.
+        if word & 0xF0F0_F0F0_F0F0_F0F0 != 0x3030_3030_3030_3030
+            || word.wrapping_add(0x0606_0606_0606_0606) & 0xF0F0_F0F0_F0F0_F0F0
+                != 0x3030_3030_3030_3030
+        {
+            return None;
+        }
+
+        // Combine adjacent digits pairwise: 1-digit lanes -> 2 -> 4 -> 8.
+        let mut value = word - 0x3030_3030_3030_3030;
+        value = (value * 10 + (value >> 8)) & 0x00FF_00FF_00FF_00FF;
+        value = (value * 100 + (value >> 16)) & 0x0000_FFFF_0000_FFFF;
+        value = (value * 10_000 + (value >> 32)) & 0xFFFF_FFFF;
+        Some(value as u32)
+    }
+}
+
 /// Parse a timestamp string, auto-detecting the format.
 ///
 /// This function attempts to parse the input in the following order:
@@ -260,6 +414,51 @@ mod tests {
         assert_eq!(dt, expected);
     }
 
+    #[cfg(feature = "fast-parse")]
+    #[test]
+    fn fast_paths_agree_with_chrono() {
+        for input in [
+            "0",
+            "-1",
+            "+42",
+            "1793362500000",
+            "9223372036854775807",
+            "-9223372036854775808",
+        ] {
+            assert_eq!(fast::i64(input.as_bytes()), input.parse().ok(), "{input}");
+        }
+        for input in ["", "-", "1_000", "9223372036854775808", " 1", "1e3"] {
+            assert_eq!(fast::i64(input.as_bytes()), None, "{input}");
+        }
+
+        for input in [
+            "2026-03-29T00:15:00Z",
+            "2026-03-29T00:15:00+01:00",
+            "2026-10-25T02:30:00.5-05:30",
+            "2024-02-29T23:59:59.123456789Z",
+            "1969-12-31T23:59:59.999Z",
+            "0001-01-01T00:00:00+23:59",
+        ] {
+            let expected = DateTime::parse_from_rfc3339(input)
+                .unwrap()
+                .with_timezone(&Utc);
+            assert_eq!(fast::rfc3339(input.as_bytes()), Some(expected), "{input}");
+        }
+        // Left to chrono: valid but outside the fast layout, or invalid.
+        for input in [
+            "2026-03-29t00:15:00z",
+            "2026-03-29 00:15:00Z",
+            "2016-12-31T23:59:60Z",
+            "2026-02-29T00:00:00Z",
+            "2026-03-29T24:00:00Z",
+            "2026-03-29T00:15:00.Z",
+            "2026-03-29T00:15:00",
+        ] {
+            assert_eq!(fast::rfc3339(input.as_bytes()), None, "{input}");
+        }
+        assert!(parse_timestamp("2016-12-31T23:59:60Z", TimestampFormat::Rfc3339).is_ok());
+    }
+
     #[test]
     fn format_display() {
         assert_eq!(format!("{}", TimestampFormat::EpochMs), "epoch_ms");
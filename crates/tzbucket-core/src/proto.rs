@@ -0,0 +1,259 @@
+//! Protobuf messages for the output types (feature `proto`).
+//!
+//! The messages mirror `proto/tzbucket/v1/tzbucket.proto`, which is the
+//! canonical schema for gRPC services and schema registries. They are
+//! declared with `prost` derives directly, so no `protoc` is needed at build
+//! time.
+
+use crate::models;
+use crate::resolve::LocalResolution;
+use crate::tz::format_rfc3339;
+
+/// A computed time bucket with boundaries in both local and UTC time.
+#[derive(Clone, PartialEq, Eq, prost::Message)]
+pub struct Bucket {
+    /// Bucket key.
+    #[prost(string, tag = "1")]
+    pub key: String,
+    /// Bucket start in local time with offset (RFC3339 format).
+    #[prost(string, tag = "2")]
+    pub start_local: String,
+    /// Bucket end in local time with offset (RFC3339 format).
+    #[prost(string, tag = "3")]
+    pub end_local: String,
+    /// Bucket start in UTC (RFC3339 format with Z suffix).
+    #[prost(string, tag = "4")]
+    pub start_utc: String,
+    /// Bucket end in UTC (RFC3339 format with Z suffix).
+    #[prost(string, tag = "5")]
+    pub end_utc: String,
+    /// ISO 8601 week number of the bucket start, when requested.
+    #[prost(uint32, optional, tag = "6")]
+    pub iso_week: Option<u32>,
+    /// ISO 8601 week-numbering year of the bucket start, when requested.
+    #[prost(int32, optional, tag = "7")]
+    pub iso_week_year: Option<i32>,
+    /// US week number of the bucket start, when requested.
+    #[prost(uint32, optional, tag = "8")]
+    pub us_week_number: Option<u32>,
+    /// Month clamp policy applied to a trailing month anchor
+    /// (`previous_valid_day`, `roll_forward`).
+    #[prost(string, optional, tag = "9")]
+    pub month_clamp: Option<String>,
+    /// Label instant in UTC (RFC3339 format with Z suffix), when requested.
+    #[prost(string, optional, tag = "10")]
+    pub label_ts: Option<String>,
+    /// Whether the UTC offset changes inside the bucket, when requested.
+    #[prost(bool, optional, tag = "11")]
+    pub is_dst_transition: Option<bool>,
+    /// UTC offset at the bucket start in seconds, when requested.
+    #[prost(int32, optional, tag = "12")]
+    pub start_offset_seconds: Option<i32>,
+    /// UTC offset at the bucket end in seconds, when requested.
+    #[prost(int32, optional, tag = "13")]
+    pub end_offset_seconds: Option<i32>,
+    /// Elapsed seconds from `start_utc` to `end_utc`.
+    #[prost(int64, tag = "14")]
+    pub duration_seconds: i64,
+}
+
+/// Parsed input timestamp.
+#[derive(Clone, PartialEq, Eq, prost::Message)]
+pub struct InputTimestamp {
+    /// Original input string.
+    #[prost(string, tag = "1")]
+    pub ts: String,
+    /// Epoch milliseconds (UTC).
+    #[prost(int64, tag = "2")]
+    pub epoch_ms: i64,
+}
+
+/// Complete result of a bucket computation.
+#[derive(Clone, PartialEq, Eq, prost::Message)]
+pub struct BucketResult {
+    /// The input timestamp that was processed.
+    #[prost(message, optional, tag = "1")]
+    pub input: Option<InputTimestamp>,
+    /// The timezone used for bucket computation.
+    #[prost(string, tag = "2")]
+    pub tz: String,
+    /// The interval as rendered in JSON output.
+    #[prost(string, tag = "3")]
+    pub interval: String,
+    /// The computed bucket.
+    #[prost(message, optional, tag = "4")]
+    pub bucket: Option<Bucket>,
+}
+
+/// How a nonexistent or ambiguous local time was resolved.
+#[derive(Clone, PartialEq, Eq, prost::Message)]
+pub struct Resolution {
+    /// Policy applied (`shift_forward`, `first`, `second`).
+    #[prost(string, tag = "1")]
+    pub policy: String,
+    /// Resolved local time with offset (RFC3339 format).
+    #[prost(string, tag = "2")]
+    pub result: String,
+}
+
+impl From<models::Bucket> for Bucket {
+    // `key` converts between `String` and `BucketKey`, which is `String`
+    // itself without the `compact-str` feature.
+    #[allow(clippy::useless_conversion)]
+    fn from(bucket: models::Bucket) -> Self {
+        let week = bucket.week_numbers;
+        let dst = bucket.dst_info;
+        Self {
+            key: bucket.key.into(),
+            start_local: bucket.start_local,
+            end_local: bucket.end_local,
+            start_utc: bucket.start_utc,
+            end_utc: bucket.end_utc,
+            iso_week: week.map(|w| w.iso_week),
+            iso_week_year: week.map(|w| w.iso_week_year),
+            us_week_number: week.map(|w| w.us_week_number),
+            month_clamp: bucket.month_clamp.map(|clamp| clamp.to_string()),
+            label_ts: bucket.label_ts,
+            is_dst_transition: dst.map(|d| d.is_dst_transition),
+            start_offset_seconds: dst.map(|d| d.start_offset_seconds),
+            end_offset_seconds: dst.map(|d| d.end_offset_seconds),
+            duration_seconds: bucket.duration_seconds,
+        }
+    }
+}
+
+impl From<Bucket> for models::Bucket {
+    // `key` converts between `String` and `BucketKey`, which is `String`
+    // itself without the `compact-str` feature.
+    #[allow(clippy::useless_conversion)]
+    fn from(bucket: Bucket) -> Self {
+        let week_numbers = match (bucket.iso_week, bucket.iso_week_year, bucket.us_week_number) {
+            (Some(iso_week), Some(iso_week_year), Some(us_week_number)) => {
+                Some(models::WeekNumbers {
+                    iso_week,
+                    iso_week_year,
+                    us_week_number,
+                })
+            }
+            _ => None,
+        };
+        let dst_info = match (
+            bucket.is_dst_transition,
+            bucket.start_offset_seconds,
+            bucket.end_offset_seconds,
+        ) {
+            (Some(is_dst_transition), Some(start_offset_seconds), Some(end_offset_seconds)) => {
+                Some(models::DstInfo {
+                    is_dst_transition,
+                    start_offset_seconds,
+                    end_offset_seconds,
+                })
+            }
+            _ => None,
+        };
+        let month_clamp = match bucket.month_clamp.as_deref() {
+            Some("previous_valid_day") => Some(models::MonthClamp::PreviousValidDay),
+            Some("roll_forward") => Some(models::MonthClamp::RollForward),
+            _ => None,
+        };
+        Self {
+            key: bucket.key.into(),
+            start_local: bucket.start_local,
+            end_local: bucket.end_local,
+            start_utc: bucket.start_utc,
+            end_utc: bucket.end_utc,
+            duration_seconds: bucket.duration_seconds,
+            week_numbers,
+            month_clamp,
+            label_ts: bucket.label_ts,
+            dst_info,
+        }
+    }
+}
+
+impl From<models::InputTimestamp> for InputTimestamp {
+    fn from(input: models::InputTimestamp) -> Self {
+        Self {
+            ts: input.ts,
+            epoch_ms: input.epoch_ms,
+        }
+    }
+}
+
+impl From<InputTimestamp> for models::InputTimestamp {
+    fn from(input: InputTimestamp) -> Self {
+        Self {
+            ts: input.ts,
+            epoch_ms: input.epoch_ms,
+        }
+    }
+}
+
+impl From<models::BucketResult> for BucketResult {
+    fn from(result: models::BucketResult) -> Self {
+        Self {
+            input: Some(result.input.into()),
+            tz: result.tz,
+            interval: result.interval.to_string(),
+            bucket: Some(result.bucket.into()),
+        }
+    }
+}
+
+impl From<LocalResolution> for Resolution {
+    fn from(resolution: LocalResolution) -> Self {
+        Self {
+            policy: resolution
+                .applied
+                .map(|applied| applied.to_string())
+                .unwrap_or_default(),
+            result: format_rfc3339(&resolution.instant),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compute::compute_bucket_from_string;
+    use crate::models::Interval;
+    use crate::parse::TimestampFormat;
+    use prost::Message;
+
+    #[test]
+    fn bucket_result_round_trips_through_wire_format() {
+        let mut result = compute_bucket_from_string(
+            "2026-03-29T12:00:00Z",
+            TimestampFormat::Rfc3339,
+            "Europe/Berlin",
+            Interval::Day,
+            None,
+        )
+        .unwrap();
+        result.bucket = result.bucket.with_dst_info(chrono_tz::Europe::Berlin);
+
+        let message = BucketResult::from(result.clone());
+        let decoded = BucketResult::decode(message.encode_to_vec().as_slice()).unwrap();
+
+        assert_eq!(decoded, message);
+        assert_eq!(decoded.interval, "day");
+        let bucket = models::Bucket::from(decoded.bucket.unwrap());
+        assert_eq!(bucket.key, result.bucket.key);
+        assert_eq!(bucket.start_utc, "2026-03-28T23:00:00Z");
+        assert_eq!(bucket.end_utc, "2026-03-29T22:00:00Z");
+        assert_eq!(bucket.duration_seconds, 23 * 3600);
+        assert_eq!(bucket.dst_info, result.bucket.dst_info);
+        assert!(bucket.dst_info.is_some());
+    }
+
+    #[test]
+    fn input_timestamp_uses_proto_field_numbers() {
+        let message = InputTimestamp {
+            ts: "x".to_string(),
+            epoch_ms: 1,
+        };
+
+        // Field 1 (string "x"), field 2 (varint 1).
+        assert_eq!(message.encode_to_vec(), vec![0x0a, 0x01, b'x', 0x10, 0x01]);
+    }
+}
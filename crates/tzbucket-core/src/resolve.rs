@@ -0,0 +1,305 @@
+//! Local time resolution.
+//!
+//! This module maps a naive local wall-clock time onto the UTC timeline under
+//! an explicit [`Policy`], reporting whether the time was normal, skipped by a
+//! DST spring forward (nonexistent), or repeated by a fall back (ambiguous).
+
+use chrono::offset::LocalResult;
+use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use serde::Serialize;
+
+use crate::compute::compute_bucket;
+use crate::error::{Result, TzBucketError};
+use crate::models::{AmbiguousPolicy, Bucket, Interval, NonexistentPolicy, Policy, WeekStart};
+use crate::tz::shift_forward_to_utc;
+
+/// How a local wall-clock time maps onto the UTC timeline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum LocalStatus {
+    /// The local time occurs exactly once.
+    Normal,
+    /// The local time is skipped (DST spring forward).
+    Nonexistent,
+    /// The local time occurs twice (DST fall back).
+    Ambiguous,
+}
+
+impl std::fmt::Display for LocalStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LocalStatus::Normal => write!(f, "normal"),
+            LocalStatus::Nonexistent => write!(f, "nonexistent"),
+            LocalStatus::Ambiguous => write!(f, "ambiguous"),
+        }
+    }
+}
+
+/// The policy choice that resolved a nonexistent or ambiguous local time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum AppliedPolicy {
+    /// Nonexistent time shifted forward by the length of the gap.
+    ShiftForward,
+    /// First occurrence of an ambiguous time.
+    First,
+    /// Second occurrence of an ambiguous time.
+    Second,
+}
+
+impl std::fmt::Display for AppliedPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AppliedPolicy::ShiftForward => write!(f, "shift_forward"),
+            AppliedPolicy::First => write!(f, "first"),
+            AppliedPolicy::Second => write!(f, "second"),
+        }
+    }
+}
+
+/// Outcome of converting a resolved instant back to local time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum RoundTrip {
+    /// The wall-clock time round-trips unchanged and was unambiguous.
+    Exact,
+    /// The wall-clock time differs from the input (the input was in a gap).
+    Shifted,
+    /// The wall-clock time round-trips, but one of two instants was chosen.
+    Disambiguated,
+}
+
+impl RoundTrip {
+    /// Combine two outcomes, keeping the more severe one.
+    pub fn worst(self, other: RoundTrip) -> RoundTrip {
+        match (self, other) {
+            (RoundTrip::Shifted, _) | (_, RoundTrip::Shifted) => RoundTrip::Shifted,
+            (RoundTrip::Disambiguated, _) | (_, RoundTrip::Disambiguated) => {
+                RoundTrip::Disambiguated
+            }
+            _ => RoundTrip::Exact,
+        }
+    }
+}
+
+impl std::fmt::Display for RoundTrip {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RoundTrip::Exact => write!(f, "exact"),
+            RoundTrip::Shifted => write!(f, "shifted"),
+            RoundTrip::Disambiguated => write!(f, "disambiguated"),
+        }
+    }
+}
+
+/// A local time resolved to a single instant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LocalResolution {
+    /// The input local time.
+    pub local: NaiveDateTime,
+    /// Whether the input was normal, nonexistent or ambiguous.
+    pub status: LocalStatus,
+    /// The policy choice applied, if the input was not normal.
+    pub applied: Option<AppliedPolicy>,
+    /// The resolved instant in the input timezone.
+    pub instant: DateTime<Tz>,
+}
+
+impl LocalResolution {
+    /// The resolved instant in UTC.
+    pub fn utc(&self) -> DateTime<Utc> {
+        self.instant.with_timezone(&Utc)
+    }
+
+    /// Convert the resolved instant back to local time and compare it with
+    /// the input wall-clock time.
+    pub fn roundtrip(&self) -> RoundTrip {
+        if self.instant.naive_local() != self.local {
+            RoundTrip::Shifted
+        } else if self.status == LocalStatus::Ambiguous {
+            RoundTrip::Disambiguated
+        } else {
+            RoundTrip::Exact
+        }
+    }
+
+    /// The bucket the resolved instant falls into.
+    pub fn bucket(&self, interval: Interval, week_start: Option<WeekStart>) -> Bucket {
+        compute_bucket(self.utc(), self.instant.timezone(), interval, week_start)
+    }
+}
+
+/// Resolve a local wall-clock time in `tz` under `policy`.
+///
+/// # Errors
+///
+/// Returns [`TzBucketError::NonexistentTime`] or
+/// [`TzBucketError::AmbiguousTime`] when the local time needs a policy
+/// decision and the corresponding policy is `Error`.
+///
+/// # Examples
+///
+/// ```
+/// use chrono::NaiveDate;
+/// use tzbucket_core::models::{NonexistentPolicy, Policy};
+/// use tzbucket_core::resolve::{LocalStatus, resolve_local};
+///
+/// let local = NaiveDate::from_ymd_opt(2026, 3, 29)
+///     .unwrap()
+///     .and_hms_opt(2, 30, 0)
+///     .unwrap();
+/// let policy = Policy {
+///     nonexistent: NonexistentPolicy::ShiftForward,
+///     ..Policy::default()
+/// };
+/// let resolved = resolve_local(local, chrono_tz::Europe::Berlin, policy).unwrap();
+///
+/// assert_eq!(resolved.status, LocalStatus::Nonexistent);
+/// assert_eq!(resolved.instant.to_rfc3339(), "2026-03-29T03:30:00+02:00");
+/// ```
+pub fn resolve_local(local: NaiveDateTime, tz: Tz, policy: Policy) -> Result<LocalResolution> {
+    let (status, applied, instant) = match tz.from_local_datetime(&local) {
+        LocalResult::Single(dt) => (LocalStatus::Normal, None, dt),
+        LocalResult::Ambiguous(first, second) => match policy.ambiguous {
+            AmbiguousPolicy::Error => {
+                return Err(TzBucketError::AmbiguousTime {
+                    local,
+                    tz: tz.to_string(),
+                });
+            }
+            AmbiguousPolicy::First => (LocalStatus::Ambiguous, Some(AppliedPolicy::First), first),
+            AmbiguousPolicy::Second => {
+                (LocalStatus::Ambiguous, Some(AppliedPolicy::Second), second)
+            }
+        },
+        LocalResult::None => match policy.nonexistent {
+            NonexistentPolicy::Error => {
+                return Err(TzBucketError::NonexistentTime {
+                    local,
+                    tz: tz.to_string(),
+                });
+            }
+            NonexistentPolicy::ShiftForward => (
+                LocalStatus::Nonexistent,
+                Some(AppliedPolicy::ShiftForward),
+                shift_forward_to_utc(local, tz).with_timezone(&tz),
+            ),
+        },
+    };
+
+    Ok(LocalResolution {
+        local,
+        status,
+        applied,
+        instant,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use chrono_tz::{America, Europe};
+
+    fn local(y: i32, m: u32, d: u32, h: u32, min: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(y, m, d)
+            .unwrap()
+            .and_hms_opt(h, min, 0)
+            .unwrap()
+    }
+
+    const LENIENT: Policy = Policy {
+        nonexistent: NonexistentPolicy::ShiftForward,
+        ambiguous: AmbiguousPolicy::First,
+    };
+
+    #[test]
+    fn resolve_normal_time() {
+        let resolved =
+            resolve_local(local(2026, 3, 29, 12, 0), Europe::Berlin, Policy::default()).unwrap();
+
+        assert_eq!(resolved.status, LocalStatus::Normal);
+        assert_eq!(resolved.applied, None);
+        assert_eq!(resolved.instant.to_rfc3339(), "2026-03-29T12:00:00+02:00");
+    }
+
+    #[test]
+    fn resolve_nonexistent_errors_by_default() {
+        let err = resolve_local(local(2026, 3, 29, 2, 30), Europe::Berlin, Policy::default())
+            .unwrap_err();
+
+        assert!(matches!(err, TzBucketError::NonexistentTime { .. }));
+    }
+
+    #[test]
+    fn resolve_nonexistent_shift_forward_preserves_minutes() {
+        let resolved = resolve_local(local(2026, 3, 8, 2, 45), America::New_York, LENIENT).unwrap();
+
+        assert_eq!(resolved.status, LocalStatus::Nonexistent);
+        assert_eq!(resolved.applied, Some(AppliedPolicy::ShiftForward));
+        assert_eq!(resolved.instant.to_rfc3339(), "2026-03-08T03:45:00-04:00");
+    }
+
+    #[test]
+    fn resolve_ambiguous_first_and_second() {
+        let at = local(2026, 10, 25, 2, 30);
+        let first = resolve_local(at, Europe::Berlin, LENIENT).unwrap();
+        let second = resolve_local(
+            at,
+            Europe::Berlin,
+            Policy {
+                ambiguous: AmbiguousPolicy::Second,
+                ..LENIENT
+            },
+        )
+        .unwrap();
+
+        assert_eq!(first.status, LocalStatus::Ambiguous);
+        assert_eq!(first.instant.to_rfc3339(), "2026-10-25T02:30:00+02:00");
+        assert_eq!(second.applied, Some(AppliedPolicy::Second));
+        assert_eq!(second.instant.to_rfc3339(), "2026-10-25T02:30:00+01:00");
+    }
+
+    #[test]
+    fn resolve_ambiguous_errors_by_default() {
+        let err = resolve_local(
+            local(2026, 10, 25, 2, 30),
+            Europe::Berlin,
+            Policy::default(),
+        )
+        .unwrap_err();
+
+        assert!(matches!(err, TzBucketError::AmbiguousTime { .. }));
+    }
+
+    #[test]
+    fn roundtrip_flags_gaps_and_overlaps() {
+        let normal = resolve_local(local(2026, 3, 29, 1, 30), Europe::Berlin, LENIENT).unwrap();
+        let gap = resolve_local(local(2026, 3, 29, 2, 30), Europe::Berlin, LENIENT).unwrap();
+        let overlap = resolve_local(local(2026, 10, 25, 2, 30), Europe::Berlin, LENIENT).unwrap();
+
+        assert_eq!(normal.roundtrip(), RoundTrip::Exact);
+        assert_eq!(gap.roundtrip(), RoundTrip::Shifted);
+        assert_eq!(overlap.roundtrip(), RoundTrip::Disambiguated);
+        assert_eq!(
+            RoundTrip::Disambiguated.worst(RoundTrip::Shifted),
+            RoundTrip::Shifted
+        );
+    }
+
+    #[test]
+    fn resolution_bucket_uses_resolved_instant() {
+        // 23:30 on the last day of a month, resolved in New York, lands in that
+        // month even though it is already the next month in UTC.
+        let resolved =
+            resolve_local(local(2026, 3, 31, 23, 30), America::New_York, LENIENT).unwrap();
+        let bucket = resolved.bucket(Interval::Month, None);
+
+        assert_eq!(bucket.key, "2026-03");
+        assert_eq!(bucket.end_utc, "2026-04-01T04:00:00Z");
+    }
+}
@@ -0,0 +1,155 @@
+//! Attribution of child buckets that cross a parent bucket boundary.
+//!
+//! Rolling finer buckets up into coarser ones is a plain sum as long as every
+//! child nests in one parent. Some intervals do not nest: a week can start in
+//! one month and end in the next. [`attribute`] divides such a child's count
+//! between the two parents according to an [`Attribution`] policy.
+//!
+//! Shares are measured in local wall-clock time, so a 23-hour DST day weighs
+//! as much as any other day.
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+/// Which parent gets a child bucket that crosses a parent boundary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum Attribution {
+    /// The parent containing the child's start.
+    Start,
+    /// The parent containing the child's last instant.
+    End,
+    /// The parent holding the larger part of the child (for weeks, the
+    /// majority of days); the start parent on a tie.
+    Majority,
+    /// Both parents, in proportion to the part of the child each holds,
+    /// rounded to whole counts that sum to the child's count.
+    Split,
+}
+
+impl std::fmt::Display for Attribution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Attribution::Start => write!(f, "start"),
+            Attribution::End => write!(f, "end"),
+            Attribution::Majority => write!(f, "majority"),
+            Attribution::Split => write!(f, "split"),
+        }
+    }
+}
+
+/// Divide `count` of the child bucket `[start, end)` between the parents
+/// before and after `boundary`, all in local wall-clock time.
+///
+/// Returns the shares of the earlier and the later parent. A `boundary`
+/// outside the child gives the whole count to the parent holding it.
+///
+/// # Examples
+///
+/// ```
+/// use chrono::NaiveDate;
+/// use tzbucket_core::rollup::{Attribution, attribute};
+///
+/// // The week of 2026-03-30 has two days in March and five in April.
+/// let local = |month, day| {
+///     NaiveDate::from_ymd_opt(2026, month, day)
+///         .unwrap()
+///         .and_hms_opt(0, 0, 0)
+///         .unwrap()
+/// };
+/// let (start, boundary, end) = (local(3, 30), local(4, 1), local(4, 6));
+///
+/// assert_eq!(attribute(70, start, boundary, end, Attribution::Start), (70, 0));
+/// assert_eq!(attribute(70, start, boundary, end, Attribution::Majority), (0, 70));
+/// assert_eq!(attribute(70, start, boundary, end, Attribution::Split), (20, 50));
+/// ```
+pub fn attribute(
+    count: u64,
+    start: NaiveDateTime,
+    boundary: NaiveDateTime,
+    end: NaiveDateTime,
+    attribution: Attribution,
+) -> (u64, u64) {
+    if boundary <= start {
+        return (0, count);
+    }
+    if boundary >= end {
+        return (count, 0);
+    }
+
+    let before = u128::try_from((boundary - start).num_milliseconds()).unwrap_or(0);
+    let after = u128::try_from((end - boundary).num_milliseconds()).unwrap_or(0);
+    match attribution {
+        Attribution::Start => (count, 0),
+        Attribution::End => (0, count),
+        Attribution::Majority if before >= after => (count, 0),
+        Attribution::Majority => (0, count),
+        Attribution::Split => {
+            // Round half up; the later parent takes the remainder.
+            let total = before + after;
+            let first = (u128::from(count) * before * 2 + total) / (total * 2);
+            let first = u64::try_from(first).unwrap_or(count).min(count);
+            (first, count - first)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn local(month: u32, day: u32, hour: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, month, day)
+            .unwrap()
+            .and_hms_opt(hour, 0, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn split_shares_sum_to_count() {
+        // Week of 2026-10-26: six days in October, one in November.
+        let (start, boundary, end) = (local(10, 26, 0), local(11, 1, 0), local(11, 2, 0));
+        for count in [0, 1, 3, 7, 13, 1000] {
+            let (first, second) = attribute(count, start, boundary, end, Attribution::Split);
+            assert_eq!(first + second, count);
+        }
+        assert_eq!(
+            attribute(7, start, boundary, end, Attribution::Split),
+            (6, 1)
+        );
+        assert_eq!(
+            attribute(1, start, boundary, end, Attribution::Split),
+            (1, 0)
+        );
+    }
+
+    #[test]
+    fn majority_goes_to_larger_part() {
+        let (start, boundary, end) = (local(3, 31, 0), local(4, 1, 0), local(4, 2, 0));
+        assert_eq!(
+            attribute(5, start, boundary, end, Attribution::Majority),
+            (5, 0)
+        );
+        let late = local(4, 1, 1);
+        assert_eq!(
+            attribute(5, start, late, end, Attribution::Majority),
+            (5, 0)
+        );
+        let early = local(3, 31, 23);
+        assert_eq!(
+            attribute(5, start, early, end, Attribution::Majority),
+            (0, 5)
+        );
+    }
+
+    #[test]
+    fn boundary_outside_child_keeps_count_whole() {
+        let (start, end) = (local(3, 2, 0), local(3, 9, 0));
+        for attribution in [Attribution::End, Attribution::Split] {
+            assert_eq!(attribute(4, start, start, end, attribution), (0, 4));
+            assert_eq!(attribute(4, start, end, end, attribution), (4, 0));
+        }
+    }
+}
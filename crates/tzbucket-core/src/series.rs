@@ -0,0 +1,141 @@
+//! Lazy enumeration of consecutive buckets.
+//!
+//! [`BucketSeries`] starts at the bucket containing an instant and yields
+//! each following bucket in turn: the next bucket is the one containing the
+//! previous bucket's end. DST-shortened days, skipped hours and month lengths
+//! are handled by [`compute_bucket`] as usual.
+
+use std::iter::FusedIterator;
+
+use chrono::{DateTime, Utc};
+
+use crate::compute::compute_bucket;
+use crate::models::{Bucket, BucketSpec};
+
+/// Iterator over consecutive buckets from the one containing a start instant.
+///
+/// The series is unbounded unless limited with [`BucketSeries::until`] or an
+/// adapter such as [`Iterator::take`]; it only ends on its own at the edge of
+/// the range chrono can represent.
+///
+/// # Examples
+///
+/// ```
+/// use chrono::{TimeZone, Utc};
+/// use tzbucket_core::{BucketSeries, BucketSpec, Interval};
+///
+/// let start = Utc.with_ymd_and_hms(2026, 3, 28, 12, 0, 0).unwrap();
+/// let spec = BucketSpec::new(chrono_tz::Europe::Berlin, Interval::Hour);
+/// let keys: Vec<_> = BucketSeries::new(start, &spec)
+///     .skip(11)
+///     .take(3)
+///     .map(|bucket| bucket.key)
+///     .collect();
+///
+/// // Local 02:00 does not exist on 2026-03-29.
+/// assert_eq!(keys, ["2026-03-29T00", "2026-03-29T01", "2026-03-29T03"]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct BucketSeries {
+    spec: BucketSpec,
+    /// An instant in the next bucket, `None` once the series has ended.
+    cursor: Option<DateTime<Utc>>,
+    end: Option<DateTime<Utc>>,
+}
+
+impl BucketSeries {
+    /// The series of `spec` buckets starting with the one containing `start`.
+    pub fn new(start: DateTime<Utc>, spec: &BucketSpec) -> Self {
+        Self {
+            spec: *spec,
+            cursor: Some(start),
+            end: None,
+        }
+    }
+
+    /// Stop before the first bucket starting at or after `end`, so the series
+    /// covers the buckets intersecting `[start, end)`.
+    ///
+    /// ```
+    /// use chrono::{TimeZone, Utc};
+    /// use tzbucket_core::{BucketSeries, BucketSpec, Interval};
+    ///
+    /// let spec = BucketSpec::new(chrono_tz::Europe::Berlin, Interval::Month);
+    /// let start = Utc.with_ymd_and_hms(2026, 1, 15, 0, 0, 0).unwrap();
+    /// let end = Utc.with_ymd_and_hms(2026, 3, 1, 0, 0, 0).unwrap();
+    /// let keys: Vec<_> = BucketSeries::new(start, &spec).until(end).map(|b| b.key).collect();
+    ///
+    /// // March starts at 2026-02-28T23:00:00Z, before `end`.
+    /// assert_eq!(keys, ["2026-01", "2026-02", "2026-03"]);
+    /// ```
+    pub fn until(mut self, end: DateTime<Utc>) -> Self {
+        self.end = Some(end);
+        self
+    }
+}
+
+impl Iterator for BucketSeries {
+    type Item = Bucket;
+
+    fn next(&mut self) -> Option<Bucket> {
+        let cursor = self.cursor.take()?;
+        if self.end.is_some_and(|end| cursor >= end) {
+            return None;
+        }
+
+        let bucket = compute_bucket(
+            cursor,
+            self.spec.tz,
+            self.spec.interval,
+            self.spec.week_start,
+        );
+        // The next bucket contains this one's end. A bucket that fails to
+        // advance (at the end of chrono's range) ends the series.
+        self.cursor = DateTime::parse_from_rfc3339(&bucket.end_utc)
+            .ok()
+            .map(|end| end.with_timezone(&Utc))
+            .filter(|&end| end > cursor);
+        Some(bucket)
+    }
+}
+
+impl FusedIterator for BucketSeries {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Interval, WeekStart};
+    use chrono::TimeZone;
+
+    #[test]
+    fn series_follows_dst_lengths() {
+        let spec = BucketSpec::new(chrono_tz::Europe::Berlin, Interval::Day);
+        let start = Utc.with_ymd_and_hms(2026, 10, 24, 12, 0, 0).unwrap();
+        let buckets: Vec<Bucket> = BucketSeries::new(start, &spec).take(3).collect();
+
+        let keys: Vec<_> = buckets.iter().map(|b| b.key.as_str()).collect();
+        assert_eq!(keys, ["2026-10-24", "2026-10-25", "2026-10-26"]);
+        assert_eq!(buckets[1].start_utc, "2026-10-24T22:00:00Z");
+        assert_eq!(buckets[1].end_utc, "2026-10-25T23:00:00Z");
+        for pair in buckets.windows(2) {
+            assert_eq!(pair[0].end_utc, pair[1].start_utc);
+        }
+    }
+
+    #[test]
+    fn until_keeps_bucket_containing_end() {
+        let spec =
+            BucketSpec::new(chrono_tz::UTC, Interval::Week).with_week_start(WeekStart::Sunday);
+        let start = Utc.with_ymd_and_hms(2026, 3, 4, 0, 0, 0).unwrap();
+        let end = Utc.with_ymd_and_hms(2026, 3, 15, 0, 0, 1).unwrap();
+        let keys: Vec<_> = BucketSeries::new(start, &spec)
+            .until(end)
+            .map(|b| b.key)
+            .collect();
+        assert_eq!(keys, ["2026-03-01", "2026-03-08", "2026-03-15"]);
+
+        let mut series = BucketSeries::new(end, &spec).until(end);
+        assert!(series.next().is_none());
+        assert!(series.next().is_none());
+    }
+}
@@ -0,0 +1,176 @@
+//! Property-test strategies for downstream users (feature `testing`).
+//!
+//! These [`proptest`] strategies generate the inputs that tend to break
+//! calendar logic: instants a few hours around DST transitions, zones with
+//! unusual offsets, and every interval/week-start combination.
+//!
+//! ```
+//! use proptest::prelude::*;
+//! use tzbucket_core::testing::instant_near_transition;
+//! use tzbucket_core::{Interval, compute_bucket};
+//!
+//! proptest!(|((tz, instant) in instant_near_transition(), interval: Interval)| {
+//!     let bucket = compute_bucket(instant, tz, interval, None);
+//!     prop_assert!(bucket.start_utc < bucket.end_utc);
+//! });
+//! ```
+
+use chrono::{DateTime, Duration, NaiveDate, TimeZone, Utc};
+use chrono_tz::{TZ_VARIANTS, Tz};
+use proptest::prelude::*;
+use proptest::sample::select;
+
+use crate::models::{Interval, MonthClamp, WeekStart};
+use crate::tz::offset_transitions;
+
+/// Zones with frequent or unusual DST rules, used by
+/// [`instant_near_transition`].
+pub const DST_ZONES: &[Tz] = &[
+    Tz::Europe__Berlin,
+    Tz::Europe__London,
+    Tz::America__New_York,
+    Tz::America__Sao_Paulo,
+    Tz::America__Havana,
+    Tz::America__Santiago,
+    Tz::Asia__Tehran,
+    Tz::Australia__Lord_Howe,
+    Tz::Australia__Sydney,
+    Tz::Pacific__Apia,
+    Tz::Pacific__Chatham,
+    Tz::Africa__Casablanca,
+];
+
+impl Arbitrary for Interval {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        prop_oneof![
+            select(vec![1u32, 5, 15, 30, 45, 90, 360]).prop_map(Interval::Minutes),
+            Just(Interval::Hour),
+            select(vec![1u32, 2, 3, 6, 12]).prop_map(Interval::Hours),
+            Just(Interval::Day),
+            (1u32..=28, -3650i64..3650).prop_map(|(n, offset)| Interval::Days {
+                n,
+                anchor: NaiveDate::from_ymd_opt(2026, 1, 5).unwrap() + Duration::days(offset),
+            }),
+            Just(Interval::Week),
+            Just(Interval::Month),
+            Just(Interval::Quarter),
+            (1u8..=12).prop_map(|start_month| Interval::FiscalQuarter { start_month }),
+            Just(Interval::Year),
+            (
+                1u8..=31,
+                prop_oneof![
+                    Just(MonthClamp::PreviousValidDay),
+                    Just(MonthClamp::RollForward)
+                ]
+            )
+                .prop_map(|(anchor_day, clamp)| Interval::TrailingMonth { anchor_day, clamp }),
+            (1u64..=604_800).prop_map(Interval::FixedSeconds),
+        ]
+        .boxed()
+    }
+}
+
+impl Arbitrary for WeekStart {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        prop_oneof![Just(WeekStart::Monday), Just(WeekStart::Sunday)].boxed()
+    }
+}
+
+/// Any IANA zone known to the bundled tzdb.
+pub fn iana_zone() -> impl Strategy<Value = Tz> {
+    select(&TZ_VARIANTS[..])
+}
+
+/// Any instant between 1970-01-01 and 2100-01-01 (UTC), second precision.
+pub fn utc_instant() -> impl Strategy<Value = DateTime<Utc>> {
+    (0i64..4_102_444_800).prop_map(|secs| Utc.timestamp_opt(secs, 0).unwrap())
+}
+
+/// A zone from [`DST_ZONES`] paired with an instant within three hours of
+/// one of its UTC offset transitions between 1990 and 2040.
+///
+/// Zone/year combinations without transitions fall back to an instant in
+/// that year, so the strategy never rejects.
+pub fn instant_near_transition() -> impl Strategy<Value = (Tz, DateTime<Utc>)> {
+    (
+        select(DST_ZONES),
+        1990i32..2040,
+        any::<prop::sample::Index>(),
+        -10_800i64..=10_800,
+    )
+        .prop_map(|(tz, year, index, jitter)| {
+            let transitions = transitions_in_year(tz, year);
+            let anchor = if transitions.is_empty() {
+                year_start(year)
+            } else {
+                transitions[index.index(transitions.len())]
+            };
+            (tz, anchor + Duration::seconds(jitter))
+        })
+}
+
+/// UTC instants in `year` at which the UTC offset of `tz` changes.
+///
+/// Each returned instant is the first second with the new offset.
+pub fn transitions_in_year(tz: Tz, year: i32) -> Vec<DateTime<Utc>> {
+    offset_transitions(tz, year_start(year), year_start(year + 1))
+}
+
+fn year_start(year: i32) -> DateTime<Utc> {
+    NaiveDate::from_ymd_opt(year, 1, 1)
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .expect("year within chrono range")
+        .and_utc()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compute::compute_bucket;
+
+    #[test]
+    fn transitions_in_year_finds_berlin_dst() {
+        let transitions = transitions_in_year(Tz::Europe__Berlin, 2026);
+
+        assert_eq!(
+            transitions,
+            vec![
+                Utc.with_ymd_and_hms(2026, 3, 29, 1, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2026, 10, 25, 1, 0, 0).unwrap(),
+            ]
+        );
+    }
+
+    #[test]
+    fn transitions_in_year_empty_for_utc() {
+        assert!(transitions_in_year(Tz::UTC, 2026).is_empty());
+    }
+
+    proptest! {
+        #[test]
+        fn bucket_contains_instant_near_transition(
+            (tz, instant) in instant_near_transition(),
+            interval: Interval,
+            week_start: WeekStart,
+        ) {
+            let bucket = compute_bucket(instant, tz, interval, Some(week_start));
+            prop_assert!(bucket.contains(instant), "{:?} not in {:?}", instant, bucket);
+        }
+
+        #[test]
+        fn bucket_contains_instant_in_any_zone(
+            tz in iana_zone(),
+            instant in utc_instant(),
+            interval: Interval,
+        ) {
+            let bucket = compute_bucket(instant, tz, interval, None);
+            prop_assert!(bucket.contains(instant), "{:?} not in {:?}", instant, bucket);
+        }
+    }
+}
@@ -3,8 +3,11 @@
 //! This module provides functions for parsing timezone names and
 //! converting between UTC and local time with proper DST handling.
 
-use chrono::{DateTime, TimeZone, Utc};
-use chrono_tz::Tz;
+use std::ops::Range;
+
+use chrono::offset::LocalResult;
+use chrono::{DateTime, FixedOffset, Offset, TimeZone, Utc};
+use chrono_tz::{OffsetComponents, OffsetName, Tz};
 
 use crate::error::{Result, TzBucketError};
 
@@ -26,6 +29,7 @@ use crate::error::{Result, TzBucketError};
 /// let tz = parse_tz("Europe/Berlin").unwrap();
 /// assert_eq!(tz.to_string(), "Europe/Berlin");
 /// ```
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "trace"))]
 pub fn parse_tz(name: &str) -> Result<Tz> {
     name.parse::<Tz>()
         .map_err(|_| TzBucketError::InvalidTimezone(name.to_string()))
@@ -63,27 +67,64 @@ pub fn utc_to_local(utc: DateTime<Utc>, tz: Tz) -> DateTime<Tz> {
 ///
 /// The UTC datetime.
 pub fn local_to_utc(local: chrono::NaiveDateTime, tz: Tz) -> DateTime<Utc> {
-    // Use `single` which returns None for ambiguous/nonexistent times,
-    // then fall back to `earliest` for ambiguous and let chrono handle nonexistent
-    match tz.from_local_datetime(&local).single() {
-        Some(dt) => dt.with_timezone(&Utc),
-        None => {
-            // Handle ambiguous or nonexistent times
+    local_to_utc_classified(local, tz).0
+}
+
+/// How a local wall-clock time mapped onto the UTC timeline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum LocalKind {
+    /// The local time occurs exactly once.
+    Normal,
+    /// The local time occurs twice (DST fall back).
+    Ambiguous,
+    /// The local time is skipped (DST spring forward).
+    Nonexistent,
+}
+
+/// Same as [`local_to_utc`], but also reports whether the local time was
+/// ambiguous or nonexistent so callers can record it.
+pub(crate) fn local_to_utc_classified(
+    local: chrono::NaiveDateTime,
+    tz: Tz,
+) -> (DateTime<Utc>, LocalKind) {
+    match tz.from_local_datetime(&local) {
+        LocalResult::Single(dt) => (dt.with_timezone(&Utc), LocalKind::Normal),
+        LocalResult::Ambiguous(earliest, _) => {
             // For ambiguous: earliest gives the first occurrence
-            // For nonexistent: chrono-tz will shift forward
-            tz.from_local_datetime(&local)
-                .earliest()
-                .map(|dt| dt.with_timezone(&Utc))
-                .unwrap_or_else(|| {
-                    // Fallback: construct from local components
-                    Utc.timestamp_opt(local.and_utc().timestamp(), 0)
-                        .single()
-                        .unwrap()
-                })
+            #[cfg(feature = "tracing")]
+            tracing::debug!(
+                monotonic_counter.tzbucket_ambiguous_resolutions = 1u64,
+                tz = %tz,
+                local = %local,
+                "resolved ambiguous local time to earliest occurrence"
+            );
+            (earliest.with_timezone(&Utc), LocalKind::Ambiguous)
+        }
+        LocalResult::None => {
+            #[cfg(feature = "tracing")]
+            tracing::debug!(
+                monotonic_counter.tzbucket_nonexistent_resolutions = 1u64,
+                tz = %tz,
+                local = %local,
+                "resolving nonexistent local time"
+            );
+            (shift_forward_to_utc(local, tz), LocalKind::Nonexistent)
         }
     }
 }
 
+/// Resolve a nonexistent local time by shifting it forward by the length of
+/// the gap.
+///
+/// The wall-clock time is interpreted with the offset in effect before the
+/// transition. The probe one day earlier lies before the gap for any
+/// real-world offset.
+pub(crate) fn shift_forward_to_utc(local: chrono::NaiveDateTime, tz: Tz) -> DateTime<Utc> {
+    let before = local - chrono::Duration::days(1);
+    let offset_before = tz.offset_from_utc_datetime(&before).fix();
+    local.and_utc() - chrono::Duration::seconds(i64::from(offset_before.local_minus_utc()))
+}
+
 /// Convert a local date and time (at midnight) to UTC.
 ///
 /// This is a convenience function for converting bucket boundaries,
@@ -118,6 +159,214 @@ where
     dt.format("%Y-%m-%dT%H:%M:%S%:z").to_string()
 }
 
+/// The UTC offset of a zone that has only ever had one: `UTC` and its
+/// aliases and the `Etc/GMT±N` zones. `None` for every geographic zone, even
+/// one that currently observes no DST.
+///
+/// # Examples
+///
+/// ```
+/// use chrono::FixedOffset;
+/// use tzbucket_core::tz::fixed_offset;
+///
+/// assert_eq!(fixed_offset(chrono_tz::UTC), FixedOffset::east_opt(0));
+/// // POSIX signs: Etc/GMT-5 is five hours east of UTC.
+/// assert_eq!(fixed_offset(chrono_tz::Etc::GMTMinus5), FixedOffset::east_opt(5 * 3600));
+/// assert_eq!(fixed_offset(chrono_tz::Asia::Tokyo), None);
+/// ```
+pub fn fixed_offset(tz: Tz) -> Option<FixedOffset> {
+    let name = tz.name();
+    let fixed = name.starts_with("Etc/")
+        || matches!(
+            name,
+            "UTC" | "UCT" | "GMT" | "GMT0" | "GMT+0" | "GMT-0" | "Greenwich" | "Universal" | "Zulu"
+        );
+    fixed.then(|| {
+        tz.offset_from_utc_datetime(&DateTime::UNIX_EPOCH.naive_utc())
+            .fix()
+    })
+}
+
+/// UTC instants in `[start, end)` at which the UTC offset of `tz` changes.
+///
+/// Each returned instant is the first second with the new offset. The range
+/// is scanned an hour at a time, so changes that revert within the same day
+/// are found too.
+///
+/// # Examples
+///
+/// ```
+/// use chrono::{TimeZone, Utc};
+/// use tzbucket_core::tz::offset_transitions;
+///
+/// let start = Utc.with_ymd_and_hms(2026, 3, 1, 0, 0, 0).unwrap();
+/// let end = Utc.with_ymd_and_hms(2026, 4, 1, 0, 0, 0).unwrap();
+/// let transitions = offset_transitions(chrono_tz::Europe::Berlin, start, end);
+///
+/// assert_eq!(transitions, vec![Utc.with_ymd_and_hms(2026, 3, 29, 1, 0, 0).unwrap()]);
+/// ```
+pub fn offset_transitions(tz: Tz, start: DateTime<Utc>, end: DateTime<Utc>) -> Vec<DateTime<Utc>> {
+    changes(start, end, |t| {
+        tz.offset_from_utc_datetime(&t.naive_utc()).fix()
+    })
+}
+
+/// A stretch of time during which a zone keeps one offset and abbreviation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OffsetPeriod {
+    /// First instant of the period.
+    pub from_utc: DateTime<Utc>,
+    /// Total UTC offset, including any DST saving.
+    pub offset: FixedOffset,
+    /// Time zone abbreviation such as `CEST`, or tzdb's numeric form such as
+    /// `+0330` for zones without an established name.
+    pub abbreviation: String,
+    /// Whether the offset includes a daylight saving.
+    pub is_dst: bool,
+}
+
+/// The offset history of `tz` over `[start, end)`: one [`OffsetPeriod`] in
+/// effect at `start`, then one per change of offset, abbreviation or DST
+/// flag.
+///
+/// The first period starts at `start` even if it began earlier. Like
+/// [`offset_transitions`], the range is scanned an hour at a time.
+///
+/// # Examples
+///
+/// ```
+/// use chrono::{TimeZone, Utc};
+/// use tzbucket_core::tz::offset_history;
+///
+/// let start = Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap();
+/// let end = Utc.with_ymd_and_hms(2027, 1, 1, 0, 0, 0).unwrap();
+/// let history = offset_history(chrono_tz::Europe::Berlin, start, end);
+///
+/// let names: Vec<_> = history.iter().map(|p| p.abbreviation.as_str()).collect();
+/// assert_eq!(names, ["CET", "CEST", "CET"]);
+/// assert_eq!(history[1].from_utc, Utc.with_ymd_and_hms(2026, 3, 29, 1, 0, 0).unwrap());
+/// assert!(history[1].is_dst);
+/// ```
+pub fn offset_history(tz: Tz, start: DateTime<Utc>, end: DateTime<Utc>) -> Vec<OffsetPeriod> {
+    let period_at = |t: DateTime<Utc>| {
+        let offset = tz.offset_from_utc_datetime(&t.naive_utc());
+        OffsetPeriod {
+            from_utc: t,
+            offset: offset.fix(),
+            abbreviation: offset
+                .abbreviation()
+                .map_or_else(|| numeric_abbreviation(offset.fix()), str::to_string),
+            is_dst: !offset.dst_offset().is_zero(),
+        }
+    };
+    let describe = |t| {
+        let period = period_at(t);
+        (period.offset, period.abbreviation, period.is_dst)
+    };
+    if start >= end {
+        return Vec::new();
+    }
+
+    std::iter::once(start)
+        .chain(changes(start, end, describe))
+        .map(period_at)
+        .collect()
+}
+
+/// Whether `a` and `b` have the same UTC offset at every instant of `range`.
+///
+/// Only offsets are compared; zones that differ in abbreviation or DST flag
+/// alone still count as equivalent. See [`first_offset_difference`].
+///
+/// # Examples
+///
+/// ```
+/// use chrono::{TimeZone, Utc};
+/// use chrono_tz::{America, Europe};
+/// use tzbucket_core::tz::zones_equivalent;
+///
+/// let start = Utc.with_ymd_and_hms(2020, 1, 1, 0, 0, 0).unwrap();
+/// let end = Utc.with_ymd_and_hms(2030, 1, 1, 0, 0, 0).unwrap();
+///
+/// assert!(zones_equivalent(Europe::Berlin, Europe::Paris, start..end));
+/// assert!(!zones_equivalent(America::New_York, America::Havana, start..end));
+/// ```
+pub fn zones_equivalent(a: Tz, b: Tz, range: Range<DateTime<Utc>>) -> bool {
+    first_offset_difference(a, b, range).is_none()
+}
+
+/// The first instant of `range` at which `a` and `b` have different UTC
+/// offsets, or `None` if they agree throughout.
+///
+/// Both zones' own transitions are checked, so differences lasting less than
+/// the [`offset_transitions`] scan step are found too.
+pub fn first_offset_difference(a: Tz, b: Tz, range: Range<DateTime<Utc>>) -> Option<DateTime<Utc>> {
+    let offset = |tz: Tz, t: DateTime<Utc>| tz.offset_from_utc_datetime(&t.naive_utc()).fix();
+    if range.is_empty() {
+        return None;
+    }
+
+    let mut candidates = offset_transitions(a, range.start, range.end);
+    candidates.extend(offset_transitions(b, range.start, range.end));
+    candidates.sort();
+    std::iter::once(range.start)
+        .chain(candidates)
+        .find(|&t| offset(a, t) != offset(b, t))
+}
+
+/// tzdb's abbreviation for an unnamed offset: `+03`, or `+0330` with minutes.
+fn numeric_abbreviation(offset: FixedOffset) -> String {
+    let seconds = offset.local_minus_utc();
+    let sign = if seconds < 0 { '-' } else { '+' };
+    let (hours, minutes) = (seconds.abs() / 3600, seconds.abs() % 3600 / 60);
+    if minutes == 0 {
+        format!("{}{:02}", sign, hours)
+    } else {
+        format!("{}{:02}{:02}", sign, hours, minutes)
+    }
+}
+
+/// How far apart [`changes`] samples. Every offset period in tzdb lasts
+/// more than a day, but a period may start and end on the same UTC day, which
+/// daily samples would step over; an hour leaves a wide margin.
+const SCAN_STEP: chrono::Duration = chrono::Duration::hours(1);
+
+/// Instants in `[start, end)` at which `describe` changes, sampling every
+/// [`SCAN_STEP`] and bisecting to the first second of the new value.
+fn changes<T: PartialEq>(
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    describe: impl Fn(DateTime<Utc>) -> T,
+) -> Vec<DateTime<Utc>> {
+    let at = |secs: i64| Utc.timestamp_opt(secs, 0).unwrap();
+
+    let mut transitions = Vec::new();
+    let mut sample = at(start.timestamp());
+    let mut value = describe(sample);
+
+    while sample < end {
+        let next = (sample + SCAN_STEP).min(end);
+        let next_value = describe(next);
+        if value != next_value {
+            // Bisect to the first second carrying the new value.
+            let (mut lo, mut hi) = (sample.timestamp(), next.timestamp());
+            while hi - lo > 1 {
+                let mid = lo + (hi - lo) / 2;
+                if describe(at(mid)) == describe(at(lo)) {
+                    lo = mid;
+                } else {
+                    hi = mid;
+                }
+            }
+            transitions.push(at(hi));
+        }
+        sample = next;
+        value = next_value;
+    }
+
+    transitions
+}
+
 /// Format a UTC datetime as RFC3339 with Z suffix.
 ///
 /// # Arguments
@@ -131,6 +380,108 @@ pub fn format_rfc3339_utc(dt: &DateTime<Utc>) -> String {
     dt.format("%Y-%m-%dT%H:%M:%SZ").to_string()
 }
 
+/// The tzdb `zone.tab` table: country code, coordinates, zone, comment.
+#[cfg(feature = "zone-tab")]
+const ZONE_TAB: &str = include_str!("../data/zone.tab");
+
+/// The IANA zones `zone.tab` lists for an ISO 3166-1 alpha-2 country code.
+///
+/// Matching is case-insensitive and the zones come in table order, the
+/// most populous region first. An unknown code returns no zones; so do zones
+/// left out of the build by `filter-tzdata`.
+///
+/// # Examples
+///
+/// ```
+/// use tzbucket_core::tz::zones_for_country;
+///
+/// let zones = zones_for_country("de");
+/// assert_eq!(zones, [chrono_tz::Europe::Berlin, chrono_tz::Europe::Busingen]);
+/// assert_eq!(zones_for_country("IS"), [chrono_tz::Atlantic::Reykjavik]);
+/// assert!(zones_for_country("XX").is_empty());
+/// ```
+#[cfg(feature = "zone-tab")]
+pub fn zones_for_country(code: &str) -> Vec<Tz> {
+    ZONE_TAB
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| {
+            let mut fields = line.split('\t');
+            let country = fields.next()?;
+            let zone = fields.nth(1)?;
+            country.eq_ignore_ascii_case(code).then_some(zone)
+        })
+        .filter_map(|zone| zone.parse::<Tz>().ok())
+        .collect()
+}
+
+/// The IANA zone at a latitude/longitude in degrees, from the timezone
+/// boundaries embedded by `tzf-rs`.
+///
+/// Points at sea resolve to their nautical `Etc/GMT±N` zone. Coordinates
+/// outside -90..=90 / -180..=180, and zones left out of the build by
+/// `filter-tzdata`, are an [`InvalidTimezone`](TzBucketError::InvalidTimezone)
+/// error. The boundary data is loaded on the first call (a few hundred
+/// milliseconds) and kept for the process.
+///
+/// # Examples
+///
+/// ```
+/// use tzbucket_core::tz::tz_for_location;
+///
+/// assert_eq!(tz_for_location(52.52, 13.405).unwrap(), chrono_tz::Europe::Berlin);
+/// assert_eq!(tz_for_location(40.7128, -74.006).unwrap(), chrono_tz::America::New_York);
+/// assert!(tz_for_location(91.0, 0.0).is_err());
+/// ```
+#[cfg(feature = "tzf-rs")]
+pub fn tz_for_location(lat: f64, lon: f64) -> Result<Tz> {
+    static FINDER: std::sync::OnceLock<tzf_rs::DefaultFinder> = std::sync::OnceLock::new();
+
+    let location = || {
+        TzBucketError::InvalidTimezone(format!("no zone at latitude {}, longitude {}", lat, lon))
+    };
+    if !(-90.0..=90.0).contains(&lat) || !(-180.0..=180.0).contains(&lon) {
+        return Err(location());
+    }
+    let name = FINDER
+        .get_or_init(tzf_rs::DefaultFinder::new)
+        .get_tz_name(lon, lat);
+    if name.is_empty() {
+        return Err(location());
+    }
+    parse_tz(name)
+}
+
+/// Legacy zone names and their current names, from tzdb's `backward` links.
+const LEGACY_ZONES: &str = include_str!("../data/legacy_zones.tab");
+
+/// The current name of a legacy zone name such as `US/Eastern` or
+/// `Asia/Calcutta`, or `None` if `tz` is not a legacy name.
+///
+/// `UTC` and `GMT` are not treated as legacy. The legacy name keeps working;
+/// this only tells callers which name to migrate to.
+///
+/// # Examples
+///
+/// ```
+/// use tzbucket_core::tz::canonical_tz;
+///
+/// assert_eq!(canonical_tz(chrono_tz::US::Eastern), Some(chrono_tz::America::New_York));
+/// assert_eq!(canonical_tz(chrono_tz::Asia::Calcutta), Some(chrono_tz::Asia::Kolkata));
+/// assert_eq!(canonical_tz(chrono_tz::Europe::Berlin), None);
+/// assert_eq!(canonical_tz(chrono_tz::UTC), None);
+/// ```
+pub fn canonical_tz(tz: Tz) -> Option<Tz> {
+    LEGACY_ZONES
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .find_map(|line| {
+            let (legacy, current) = line.split_once('\t')?;
+            (legacy == tz.name()).then_some(current)
+        })
+        .and_then(|current| current.parse::<Tz>().ok())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -142,6 +493,82 @@ mod tests {
         assert_eq!(tz.to_string(), "Europe/Berlin");
     }
 
+    #[test]
+    fn offset_transitions_in_year() {
+        let start = Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap();
+        let end = Utc.with_ymd_and_hms(2027, 1, 1, 0, 0, 0).unwrap();
+
+        assert_eq!(
+            offset_transitions(chrono_tz::Europe::Berlin, start, end),
+            vec![
+                Utc.with_ymd_and_hms(2026, 3, 29, 1, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2026, 10, 25, 1, 0, 0).unwrap(),
+            ]
+        );
+        assert!(offset_transitions(chrono_tz::Asia::Tokyo, start, end).is_empty());
+    }
+
+    #[test]
+    fn offset_history_ends_with_abolished_dst() {
+        // Iran observed DST for the last time in 2022.
+        let start = Utc.with_ymd_and_hms(2020, 1, 1, 0, 0, 0).unwrap();
+        let end = Utc.with_ymd_and_hms(2030, 1, 1, 0, 0, 0).unwrap();
+        let history = offset_history(chrono_tz::Asia::Tehran, start, end);
+
+        assert_eq!(history.len(), 7);
+        assert_eq!(history[0].from_utc, start);
+        let dst: Vec<bool> = history.iter().map(|period| period.is_dst).collect();
+        assert_eq!(dst, [false, true, false, true, false, true, false]);
+        let last = history.last().unwrap();
+        assert_eq!(last.offset.to_string(), "+03:30");
+        assert_eq!(last.abbreviation, "+0330");
+
+        assert!(offset_history(chrono_tz::Asia::Tehran, end, start).is_empty());
+    }
+
+    #[test]
+    fn changes_finds_same_day_revert() {
+        // A value that flips from 10:00 to 14:00 UTC and back: the day's
+        // midnights agree, the hours in between do not.
+        let start = Utc.with_ymd_and_hms(2026, 3, 1, 0, 0, 0).unwrap();
+        let end = Utc.with_ymd_and_hms(2026, 3, 4, 0, 0, 0).unwrap();
+        let flip = Utc.with_ymd_and_hms(2026, 3, 2, 10, 0, 0).unwrap();
+        let back = Utc.with_ymd_and_hms(2026, 3, 2, 14, 0, 0).unwrap();
+
+        let found = changes(start, end, |t| (flip..back).contains(&t));
+        assert_eq!(found, [flip, back]);
+    }
+
+    #[test]
+    fn first_offset_difference_finds_hours_long_gaps() {
+        // Havana and New York share -05:00/-04:00 but switch at different
+        // local times, so they disagree for two hours in March.
+        let start = Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap();
+        let end = Utc.with_ymd_and_hms(2027, 1, 1, 0, 0, 0).unwrap();
+        let (ny, havana) = (chrono_tz::America::New_York, chrono_tz::America::Havana);
+
+        assert_eq!(
+            first_offset_difference(ny, havana, start..end),
+            Some(Utc.with_ymd_and_hms(2026, 3, 8, 5, 0, 0).unwrap())
+        );
+        assert_eq!(
+            first_offset_difference(ny, chrono_tz::America::Toronto, start..end),
+            None
+        );
+        assert_eq!(first_offset_difference(ny, havana, end..start), None);
+    }
+
+    #[test]
+    fn legacy_zone_table_names_known_zones() {
+        for line in LEGACY_ZONES.lines().filter(|line| !line.starts_with('#')) {
+            let (legacy, current) = line.split_once('\t').unwrap();
+            let legacy: Tz = legacy.parse().unwrap();
+            let current: Tz = current.parse().unwrap();
+            assert_eq!(canonical_tz(legacy), Some(current));
+            assert_eq!(canonical_tz(current), None, "{} is legacy itself", current);
+        }
+    }
+
     #[test]
     fn parse_invalid_timezone() {
         let result = parse_tz("Invalid/Timezone");
@@ -203,4 +630,56 @@ mod tests {
 
         assert_eq!(formatted, "2026-03-28T23:00:00Z");
     }
+
+    #[test]
+    fn local_to_utc_nonexistent_shifts_forward() {
+        // Berlin skips 02:00-03:00 on 2026-03-29; 02:30 shifts to 03:30 (+02:00).
+        let tz = parse_tz("Europe/Berlin").unwrap();
+        let local = chrono::NaiveDate::from_ymd_opt(2026, 3, 29)
+            .unwrap()
+            .and_hms_opt(2, 30, 0)
+            .unwrap();
+        let utc = local_to_utc(local, tz);
+
+        assert_eq!(format_rfc3339_utc(&utc), "2026-03-29T01:30:00Z");
+    }
+
+    #[test]
+    fn local_midnight_to_utc_skipped_day() {
+        // Samoa skipped 2011-12-30 entirely; its midnight maps to the transition.
+        let tz = parse_tz("Pacific/Apia").unwrap();
+        let date = chrono::NaiveDate::from_ymd_opt(2011, 12, 30).unwrap();
+        let utc = local_midnight_to_utc(date, tz);
+
+        assert_eq!(format_rfc3339_utc(&utc), "2011-12-30T10:00:00Z");
+        assert_eq!(
+            format_rfc3339(&utc.with_timezone(&tz)),
+            "2011-12-31T00:00:00+14:00"
+        );
+    }
+
+    #[cfg(feature = "tzf-rs")]
+    #[test]
+    fn tz_for_location_zones_and_sea() {
+        assert_eq!(
+            tz_for_location(52.52, 13.405).unwrap(),
+            chrono_tz::Europe::Berlin
+        );
+        assert_eq!(
+            tz_for_location(40.7128, -74.006).unwrap(),
+            chrono_tz::America::New_York
+        );
+        // Büsingen is a German exclave inside Switzerland.
+        assert_eq!(
+            tz_for_location(47.6979, 8.6871).unwrap(),
+            chrono_tz::Europe::Busingen
+        );
+        // Mid-Atlantic, at 30°W: nautical UTC-2.
+        assert_eq!(
+            tz_for_location(0.0, -30.0).unwrap(),
+            chrono_tz::Etc::GMTPlus2
+        );
+        assert!(tz_for_location(0.0, 181.0).is_err());
+        assert!(tz_for_location(f64::NAN, 0.0).is_err());
+    }
 }
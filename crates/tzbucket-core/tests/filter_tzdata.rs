@@ -0,0 +1,44 @@
+//! Zones left out by `filter-tzdata` fail like unknown zones.
+//!
+//! An integration test because the unit tests name zones a filtered build
+//! does not embed. Run it with a filter, e.g.
+//! `CHRONO_TZ_TIMEZONE_FILTER="^(Europe/Berlin|America/New_York)$" cargo test -p tzbucket-core --features filter-tzdata --test filter_tzdata`;
+//! without one every zone is embedded.
+
+#![cfg(feature = "filter-tzdata")]
+
+use tzbucket_core::TzBucketError;
+use tzbucket_core::tz::parse_tz;
+
+const ZONE_TAB: &str = include_str!("../data/zone.tab");
+
+#[test]
+fn filtered_out_zone_is_unknown_timezone() {
+    let filtered = option_env!("CHRONO_TZ_TIMEZONE_FILTER").is_some_and(|f| !f.trim().is_empty());
+    let unknown = parse_tz("Invalid/Timezone").unwrap_err().to_string();
+    assert_eq!(unknown, "Invalid timezone: Invalid/Timezone");
+
+    let mut rejected = Vec::new();
+    for zone in ZONE_TAB
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| line.split('\t').nth(2))
+    {
+        match parse_tz(zone) {
+            Ok(tz) => assert_eq!(tz.name(), zone),
+            Err(TzBucketError::InvalidTimezone(name)) => {
+                assert_eq!(name, zone);
+                assert_eq!(
+                    parse_tz(zone).unwrap_err().to_string(),
+                    unknown.replace("Invalid/Timezone", zone)
+                );
+                rejected.push(name);
+            }
+            Err(e) => panic!("{zone}: expected InvalidTimezone, got {e:?}"),
+        }
+    }
+
+    // UTC is always embedded.
+    assert!(parse_tz("UTC").is_ok());
+    assert_eq!(filtered, !rejected.is_empty(), "rejected: {rejected:?}");
+}